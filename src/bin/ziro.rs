@@ -1,13 +1,27 @@
 use anyhow::Result;
-use clap::Parser;
+use std::ffi::OsString;
+use std::time::Duration;
+use ziro::cli::config::{DEFAULT_PROTECTED_PORTS, ThemeName, TopColumn};
+use ziro::cli::logging;
 use ziro::cli::{
-    Cli, Commands, RemoveOptions, handle_find, handle_kill, handle_list, handle_remove, handle_top,
-    handle_who,
+    Cli, Commands, Config, ConnsArgs, KillOptions, NetArgs, PkillArgs, PortPolicy, PsArgs,
+    ReapArgs, RemoveOptions, TopArgs, handle_big, handle_check, handle_complete_ports,
+    handle_completions, handle_config, handle_conns, handle_doctor, handle_du, handle_find,
+    handle_info, handle_kill, handle_list, handle_net, handle_nice, handle_open, handle_pause,
+    handle_pkill, handle_ps, handle_pstree, handle_reap, handle_remove, handle_restore,
+    handle_serve, handle_snapshot, handle_tmp, handle_top, handle_unlock, handle_waitpid,
+    handle_watch, handle_who, resolve_ports,
 };
+use ziro::core::fs_ops;
+use ziro::core::port::check::CheckOptions;
+use ziro::core::top::AlertOptions;
+use ziro::error;
+use ziro::platform::command;
 #[cfg(target_os = "windows")]
 use ziro::platform::encoding;
 use ziro::platform::term;
 use ziro::ui;
+use ziro::ui::{i18n, prompt};
 
 fn main() {
     #[cfg(target_os = "windows")]
@@ -15,21 +29,104 @@ fn main() {
 
     if let Err(e) = run() {
         ui::display_error(&e);
-        std::process::exit(1);
+        std::process::exit(error::exit_code(&e));
     }
 }
 
 fn run() -> Result<()> {
-    let cli = Cli::parse();
-    let profile = term::detect_profile(&cli);
+    let args: Vec<OsString> = std::env::args_os().collect();
+    i18n::set_lang(Cli::lang_in(&args).unwrap_or_else(i18n::lang_from_env));
+    let cli = Cli::parse_localized(args);
+    logging::init_logging(&cli.log_options())?;
+    prompt::set_input_mode(prompt::detect_input_mode(cli.yes, cli.no_input));
+    if cli.no_pager {
+        ui::pager::disable();
+    }
+    // `ziro config` must still work to repair a broken file, `ziro doctor` reports it, and
+    // completion must not print errors into the shell
+    let tolerant = matches!(
+        cli.command,
+        Some(Commands::Config { .. } | Commands::Doctor | Commands::CompletePorts)
+    );
+    let config = match Config::load() {
+        Err(_) if tolerant => Ok(Config::default()),
+        loaded => loaded,
+    };
+    // `--theme` wins over `ZIRO_THEME`, which wins over the config file
+    let theme = cli
+        .theme
+        .clone()
+        .or_else(ThemeName::from_env)
+        .or_else(|| config.as_ref().ok().map(|c| c.theme.clone()))
+        .unwrap_or_default();
+    let profile = term::detect_profile(&cli, &theme);
     term::apply_profile_env(&profile);
     term::set_global_profile(profile);
+    let config = config?;
+    match config.palette(&theme) {
+        Ok(palette) => ui::theme::install(palette),
+        Err(_) if tolerant => {}
+        Err(e) => return Err(e),
+    }
+    let output = cli.output_format(config.output);
+    if let Some(secs) = cli.command_timeout {
+        command::set_command_timeout(Duration::from_secs(secs));
+    }
 
     match cli.command {
-        Some(Commands::Find { ports }) => handle_find(ports)?,
-        Some(Commands::Kill { ports, force }) => handle_kill(ports, force)?,
-        Some(Commands::List) => handle_list()?,
+        Some(Commands::Find { ports, copy }) => {
+            handle_find(resolve_ports(ports, &config.groups)?, copy)?
+        }
+        Some(Commands::Kill {
+            ports,
+            pids,
+            force,
+            tree,
+            signal,
+            elevate,
+            select_all,
+            select_none,
+            port_range_exclude,
+            allow_protected,
+            name,
+            older_than,
+            newer_than,
+        }) => handle_kill(KillOptions {
+            ports: resolve_ports(ports, &config.groups)?,
+            pids,
+            name,
+            older_than,
+            newer_than,
+            force,
+            tree,
+            elevate,
+            signal: signal.or(config.kill.signal).unwrap_or_default(),
+            preselect: match (select_all, select_none) {
+                (true, _) => ui::Preselect::All,
+                (_, true) => ui::Preselect::None,
+                _ => config.kill.preselect.unwrap_or_default(),
+            },
+            policy: if allow_protected {
+                PortPolicy {
+                    bypassed: true,
+                    ..Default::default()
+                }
+            } else {
+                PortPolicy {
+                    protected: config
+                        .kill
+                        .protected
+                        .clone()
+                        .unwrap_or_else(|| DEFAULT_PROTECTED_PORTS.to_vec()),
+                    configured: config.kill.protected.is_some(),
+                    excluded: port_range_exclude,
+                    bypassed: false,
+                }
+            },
+        })?,
+        Some(Commands::List { host_ports }) => handle_list(host_ports)?,
         Some(Commands::Who { paths }) => handle_who(paths)?,
+        Some(Commands::Unlock { paths, force, then }) => handle_unlock(paths, force, then)?,
         Some(Commands::Remove {
             paths,
             force,
@@ -37,6 +134,25 @@ fn run() -> Result<()> {
             dry_run,
             verbose,
             anyway,
+            max_depth,
+            one_file_system,
+            stdin,
+            backup,
+            retries,
+            retry_delay,
+            keep_going,
+            fail_fast,
+            hidden: _,
+            no_hidden,
+            gitignore,
+            ignored,
+            confirm_each,
+            symlinks,
+            trash,
+            no_trash,
+            verify,
+            elevate,
+            notify,
         }) => handle_remove(RemoveOptions {
             paths,
             force,
@@ -44,14 +160,275 @@ fn run() -> Result<()> {
             dry_run,
             verbose,
             anyway,
+            max_depth,
+            one_file_system,
+            stdin,
+            retries: retries
+                .or(config.remove.retries)
+                .unwrap_or(fs_ops::DEFAULT_RETRIES),
+            retry_delay: retry_delay
+                .or(config.remove.retry_delay)
+                .unwrap_or(fs_ops::DEFAULT_RETRY_DELAY_MS),
+            keep_going,
+            fail_fast,
+            no_hidden,
+            gitignore,
+            ignored,
+            confirm_each,
+            symlinks,
+            // Only an explicit --backup or --no-trash turns the configured default off
+            trash: trash || (config.remove.trash && !no_trash && backup.is_none()),
+            backup,
+            verify,
+            elevate,
+            notify,
+            protected: config.remove.protected.clone(),
+            output,
         })?,
+        Some(Commands::Restore { last }) => handle_restore(last)?,
+        Some(Commands::Du {
+            paths,
+            limit,
+            depth,
+            one_file_system,
+            hidden: _,
+            no_hidden,
+            gitignore,
+        }) => handle_du(paths, limit, depth, one_file_system, no_hidden, gitignore)?,
+        Some(Commands::Big {
+            paths,
+            limit,
+            extensions,
+            older_than,
+            one_file_system,
+            gitignore,
+        }) => handle_big(
+            paths,
+            limit,
+            extensions,
+            older_than,
+            one_file_system,
+            gitignore,
+        )?,
+        Some(Commands::Tmp {
+            older_than,
+            dry_run,
+            force,
+        }) => handle_tmp(older_than, dry_run, force)?,
         Some(Commands::Top {
             interval,
             limit,
             cpu,
             cmd,
+            io,
+            delta,
+            threads,
+            fds,
+            containers,
+            gpu,
+            net,
+            cgroup_limit,
+            once,
+            batch,
+            iterations,
+            tree,
+            aggregate,
+            group,
+            sort,
+            reverse,
+            filter,
+            user,
+            zombies,
+            cgroup,
+            min_mem,
+            min_cpu,
+            export,
+            export_file,
+            record,
+            replay,
+            speed,
+            alert_mem,
+            alert_cpu,
+            beep,
+            notify,
+            on_alert,
+            pid,
+        }) => handle_top(TopArgs {
+            interval: interval.or(config.top.interval).unwrap_or(1.0),
+            limit,
+            cpu: cpu || config.top.shows(TopColumn::Cpu),
+            cmd: cmd || config.top.shows(TopColumn::Cmd),
+            io: io || config.top.shows(TopColumn::Io),
+            delta: delta || config.top.shows(TopColumn::Delta),
+            threads: threads || config.top.shows(TopColumn::Threads),
+            fds: fds || config.top.shows(TopColumn::Fds),
+            containers: containers || config.top.shows(TopColumn::Containers),
+            gpu: gpu || config.top.shows(TopColumn::Gpu),
+            net: net || config.top.shows(TopColumn::Net),
+            cgroup_limit,
             once,
-        }) => handle_top(interval, limit, cpu, cmd, once)?,
+            batch,
+            iterations,
+            tree,
+            aggregate,
+            group,
+            sort,
+            reverse,
+            filter,
+            user,
+            zombies,
+            cgroup,
+            min_mem,
+            min_cpu,
+            export,
+            export_file,
+            record,
+            replay,
+            speed,
+            alerts: AlertOptions {
+                memory: alert_mem,
+                cpu: alert_cpu,
+                beep,
+                notify,
+                on_alert,
+            },
+            pid,
+        })?,
+        Some(Commands::Ps {
+            name,
+            user,
+            min_mem,
+            older_than,
+            newer_than,
+            sort,
+            reverse,
+            limit,
+        }) => handle_ps(
+            PsArgs {
+                name,
+                user,
+                min_mem,
+                older_than,
+                newer_than,
+                sort,
+                reverse,
+                limit,
+            },
+            output,
+        )?,
+        Some(Commands::Net {
+            name,
+            listening,
+            sort,
+            reverse,
+            limit,
+        }) => handle_net(
+            NetArgs {
+                name,
+                listening,
+                sort,
+                reverse,
+                limit,
+            },
+            output,
+        )?,
+        Some(Commands::Conns {
+            name,
+            remote,
+            resolve,
+            kill_remote,
+            force,
+            signal,
+        }) => handle_conns(
+            ConnsArgs {
+                name,
+                remote,
+                resolve,
+                kill_remote,
+                force,
+                signal: signal.or(config.kill.signal).unwrap_or_default().into(),
+            },
+            output,
+        )?,
+        Some(Commands::Serve { dir, port }) => handle_serve(dir, port)?,
+        Some(Commands::Check {
+            port,
+            host,
+            http,
+            retries,
+            interval,
+            timeout,
+        }) => handle_check(
+            CheckOptions {
+                host,
+                port,
+                http,
+                retries,
+                interval,
+                timeout,
+            },
+            output,
+        )?,
+        Some(Commands::Open { port }) => handle_open(port)?,
+        Some(Commands::Info { pid, env }) => handle_info(pid, env, output)?,
+        Some(Commands::Pause { pids }) => handle_pause(pids, true)?,
+        Some(Commands::Resume { pids }) => handle_pause(pids, false)?,
+        Some(Commands::Nice { pids, to }) => handle_nice(pids, to)?,
+        Some(Commands::Waitpid {
+            pids,
+            name,
+            timeout,
+            interval,
+            notify,
+        }) => handle_waitpid(pids, name, timeout, interval, notify, output)?,
+        Some(Commands::Watch {
+            interval,
+            iterations,
+            command,
+        }) => handle_watch(command, interval, iterations)?,
+        Some(Commands::Pkill {
+            pattern,
+            oldest,
+            newest,
+            all,
+            user,
+            older_than,
+            newer_than,
+            force,
+            signal,
+        }) => handle_pkill(PkillArgs {
+            pattern,
+            oldest,
+            newest,
+            all,
+            user,
+            older_than,
+            newer_than,
+            force,
+            signal: signal.or(config.kill.signal).unwrap_or_default().into(),
+        })?,
+        Some(Commands::Config { action }) => handle_config(action, output)?,
+        Some(Commands::Doctor) => handle_doctor(output)?,
+        #[cfg(feature = "self-update")]
+        Some(Commands::SelfUpdate { check }) => ziro::cli::handle_self_update(check, output)?,
+        Some(Commands::Completions { shell }) => handle_completions(shell)?,
+        Some(Commands::CompletePorts) => handle_complete_ports(&config.groups),
+        Some(Commands::Reap {
+            min_age,
+            nudge,
+            kill_parents,
+            force,
+        }) => handle_reap(
+            ReapArgs {
+                min_age,
+                nudge,
+                kill_parents,
+                force,
+            },
+            output,
+        )?,
+        Some(Commands::Pstree { pid }) => handle_pstree(pid, output)?,
+        Some(Commands::Snapshot { action }) => handle_snapshot(action, output)?,
         None => {
             let theme = ziro::ui::Theme::new();
             println!(
@@ -1,5 +1,19 @@
-use clap::{Parser, Subcommand};
+use crate::cli::config::ThemeName;
+use crate::cli::logging::{LogLevel, LogOptions};
+use crate::core::port::net::NetSortKey;
+use crate::core::{fs_ops, process, top};
+use crate::error::ZiroError;
+use crate::ui::i18n::{self, Lang};
+use anyhow::{Result, bail};
+use clap::{ArgGroup, Command, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::net::IpAddr;
+use std::ops::RangeInclusive;
 use std::path::PathBuf;
+use std::time::Duration;
 
 /// Ziro - Cross-platform port management tool
 #[derive(Parser)]
@@ -23,32 +37,456 @@ pub struct Cli {
     #[arg(long = "plain")]
     pub plain: bool,
 
+    /// Color theme: default, solarized, monochrome, high-contrast or one from the config file
+    /// (equivalent to ZIRO_THEME)
+    #[arg(long = "theme", value_name = "NAME", global = true, value_parser = parse_theme_name)]
+    pub theme: Option<ThemeName>,
+
+    /// Output format [default: text, or `output` in the config file]
+    #[arg(long = "output", value_enum, global = true)]
+    pub output: Option<OutputFormat>,
+
+    /// Shorthand for --output json
+    #[arg(long = "json", global = true)]
+    pub json: bool,
+
+    /// Seconds to wait for lsof, netstat or PowerShell before giving up on them
+    /// (equivalent to ZIRO_COMMAND_TIMEOUT; default 15)
+    #[arg(long = "command-timeout", value_name = "SECS", global = true, value_parser = clap::value_parser!(u64).range(1..))]
+    pub command_timeout: Option<u64>,
+
+    /// Answer yes to every confirmation (kill, remove, ...)
+    #[arg(short = 'y', long = "yes", global = true, conflicts_with = "no_input")]
+    pub yes: bool,
+
+    /// Fail instead of prompting (also ZIRO_NONINTERACTIVE=1, or when there is no terminal)
+    #[arg(long = "no-input", global = true)]
+    pub no_input: bool,
+
+    /// Log more: -v info, -vv debug, -vvv trace (equivalent to ZIRO_LOG=LEVEL)
+    #[arg(short = 'v', action = clap::ArgAction::Count, global = true)]
+    pub verbosity: u8,
+
+    /// Log level; overrides -v and ZIRO_LOG
+    #[arg(long = "log-level", value_enum, global = true)]
+    pub log_level: Option<LogLevel>,
+
+    /// Also append detailed (debug) logs to this file
+    #[arg(long = "log-file", value_name = "PATH", global = true)]
+    pub log_file: Option<PathBuf>,
+
+    /// Print long listings directly instead of through $PAGER
+    #[arg(long = "no-pager", global = true)]
+    pub no_pager: bool,
+
+    /// Language of the output [default: from LANG]
+    #[arg(long = "lang", value_enum, global = true)]
+    pub lang: Option<LangName>,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
 
+impl Cli {
+    /// Parse `args` with the help texts in the language selected by `i18n::set_lang`
+    pub fn parse_localized(args: Vec<OsString>) -> Self {
+        let mut command = Self::command();
+        command.build();
+        let matches = localize(command).get_matches_from(args);
+        Self::from_arg_matches(&matches).unwrap_or_else(|e| e.exit())
+    }
+
+    /// `--lang` as given in the raw arguments; it picks the language of the help, so it is
+    /// needed before clap parses them
+    pub fn lang_in(args: &[OsString]) -> Option<Lang> {
+        let mut args = args.iter().filter_map(|arg| arg.to_str());
+        while let Some(arg) = args.next() {
+            let value = match arg.strip_prefix("--lang") {
+                Some("") => args.next(),
+                Some(value) => value.strip_prefix('='),
+                None if arg == "--" => return None,
+                None => continue,
+            };
+            return value
+                .and_then(|value| LangName::from_str(value, true).ok())
+                .map(Lang::from);
+        }
+        None
+    }
+
+    pub fn log_options(&self) -> LogOptions {
+        LogOptions {
+            verbosity: self.verbosity,
+            level: self.log_level,
+            file: self.log_file.clone(),
+        }
+    }
+
+    /// Resolve the effective output format (`--json` wins over `--output`, which wins over the config)
+    pub fn output_format(&self, configured: Option<OutputFormat>) -> OutputFormat {
+        if self.json {
+            OutputFormat::Json
+        } else {
+            self.output.or(configured).unwrap_or_default()
+        }
+    }
+}
+
+/// Translate the help texts of `command` and its subcommands; it must be built so the
+/// `--help` and `--version` flags are there to translate
+fn localize(command: Command) -> Command {
+    if i18n::lang() == Lang::En {
+        return command;
+    }
+    let translated = |text: Option<&clap::builder::StyledStr>| {
+        text.and_then(|text| i18n::translate(&text.to_string()))
+    };
+    let about = translated(command.get_about());
+    let long_about = translated(command.get_long_about());
+    let mut command = command
+        .help_template(i18n::tr(HELP_TEMPLATE))
+        .subcommand_help_heading(i18n::tr("Commands"))
+        .mut_args(|arg| {
+            let help = translated(arg.get_help());
+            let long_help = translated(arg.get_long_help());
+            let heading = if arg.is_positional() {
+                "Arguments"
+            } else {
+                "Options"
+            };
+            let arg = arg.help_heading(i18n::tr(heading));
+            let arg = match help {
+                Some(help) => arg.help(help),
+                None => arg,
+            };
+            match long_help {
+                Some(long_help) => arg.long_help(long_help),
+                None => arg,
+            }
+        })
+        .mut_subcommands(localize);
+    if let Some(about) = about {
+        command = command.about(about);
+    }
+    if let Some(long_about) = long_about {
+        command = command.long_about(long_about);
+    }
+    command
+}
+
+/// clap's default help layout; translated to rename the `Usage:` heading
+const HELP_TEMPLATE: &str =
+    "{before-help}{about-with-newline}\n{usage-heading} {usage}\n\n{all-args}{after-help}";
+
+/// Output language
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum LangName {
+    En,
+    /// Simplified Chinese
+    Zh,
+}
+
+impl From<LangName> for Lang {
+    fn from(lang: LangName) -> Self {
+        match lang {
+            LangName::En => Self::En,
+            LangName::Zh => Self::Zh,
+        }
+    }
+}
+
+/// Output format for command results
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputFormat {
+    /// Human-readable, themed output
+    #[default]
+    Text,
+    /// Machine-readable JSON
+    Json,
+}
+
+/// How `remove` treats symlinks
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum SymlinkMode {
+    /// Delete the link, keep what it points to
+    #[default]
+    KeepTarget,
+    /// Delete the link and what it points to
+    Follow,
+    /// Leave symlinks untouched
+    Skip,
+}
+
+impl From<SymlinkMode> for fs_ops::SymlinkPolicy {
+    fn from(mode: SymlinkMode) -> Self {
+        match mode {
+            SymlinkMode::KeepTarget => Self::KeepTarget,
+            SymlinkMode::Follow => Self::Follow,
+            SymlinkMode::Skip => Self::Skip,
+        }
+    }
+}
+
+/// Signal for confirmed kills
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SignalName {
+    /// SIGKILL: stop at once
+    #[default]
+    Kill,
+    /// SIGTERM: let the process clean up (same as kill on Windows)
+    Term,
+}
+
+impl From<SignalName> for process::KillSignal {
+    fn from(signal: SignalName) -> Self {
+        match signal {
+            SignalName::Kill => Self::Kill,
+            SignalName::Term => Self::Terminate,
+        }
+    }
+}
+
+/// Sort key for `top`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum TopSort {
+    /// Resident memory, largest first
+    #[default]
+    Mem,
+    /// CPU usage, busiest first
+    Cpu,
+    /// Process ID, ascending
+    Pid,
+    /// Process name, alphabetical
+    Name,
+    /// Start time, newest first
+    Start,
+    /// Disk reads plus writes per second
+    Io,
+    /// GPU memory, largest first (implies --gpu)
+    Gpu,
+}
+
+impl From<TopSort> for top::SortKey {
+    fn from(sort: TopSort) -> Self {
+        match sort {
+            TopSort::Mem => Self::Memory,
+            TopSort::Cpu => Self::Cpu,
+            TopSort::Pid => Self::Pid,
+            TopSort::Name => Self::Name,
+            TopSort::Start => Self::Start,
+            TopSort::Io => Self::Io,
+            TopSort::Gpu => Self::Gpu,
+        }
+    }
+}
+
+/// Sort key for `net`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum NetSort {
+    /// Established connections, most first
+    #[default]
+    Conns,
+    /// Listening sockets, most first
+    Listen,
+    /// Bytes received per second, highest first
+    Rx,
+    /// Bytes sent per second, highest first
+    Tx,
+    /// Process ID, ascending
+    Pid,
+    /// Process name, alphabetical
+    Name,
+}
+
+impl From<NetSort> for NetSortKey {
+    fn from(sort: NetSort) -> Self {
+        match sort {
+            NetSort::Conns => Self::Connections,
+            NetSort::Listen => Self::Listening,
+            NetSort::Rx => Self::Rx,
+            NetSort::Tx => Self::Tx,
+            NetSort::Pid => Self::Pid,
+            NetSort::Name => Self::Name,
+        }
+    }
+}
+
+fn parse_theme_name(s: &str) -> Result<ThemeName, String> {
+    match s.trim() {
+        "" => Err("missing theme name".to_string()),
+        name => Ok(ThemeName::from(name.to_string())),
+    }
+}
+
+/// A port on the command line: a number, or `@name` for a group from the config file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PortArg {
+    Port(u16),
+    Group(String),
+}
+
+fn parse_port_arg(s: &str) -> Result<PortArg, String> {
+    match s.strip_prefix('@') {
+        Some("") => Err("missing group name after '@'".to_string()),
+        Some(name) => Ok(PortArg::Group(name.to_string())),
+        None => s
+            .parse()
+            .map(PortArg::Port)
+            .map_err(|_| format!("invalid port: {s} (expected 0-65535 or @group)")),
+    }
+}
+
+/// A port or an inclusive range of ports: `22`, `5432-5439`
+fn parse_port_range(s: &str) -> Result<RangeInclusive<u16>, String> {
+    let port = |p: &str| {
+        p.trim()
+            .parse::<u16>()
+            .map_err(|_| format!("invalid port: {p} (expected 0-65535)"))
+    };
+    match s.split_once('-') {
+        Some((start, end)) => {
+            let (start, end) = (port(start)?, port(end)?);
+            if start > end {
+                return Err(format!("empty port range: {s}"));
+            }
+            Ok(start..=end)
+        }
+        None => port(s).map(|p| p..=p),
+    }
+}
+
+/// Expand `@group` arguments with `[groups]` from the config file; a port named twice is
+/// kept once, where it first appears
+pub fn resolve_ports(args: Vec<PortArg>, groups: &BTreeMap<String, Vec<u16>>) -> Result<Vec<u16>> {
+    let mut ports = Vec::new();
+    for arg in args {
+        let expanded = match arg {
+            PortArg::Port(port) => vec![port],
+            PortArg::Group(name) => match groups.get(&name) {
+                Some(group) => group.clone(),
+                None if groups.is_empty() => {
+                    bail!(ZiroError::Usage(format!(
+                        "Unknown port group @{name}; define it under [groups] in the config file"
+                    )))
+                }
+                None => {
+                    let known: Vec<&str> = groups.keys().map(String::as_str).collect();
+                    bail!(ZiroError::Usage(format!(
+                        "Unknown port group @{name} (known: {})",
+                        known.join(", ")
+                    )))
+                }
+            },
+        };
+        for port in expanded {
+            if !ports.contains(&port) {
+                ports.push(port);
+            }
+        }
+    }
+    Ok(ports)
+}
+
+/// Row format for `top --export`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// One JSON object per process per refresh
+    Json,
+    /// CSV with a header row
+    Csv,
+    /// One JSON event per line: port_opened, port_closed, proc_killed, threshold_crossed
+    Events,
+}
+
+impl From<ExportFormat> for top::ExportFormat {
+    fn from(format: ExportFormat) -> Self {
+        match format {
+            ExportFormat::Json => Self::Json,
+            ExportFormat::Csv => Self::Csv,
+            ExportFormat::Events => Self::Events,
+        }
+    }
+}
+
+// Parsed once per run, so the size of `Top` is not worth boxing its flags
+#[allow(clippy::large_enum_variant)]
 #[derive(Subcommand)]
 pub enum Commands {
     /// Find processes occupying specified ports
     Find {
-        /// Port numbers to find (multiple allowed)
-        ports: Vec<u16>,
+        /// Port numbers or @groups to find (multiple allowed)
+        #[arg(value_parser = parse_port_arg)]
+        ports: Vec<PortArg>,
+        /// Copy the PIDs found to the clipboard
+        #[arg(long = "copy")]
+        copy: bool,
     },
     /// Kill processes occupying specified ports
     Kill {
-        /// Port numbers to kill (multiple allowed)
-        ports: Vec<u16>,
+        /// Port numbers or @groups to kill (multiple allowed)
+        #[arg(value_parser = parse_port_arg)]
+        ports: Vec<PortArg>,
+        /// Kill these PIDs without looking up ports or asking (used by --elevate)
+        #[arg(long = "pid", value_name = "PID", hide = true, conflicts_with_all = ["ports", "select_all", "select_none"])]
+        pids: Vec<u32>,
         /// Force kill without confirmation
         #[arg(short = 'f', long = "force")]
         force: bool,
+        /// Also kill every child process of the port holders
+        #[arg(long = "tree")]
+        tree: bool,
+        /// Signal for confirmed kills [default: kill, or `kill.signal` in the config file]
+        #[arg(long = "signal", value_enum, conflicts_with = "force")]
+        signal: Option<SignalName>,
+        /// Re-run kills refused for lack of privileges through sudo (UAC on Windows)
+        #[arg(long = "elevate")]
+        elevate: bool,
+        /// Start the picker with every process ticked [default: none, or `kill.preselect`]
+        #[arg(long = "select-all", conflicts_with_all = ["select_none", "force"])]
+        select_all: bool,
+        /// Start the picker with no process ticked
+        #[arg(long = "select-none", conflicts_with = "force")]
+        select_none: bool,
+        /// Also refuse these ports this time, e.g. 22,5432-5439 (on top of `kill.protected`)
+        #[arg(long = "port-range-exclude", value_name = "RANGES", value_delimiter = ',', value_parser = parse_port_range)]
+        port_range_exclude: Vec<RangeInclusive<u16>>,
+        /// Kill even ports the safe-port policy protects
+        #[arg(long = "allow-protected", conflicts_with = "port_range_exclude")]
+        allow_protected: bool,
+        /// Kill processes whose name or command line matches this regex instead of port holders
+        #[arg(long = "name", value_name = "REGEX", value_parser = top::parse_filter, conflicts_with_all = ["ports", "pids"])]
+        name: Option<Regex>,
+        /// Only match processes started at least this long ago (e.g. 30m, 2h, 1d)
+        #[arg(long = "older-than", value_name = "AGE", value_parser = fs_ops::parse_age, requires = "name", conflicts_with = "ports")]
+        older_than: Option<Duration>,
+        /// Only match processes started less than this long ago (e.g. 10m)
+        #[arg(long = "newer-than", value_name = "AGE", value_parser = fs_ops::parse_age, requires = "name", conflicts_with = "ports")]
+        newer_than: Option<Duration>,
     },
     /// List all port usage
-    List,
+    List {
+        /// Inside WSL, also list ports held by Windows processes on the host
+        #[arg(long = "host-ports")]
+        host_ports: bool,
+    },
     /// Find processes locking specified files or directories
     Who {
         /// File or directory paths to check (multiple allowed)
         paths: Vec<PathBuf>,
     },
+    /// Free locked files by killing the processes holding them
+    Unlock {
+        /// File or directory paths to unlock (multiple allowed)
+        paths: Vec<PathBuf>,
+        /// Kill all locking processes without confirmation
+        #[arg(short = 'f', long = "force")]
+        force: bool,
+        /// Command to run once the paths are free (e.g. `ziro unlock app.db -- cargo build`)
+        #[arg(last = true, value_name = "COMMAND")]
+        then: Vec<String>,
+    },
     /// Remove files or directories (supports recursive deletion)
     Remove {
         /// File or directory paths to remove (multiple allowed)
@@ -66,14 +504,142 @@ pub enum Commands {
         #[arg(short = 'V', long = "verbose")]
         verbose: bool,
         /// Force kill processes locking the files, then delete
+        ///
+        /// Lockers are killed again before each retry; `--verbose` lists the PIDs killed for each
+        /// entry. Entries that stay locked can be moved into `.ziro-pending-delete` to free their
+        /// paths.
         #[arg(long = "anyway", visible_alias = "kill-lockers")]
         anyway: bool,
+        /// Only remove entries up to this depth below each directory (1 = direct children)
+        #[arg(
+            long = "max-depth",
+            value_name = "N",
+            requires = "recursive",
+            value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+        )]
+        max_depth: Option<usize>,
+        /// Skip directories on a different filesystem than the given path
+        #[arg(long = "one-file-system")]
+        one_file_system: bool,
+        /// Read additional paths from stdin (newline or NUL separated)
+        #[arg(long = "stdin")]
+        stdin: bool,
+        /// Archive everything into a zstd-compressed tar before deleting it
+        #[arg(long = "backup", value_name = "FILE.tar.zst")]
+        backup: Option<PathBuf>,
+        /// Retries for entries locked by another process [default: 5, or `remove.retries` in the config file]
+        #[arg(long = "retries")]
+        retries: Option<u32>,
+        /// Initial delay between retries in milliseconds, doubling on each retry [default: 100, or `remove.retry_delay` in the config file]
+        #[arg(long = "retry-delay", value_name = "MS")]
+        retry_delay: Option<u64>,
+        /// Skip unreadable entries while scanning and report them in the summary
+        #[arg(long = "keep-going")]
+        keep_going: bool,
+        /// Stop at the first entry that fails to delete
+        #[arg(long = "fail-fast")]
+        fail_fast: bool,
+        /// Include dotfiles and hidden/system files (default)
+        #[arg(long = "hidden", overrides_with = "no_hidden")]
+        hidden: bool,
+        /// Leave dotfiles and hidden/system files out of the walk
+        #[arg(long = "no-hidden", overrides_with = "hidden")]
+        no_hidden: bool,
+        /// Skip entries matched by .gitignore/.ignore rules
+        #[arg(long = "gitignore", conflicts_with_all = ["ignored", "max_depth"], requires = "recursive")]
+        gitignore: bool,
+        /// Only remove entries matched by .gitignore/.ignore rules (like `git clean -X`)
+        #[arg(long = "ignored", conflicts_with = "max_depth", requires = "recursive")]
+        ignored: bool,
+        /// Ask yes/no/all/quit for each path given on the command line (like `rm -i`)
+        #[arg(short = 'i', long = "confirm-each", conflicts_with_all = ["force", "anyway"])]
+        confirm_each: bool,
+        /// How to treat symlinks
+        #[arg(long = "symlinks", value_enum, default_value_t = SymlinkMode::KeepTarget)]
+        symlinks: SymlinkMode,
+        /// Move to the system trash instead of deleting (undo with `ziro restore`)
+        #[arg(long = "trash", conflicts_with = "backup", overrides_with = "no_trash")]
+        trash: bool,
+        /// Delete for good even if `remove.trash` is set in the config file
+        #[arg(long = "no-trash", overrides_with = "trash")]
+        no_trash: bool,
+        /// Re-check removed paths afterwards and name processes that recreate them
+        #[arg(long = "verify")]
+        verify: bool,
+        /// Re-run deletions refused for lack of privileges through sudo (UAC on Windows)
+        #[arg(long = "elevate")]
+        elevate: bool,
+        /// Send a desktop notification when the removal finishes
+        #[arg(long = "notify")]
+        notify: bool,
+    },
+    /// Restore entries removed with `remove --trash`
+    Restore {
+        /// Restore the most recent trash removal without asking
+        #[arg(long = "last")]
+        last: bool,
+    },
+    /// Show disk usage per subdirectory (largest entries first)
+    Du {
+        /// Directories to analyze (default: current directory)
+        paths: Vec<PathBuf>,
+        /// Number of largest entries to show per directory
+        #[arg(short = 'n', long = "limit", default_value_t = 10)]
+        limit: usize,
+        /// Directory levels to expand
+        #[arg(short = 'd', long = "depth", default_value_t = 1)]
+        depth: usize,
+        /// Skip directories on a different filesystem than the given path
+        #[arg(short = 'x', long = "one-file-system")]
+        one_file_system: bool,
+        /// Include dotfiles and hidden/system files (default)
+        #[arg(long = "hidden", overrides_with = "no_hidden")]
+        hidden: bool,
+        /// Leave dotfiles and hidden/system files out of the walk
+        #[arg(long = "no-hidden", overrides_with = "hidden")]
+        no_hidden: bool,
+        /// Skip entries matched by .gitignore/.ignore rules
+        #[arg(long = "gitignore")]
+        gitignore: bool,
+    },
+    /// Find the largest files under a path
+    Big {
+        /// Directories to search (default: current directory)
+        paths: Vec<PathBuf>,
+        /// Number of files to show
+        #[arg(short = 'n', long = "limit", default_value_t = 20)]
+        limit: usize,
+        /// Only include these extensions (comma separated, e.g. log,zip)
+        #[arg(short = 'e', long = "ext", value_delimiter = ',')]
+        extensions: Vec<String>,
+        /// Only include files not modified within this age (e.g. 30m, 12h, 7d, 2w)
+        #[arg(long = "older-than", value_name = "AGE", value_parser = fs_ops::parse_age)]
+        older_than: Option<Duration>,
+        /// Skip directories on a different filesystem than the given path
+        #[arg(short = 'x', long = "one-file-system")]
+        one_file_system: bool,
+        /// Skip entries matched by .gitignore/.ignore rules
+        #[arg(long = "gitignore")]
+        gitignore: bool,
+    },
+    /// Clean OS and user temp directories, skipping files in use
+    Tmp {
+        /// Only clean entries not modified within this age (e.g. 12h, 7d)
+        #[arg(long = "older-than", value_name = "AGE", default_value = "1d", value_parser = fs_ops::parse_age)]
+        older_than: Duration,
+        /// Preview what would be cleaned without deleting
+        #[arg(short = 'n', long = "dry-run")]
+        dry_run: bool,
+        /// Skip confirmation
+        #[arg(short = 'f', long = "force")]
+        force: bool,
     },
     /// Monitor process memory usage in real time (like top)
+    #[command(group = ArgGroup::new("alert").args(["alert_mem", "alert_cpu"]).multiple(true))]
     Top {
-        /// Refresh interval in seconds
-        #[arg(long = "interval", default_value_t = 1.0)]
-        interval: f32,
+        /// Refresh interval in seconds [default: 1, or `top.interval` in the config file]
+        #[arg(long = "interval")]
+        interval: Option<f32>,
         /// Number of processes to display
         #[arg(long = "limit", default_value_t = 20)]
         limit: usize,
@@ -83,8 +649,438 @@ pub enum Commands {
         /// Show process command lines
         #[arg(long = "cmd")]
         cmd: bool,
+        /// Show disk read/write rates per process
+        #[arg(long = "io")]
+        io: bool,
+        /// Show how much each process's memory changed since the previous refresh
+        #[arg(long = "delta")]
+        delta: bool,
+        /// Show the number of threads of each process
+        #[arg(long = "threads")]
+        threads: bool,
+        /// Show the number of open file descriptors of each process (Linux)
+        #[arg(long = "fds")]
+        fds: bool,
+        /// Show the container or cgroup of each process (Linux)
+        #[arg(long = "containers")]
+        containers: bool,
+        /// Show GPU memory and utilization of each process (NVIDIA; needs the `gpu` build feature)
+        #[arg(long = "gpu")]
+        gpu: bool,
+        /// Show listening sockets and established connections of each process
+        #[arg(long = "net")]
+        net: bool,
+        /// Show memory as a share of each process's cgroup limit instead of host RAM (Linux)
+        #[arg(long = "cgroup-limit", conflicts_with = "replay")]
+        cgroup_limit: bool,
         /// Output once without continuous refresh
         #[arg(long = "once")]
         once: bool,
+        /// Print plain, timestamped frames for logs and pipes (like `top -b`)
+        #[arg(short = 'b', long = "batch")]
+        batch: bool,
+        /// Stop after this many refreshes
+        #[arg(short = 'n', long = "iterations", value_name = "N")]
+        iterations: Option<u64>,
+        /// Show processes under their parents
+        #[arg(long = "tree")]
+        tree: bool,
+        /// With --tree, count children's memory in their parent's row
+        #[arg(long = "aggregate", requires = "tree")]
+        aggregate: bool,
+        /// Fold processes with the same executable name into one row (Enter expands a group)
+        #[arg(long = "group", conflicts_with = "tree")]
+        group: bool,
+        /// Sort key
+        #[arg(long = "sort", value_enum, default_value_t = TopSort::Mem)]
+        sort: TopSort,
+        /// Reverse the sort direction
+        #[arg(short = 'r', long = "reverse")]
+        reverse: bool,
+        /// Only show processes whose name or command line matches this regex (case-insensitive)
+        #[arg(long = "filter", value_name = "REGEX", value_parser = top::parse_filter)]
+        filter: Option<Regex>,
+        /// Only show processes owned by this user (`me` for yourself)
+        #[arg(long = "user", value_name = "NAME")]
+        user: Option<String>,
+        /// Only show zombie processes (exited but not reaped by their parent)
+        #[arg(long = "zombies")]
+        zombies: bool,
+        /// Only show processes whose cgroup path contains NAME, e.g. a container ID (Linux)
+        #[arg(long = "cgroup", value_name = "NAME")]
+        cgroup: Option<String>,
+        /// Hide processes using less memory than this (e.g. 50MB)
+        #[arg(long = "min-mem", value_name = "SIZE", value_parser = top::parse_size)]
+        min_mem: Option<u64>,
+        /// Hide processes using less CPU than this (e.g. 1%)
+        #[arg(long = "min-cpu", value_name = "PCT", value_parser = top::parse_percent)]
+        min_cpu: Option<f32>,
+        /// Write each refresh as timestamped rows instead of the live view
+        #[arg(long = "export", value_enum, value_name = "FORMAT")]
+        export: Option<ExportFormat>,
+        /// Append exported rows to this file and keep the live view
+        #[arg(long = "export-file", value_name = "FILE", requires = "export")]
+        export_file: Option<PathBuf>,
+        /// Save every refresh to a session file for later `--replay`
+        #[arg(long = "record", value_name = "FILE", conflicts_with = "replay")]
+        record: Option<PathBuf>,
+        /// Play back a session recorded with `--record`
+        #[arg(long = "replay", value_name = "FILE")]
+        replay: Option<PathBuf>,
+        /// Replay speed multiplier (e.g. 4 plays four times faster)
+        #[arg(long = "speed", default_value_t = 1.0, requires = "replay")]
+        speed: f32,
+        /// Highlight processes using at least this much memory (e.g. 2G, 512M, 25%)
+        #[arg(long = "alert-mem", value_name = "SIZE|PCT", value_parser = top::parse_memory_threshold)]
+        alert_mem: Option<top::MemoryThreshold>,
+        /// Highlight processes using at least this much CPU, in percent
+        #[arg(long = "alert-cpu", value_name = "PCT", value_parser = top::parse_percent)]
+        alert_cpu: Option<f32>,
+        /// Ring the terminal bell when a process crosses an alert threshold
+        #[arg(long = "beep", requires = "alert")]
+        beep: bool,
+        /// Send a desktop notification when a process crosses an alert threshold
+        #[arg(long = "notify", requires = "alert")]
+        notify: bool,
+        /// Run a shell command when a process crosses an alert threshold
+        ///
+        /// The process is described by ZIRO_ALERT_PID, ZIRO_ALERT_NAME and ZIRO_ALERT_REASON.
+        #[arg(long = "on-alert", value_name = "CMD", requires = "alert")]
+        on_alert: Option<String>,
+        /// Follow one process: memory/CPU history, children, ports and open files
+        #[arg(
+            long = "pid",
+            value_name = "PID",
+            conflicts_with_all = ["tree", "group", "filter", "user", "zombies", "cgroup", "export", "record", "replay", "alert"]
+        )]
+        pid: Option<u32>,
+    },
+    /// List processes once, with filters (like `ps aux`)
+    Ps {
+        /// Only show processes whose name or command line matches this regex (case-insensitive)
+        #[arg(long = "name", value_name = "REGEX", value_parser = top::parse_filter)]
+        name: Option<Regex>,
+        /// Only show processes owned by this user (`me` for yourself)
+        #[arg(long = "user", value_name = "NAME")]
+        user: Option<String>,
+        /// Hide processes using less memory than this (e.g. 50MB)
+        #[arg(long = "min-mem", value_name = "SIZE", value_parser = top::parse_size)]
+        min_mem: Option<u64>,
+        /// Only show processes started at least this long ago (e.g. 30m, 2h, 1d)
+        #[arg(long = "older-than", value_name = "AGE", value_parser = fs_ops::parse_age)]
+        older_than: Option<Duration>,
+        /// Only show processes started less than this long ago (e.g. 10m)
+        #[arg(long = "newer-than", value_name = "AGE", value_parser = fs_ops::parse_age)]
+        newer_than: Option<Duration>,
+        /// Sort key
+        #[arg(long = "sort", value_enum, default_value_t = TopSort::Mem)]
+        sort: TopSort,
+        /// Reverse the sort direction
+        #[arg(short = 'r', long = "reverse")]
+        reverse: bool,
+        /// Show at most this many processes
+        #[arg(long = "limit")]
+        limit: Option<usize>,
+    },
+    /// Per-process network statistics: connections, listening sockets, ports and traffic
+    Net {
+        /// Only show processes whose name or command line matches this regex (case-insensitive)
+        #[arg(long = "name", value_name = "REGEX", value_parser = top::parse_filter)]
+        name: Option<Regex>,
+        /// Only show processes with a listening socket
+        #[arg(long = "listening")]
+        listening: bool,
+        /// Sort key
+        #[arg(long = "sort", value_enum, default_value_t = NetSort::Conns)]
+        sort: NetSort,
+        /// Reverse the sort direction
+        #[arg(short = 'r', long = "reverse")]
+        reverse: bool,
+        /// Show at most this many processes
+        #[arg(long = "limit")]
+        limit: Option<usize>,
+    },
+    /// List established connections with their remote peers
+    Conns {
+        /// Only show processes whose name or command line matches this regex (case-insensitive)
+        #[arg(long = "name", value_name = "REGEX", value_parser = top::parse_filter)]
+        name: Option<Regex>,
+        /// Only show connections to this remote address
+        #[arg(long = "remote", value_name = "IP", conflicts_with = "kill_remote")]
+        remote: Option<IpAddr>,
+        /// Look up the host names of remote addresses (reverse DNS)
+        #[arg(long = "resolve")]
+        resolve: bool,
+        /// Kill the processes connected to this remote address
+        #[arg(long = "kill-remote", value_name = "IP")]
+        kill_remote: Option<IpAddr>,
+        /// With --kill-remote, force kill without confirmation
+        #[arg(short = 'f', long = "force", requires = "kill_remote")]
+        force: bool,
+        /// Signal for confirmed kills [default: kill, or `kill.signal` in the config file]
+        #[arg(
+            long = "signal",
+            value_enum,
+            requires = "kill_remote",
+            conflicts_with = "force"
+        )]
+        signal: Option<SignalName>,
+    },
+    /// Share a directory over HTTP on the LAN, on a free port unless one is given
+    Serve {
+        /// Directory to serve
+        #[arg(default_value = ".")]
+        dir: PathBuf,
+        /// Port to listen on [default: the first free one from 8000]
+        #[arg(short = 'p', long = "port")]
+        port: Option<u16>,
+    },
+    /// Check that a port accepts connections, and optionally answers HTTP 2xx
+    Check {
+        /// Port to check
+        port: u16,
+        /// Host to connect to
+        #[arg(long = "host", default_value = "localhost")]
+        host: String,
+        /// Also GET this path and require a 2xx status (e.g. /healthz)
+        #[arg(long = "http", value_name = "PATH")]
+        http: Option<String>,
+        /// Attempts after the first before giving up
+        #[arg(long = "retries", default_value_t = 0)]
+        retries: u32,
+        /// Pause between attempts (e.g. 1s, 1m)
+        #[arg(long = "interval", value_name = "AGE", default_value = "1s", value_parser = fs_ops::parse_age)]
+        interval: Duration,
+        /// How long one attempt may take to connect, and to answer with --http
+        #[arg(long = "timeout", value_name = "AGE", default_value = "3s", value_parser = fs_ops::parse_age)]
+        timeout: Duration,
+    },
+    /// Open the web page served on a port in the default browser
+    Open {
+        /// Port the page is served on
+        port: u16,
+    },
+    /// Show everything known about one process
+    Info {
+        /// Process ID
+        pid: u32,
+        /// Also print the process's environment variables
+        #[arg(long = "env")]
+        env: bool,
+    },
+    /// Freeze processes without killing them (SIGSTOP); undo with `resume`
+    Pause {
+        /// Process IDs (multiple allowed)
+        #[arg(required = true)]
+        pids: Vec<u32>,
+    },
+    /// Let paused processes run again (SIGCONT)
+    Resume {
+        /// Process IDs (multiple allowed)
+        #[arg(required = true)]
+        pids: Vec<u32>,
+    },
+    /// Change the scheduling priority of processes (e.g. `ziro nice 1234 --to 10`)
+    Nice {
+        /// Process IDs (multiple allowed)
+        #[arg(required = true)]
+        pids: Vec<u32>,
+        /// Niceness from -20 (highest priority) to 19 (lowest); mapped to a priority class on Windows
+        #[arg(
+            long = "to",
+            value_name = "N",
+            allow_negative_numbers = true,
+            value_parser = clap::value_parser!(i32).range(-20..=19)
+        )]
+        to: i32,
+    },
+    /// Wait until processes exit (e.g. before starting a replacement server)
+    Waitpid {
+        /// Process IDs to wait for (multiple allowed)
+        #[arg(required_unless_present = "name")]
+        pids: Vec<u32>,
+        /// Also wait for every process whose name or command line matches this regex now
+        #[arg(long = "name", value_name = "REGEX", value_parser = top::parse_filter)]
+        name: Option<Regex>,
+        /// Give up and exit with an error after this long (e.g. 30s, 5m)
+        #[arg(long = "timeout", value_name = "AGE", value_parser = fs_ops::parse_age)]
+        timeout: Option<Duration>,
+        /// Seconds between status lines while waiting
+        #[arg(long = "interval", default_value_t = 5)]
+        interval: u64,
+        /// Send a desktop notification when the wait ends
+        #[arg(long = "notify")]
+        notify: bool,
+    },
+    /// Re-run a ziro subcommand or any other command on an interval (e.g. `ziro watch find 8080`)
+    Watch {
+        /// Seconds between runs
+        #[arg(short = 'n', long = "interval", default_value_t = 2.0)]
+        interval: f32,
+        /// Exit after this many runs
+        #[arg(long = "iterations")]
+        iterations: Option<u64>,
+        /// The command: a ziro subcommand with its arguments, or any program
+        #[arg(
+            required = true,
+            trailing_var_arg = true,
+            allow_hyphen_values = true,
+            value_name = "COMMAND"
+        )]
+        command: Vec<OsString>,
+    },
+    /// Kill processes whose name or command line matches a regex, after a preview
+    #[command(group = ArgGroup::new("pick").args(["oldest", "newest", "all"]))]
+    Pkill {
+        /// Regex matched against process names and command lines (case-insensitive)
+        #[arg(value_parser = top::parse_filter)]
+        pattern: Regex,
+        /// Only kill the longest-running match
+        #[arg(long = "oldest")]
+        oldest: bool,
+        /// Only kill the most recently started match
+        #[arg(long = "newest")]
+        newest: bool,
+        /// Kill every match without picking them one by one
+        #[arg(long = "all")]
+        all: bool,
+        /// Only match processes owned by this user (`me` for yourself)
+        #[arg(long = "user", value_name = "NAME")]
+        user: Option<String>,
+        /// Only match processes started at least this long ago (e.g. 30m, 2h, 1d)
+        #[arg(long = "older-than", value_name = "AGE", value_parser = fs_ops::parse_age)]
+        older_than: Option<Duration>,
+        /// Only match processes started less than this long ago (e.g. 10m)
+        #[arg(long = "newer-than", value_name = "AGE", value_parser = fs_ops::parse_age)]
+        newer_than: Option<Duration>,
+        /// Force kill without confirmation
+        #[arg(short = 'f', long = "force")]
+        force: bool,
+        /// Signal for confirmed kills [default: kill, or `kill.signal` in the config file]
+        #[arg(long = "signal", value_enum, conflicts_with = "force")]
+        signal: Option<SignalName>,
+    },
+    /// List zombie and orphaned processes, and get the zombies reaped
+    Reap {
+        /// Only list orphans that have been running this long (e.g. 30s, 5m)
+        #[arg(long = "min-age", value_name = "AGE", default_value = "1m", value_parser = fs_ops::parse_age)]
+        min_age: Duration,
+        /// Send SIGCHLD to the parents of zombies so they collect them
+        #[arg(long = "nudge")]
+        nudge: bool,
+        /// Terminate the parents of zombies so init adopts and reaps them
+        #[arg(long = "kill-parents")]
+        kill_parents: bool,
+        /// Terminate parents without confirmation
+        #[arg(short = 'f', long = "force")]
+        force: bool,
     },
+    /// Show the process hierarchy as a tree, with memory and CPU per process
+    Pstree {
+        /// Only show this process and its descendants
+        #[arg(long = "pid", value_name = "PID")]
+        pid: Option<u32>,
+    },
+    /// Print a shell completion script (e.g. `ziro completions bash > ~/.local/share/bash-completion/completions/ziro`)
+    Completions { shell: clap_complete::Shell },
+    /// Occupied port numbers, one per line, for completion scripts
+    #[command(name = crate::cli::completions::COMPLETE_PORTS, hide = true)]
+    CompletePorts,
+    /// Show or change the defaults in the config file (flags always override them)
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Report what ziro detected: terminal, tools, privileges and platform backends
+    Doctor,
+    /// Replace this binary with the latest GitHub release, after checking its SHA-256
+    #[cfg(feature = "self-update")]
+    SelfUpdate {
+        /// Only report whether a newer version exists
+        #[arg(long = "check")]
+        check: bool,
+    },
+    /// Save the process table to a file or compare two of them
+    Snapshot {
+        #[command(subcommand)]
+        action: SnapshotAction,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ConfigAction {
+    /// Print every key with its value
+    List,
+    /// Print one value (e.g. `ziro config get top.interval`)
+    Get { key: String },
+    /// Change one value; lists are comma-separated (e.g. `ziro config set top.columns cpu,net`)
+    Set { key: String, value: String },
+    /// Go back to the built-in default of a key
+    Unset { key: String },
+    /// Print where the config file lives
+    Path,
+}
+
+#[derive(Subcommand)]
+pub enum SnapshotAction {
+    /// Capture the current process table
+    Save {
+        /// File to write the snapshot to
+        file: PathBuf,
+    },
+    /// Report started and exited processes and memory changes between two snapshots
+    Diff {
+        /// Older snapshot
+        before: PathBuf,
+        /// Newer snapshot (default: the current process table)
+        after: Option<PathBuf>,
+        /// Hide memory changes smaller than this (e.g. 10M)
+        #[arg(long = "min-delta", value_name = "SIZE", value_parser = top::parse_size)]
+        min_delta: Option<u64>,
+        /// Number of processes to list per section
+        #[arg(long = "limit", default_value_t = 20)]
+        limit: usize,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_ports() {
+        let groups = BTreeMap::from([
+            ("dev".to_string(), vec![3000, 5173, 8080]),
+            ("db".to_string(), vec![5432, 8080]),
+        ]);
+        let args = ["8080", "@dev", "@db"]
+            .map(|arg| parse_port_arg(arg).unwrap())
+            .to_vec();
+        assert_eq!(
+            resolve_ports(args, &groups).unwrap(),
+            [8080, 3000, 5173, 5432]
+        );
+
+        let err = resolve_ports(vec![PortArg::Group("web".to_string())], &groups).unwrap_err();
+        assert_eq!(err.to_string(), "Unknown port group @web (known: db, dev)");
+        assert!(parse_port_arg("@").is_err() && parse_port_arg("70000").is_err());
+    }
+
+    #[test]
+    fn test_parse_port_range() {
+        assert_eq!(parse_port_range("22"), Ok(22..=22));
+        assert_eq!(parse_port_range("5432-5439"), Ok(5432..=5439));
+        assert!(parse_port_range("9000-8000").is_err());
+        assert!(parse_port_range("1-70000").is_err());
+    }
+
+    #[test]
+    fn test_kill_by_name() {
+        let parses =
+            |args: &[&str]| Cli::try_parse_from(["ziro", "kill"].iter().chain(args)).is_ok();
+        assert!(parses(&["--name", "vite", "--older-than", "2h"]));
+        // Ages only narrow a name match
+        assert!(!parses(&["8080", "--older-than", "2h"]));
+        assert!(!parses(&["8080", "--name", "vite"]));
+    }
 }
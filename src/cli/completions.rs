@@ -0,0 +1,95 @@
+/// Shell completion scripts, with occupied ports offered for `kill` and `find`
+///
+/// clap generates the static part. Where the shell can run a command while completing
+/// (bash, zsh, fish), the script is extended to ask `ziro __complete-ports`, which prints
+/// `PORT<TAB>NAME (PID)` lines.
+use crate::cli::Cli;
+use anyhow::Result;
+use clap::CommandFactory;
+use clap_complete::Shell;
+use regex::Regex;
+
+/// Name of the hidden subcommand listing occupied ports
+pub const COMPLETE_PORTS: &str = "__complete-ports";
+
+/// The completion script for `shell`
+pub fn completion_script(shell: Shell) -> Result<String> {
+    let mut script = Vec::new();
+    clap_complete::generate(shell, &mut Cli::command(), "ziro", &mut script);
+    let script = String::from_utf8(script)?;
+    Ok(match shell {
+        Shell::Bash => bash_with_ports(script),
+        Shell::Zsh => zsh_with_ports(&script),
+        Shell::Fish => script + FISH_PORTS,
+        _ => script,
+    })
+}
+
+/// Complete the port arguments before handing anything else to the generated `_ziro`
+const BASH_PORTS: &str = r#"_ziro_with_ports() {
+    local cur="${COMP_WORDS[COMP_CWORD]}" prev="${COMP_WORDS[COMP_CWORD-1]}" sub="" word
+    for word in "${COMP_WORDS[@]:1:COMP_CWORD-1}"; do
+        if [[ ${word} != -* ]]; then
+            sub="${word}"
+            break
+        fi
+    done
+    if [[ ( ${sub} == kill || ${sub} == find ) && ${cur} != -* && ${prev} != --* ]]; then
+        COMPREPLY=( $(compgen -W "$(ziro __complete-ports 2>/dev/null | cut -f1)" -- "${cur}") )
+        return 0
+    fi
+    _ziro "$@"
+}
+
+"#;
+
+fn bash_with_ports(script: String) -> String {
+    let script = script.replace("complete -F _ziro ", "complete -F _ziro_with_ports ");
+    match script.rfind("\nif [[ \"${BASH_VERSINFO[0]}\"") {
+        Some(at) => format!("{}\n{BASH_PORTS}{}", &script[..at], &script[at + 1..]),
+        None => script,
+    }
+}
+
+const ZSH_PORTS: &str = r#"(( $+functions[_ziro_ports] )) ||
+_ziro_ports() {
+    local -a ports
+    ports=(${${(f)"$(ziro __complete-ports 2>/dev/null)"}/$'\t'/:})
+    _describe -t ports 'occupied port' ports
+}
+
+"#;
+
+fn zsh_with_ports(script: &str) -> String {
+    // The generated specs complete ports with `_default` (file names)
+    let spec = Regex::new(r"('\*::ports -- Port numbers[^':]* to (?:find|kill)[^':]*):_default'")
+        .expect("valid regex");
+    let script = spec.replace_all(script, "$1:_ziro_ports'").into_owned();
+    match script.rfind("\nif [ \"$funcstack[1]\" = \"_ziro\" ]") {
+        Some(at) => format!("{}\n{ZSH_PORTS}{}", &script[..at], &script[at + 1..]),
+        None => script,
+    }
+}
+
+const FISH_PORTS: &str = r#"complete -c ziro -n "__fish_seen_subcommand_from kill find" -f -a "(ziro __complete-ports 2>/dev/null)"
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_completion_script_offers_ports() {
+        let bash = completion_script(Shell::Bash).unwrap();
+        assert!(bash.contains("_ziro_with_ports() {"));
+        assert!(bash.contains("complete -F _ziro_with_ports -o nosort"));
+
+        let zsh = completion_script(Shell::Zsh).unwrap();
+        assert!(zsh.contains("@groups to kill (multiple allowed):_ziro_ports'"));
+        assert!(zsh.contains("@groups to find (multiple allowed):_ziro_ports'"));
+        assert!(zsh.find("_ziro_ports() {") < zsh.rfind("if [ \"$funcstack[1]\""));
+
+        let fish = completion_script(Shell::Fish).unwrap();
+        assert!(fish.contains(COMPLETE_PORTS));
+    }
+}
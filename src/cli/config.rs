@@ -0,0 +1,573 @@
+/// Defaults from `config.toml`; command-line flags always win over them
+use crate::cli::{OutputFormat, SignalName};
+use crate::platform::dirs;
+use crate::ui::Preselect;
+use crate::ui::theme::{self, BUILTIN_THEMES, Palette};
+use anyhow::{Context, Result, anyhow, bail};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use toml_edit::{Array, DocumentMut, Item, Table, Value};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub theme: ThemeName,
+    pub output: Option<OutputFormat>,
+    pub top: TopConfig,
+    pub kill: KillConfig,
+    pub remove: RemoveConfig,
+    /// Named port lists, used as `@name` in place of port numbers
+    pub groups: BTreeMap<String, Vec<u16>>,
+    /// User palettes, selected by name like the built-in themes
+    pub themes: BTreeMap<String, ThemeConfig>,
+}
+
+/// Look of the output; `--plain`, `--ascii` and `--no-color` add to it
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(from = "String")]
+pub enum ThemeName {
+    /// Colors and icons as far as the terminal supports them (also `default`)
+    #[default]
+    Auto,
+    Plain,
+    Ascii,
+    NoColor,
+    Solarized,
+    Monochrome,
+    HighContrast,
+    /// A palette from `[themes.NAME]`
+    Custom(String),
+}
+
+impl From<String> for ThemeName {
+    fn from(name: String) -> Self {
+        match name.as_str() {
+            "auto" | "default" => Self::Auto,
+            "plain" => Self::Plain,
+            "ascii" => Self::Ascii,
+            "no-color" => Self::NoColor,
+            "solarized" => Self::Solarized,
+            "monochrome" => Self::Monochrome,
+            "high-contrast" => Self::HighContrast,
+            _ => Self::Custom(name),
+        }
+    }
+}
+
+impl ThemeName {
+    /// `ZIRO_THEME`, when set
+    pub fn from_env() -> Option<Self> {
+        std::env::var("ZIRO_THEME")
+            .ok()
+            .filter(|name| !name.trim().is_empty())
+            .map(|name| Self::from(name.trim().to_string()))
+    }
+
+    /// Name of the built-in palette; the display modes keep the default colors
+    fn builtin_palette(&self) -> Option<&'static str> {
+        match self {
+            Self::Auto | Self::Plain | Self::Ascii | Self::NoColor => Some("default"),
+            Self::Solarized => Some("solarized"),
+            Self::Monochrome => Some("monochrome"),
+            Self::HighContrast => Some("high-contrast"),
+            Self::Custom(_) => None,
+        }
+    }
+}
+
+/// `[themes.NAME]`: a built-in palette to start from, and colors by role
+#[derive(Debug, Default, Deserialize)]
+pub struct ThemeConfig {
+    /// One of the built-in palettes [default: default]
+    pub base: Option<String>,
+    /// Role → color, e.g. `error = "#dc322f"`
+    #[serde(flatten)]
+    pub colors: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TopConfig {
+    /// Refresh interval in seconds
+    pub interval: Option<f32>,
+    /// Optional columns shown without their flags
+    pub columns: Vec<TopColumn>,
+}
+
+impl TopConfig {
+    pub fn shows(&self, column: TopColumn) -> bool {
+        self.columns.contains(&column)
+    }
+}
+
+/// Optional `top` columns, named like their flags
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TopColumn {
+    Cpu,
+    Cmd,
+    Io,
+    Delta,
+    Threads,
+    Fds,
+    Containers,
+    Gpu,
+    Net,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KillConfig {
+    /// Signal for confirmed (not `--force`) kills
+    pub signal: Option<SignalName>,
+    /// Which processes the `kill` picker starts with ticked
+    pub preselect: Option<Preselect>,
+    /// Ports `kill` refuses without `--allow-protected` [default: `DEFAULT_PROTECTED_PORTS`]
+    pub protected: Option<Vec<u16>>,
+}
+
+/// Ports `kill` refuses unless `kill.protected` says otherwise: SSH and Remote Desktop,
+/// which would cut off the session running ziro
+pub const DEFAULT_PROTECTED_PORTS: &[u16] = &[22, 3389];
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RemoveConfig {
+    /// Move to the trash unless `--no-trash` or `--backup` is given
+    pub trash: bool,
+    /// Paths `remove` refuses to delete, along with everything in and above them
+    pub protected: Vec<PathBuf>,
+    /// Retries for locked entries [default: `fs_ops::DEFAULT_RETRIES`]
+    pub retries: Option<u32>,
+    /// Initial delay between those retries in milliseconds
+    /// [default: `fs_ops::DEFAULT_RETRY_DELAY_MS`]
+    pub retry_delay: Option<u64>,
+}
+
+/// Type of a config value, for parsing `ziro config set`
+#[derive(Clone, Copy)]
+enum Kind {
+    Text,
+    Number,
+    /// Whole number, e.g. a count
+    Integer,
+    Bool,
+    /// Comma-separated on the command line
+    List,
+    /// Comma-separated port numbers
+    Ports,
+}
+
+struct Key {
+    name: &'static str,
+    kind: Kind,
+    default: &'static str,
+}
+
+/// Every key `ziro config` knows, in `list` order
+const KEYS: &[Key] = &[
+    Key {
+        name: "theme",
+        kind: Kind::Text,
+        default: "\"auto\"",
+    },
+    Key {
+        name: "output",
+        kind: Kind::Text,
+        default: "\"text\"",
+    },
+    Key {
+        name: "top.interval",
+        kind: Kind::Number,
+        default: "1.0",
+    },
+    Key {
+        name: "top.columns",
+        kind: Kind::List,
+        default: "[]",
+    },
+    Key {
+        name: "kill.signal",
+        kind: Kind::Text,
+        default: "\"kill\"",
+    },
+    Key {
+        name: "kill.preselect",
+        kind: Kind::Text,
+        default: "\"none\"",
+    },
+    Key {
+        name: "kill.protected",
+        kind: Kind::Ports,
+        default: "[22, 3389]",
+    },
+    Key {
+        name: "remove.trash",
+        kind: Kind::Bool,
+        default: "false",
+    },
+    Key {
+        name: "remove.protected",
+        kind: Kind::List,
+        default: "[]",
+    },
+    Key {
+        name: "remove.retries",
+        kind: Kind::Integer,
+        default: "5",
+    },
+    Key {
+        name: "remove.retry_delay",
+        kind: Kind::Integer,
+        default: "100",
+    },
+];
+
+/// `groups.NAME`: any name, one per port group
+const GROUP_KEY: Key = Key {
+    name: "groups.<name>",
+    kind: Kind::Ports,
+    default: "[]",
+};
+
+/// `ZIRO_CONFIG`, or `config.toml` in the platform config directory
+pub fn config_path() -> Option<PathBuf> {
+    std::env::var_os("ZIRO_CONFIG")
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| dirs::config_dir().map(|dir| dir.join("config.toml")))
+}
+
+impl Config {
+    /// Read the config file; a missing file gives the defaults
+    pub fn load() -> Result<Self> {
+        let Some(path) = config_path().filter(|path| path.exists()) else {
+            return Ok(Self::default());
+        };
+        let text = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+        let mut config = Self::parse(&text).map_err(|e| {
+            let line = e
+                .span()
+                .map(|span| text[..span.start].matches('\n').count() + 1);
+            anyhow!(
+                "Invalid config file {} (line {}): {}",
+                path.display(),
+                line.unwrap_or(1),
+                e.message()
+            )
+        })?;
+        for path in &mut config.remove.protected {
+            *path = expand_home(path);
+        }
+        Ok(config)
+    }
+
+    fn parse(text: &str) -> Result<Self, toml::de::Error> {
+        let config: Self = toml::from_str(text)?;
+        // Check every palette, not just the selected one, so mistakes show up right away
+        for name in config.themes.keys() {
+            config
+                .palette(&ThemeName::Custom(name.clone()))
+                .map_err(|e| {
+                    <toml::de::Error as serde::de::Error>::custom(format!("theme '{name}': {e}"))
+                })?;
+        }
+        if let ThemeName::Custom(name) = &config.theme
+            && !config.themes.contains_key(name)
+        {
+            return Err(serde::de::Error::custom(unknown_theme(
+                name,
+                &config.themes,
+            )));
+        }
+        Ok(config)
+    }
+
+    /// Colors of the theme `name`, built in or from `[themes]`
+    pub fn palette(&self, name: &ThemeName) -> Result<Palette> {
+        if let Some(builtin) = name.builtin_palette() {
+            return Ok(Palette::builtin(builtin).expect("built-in theme"));
+        }
+        let ThemeName::Custom(name) = name else {
+            unreachable!("every other theme is built in");
+        };
+        let custom = self
+            .themes
+            .get(name)
+            .ok_or_else(|| anyhow!(unknown_theme(name, &self.themes)))?;
+        let base = custom.base.as_deref().unwrap_or("default");
+        let mut palette = Palette::builtin(base).ok_or_else(|| {
+            anyhow!(
+                "unknown base '{base}' (built in: {})",
+                BUILTIN_THEMES.join(", ")
+            )
+        })?;
+        for (role, color) in &custom.colors {
+            palette
+                .set(role, theme::parse_color(color).map_err(|e| anyhow!(e))?)
+                .map_err(|e| anyhow!(e))?;
+        }
+        Ok(palette)
+    }
+}
+
+fn unknown_theme(name: &str, themes: &BTreeMap<String, ThemeConfig>) -> String {
+    let mut known: Vec<&str> = ["auto", "plain", "ascii", "no-color"].to_vec();
+    known.extend(&BUILTIN_THEMES[1..]);
+    known.extend(themes.keys().map(String::as_str));
+    format!("unknown theme '{name}' (known: {})", known.join(", "))
+}
+
+/// `~/x` → `$HOME/x`
+fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// The config file as written, for `ziro config get/set/unset/list`
+pub struct ConfigFile {
+    path: PathBuf,
+    doc: DocumentMut,
+}
+
+impl ConfigFile {
+    pub fn open() -> Result<Self> {
+        let path = config_path().ok_or_else(|| anyhow!("Cannot locate the config directory"))?;
+        let doc = match std::fs::read_to_string(&path) {
+            Ok(text) => text
+                .parse()
+                .with_context(|| format!("Invalid config file: {}", path.display()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => DocumentMut::new(),
+            Err(e) => {
+                return Err(anyhow::Error::new(e)
+                    .context(format!("Failed to read config file: {}", path.display())));
+            }
+        };
+        Ok(Self { path, doc })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The value set in the file, as TOML
+    pub fn get(&self, key: &str) -> Result<Option<String>> {
+        find_key(key)?;
+        let (table, field) = split_key(key);
+        let item = match table {
+            Some(table) => self.doc.get(table).and_then(|t| t.get(field)),
+            None => self.doc.get(field),
+        };
+        Ok(item
+            .and_then(Item::as_value)
+            .map(|value| value.clone().decorated("", "").to_string()))
+    }
+
+    /// `(key, value, is_default)` for every known key, then every port group
+    pub fn entries(&self) -> Vec<(String, String, bool)> {
+        let mut entries: Vec<_> = KEYS
+            .iter()
+            .map(|key| match self.get(key.name).ok().flatten() {
+                Some(value) => (key.name.to_string(), value, false),
+                None => (key.name.to_string(), key.default.to_string(), true),
+            })
+            .collect();
+        if let Some(groups) = self.doc.get("groups").and_then(Item::as_table_like) {
+            for (name, item) in groups.iter() {
+                if let Some(value) = item.as_value() {
+                    let value = value.clone().decorated("", "").to_string();
+                    entries.push((format!("groups.{name}"), value, false));
+                }
+            }
+        }
+        entries
+    }
+
+    pub fn default_of(key: &str) -> Result<&'static str> {
+        Ok(find_key(key)?.default)
+    }
+
+    /// Set `key` from its command-line spelling; checked against the schema before it is kept
+    pub fn set(&mut self, key: &str, raw: &str) -> Result<()> {
+        let value = parse_value(find_key(key)?.kind, raw)?;
+        let mut doc = self.doc.clone();
+        match split_key(key) {
+            (Some(table), field) => {
+                // A `[table]` section rather than the inline table indexing would create
+                if !doc.contains_key(table) {
+                    doc.insert(table, Item::Table(Table::new()));
+                }
+                doc[table][field] = Item::Value(value);
+            }
+            (None, field) => doc[field] = Item::Value(value),
+        }
+        Config::parse(&doc.to_string())
+            .map_err(|e| anyhow!("Invalid value for {key}: {}", e.message()))?;
+        self.doc = doc;
+        Ok(())
+    }
+
+    /// Remove `key`; returns whether it was set
+    pub fn unset(&mut self, key: &str) -> Result<bool> {
+        find_key(key)?;
+        let removed = match split_key(key) {
+            (Some(table), field) => self
+                .doc
+                .get_mut(table)
+                .and_then(Item::as_table_like_mut)
+                .and_then(|t| t.remove(field)),
+            (None, field) => self.doc.remove(field),
+        };
+        Ok(removed.is_some())
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        std::fs::write(&self.path, self.doc.to_string())
+            .with_context(|| format!("Failed to write config file: {}", self.path.display()))
+    }
+}
+
+fn find_key(name: &str) -> Result<&'static Key> {
+    if let Some(group) = name.strip_prefix("groups.") {
+        if group.is_empty() || group.contains(['.', '@']) {
+            bail!("Invalid port group name '{group}'");
+        }
+        return Ok(&GROUP_KEY);
+    }
+    KEYS.iter().find(|key| key.name == name).ok_or_else(|| {
+        let known: Vec<&str> = KEYS
+            .iter()
+            .chain([&GROUP_KEY])
+            .map(|key| key.name)
+            .collect();
+        anyhow!("Unknown config key '{name}' (known: {})", known.join(", "))
+    })
+}
+
+fn split_key(key: &str) -> (Option<&str>, &str) {
+    match key.split_once('.') {
+        Some((table, field)) => (Some(table), field),
+        None => (None, key),
+    }
+}
+
+fn parse_value(kind: Kind, raw: &str) -> Result<Value> {
+    let raw = raw.trim();
+    Ok(match kind {
+        Kind::Text => raw.into(),
+        Kind::Number => raw
+            .parse::<f64>()
+            .map_err(|_| anyhow!("Expected a number, got '{raw}'"))?
+            .into(),
+        Kind::Integer => raw
+            .parse::<i64>()
+            .map_err(|_| anyhow!("Expected a whole number, got '{raw}'"))?
+            .into(),
+        Kind::Bool => match raw {
+            "true" | "yes" | "on" | "1" => true.into(),
+            "false" | "no" | "off" | "0" => false.into(),
+            _ => bail!("Expected true or false, got '{raw}'"),
+        },
+        Kind::List => raw
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .collect::<Array>()
+            .into(),
+        Kind::Ports => raw
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(|item| {
+                item.parse::<u16>()
+                    .map(i64::from)
+                    .map_err(|_| anyhow!("Expected port numbers, got '{item}'"))
+            })
+            .collect::<Result<Array>>()?
+            .into(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        let config = Config::parse(
+            "theme = \"plain\"\n[top]\ninterval = 2.5\ncolumns = [\"cpu\", \"net\"]\n\
+             [kill]\nsignal = \"term\"\npreselect = \"all\"\nprotected = [22, 5432]\n[remove]\ntrash = true\nretries = 2\n",
+        )
+        .unwrap();
+        assert_eq!(config.theme, ThemeName::Plain);
+        assert_eq!(config.top.interval, Some(2.5));
+        assert!(config.top.shows(TopColumn::Net) && !config.top.shows(TopColumn::Io));
+        assert_eq!(config.kill.signal, Some(SignalName::Term));
+        assert_eq!(config.kill.preselect, Some(Preselect::All));
+        assert_eq!(config.kill.protected, Some(vec![22, 5432]));
+        assert!(config.remove.trash);
+        assert_eq!(config.remove.retries, Some(2));
+        assert_eq!(config.remove.retry_delay, None);
+
+        let config = Config::parse("[groups]\ndev = [3000, 5173]\n").unwrap();
+        assert_eq!(config.groups["dev"], [3000, 5173]);
+        assert!(Config::parse("[groups]\ndev = [70000]\n").is_err());
+
+        assert!(Config::parse("[top]\nintervall = 2\n").is_err());
+        assert!(Config::parse("theme = \"neon\"\n").is_err());
+
+        let config = Config::parse(
+            "theme = \"neon\"\n[themes.neon]\nbase = \"high-contrast\"\nmuted = \"#808080\"\n",
+        )
+        .unwrap();
+        let palette = config.palette(&config.theme).unwrap();
+        assert_eq!(
+            palette.error,
+            Palette::builtin("high-contrast").unwrap().error
+        );
+        assert!(palette.muted.is_some() && palette.muted != Palette::default().muted);
+        assert!(Config::parse("[themes.neon]\nglow = \"red\"\n").is_err());
+        assert!(Config::parse("[themes.neon]\nerror = \"sparkly\"\n").is_err());
+    }
+
+    #[test]
+    fn test_set_and_unset() {
+        let mut file = ConfigFile {
+            path: PathBuf::new(),
+            doc: "# mine\ntheme = \"ascii\"\n".parse().unwrap(),
+        };
+        file.set("top.columns", "cpu, io").unwrap();
+        file.set("remove.trash", "yes").unwrap();
+        file.set("remove.retries", "3").unwrap();
+        assert_eq!(file.get("remove.retries").unwrap().as_deref(), Some("3"));
+        assert!(file.set("remove.retry_delay", "-1").is_err());
+        assert_eq!(
+            file.get("top.columns").unwrap().as_deref(),
+            Some("[\"cpu\", \"io\"]")
+        );
+        assert!(file.doc.to_string().starts_with("# mine\n"));
+
+        assert!(file.set("top.columns", "cpu,sparkles").is_err());
+        assert!(file.set("top.interval", "fast").is_err());
+        assert!(file.set("nope", "1").is_err());
+
+        file.set("groups.dev", "3000, 8080").unwrap();
+        assert_eq!(
+            file.entries().last().unwrap(),
+            &("groups.dev".to_string(), "[3000, 8080]".to_string(), false)
+        );
+        assert!(file.set("groups.dev", "web").is_err());
+
+        assert!(file.unset("theme").unwrap());
+        assert!(!file.unset("theme").unwrap());
+        assert_eq!(file.get("theme").unwrap(), None);
+    }
+}
@@ -0,0 +1,247 @@
+/// What ziro detected about this system, for `ziro doctor`
+use crate::cli::config::{self, Config};
+use crate::platform::term::{self, TerminalCapabilities, TerminalProfile};
+use crate::platform::{command, elevation};
+use crate::ui::Theme;
+use crate::ui::i18n::{self, Lang};
+use crate::ui::prompt::{self, InputMode};
+use serde::Serialize;
+use std::io::IsTerminal;
+use std::path::PathBuf;
+
+#[derive(Debug, Serialize)]
+pub struct Diagnostics {
+    pub version: &'static str,
+    pub os: &'static str,
+    pub arch: &'static str,
+    /// Running as root / elevated administrator
+    pub elevated: bool,
+    pub terminal: TerminalReport,
+    pub tools: Vec<Tool>,
+    pub backends: Vec<Backend>,
+    pub config: ConfigReport,
+    pub language: &'static str,
+    pub input: &'static str,
+    pub command_timeout_secs: f32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TerminalReport {
+    pub term: Option<String>,
+    pub term_program: Option<String>,
+    pub stdout_is_terminal: bool,
+    pub capabilities: TerminalCapabilities,
+    /// What flags, environment, config and the capabilities settled on
+    pub profile: TerminalProfile,
+    pub colors: bool,
+}
+
+/// An external program ziro may run
+#[derive(Debug, Serialize)]
+pub struct Tool {
+    pub name: &'static str,
+    pub path: Option<PathBuf>,
+    /// Some feature does not work without it
+    pub required: bool,
+    pub purpose: &'static str,
+}
+
+/// How one kind of lookup or action is done on this platform
+#[derive(Debug, Serialize)]
+pub struct Backend {
+    pub task: &'static str,
+    pub backend: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConfigReport {
+    pub path: Option<PathBuf>,
+    pub exists: bool,
+    /// Why the file cannot be used
+    pub error: Option<String>,
+}
+
+pub fn collect_diagnostics() -> Diagnostics {
+    let config_path = config::config_path();
+    Diagnostics {
+        version: env!("CARGO_PKG_VERSION"),
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        elevated: elevation::is_elevated(),
+        terminal: TerminalReport {
+            term: std::env::var("TERM").ok(),
+            term_program: std::env::var("TERM_PROGRAM").ok(),
+            stdout_is_terminal: std::io::stdout().is_terminal(),
+            capabilities: term::detect_capabilities(),
+            profile: term::global_profile(),
+            colors: Theme::new().uses_color(),
+        },
+        tools: TOOLS
+            .iter()
+            .map(|&(name, required, purpose)| Tool {
+                name,
+                path: command::find_program(name),
+                required,
+                purpose,
+            })
+            .collect(),
+        backends: BACKENDS
+            .iter()
+            .map(|&(task, backend)| Backend { task, backend })
+            .collect(),
+        config: ConfigReport {
+            exists: config_path.as_ref().is_some_and(|path| path.exists()),
+            path: config_path,
+            error: Config::load().err().map(|e| format!("{e:#}")),
+        },
+        language: match i18n::lang() {
+            Lang::En => "en",
+            Lang::Zh => "zh",
+        },
+        input: match prompt::input_mode() {
+            InputMode::Interactive => "interactive",
+            InputMode::AssumeYes => "assume yes",
+            InputMode::NoInput => "no input",
+        },
+        command_timeout_secs: command::command_timeout().as_secs_f32(),
+    }
+}
+
+/// `(name, required, purpose)`
+#[cfg(target_os = "linux")]
+const TOOLS: &[(&str, bool, &str)] = &[
+    (
+        "lsof",
+        false,
+        "file locks when /proc is unreadable; open files in `info`",
+    ),
+    ("sudo", false, "--elevate"),
+];
+
+#[cfg(all(
+    unix,
+    not(any(
+        target_os = "linux",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "dragonfly",
+        target_os = "openbsd"
+    ))
+))]
+const TOOLS: &[(&str, bool, &str)] = &[
+    ("lsof", true, "ports, file locks and open files"),
+    ("nettop", false, "traffic rates in `net`"),
+    ("sudo", false, "--elevate"),
+];
+
+#[cfg(any(target_os = "freebsd", target_os = "netbsd", target_os = "dragonfly"))]
+const TOOLS: &[(&str, bool, &str)] = &[
+    ("sockstat", true, "ports"),
+    ("fstat", false, "file locks when lsof is missing"),
+    ("lsof", false, "file locks; open files in `info`"),
+    ("sudo", false, "--elevate"),
+];
+
+#[cfg(target_os = "openbsd")]
+const TOOLS: &[(&str, bool, &str)] = &[
+    ("fstat", true, "ports; file locks when lsof is missing"),
+    ("lsof", false, "file locks; open files in `info`"),
+    ("sudo", false, "--elevate"),
+];
+
+#[cfg(windows)]
+const TOOLS: &[(&str, bool, &str)] = &[
+    ("netstat", true, "ports"),
+    (
+        "powershell",
+        false,
+        "file locks when Restart Manager fails; --elevate",
+    ),
+    (
+        "handle",
+        false,
+        "Sysinternals handle.exe, for lock holders ziro cannot see",
+    ),
+];
+
+/// `(task, backend)`
+#[cfg(target_os = "linux")]
+const BACKENDS: &[(&str, &str)] = &[
+    ("ports", "/proc/net/{tcp,tcp6,udp,udp6}"),
+    ("file locks", "/proc/*/fd, lsof as fallback"),
+    ("signals", "kill(2)"),
+    ("processes", "sysinfo (/proc)"),
+    ("trash", "freedesktop trash"),
+    ("elevation", "sudo"),
+];
+
+#[cfg(target_os = "macos")]
+const BACKENDS: &[(&str, &str)] = &[
+    ("ports", "lsof -i"),
+    ("traffic", "nettop"),
+    ("file locks", "lsof"),
+    ("signals", "kill(2)"),
+    ("processes", "sysinfo (libproc)"),
+    ("trash", "Finder trash"),
+    ("elevation", "sudo"),
+];
+
+#[cfg(target_os = "freebsd")]
+const BACKENDS: &[(&str, &str)] = &[
+    ("ports", "sockstat -4 -6"),
+    ("file locks", "lsof, fstat as fallback"),
+    ("signals", "kill(2)"),
+    ("processes", "sysinfo (sysctl)"),
+    ("trash", "freedesktop trash"),
+    ("elevation", "sudo"),
+];
+
+#[cfg(any(target_os = "netbsd", target_os = "dragonfly"))]
+const BACKENDS: &[(&str, &str)] = &[
+    ("ports", "sockstat -4 -6"),
+    ("file locks", "lsof, fstat as fallback"),
+    ("signals", "kill(2)"),
+    ("processes", "ps for port holders"),
+    ("trash", "freedesktop trash"),
+    ("elevation", "sudo"),
+];
+
+#[cfg(target_os = "openbsd")]
+const BACKENDS: &[(&str, &str)] = &[
+    ("ports", "fstat"),
+    ("file locks", "lsof, fstat as fallback"),
+    ("signals", "kill(2)"),
+    ("processes", "ps for port holders"),
+    ("trash", "freedesktop trash"),
+    ("elevation", "sudo"),
+];
+
+#[cfg(all(
+    unix,
+    not(any(
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "dragonfly",
+        target_os = "openbsd"
+    ))
+))]
+const BACKENDS: &[(&str, &str)] = &[
+    ("ports", "not supported"),
+    ("file locks", "lsof"),
+    ("signals", "kill(2)"),
+    ("processes", "sysinfo"),
+    ("trash", "freedesktop trash"),
+    ("elevation", "sudo"),
+];
+
+#[cfg(windows)]
+const BACKENDS: &[(&str, &str)] = &[
+    ("ports", "netstat -ano"),
+    ("file locks", "Restart Manager, PowerShell as fallback"),
+    ("signals", "TerminateProcess"),
+    ("processes", "sysinfo (Win32)"),
+    ("trash", "Recycle Bin"),
+    ("elevation", "UAC (PowerShell Start-Process -Verb RunAs)"),
+];
@@ -1,8 +1,99 @@
-use crate::core::{fs_ops, port, process, top};
+use crate::cli::completions;
+use crate::cli::config::{self, ConfigFile};
+use crate::cli::doctor;
+use crate::cli::{
+    Cli, ConfigAction, ExportFormat, NetSort, OutputFormat, SignalName, SnapshotAction,
+    SymlinkMode, TopSort,
+};
+use crate::core::port::{check, conns, net};
+use crate::core::snapshot::Snapshot;
+use crate::core::{fs_ops, process, serve, top};
+use crate::error::ZiroError;
+use crate::platform::{self, clipboard, elevation, notify, temp, term, wsl};
+use crate::ports;
+use crate::tr;
 use crate::ui;
 use crate::ui::Theme;
-use anyhow::{Result, bail};
+use crate::ui::i18n;
+use anyhow::{Context, Result, bail};
+use clap::{CommandFactory, ValueEnum};
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ffi::OsString;
+use std::io::{IsTerminal, Read};
+use std::ops::RangeInclusive;
 use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Options for the top command
+pub struct TopArgs {
+    pub interval: f32,
+    pub limit: usize,
+    pub cpu: bool,
+    pub cmd: bool,
+    pub io: bool,
+    pub delta: bool,
+    pub threads: bool,
+    pub fds: bool,
+    pub containers: bool,
+    pub gpu: bool,
+    pub net: bool,
+    pub cgroup_limit: bool,
+    pub once: bool,
+    pub batch: bool,
+    pub iterations: Option<u64>,
+    pub tree: bool,
+    pub aggregate: bool,
+    pub group: bool,
+    pub sort: TopSort,
+    pub reverse: bool,
+    pub filter: Option<Regex>,
+    pub user: Option<String>,
+    pub zombies: bool,
+    pub cgroup: Option<String>,
+    pub min_mem: Option<u64>,
+    pub min_cpu: Option<f32>,
+    pub export: Option<ExportFormat>,
+    pub export_file: Option<PathBuf>,
+    pub record: Option<PathBuf>,
+    pub replay: Option<PathBuf>,
+    pub speed: f32,
+    pub alerts: top::AlertOptions,
+    pub pid: Option<u32>,
+}
+
+/// Ports `kill` refuses to touch
+#[derive(Debug, Default)]
+pub struct PortPolicy {
+    /// `kill.protected`, or `config::DEFAULT_PROTECTED_PORTS` when unset
+    pub protected: Vec<u16>,
+    /// Whether `protected` comes from the config file
+    pub configured: bool,
+    /// `--port-range-exclude` for this run
+    pub excluded: Vec<RangeInclusive<u16>>,
+    /// `--allow-protected`, which leaves the lists above empty
+    pub bypassed: bool,
+}
+
+impl PortPolicy {
+    /// The rule that protects `port`, as named in the refusal
+    fn rule_for(&self, port: u16) -> Option<String> {
+        if let Some(range) = self.excluded.iter().find(|range| range.contains(&port)) {
+            return Some(if range.start() == range.end() {
+                format!("--port-range-exclude {}", range.start())
+            } else {
+                format!("--port-range-exclude {}-{}", range.start(), range.end())
+            });
+        }
+        if !self.protected.contains(&port) {
+            None
+        } else if self.configured {
+            Some(format!("kill.protected in {}", config_path_hint()))
+        } else {
+            Some(tr!("the default kill.protected list").to_string())
+        }
+    }
+}
 
 /// Options for the remove command
 pub struct RemoveOptions {
@@ -12,65 +103,406 @@ pub struct RemoveOptions {
     pub dry_run: bool,
     pub verbose: bool,
     pub anyway: bool,
+    pub max_depth: Option<usize>,
+    pub one_file_system: bool,
+    pub stdin: bool,
+    pub backup: Option<PathBuf>,
+    pub retries: u32,
+    pub retry_delay: u64,
+    pub keep_going: bool,
+    pub fail_fast: bool,
+    pub no_hidden: bool,
+    pub gitignore: bool,
+    pub ignored: bool,
+    pub confirm_each: bool,
+    pub symlinks: SymlinkMode,
+    pub trash: bool,
+    pub verify: bool,
+    pub elevate: bool,
+    pub notify: bool,
+    /// Paths refused outright (`remove.protected` in the config file)
+    pub protected: Vec<PathBuf>,
+    pub output: OutputFormat,
 }
 
-pub fn handle_find(ports: Vec<u16>) -> Result<()> {
+pub fn handle_find(ports: Vec<u16>, copy: bool) -> Result<()> {
     if ports.is_empty() {
-        bail!("Please specify at least one port number");
+        bail!(ZiroError::Usage(
+            tr!("Please specify at least one port number").to_string()
+        ));
     }
 
-    let port_infos = port::find_processes_by_ports(&ports)?;
+    let port_infos = ports::snapshot()?.find(&ports);
+    let mut pids: Vec<u32> = port_infos.iter().map(|info| info.process.pid).collect();
+    let mut seen = HashSet::new();
+    pids.retain(|pid| seen.insert(*pid));
     ui::display_ports_tree(&ports, port_infos);
+    if pids.is_empty() {
+        bail!(ZiroError::NotFound(
+            tr!("No processes found occupying the specified ports").to_string()
+        ));
+    }
+
+    if copy {
+        let text = pids
+            .iter()
+            .map(u32::to_string)
+            .collect::<Vec<_>>()
+            .join(" ");
+        clipboard::copy(&text)?;
+        let theme = Theme::new();
+        println!(
+            "{}",
+            theme.success(tr!("Copied to the clipboard: {}", text))
+        );
+    }
+    Ok(())
+}
+
+/// Options for the kill command
+pub struct KillOptions {
+    pub ports: Vec<u16>,
+    /// Kill these PIDs without looking up ports or asking (the elevated re-run)
+    pub pids: Vec<u32>,
+    /// Kill processes whose name or command line matches instead (`--name`)
+    pub name: Option<Regex>,
+    /// With `name`, only processes started at least this long ago
+    pub older_than: Option<Duration>,
+    /// With `name`, only processes started less than this long ago
+    pub newer_than: Option<Duration>,
+    pub force: bool,
+    pub tree: bool,
+    pub elevate: bool,
+    pub signal: SignalName,
+    pub preselect: ui::Preselect,
+    pub policy: PortPolicy,
+}
+
+/// Refuse the first of `ports` the policy protects
+fn check_protected(ports: impl IntoIterator<Item = u16>, policy: &PortPolicy) -> Result<()> {
+    if let Some((port, rule)) = ports
+        .into_iter()
+        .find_map(|port| Some((port, policy.rule_for(port)?)))
+    {
+        bail!(ZiroError::Usage(tr!(
+            "Refusing to kill port {}: protected by {} (use --allow-protected to override)",
+            port,
+            rule
+        )));
+    }
     Ok(())
 }
 
-pub fn handle_kill(ports: Vec<u16>, force: bool) -> Result<()> {
+pub fn handle_kill(mut opts: KillOptions) -> Result<()> {
+    if let Some(pattern) = opts.name.take() {
+        opts.pids = pids_by_name(pattern, &opts)?;
+        if opts.pids.is_empty() {
+            return Ok(());
+        }
+    }
+    if !opts.pids.is_empty() {
+        return kill_pids(&opts);
+    }
+    let (ports, force, tree) = (&opts.ports, opts.force, opts.tree);
+    let signal = process::KillSignal::from(opts.signal);
     if ports.is_empty() {
-        bail!("Please specify at least one port number");
+        bail!(ZiroError::Usage(
+            tr!("Please specify at least one port number").to_string()
+        ));
     }
+    check_protected(ports.iter().copied(), &opts.policy)?;
 
-    let port_infos = port::find_processes_by_ports(&ports)?;
+    let snapshot = ports::snapshot()?;
+    let port_infos = snapshot.find(ports);
 
     if port_infos.is_empty() {
-        let theme = Theme::new();
-        println!(
-            "{}",
-            theme.warn("No processes found occupying the specified ports")
-        );
-        for &port in &ports {
+        for &port in ports {
             ui::display_port_not_found(port);
         }
-        return Ok(());
+        bail!(ZiroError::NotFound(
+            tr!("No processes found occupying the specified ports").to_string()
+        ));
     }
 
-    if force {
+    let children: HashMap<u32, Vec<process::ChildProcess>> = port_infos
+        .iter()
+        .map(|info| (info.process.pid, snapshot.descendants(info.process.pid)))
+        .collect();
+    // Whether children were included, as the elevated re-run must repeat it
+    let mut with_children = tree;
+    let (refused, failure): (Vec<u32>, Option<ZiroError>) = if force {
         let pids: Vec<u32> = port_infos.iter().map(|info| info.process.pid).collect();
-        let results = process::kill_processes_force(&pids);
-        ui::display_kill_results_force(&port_infos, &results);
+        let reports = if tree {
+            process::kill_trees_force(&pids, &with_descendants(&pids, &children))
+        } else {
+            process::kill_processes_force(&pids)
+        };
+        ui::display_kill_results_force(&port_infos, &reports);
+        let results: Vec<(u32, &Result<()>)> = reports.iter().map(|r| (r.pid, &r.result)).collect();
+        split_failures(&results, "processes could not be killed")
     } else {
-        let selected = ui::select_processes_to_kill(port_infos)?;
+        let selection = ui::select_processes_to_kill(port_infos, &children, tree, opts.preselect)?;
+        with_children = selection.with_children;
 
-        if selected.is_empty() {
+        if selection.processes.is_empty() {
             return Ok(());
         }
 
-        let pids: Vec<u32> = selected.iter().map(|info| info.process.pid).collect();
-        let results = process::kill_processes(&pids);
-        ui::display_kill_results(&results);
+        let pids: Vec<u32> = selection
+            .processes
+            .iter()
+            .map(|info| info.process.pid)
+            .collect();
+        let roots = pids.clone();
+        let pids = if selection.with_children {
+            with_descendants(&pids, &children)
+        } else {
+            pids
+        };
+        match selection.action {
+            ui::KillAction::Kill => {
+                let results = if selection.with_children {
+                    process::kill_trees(&roots, &pids, signal)
+                } else {
+                    process::signal_processes(&pids, signal)
+                };
+                ui::display_kill_results(&results);
+                let results: Vec<(u32, &Result<()>)> =
+                    results.iter().map(|(pid, result)| (*pid, result)).collect();
+                split_failures(&results, "processes could not be killed")
+            }
+            ui::KillAction::Renice => {
+                let results: Vec<(u32, Result<()>)> = pids
+                    .iter()
+                    .map(|&pid| (pid, process::set_priority(pid, ui::BACKGROUND_NICE)))
+                    .collect();
+                ui::display_nice_results(&results, ui::BACKGROUND_NICE);
+                let failure = ZiroError::batch(
+                    results.len(),
+                    results
+                        .iter()
+                        .filter_map(|(_, result)| result.as_ref().err()),
+                    "priorities could not be changed",
+                );
+                (Vec::new(), failure)
+            }
+        }
+    };
+
+    let args = elevated_kill_args(&refused, force, with_children, &opts);
+    retry_elevated(refused.len(), opts.elevate, &args)?;
+    match failure {
+        Some(failure) => Err(failure.into()),
+        None => Ok(()),
+    }
+}
+
+/// `kill --pid`: signal processes an earlier run already picked, without asking again
+/// `kill --name`: the matching processes the user picks, or all of them with `--force`
+fn pids_by_name(pattern: Regex, opts: &KillOptions) -> Result<Vec<u32>> {
+    let own_pid = std::process::id();
+    // Newest first
+    let matches: Vec<top::ProcessView> = top::list_processes(&top::ListOptions {
+        filter: Some(pattern),
+        older_than: opts.older_than,
+        newer_than: opts.newer_than,
+        sort: top::SortKey::Start,
+        ..Default::default()
+    })?
+    .into_iter()
+    // Our own command line contains the pattern
+    .filter(|p| p.pid != own_pid)
+    .collect();
+
+    if matches.is_empty() {
+        bail!(ZiroError::NotFound(
+            tr!("No processes match the pattern").to_string()
+        ));
+    }
+    let targets = if opts.force {
+        ui::display_pkill_targets(&matches);
+        matches
+    } else {
+        ui::select_pkill_targets(&matches)?
+    };
+    Ok(targets.iter().map(|p| p.pid).collect())
+}
+
+fn kill_pids(opts: &KillOptions) -> Result<()> {
+    let snapshot = ports::snapshot()?;
+    check_protected(
+        opts.pids.iter().flat_map(|&pid| snapshot.ports_of(pid)),
+        &opts.policy,
+    )?;
+    let children: HashMap<u32, Vec<process::ChildProcess>> = opts
+        .pids
+        .iter()
+        .map(|&pid| (pid, snapshot.descendants(pid)))
+        .collect();
+    let pids = if opts.tree {
+        with_descendants(&opts.pids, &children)
+    } else {
+        opts.pids.clone()
+    };
+
+    let signal = process::KillSignal::from(opts.signal);
+    let results: Vec<(u32, Result<()>)> = match (opts.force, opts.tree) {
+        (true, true) => process::kill_trees_force(&opts.pids, &pids),
+        (true, false) => process::kill_processes_force(&pids),
+        (false, true) => {
+            return finish_kill_pids(process::kill_trees(&opts.pids, &pids, signal), opts);
+        }
+        (false, false) => return finish_kill_pids(process::signal_processes(&pids, signal), opts),
+    }
+    .into_iter()
+    .map(|report| (report.pid, report.result))
+    .collect();
+    finish_kill_pids(results, opts)
+}
+
+fn finish_kill_pids(results: Vec<(u32, Result<()>)>, opts: &KillOptions) -> Result<()> {
+    ui::display_kill_results(&results);
+    let results: Vec<(u32, &Result<()>)> =
+        results.iter().map(|(pid, result)| (*pid, result)).collect();
+    let (refused, failure) = split_failures(&results, "processes could not be killed");
+    let args = elevated_kill_args(&refused, opts.force, opts.tree, opts);
+    retry_elevated(refused.len(), opts.elevate, &args)?;
+    match failure {
+        Some(failure) => Err(failure.into()),
+        None => Ok(()),
+    }
+}
+
+/// Arguments re-running `kill` elevated on the `refused` PIDs only, with the same choices
+fn elevated_kill_args(
+    refused: &[u32],
+    force: bool,
+    tree: bool,
+    opts: &KillOptions,
+) -> Vec<OsString> {
+    let mut args: Vec<OsString> = vec!["kill".into()];
+    if force {
+        args.push("--force".into());
+    } else if let Some(name) = opts.signal.to_possible_value() {
+        args.push("--signal".into());
+        args.push(name.get_name().into());
+    }
+    if tree {
+        args.push("--tree".into());
+    }
+    if opts.policy.bypassed {
+        args.push("--allow-protected".into());
     }
+    for pid in refused {
+        args.push("--pid".into());
+        args.push(pid.to_string().into());
+    }
+    args
+}
+
+/// PIDs refused for lack of privileges, which `retry_elevated` deals with, and the error
+/// for the other failures
+fn split_failures(results: &[(u32, &Result<()>)], what: &str) -> (Vec<u32>, Option<ZiroError>) {
+    let refused = results
+        .iter()
+        .filter(|(_, r)| r.as_ref().is_err_and(elevation::elevation_would_help))
+        .map(|(pid, _)| *pid)
+        .collect();
+    let failure = ZiroError::batch(
+        results.len(),
+        results
+            .iter()
+            .filter_map(|(_, r)| r.as_ref().err())
+            .filter(|e| !elevation::elevation_would_help(e)),
+        what,
+    );
+    (refused, failure)
+}
+
+/// `pids` followed by their descendants, each once; parents go first so a
+/// supervisor cannot respawn the workers killed before it
+fn with_descendants(pids: &[u32], children: &HashMap<u32, Vec<process::ChildProcess>>) -> Vec<u32> {
+    let mut all: Vec<u32> = Vec::new();
+    for &pid in pids {
+        if !all.contains(&pid) {
+            all.push(pid);
+        }
+    }
+    for pid in pids {
+        for child in children.get(pid).into_iter().flatten() {
+            if !all.contains(&child.pid) {
+                all.push(child.pid);
+            }
+        }
+    }
+    all
+}
 
+/// After operations were refused for lack of privileges, re-run `args` elevated
+/// (with `--elevate`) or fail explaining how to
+fn retry_elevated(refused: usize, elevate: bool, args: &[OsString]) -> Result<()> {
+    if refused == 0 {
+        return Ok(());
+    }
+    if elevation::is_elevated() {
+        bail!(ZiroError::PermissionDenied(tr!(
+            "{} item(s) were refused even with elevated privileges",
+            refused
+        )));
+    }
+    if !elevate {
+        bail!(ZiroError::PermissionDenied(tr!(
+            "{} item(s) need more privileges; {}",
+            refused,
+            elevation::elevation_hint()
+        )));
+    }
+
+    let theme = Theme::new();
+    let command = args
+        .iter()
+        .map(|arg| arg.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join(" ");
+    println!();
+    println!(
+        "{} {}",
+        theme.icon_lightning(),
+        theme.info(tr!("Re-running with elevated privileges: ziro {}", command))
+    );
+    let status = elevation::run_elevated(args)?;
+    if !status.success() {
+        bail!(tr!("Elevated run failed ({})", status));
+    }
+    println!(
+        "{} {}",
+        theme.icon_success(),
+        theme.success(tr!("Elevated run completed"))
+    );
     Ok(())
 }
 
-pub fn handle_list() -> Result<()> {
-    let port_infos = port::list_all_ports()?;
-    ui::display_ports_tree_all(port_infos);
+pub fn handle_list(host_ports: bool) -> Result<()> {
+    let (port_infos, host) = if host_ports {
+        if !wsl::is_wsl() {
+            bail!(ZiroError::Usage(
+                tr!("--host-ports only works inside WSL").to_string()
+            ));
+        }
+        let (port_infos, host) = ports::list_with_host();
+        (port_infos?, host?)
+    } else {
+        (ports::snapshot()?.list(), Vec::new())
+    };
+    ui::display_ports_tree_all(port_infos, &host);
     Ok(())
 }
 
 pub fn handle_who(paths: Vec<PathBuf>) -> Result<()> {
     if paths.is_empty() {
-        bail!("Please specify at least one file or directory path");
+        bail!(ZiroError::Usage(
+            tr!("Please specify at least one file or directory path").to_string()
+        ));
     }
 
     fs_ops::validate_paths(&paths)?;
@@ -79,45 +511,1305 @@ pub fn handle_who(paths: Vec<PathBuf>) -> Result<()> {
     Ok(())
 }
 
-pub fn handle_top(interval: f32, limit: usize, cpu: bool, cmd: bool, once: bool) -> Result<()> {
+pub fn handle_unlock(paths: Vec<PathBuf>, force: bool, then: Vec<String>) -> Result<()> {
+    if paths.is_empty() {
+        bail!(ZiroError::Usage(
+            tr!("Please specify at least one file or directory path").to_string()
+        ));
+    }
+
+    fs_ops::validate_paths(&paths)?;
+    let infos = process::inspect_file_locks(&paths)?;
+    let has_lockers = infos.iter().any(|info| !info.processes.is_empty());
+
+    if has_lockers {
+        let pids = if force {
+            let mut pids: Vec<u32> = infos
+                .iter()
+                .flat_map(|info| info.processes.iter().map(|p| p.pid))
+                .collect();
+            pids.sort_unstable();
+            pids.dedup();
+            pids
+        } else {
+            ui::display_file_locks(&infos);
+            println!();
+            ui::select_lock_holders_to_kill(&infos)?
+        };
+
+        if pids.is_empty() {
+            return Ok(());
+        }
+
+        let reports = process::kill_processes_force(&pids);
+        ui::display_force_kill_reports(&reports);
+        println!();
+    }
+
+    // Re-check so the follow-up command only runs on actually freed paths
+    let infos = process::inspect_file_locks(&paths)?;
+    ui::display_file_locks(&infos);
+    if infos.iter().any(|info| info.locked) {
+        bail!(ZiroError::PartialFailure(
+            tr!("Some paths are still locked").to_string()
+        ));
+    }
+
+    if let Some((program, args)) = then.split_first() {
+        println!();
+        let status = std::process::Command::new(program)
+            .args(args)
+            .status()
+            .with_context(|| tr!("Failed to run command: {}", program))?;
+        if !status.success() {
+            bail!(tr!("Command exited with {}", status));
+        }
+    }
+
+    Ok(())
+}
+
+pub fn handle_du(
+    paths: Vec<PathBuf>,
+    limit: usize,
+    depth: usize,
+    one_file_system: bool,
+    no_hidden: bool,
+    gitignore: bool,
+) -> Result<()> {
+    let paths = if paths.is_empty() {
+        vec![PathBuf::from(".")]
+    } else {
+        paths
+    };
+
+    fs_ops::validate_paths(&paths)?;
+    let collect_opts = fs_ops::CollectOptions {
+        one_file_system,
+        skip_hidden: no_hidden,
+        gitignore: ignore_filter(gitignore),
+        ..Default::default()
+    };
+    for (index, path) in paths.iter().enumerate() {
+        if index > 0 {
+            println!();
+        }
+        let usage = fs_ops::disk_usage(path, &collect_opts)?;
+        ui::display_left_out(&usage.left_out);
+        ui::display_disk_usage(&usage, limit, depth);
+    }
+    Ok(())
+}
+
+pub fn handle_big(
+    paths: Vec<PathBuf>,
+    limit: usize,
+    extensions: Vec<String>,
+    older_than: Option<Duration>,
+    one_file_system: bool,
+    gitignore: bool,
+) -> Result<()> {
+    let paths = if paths.is_empty() {
+        vec![PathBuf::from(".")]
+    } else {
+        paths
+    };
+
+    fs_ops::validate_paths(&paths)?;
+    let filter = fs_ops::LargestFilter {
+        extensions,
+        older_than,
+        one_file_system,
+        gitignore: ignore_filter(gitignore),
+    };
+    let fs_ops::LargestFiles {
+        files,
+        skipped,
+        left_out,
+    } = fs_ops::largest_files(&paths, limit, &filter)?;
+    ui::display_left_out(&left_out);
+    ui::display_largest_files(&files, &skipped);
+
+    if files.is_empty() || !ui::prompt::is_interactive() {
+        return Ok(());
+    }
+
+    let selected = ui::select_files_to_delete(&files)?;
+    if selected.is_empty() {
+        return Ok(());
+    }
+
+    let report = fs_ops::remove_files(&selected, &fs_ops::RemovalOptions::default(), None);
+    let plan = fs_ops::RemovalPlan {
+        files: selected,
+        ..Default::default()
+    };
+    ui::display_removal_results(&plan, &report, false);
+    Ok(())
+}
+
+pub fn handle_tmp(older_than: Duration, dry_run: bool, force: bool) -> Result<()> {
+    let roots = temp::temp_dirs();
+    if roots.is_empty() {
+        bail!(ZiroError::NotFound(
+            tr!("No temp directories found").to_string()
+        ));
+    }
+
+    let entries = fs_ops::scan_temp_dirs(&roots, older_than);
+    ui::display_temp_summary(&roots, &entries, older_than);
+
+    let reclaimable: Vec<&fs_ops::TempEntry> = entries
+        .iter()
+        .filter(|e| e.status == fs_ops::TempStatus::Reclaimable)
+        .collect();
+    if reclaimable.is_empty() {
+        return Ok(());
+    }
+
+    let size: u64 = reclaimable.iter().map(|e| e.size).sum();
+    if !dry_run && !force && !ui::confirm_temp_cleanup(reclaimable.len(), size)? {
+        let theme = Theme::new();
+        println!("{}", theme.warn(tr!("Operation cancelled")));
+        return Ok(());
+    }
+
+    let plan = fs_ops::RemovalPlan {
+        files: reclaimable
+            .iter()
+            .flat_map(|e| e.files.iter().cloned())
+            .collect(),
+        ..Default::default()
+    };
+    let removal_opts = fs_ops::RemovalOptions {
+        dry_run,
+        ..Default::default()
+    };
+
+    println!();
+    let report = fs_ops::remove_files(&plan.files, &removal_opts, None);
+    ui::display_removal_results(&plan, &report, false);
+    Ok(())
+}
+
+pub fn handle_restore(last: bool) -> Result<()> {
+    let mut journal = fs_ops::TrashJournal::load()?;
+
+    let selected: Vec<(i64, PathBuf)> = match journal.records.last() {
+        Some(record) if last => record
+            .paths
+            .iter()
+            .map(|path| (record.time, path.clone()))
+            .collect(),
+        Some(_) => ui::select_trash_entries_to_restore(&journal.records)?,
+        None => {
+            let theme = Theme::new();
+            println!("{}", theme.warn(tr!("Nothing to restore")));
+            return Ok(());
+        }
+    };
+    if selected.is_empty() {
+        return Ok(());
+    }
+
+    let since = selected
+        .iter()
+        .map(|(time, _)| *time)
+        .min()
+        .unwrap_or_default();
+    let paths: Vec<PathBuf> = selected.into_iter().map(|(_, path)| path).collect();
+    let restored = fs_ops::restore_from_trash(&paths, since)?;
+
+    journal.forget(&restored);
+    journal.save()?;
+    ui::display_restore_results(&paths, &restored);
+    Ok(())
+}
+
+fn ignore_filter(gitignore: bool) -> fs_ops::IgnoreFilter {
+    if gitignore {
+        fs_ops::IgnoreFilter::SkipIgnored
+    } else {
+        fs_ops::IgnoreFilter::Off
+    }
+}
+
+pub fn handle_top(args: TopArgs) -> Result<()> {
+    if !(args.speed > 0.0 && args.speed.is_finite()) {
+        bail!(ZiroError::Usage(
+            tr!("--speed must be a positive number").to_string()
+        ));
+    }
+
     let opts = top::TopOptions {
-        interval,
-        limit,
-        show_cpu: cpu,
-        show_cmd: cmd,
-        once,
+        interval: args.interval,
+        limit: args.limit,
+        show_cpu: args.cpu,
+        show_cmd: args.cmd,
+        show_io: args.io,
+        show_delta: args.delta,
+        show_threads: args.threads,
+        show_fds: args.fds,
+        show_containers: args.containers,
+        // Sorting by a column nobody collects would be confusing
+        show_gpu: args.gpu || args.sort == TopSort::Gpu,
+        show_net: args.net,
+        cgroup_limit: args.cgroup_limit,
+        once: args.once,
+        batch: args.batch,
+        iterations: args.iterations,
+        tree: args.tree,
+        aggregate: args.aggregate,
+        group: args.group,
+        sort: args.sort.into(),
+        reverse: args.reverse,
+        filter: args.filter,
+        user: args.user,
+        zombies: args.zombies,
+        cgroup: args.cgroup,
+        min_memory: args.min_mem,
+        min_cpu: args.min_cpu,
+        export: args.export.map(|format| top::ExportTarget {
+            format: format.into(),
+            path: args.export_file,
+        }),
+        record: args.record,
+        replay: args.replay,
+        speed: args.speed,
+        alerts: args.alerts,
+        pid: args.pid,
     };
-    top::run_top(opts, ui::display_top)
+    match opts.pid {
+        Some(pid) => top::run_detail(pid, opts, ui::display_process_detail),
+        None => top::run_top(opts, ui::display_top),
+    }
 }
 
-pub fn handle_remove(opts: RemoveOptions) -> Result<()> {
-    if opts.paths.is_empty() {
-        bail!("Please specify at least one file or directory path");
+/// Options for the ps command
+pub struct PsArgs {
+    pub name: Option<Regex>,
+    pub user: Option<String>,
+    pub min_mem: Option<u64>,
+    pub older_than: Option<Duration>,
+    pub newer_than: Option<Duration>,
+    pub sort: TopSort,
+    pub reverse: bool,
+    pub limit: Option<usize>,
+}
+
+pub fn handle_ps(args: PsArgs, output: OutputFormat) -> Result<()> {
+    let processes = top::list_processes(&top::ListOptions {
+        filter: args.name,
+        user: args.user,
+        min_memory: args.min_mem,
+        older_than: args.older_than,
+        newer_than: args.newer_than,
+        sort: args.sort.into(),
+        reverse: args.reverse,
+        limit: args.limit,
+    })?;
+    match output {
+        OutputFormat::Json => ui::display_process_list_json(&processes),
+        OutputFormat::Text => {
+            ui::display_process_list(&processes);
+            Ok(())
+        }
     }
+}
+
+/// Options for the net command
+pub struct NetArgs {
+    pub name: Option<Regex>,
+    pub listening: bool,
+    pub sort: NetSort,
+    pub reverse: bool,
+    pub limit: Option<usize>,
+}
+
+pub fn handle_net(args: NetArgs, output: OutputFormat) -> Result<()> {
+    let processes = net::net_stats(&net::NetOptions {
+        filter: args.name,
+        listening_only: args.listening,
+        sort: args.sort.into(),
+        reverse: args.reverse,
+        limit: args.limit,
+    })?;
+    match output {
+        OutputFormat::Json => ui::display_net_stats_json(&processes),
+        OutputFormat::Text => {
+            ui::display_net_stats(&processes);
+            Ok(())
+        }
+    }
+}
+
+pub fn handle_serve(dir: PathBuf, port: Option<u16>) -> Result<()> {
+    let server = serve::Server::bind(&serve::ServeOptions { root: dir, port })?;
+    let port = server.port();
+    ui::display_serve_started(server.root(), port, &server.urls());
+    server.run(ui::display_served_request)?;
+    ui::display_serve_stopped(port);
+    Ok(())
+}
+
+/// Exit 0 when healthy; 3 when nothing accepted the connection, 1 for any other failure
+pub fn handle_check(opts: check::CheckOptions, output: OutputFormat) -> Result<()> {
+    let outcome = check::check_port(&opts, |attempt| {
+        if output == OutputFormat::Text {
+            ui::display_check_retry(attempt, opts.retries, opts.interval);
+        }
+    });
+    match output {
+        OutputFormat::Json => ui::display_check_outcome_json(&outcome)?,
+        OutputFormat::Text => ui::display_check_outcome(&outcome),
+    }
+    let target = format!("{}:{}", outcome.host, outcome.port);
+    match outcome.failure() {
+        None => Ok(()),
+        Some(check::CheckFailure::Unreachable(_)) => {
+            bail!(ZiroError::NotFound(tr!(
+                "Nothing accepts connections on {}",
+                target
+            )))
+        }
+        Some(failure) => bail!(tr!("{} is unhealthy: {}", target, failure)),
+    }
+}
+
+/// How long `open` waits for a port to answer its HTTP probe
+const OPEN_PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Open `http://localhost:<port>/` when something there speaks HTTP; otherwise show who
+/// holds the port
+pub fn handle_open(port: u16) -> Result<()> {
+    let Some(info) = ports::find(&[port])?.into_iter().next() else {
+        bail!(ZiroError::NotFound(tr!("Port {} is not in use", port)));
+    };
+    if !check::speaks_http("localhost", port, OPEN_PROBE_TIMEOUT) {
+        ui::display_ports_tree(&[port], vec![info]);
+        ui::display_not_http(port);
+        return Ok(());
+    }
+    let url = format!("http://localhost:{port}/");
+    ui::display_opening_url(&url, &info);
+    platform::browser::open_url(&url)
+}
+
+/// Options for the conns command
+pub struct ConnsArgs {
+    pub name: Option<Regex>,
+    pub remote: Option<std::net::IpAddr>,
+    pub resolve: bool,
+    pub kill_remote: Option<std::net::IpAddr>,
+    pub force: bool,
+    pub signal: process::KillSignal,
+}
 
-    fs_ops::validate_paths(&opts.paths)?;
-    let files = fs_ops::collect_files_to_remove(&opts.paths, opts.recursive)?;
+pub fn handle_conns(args: ConnsArgs, output: OutputFormat) -> Result<()> {
+    let connections = conns::list_connections(&conns::ConnOptions {
+        filter: args.name,
+        remote: args.remote.or(args.kill_remote),
+        resolve: args.resolve,
+    })?;
+    let Some(ip) = args.kill_remote else {
+        return match output {
+            OutputFormat::Json => ui::display_connections_json(&connections),
+            OutputFormat::Text => {
+                ui::display_connections(&connections);
+                Ok(())
+            }
+        };
+    };
 
-    if files.is_empty() {
+    if connections.is_empty() {
+        bail!(ZiroError::NotFound(tr!(
+            "No process is connected to {}",
+            ip
+        )));
+    }
+    let mut pids: Vec<u32> = connections.iter().map(|c| c.pid).collect();
+    pids.dedup();
+    ui::display_connections(&connections);
+    if !args.force && !ui::confirm_pkill(pids.len())? {
         let theme = Theme::new();
-        println!("{}", theme.warn("No matching files or directories found"));
+        println!("{}", theme.warn(tr!("Operation cancelled")));
+        return Ok(());
+    }
+
+    let failure = if args.force {
+        let reports = process::kill_processes_force(&pids);
+        ui::display_force_kill_reports(&reports);
+        ZiroError::batch(
+            reports.len(),
+            reports.iter().filter_map(|r| r.result.as_ref().err()),
+            "processes could not be killed",
+        )
+    } else {
+        let results = process::signal_processes(&pids, args.signal);
+        ui::display_kill_results(&results);
+        ZiroError::batch(
+            results.len(),
+            results
+                .iter()
+                .filter_map(|(_, result)| result.as_ref().err()),
+            "processes could not be killed",
+        )
+    };
+    match failure {
+        Some(failure) => Err(failure.into()),
+        None => Ok(()),
+    }
+}
+
+pub fn handle_info(pid: u32, env: bool, output: OutputFormat) -> Result<()> {
+    let info = process::inspect_process(pid, env)?;
+    match output {
+        OutputFormat::Json => ui::display_process_info_json(&info),
+        OutputFormat::Text => {
+            ui::display_process_info(&info);
+            Ok(())
+        }
+    }
+}
+
+/// Pause (or with `pause == false`, resume) each PID
+pub fn handle_pause(pids: Vec<u32>, pause: bool) -> Result<()> {
+    let results: Vec<(u32, Result<()>)> = pids
+        .into_iter()
+        .map(|pid| {
+            let result = if pause {
+                process::pause_process(pid)
+            } else {
+                process::resume_process(pid)
+            };
+            (pid, result)
+        })
+        .collect();
+    ui::display_pause_results(&results, pause);
+    let what = if pause {
+        "processes could not be paused"
+    } else {
+        "processes could not be resumed"
+    };
+    match ZiroError::batch(
+        results.len(),
+        results
+            .iter()
+            .filter_map(|(_, result)| result.as_ref().err()),
+        what,
+    ) {
+        Some(failure) => Err(failure.into()),
+        None => Ok(()),
+    }
+}
+
+pub fn handle_nice(pids: Vec<u32>, nice: i32) -> Result<()> {
+    let results: Vec<(u32, Result<()>)> = pids
+        .into_iter()
+        .map(|pid| (pid, process::set_priority(pid, nice)))
+        .collect();
+    ui::display_nice_results(&results, nice);
+    match ZiroError::batch(
+        results.len(),
+        results
+            .iter()
+            .filter_map(|(_, result)| result.as_ref().err()),
+        "priorities could not be changed",
+    ) {
+        Some(failure) => Err(failure.into()),
+        None => Ok(()),
+    }
+}
+
+pub fn handle_waitpid(
+    pids: Vec<u32>,
+    name: Option<Regex>,
+    timeout: Option<Duration>,
+    interval: u64,
+    notify: bool,
+    output: OutputFormat,
+) -> Result<()> {
+    let opts = process::WaitOptions {
+        pids,
+        name,
+        timeout,
+        status_interval: Duration::from_secs(interval.max(1)),
+    };
+    let outcome = process::wait_for_exit(&opts, |elapsed, remaining| {
+        if output == OutputFormat::Text {
+            ui::display_wait_progress(elapsed, remaining);
+        }
+    })?;
+    match output {
+        OutputFormat::Json => ui::display_wait_outcome_json(&outcome)?,
+        OutputFormat::Text => ui::display_wait_outcome(&outcome),
+    }
+    if notify {
+        let body = if outcome.timed_out {
+            tr!(
+                "Timed out with {} process(es) still running",
+                outcome.remaining.len()
+            )
+        } else {
+            tr!("{} process(es) exited", outcome.exited.len())
+        };
+        notify::desktop_notify("ziro waitpid", &body);
+    }
+    outcome.ensure_finished()
+}
+
+/// Re-run `command` every `interval` seconds; a leading ziro subcommand runs this binary
+pub fn handle_watch(command: Vec<OsString>, interval: f32, iterations: Option<u64>) -> Result<()> {
+    if !(interval > 0.0 && interval.is_finite()) {
+        bail!(ZiroError::Usage(
+            tr!("--interval must be a positive number").to_string()
+        ));
+    }
+    let label = command
+        .iter()
+        .map(|arg| arg.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let Some((first, rest)) = command.split_first() else {
+        bail!(ZiroError::Usage(
+            tr!("Please specify a command to watch").to_string()
+        ));
+    };
+
+    let subcommand = first.to_str().and_then(|name| {
+        Cli::command()
+            .find_subcommand(name)
+            .map(|c| c.get_name().to_string())
+    });
+    let (program, args, env) = match subcommand {
+        Some(name) if name == "watch" => {
+            bail!(ZiroError::Usage(
+                tr!("ziro watch cannot watch itself").to_string()
+            ))
+        }
+        Some(_) => {
+            let exe = std::env::current_exe().context("Cannot locate the ziro executable")?;
+            let lang = match i18n::lang() {
+                i18n::Lang::Zh => "zh",
+                i18n::Lang::En => "en",
+            };
+            // Nobody can answer a prompt or page through output in the middle of a frame
+            let mut args: Vec<OsString> = ["--no-input", "--no-pager", "--lang", lang]
+                .map(OsString::from)
+                .into();
+            args.extend(command.iter().cloned());
+            // Its stdout is a pipe, which would turn colors off
+            let profile = term::global_profile();
+            let env = if std::io::stdout().is_terminal() && !profile.no_color && !profile.plain {
+                vec![("CLICOLOR_FORCE".into(), "1".into())]
+            } else {
+                Vec::new()
+            };
+            (exe.into_os_string(), args, env)
+        }
+        None => (first.clone(), rest.to_vec(), Vec::new()),
+    };
+
+    top::run_watch(
+        top::WatchOptions {
+            program,
+            args,
+            env,
+            label,
+            interval,
+            iterations,
+        },
+        ui::display_watch,
+    )
+}
+
+pub struct PkillArgs {
+    pub pattern: Regex,
+    pub oldest: bool,
+    pub newest: bool,
+    pub all: bool,
+    pub user: Option<String>,
+    pub older_than: Option<Duration>,
+    pub newer_than: Option<Duration>,
+    pub force: bool,
+    pub signal: process::KillSignal,
+}
+
+/// How `ziro pkill` chooses among the matching processes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PkillPick {
+    Oldest,
+    Newest,
+    All,
+    /// Every match, for the user to choose from
+    Ask,
+}
+
+/// The processes of `matches` owned by `user` that `pick` selects, in their given order
+///
+/// Processes started in the same second tie: `Oldest` takes the lowest PID of them,
+/// `Newest` the highest.
+fn pick_pkill_targets(
+    matches: Vec<top::ProcessView>,
+    user: Option<&str>,
+    pick: PkillPick,
+) -> Vec<top::ProcessView> {
+    let owned = matches
+        .into_iter()
+        .filter(|p| user.is_none_or(|user| p.user.as_deref() == Some(user)));
+    match pick {
+        PkillPick::Oldest => owned
+            .min_by_key(|p| (p.start_time, p.pid))
+            .into_iter()
+            .collect(),
+        PkillPick::Newest => owned
+            .max_by_key(|p| (p.start_time, p.pid))
+            .into_iter()
+            .collect(),
+        PkillPick::All | PkillPick::Ask => owned.collect(),
+    }
+}
+
+pub fn handle_pkill(args: PkillArgs) -> Result<()> {
+    let own_pid = std::process::id();
+    let user = top::resolve_user(args.user)?;
+    // Newest first
+    let matches: Vec<top::ProcessView> = top::list_processes(&top::ListOptions {
+        filter: Some(args.pattern),
+        older_than: args.older_than,
+        newer_than: args.newer_than,
+        sort: top::SortKey::Start,
+        ..Default::default()
+    })?
+    .into_iter()
+    // Our own command line contains the pattern
+    .filter(|p| p.pid != own_pid)
+    .collect();
+
+    let pick = if args.oldest {
+        PkillPick::Oldest
+    } else if args.newest {
+        PkillPick::Newest
+    } else if args.all || args.force {
+        PkillPick::All
+    } else {
+        PkillPick::Ask
+    };
+    let matches = pick_pkill_targets(matches, user.as_deref(), pick);
+    if matches.is_empty() {
+        bail!(ZiroError::NotFound(
+            tr!("No processes match the pattern").to_string()
+        ));
+    }
+
+    let picked = args.oldest || args.newest || args.all;
+    let targets = if pick == PkillPick::Ask {
+        ui::select_pkill_targets(&matches)?
+    } else {
+        matches
+    };
+    if targets.is_empty() {
         return Ok(());
     }
 
-    if !ui::confirm_deletion(&files, opts.force || opts.anyway, opts.dry_run)? {
+    if args.force || picked {
+        ui::display_pkill_targets(&targets);
+    }
+    if !args.force && !ui::confirm_pkill(targets.len())? {
         let theme = Theme::new();
-        println!("{}", theme.warn("Operation cancelled"));
+        println!("{}", theme.warn(tr!("Operation cancelled")));
+        return Ok(());
+    }
+
+    let pids: Vec<u32> = targets.iter().map(|p| p.pid).collect();
+    let failure = if args.force {
+        let reports = process::kill_processes_force(&pids);
+        ui::display_force_kill_reports(&reports);
+        ZiroError::batch(
+            reports.len(),
+            reports.iter().filter_map(|r| r.result.as_ref().err()),
+            "processes could not be killed",
+        )
+    } else {
+        let results = process::signal_processes(&pids, args.signal);
+        ui::display_kill_results(&results);
+        ZiroError::batch(
+            results.len(),
+            results
+                .iter()
+                .filter_map(|(_, result)| result.as_ref().err()),
+            "processes could not be killed",
+        )
+    };
+    match failure {
+        Some(failure) => Err(failure.into()),
+        None => Ok(()),
+    }
+}
+
+/// How long `ziro reap` waits for signalled parents to collect their zombies
+const REAP_GRACE: Duration = Duration::from_secs(2);
+
+pub struct ReapArgs {
+    pub min_age: Duration,
+    pub nudge: bool,
+    pub kill_parents: bool,
+    pub force: bool,
+}
+
+pub fn handle_reap(args: ReapArgs, output: OutputFormat) -> Result<()> {
+    let report = process::find_strays(args.min_age);
+    let acting = args.nudge || args.kill_parents;
+    if output == OutputFormat::Json {
+        if acting {
+            bail!(ZiroError::Usage(
+                tr!("--json only lists; run --nudge or --kill-parents without it").to_string()
+            ));
+        }
+        return ui::display_reap_report_json(&report);
+    }
+
+    ui::display_reap_report(&report, args.min_age);
+    if !acting || report.zombies.is_empty() {
         return Ok(());
     }
 
-    // Check file locks and warn user
-    if !ui::check_and_warn_file_locks(&files, opts.anyway)? {
+    // Only init can reap what init holds, and ziro waits for its own children
+    let own_pid = std::process::id();
+    let parents: Vec<u32> = report
+        .zombies
+        .iter()
+        .map(|parent| parent.pid)
+        .filter(|&pid| pid != process::INIT_PID && pid != own_pid)
+        .collect();
+    if parents.is_empty() {
         let theme = Theme::new();
-        println!("{}", theme.warn("Operation cancelled"));
+        println!(
+            "{}",
+            theme.warn(tr!("Only init holds zombies; there is no parent to signal"))
+        );
+        return Ok(());
+    }
+
+    if args.nudge {
+        let results: Vec<(u32, Result<()>)> = parents
+            .iter()
+            .map(|&pid| (pid, process::nudge_parent(pid)))
+            .collect();
+        ui::display_reap_results(&results, false);
+    }
+    if args.kill_parents {
+        if !args.force && !ui::confirm_kill_parents(parents.len())? {
+            let theme = Theme::new();
+            println!("{}", theme.warn(tr!("Operation cancelled")));
+            return Ok(());
+        }
+        let results: Vec<(u32, Result<()>)> = parents
+            .iter()
+            .map(|&pid| {
+                (
+                    pid,
+                    process::send_signal(pid, process::KillSignal::Terminate),
+                )
+            })
+            .collect();
+        ui::display_reap_results(&results, true);
+    }
+
+    // Give the parents (or init, after adopting) a moment to collect the zombies
+    let deadline = Instant::now() + REAP_GRACE;
+    let left = loop {
+        std::thread::sleep(Duration::from_millis(200));
+        process::shared().invalidate();
+        let left = process::find_strays(args.min_age).zombie_count();
+        if left == 0 || Instant::now() >= deadline {
+            break left;
+        }
+    };
+    ui::display_zombies_left(report.zombie_count(), left);
+    Ok(())
+}
+
+/// Print the completion script for `shell`
+pub fn handle_completions(shell: clap_complete::Shell) -> Result<()> {
+    print!("{}", completions::completion_script(shell)?);
+    Ok(())
+}
+
+/// Occupied ports for completion scripts; prints nothing when they cannot be listed
+/// Occupied ports, then the `@groups` of the config file
+pub fn handle_complete_ports(groups: &BTreeMap<String, Vec<u16>>) {
+    let mut seen = std::collections::HashSet::new();
+    for info in ports::list().unwrap_or_default() {
+        if seen.insert(info.port) {
+            println!(
+                "{}\t{} ({})",
+                info.port, info.process.name, info.process.pid
+            );
+        }
+    }
+    for (name, ports) in groups {
+        let ports: Vec<String> = ports.iter().map(u16::to_string).collect();
+        println!("@{name}\tgroup: {}", ports.join(", "));
+    }
+}
+
+pub fn handle_config(action: ConfigAction, output: OutputFormat) -> Result<()> {
+    let mut file = ConfigFile::open()?;
+    let theme = Theme::new();
+    match action {
+        ConfigAction::Path => println!("{}", file.path().display()),
+        ConfigAction::List => match output {
+            OutputFormat::Json => ui::display_config_json(&file.entries())?,
+            OutputFormat::Text => ui::display_config(file.path(), &file.entries()),
+        },
+        ConfigAction::Get { key } => match file.get(&key)? {
+            Some(value) => println!("{value}"),
+            None => println!("{}", ConfigFile::default_of(&key)?),
+        },
+        ConfigAction::Set { key, value } => {
+            file.set(&key, &value)?;
+            file.save()?;
+            let value = file.get(&key)?.unwrap_or_default();
+            println!(
+                "{} {}",
+                theme.icon_success(),
+                theme.success(tr!("Set {} = {}", key, value))
+            );
+        }
+        ConfigAction::Unset { key } => {
+            if file.unset(&key)? {
+                file.save()?;
+            }
+            println!(
+                "{} {}",
+                theme.icon_success(),
+                theme.success(tr!(
+                    "{} is back to its default ({})",
+                    key,
+                    ConfigFile::default_of(&key)?
+                ))
+            );
+        }
+    }
+    Ok(())
+}
+
+pub fn handle_doctor(output: OutputFormat) -> Result<()> {
+    let report = doctor::collect_diagnostics();
+    match output {
+        OutputFormat::Json => ui::display_diagnostics_json(&report),
+        OutputFormat::Text => {
+            ui::display_diagnostics(&report);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "self-update")]
+pub fn handle_self_update(check_only: bool, output: OutputFormat) -> Result<()> {
+    use crate::cli::self_update;
+
+    if output == OutputFormat::Json && !check_only {
+        bail!(ZiroError::Usage(
+            tr!("JSON output is only available with --check").to_string()
+        ));
+    }
+    let update = self_update::check()?;
+    if output == OutputFormat::Json {
+        println!("{}", serde_json::to_string_pretty(&update)?);
+        return Ok(());
+    }
+
+    let theme = Theme::new();
+    if !update.update_available {
+        println!(
+            "{} {}",
+            theme.icon_success(),
+            theme.success(tr!("ziro {} is up to date", update.current))
+        );
+        return Ok(());
+    }
+    println!(
+        "{} {}",
+        theme.icon_lightning(),
+        tr!(
+            "ziro {} is available (current {}): {}",
+            theme.highlight(&update.latest),
+            update.current,
+            theme.muted(&update.url)
+        )
+    );
+    if check_only {
         return Ok(());
     }
 
-    let results = fs_ops::remove_files(&files, opts.dry_run, opts.anyway);
-    ui::display_removal_results(&results, opts.dry_run, opts.verbose);
+    let question = tr!("Update ziro {} to {}?", update.current, update.latest);
+    if !ui::prompt::confirm(inquire::Confirm::new(&question).with_default(true))? {
+        println!("{}", theme.warn(tr!("Operation cancelled")));
+        return Ok(());
+    }
+    let path = self_update::install(&update)?;
+    println!(
+        "{} {}",
+        theme.icon_success(),
+        theme.success(tr!(
+            "Updated to ziro {} ({})",
+            update.latest,
+            path.display()
+        ))
+    );
     Ok(())
 }
+
+fn config_path_hint() -> String {
+    config::config_path().map_or_else(
+        || "the config file".to_string(),
+        |p| p.display().to_string(),
+    )
+}
+
+pub fn handle_pstree(pid: Option<u32>, output: OutputFormat) -> Result<()> {
+    let forest = top::process_tree(pid)?;
+    match output {
+        OutputFormat::Json => ui::display_process_tree_json(&forest),
+        OutputFormat::Text => {
+            ui::display_process_tree(&forest);
+            Ok(())
+        }
+    }
+}
+
+pub fn handle_snapshot(action: SnapshotAction, output: OutputFormat) -> Result<()> {
+    match action {
+        SnapshotAction::Save { file } => {
+            let snapshot = Snapshot::capture();
+            snapshot.save(&file)?;
+            ui::display_snapshot_saved(&file, &snapshot);
+        }
+        SnapshotAction::Diff {
+            before,
+            after,
+            min_delta,
+            limit,
+        } => {
+            let before = Snapshot::load(&before)?;
+            let after = match after {
+                Some(path) => Snapshot::load(&path)?,
+                None => Snapshot::capture(),
+            };
+            let diff = before.diff(&after, min_delta.unwrap_or(0));
+            match output {
+                OutputFormat::Json => ui::display_snapshot_diff_json(&diff)?,
+                OutputFormat::Text => ui::display_snapshot_diff(&diff, limit),
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn handle_remove(mut opts: RemoveOptions) -> Result<()> {
+    if opts.stdin {
+        let mut input = Vec::new();
+        std::io::stdin()
+            .read_to_end(&mut input)
+            .context("Failed to read paths from stdin")?;
+        opts.paths.extend(fs_ops::parse_path_list(&input));
+    }
+
+    if opts.paths.is_empty() {
+        bail!(ZiroError::Usage(
+            tr!("Please specify at least one file or directory path").to_string()
+        ));
+    }
+    if let Some((path, guard)) = fs_ops::find_protected(&opts.paths, &opts.protected) {
+        bail!(tr!(
+            "Refusing to remove {}: {} is protected (remove.protected in {})",
+            path.display(),
+            guard.display(),
+            config_path_hint()
+        ));
+    }
+
+    let json = opts.output == OutputFormat::Json;
+    if json && !opts.force && !opts.anyway && !opts.dry_run {
+        bail!(ZiroError::Usage(
+            tr!("JSON output cannot prompt for confirmation, use --force or --dry-run").to_string()
+        ));
+    }
+
+    let collect_opts = fs_ops::CollectOptions {
+        recursive: opts.recursive,
+        one_file_system: opts.one_file_system,
+        max_depth: opts.max_depth,
+        keep_going: opts.keep_going,
+        skip_hidden: opts.no_hidden,
+        gitignore: if opts.ignored {
+            fs_ops::IgnoreFilter::OnlyIgnored
+        } else {
+            ignore_filter(opts.gitignore)
+        },
+        symlinks: opts.symlinks.into(),
+    };
+    let mut plan = crate::fs::remove_plan(&opts.paths, &collect_opts)?;
+
+    if !json {
+        ui::display_left_out(&plan.left_out);
+        if plan.files.is_empty() {
+            let theme = Theme::new();
+            println!(
+                "{}",
+                theme.warn(tr!("No matching files or directories found"))
+            );
+            return Ok(());
+        }
+
+        let confirm = ui::ConfirmOptions {
+            skip: opts.force || opts.anyway,
+            dry_run: opts.dry_run,
+            each: opts.confirm_each,
+            trash: opts.trash,
+        };
+        if !ui::confirm_deletion(&mut plan, &opts.paths, &confirm)? {
+            let theme = Theme::new();
+            println!("{}", theme.warn(tr!("Operation cancelled")));
+            return Ok(());
+        }
+
+        // Check file locks and warn user
+        if !ui::check_and_warn_file_locks(&plan.files, opts.anyway)? {
+            let theme = Theme::new();
+            println!("{}", theme.warn(tr!("Operation cancelled")));
+            return Ok(());
+        }
+    }
+
+    let mut backup = match &opts.backup {
+        Some(path) if !opts.dry_run => Some(fs_ops::BackupArchive::create(path)?),
+        _ => None,
+    };
+
+    let removal_opts = fs_ops::RemovalOptions {
+        dry_run: opts.dry_run,
+        anyway: opts.anyway,
+        retry: fs_ops::RetryPolicy {
+            retries: opts.retries,
+            delay: Duration::from_millis(opts.retry_delay),
+        },
+        fail_fast: opts.fail_fast,
+        trash: opts.trash,
+    };
+    let files = &plan.files;
+    let mut report = fs_ops::remove_files(files, &removal_opts, backup.as_mut());
+
+    // Lockers that --anyway couldn't identify or kill: at least free the paths
+    let locked = fs_ops::locked_entries(&report);
+    let pending = if opts.anyway
+        && !opts.dry_run
+        && !locked.is_empty()
+        && (opts.force || (!json && ui::confirm_pending_delete(&locked)?))
+    {
+        fs_ops::defer_locked(files, &mut report)
+    } else {
+        Vec::new()
+    };
+
+    let lingering = (opts.verify && !opts.dry_run).then(|| fs_ops::verify_removed(&report));
+
+    if opts.notify && !opts.dry_run {
+        let failed = report.failures().count();
+        let mut body = tr!(
+            "Removed {} of {} entries in {}",
+            report.succeeded(),
+            report.entries.len(),
+            format!("{:.1}s", report.elapsed.as_secs_f64())
+        );
+        if failed > 0 {
+            body.push_str(&tr!(", {} failed", failed));
+        }
+        notify::desktop_notify("ziro remove", &body);
+    }
+
+    if json {
+        ui::display_removal_results_json(&plan, &report, lingering.as_deref())?;
+    } else {
+        ui::display_removal_results(&plan, &report, opts.verbose);
+        if !pending.is_empty() {
+            ui::display_pending_deletes(&pending);
+        }
+        if let Some(lingering) = &lingering {
+            ui::display_verify_results(lingering);
+        }
+    }
+
+    if let Some(backup) = backup {
+        let path = backup.path().to_path_buf();
+        backup.finish()?;
+        if !json {
+            ui::display_backup_saved(&path);
+        }
+    }
+
+    // Refusals are left to `retry_elevated`; the elevated run reports its own failures
+    let retried = !json && !opts.dry_run;
+    if retried {
+        let refused: Vec<&PathBuf> = report
+            .entries
+            .iter()
+            .filter(|e| match &e.outcome {
+                fs_ops::RemovalOutcome::Failed {
+                    kind: fs_ops::DeletionError::PermissionDenied,
+                    error,
+                } => elevation::elevation_would_help(error),
+                _ => false,
+            })
+            .map(|e| &e.path)
+            .collect();
+        let mut args: Vec<OsString> = vec!["remove".into(), "--force".into()];
+        if opts.recursive {
+            args.push("--recursive".into());
+        }
+        // The elevated run reads another user's config
+        args.push(if opts.trash { "--trash" } else { "--no-trash" }.into());
+        args.push("--".into());
+        args.extend(refused.iter().map(|path| path.as_os_str().to_os_string()));
+        retry_elevated(refused.len(), opts.elevate, &args)?;
+    }
+    let failures = report
+        .entries
+        .iter()
+        .filter_map(|e| match &e.outcome {
+            fs_ops::RemovalOutcome::Failed { kind, error } => Some((*kind, error)),
+            _ => None,
+        })
+        .filter(|&(kind, error)| {
+            !retried
+                || kind != fs_ops::DeletionError::PermissionDenied
+                || !elevation::elevation_would_help(error)
+        })
+        .map(|(_, error)| error);
+    match ZiroError::batch(
+        report.entries.len(),
+        failures,
+        "entries could not be removed",
+    ) {
+        Some(failure) => Err(failure.into()),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_elevated_kill_args() {
+        let mut opts = KillOptions {
+            ports: vec![22],
+            pids: Vec::new(),
+            name: None,
+            older_than: None,
+            newer_than: None,
+            force: false,
+            tree: false,
+            elevate: true,
+            signal: SignalName::Term,
+            preselect: ui::Preselect::default(),
+            policy: PortPolicy::default(),
+        };
+        let args = |refused: &[u32], force, tree, opts: &KillOptions| {
+            elevated_kill_args(refused, force, tree, opts)
+                .into_iter()
+                .map(|arg| arg.into_string().unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            args(&[41, 42], false, true, &opts),
+            [
+                "kill", "--signal", "term", "--tree", "--pid", "41", "--pid", "42"
+            ]
+        );
+        assert_eq!(
+            args(&[7], true, false, &opts),
+            ["kill", "--force", "--pid", "7"]
+        );
+
+        opts.policy.bypassed = true;
+        assert_eq!(
+            args(&[22], false, false, &opts),
+            [
+                "kill",
+                "--signal",
+                "term",
+                "--allow-protected",
+                "--pid",
+                "22"
+            ]
+        );
+    }
+    fn process(pid: u32, start_time: u64, user: &str) -> top::ProcessView {
+        top::ProcessView {
+            pid,
+            name: "node".to_string(),
+            memory_bytes: 0,
+            memory_percent: 0.0,
+            cpu: 0.0,
+            cmd: String::new(),
+            state: 'S',
+            threads: None,
+            open_fds: None,
+            user: Some(user.to_string()),
+            cgroup: None,
+            gpu_memory: None,
+            gpu_util: None,
+            listening: None,
+            connections: None,
+            start_time,
+            parent: None,
+            is_thread: false,
+            depth: 0,
+            tree_memory: None,
+            read_rate: 0.0,
+            write_rate: 0.0,
+            alert: false,
+            memory_delta: None,
+            group_size: None,
+        }
+    }
+
+    #[test]
+    fn test_pick_pkill_targets() {
+        // Newest first, as listed; 3 and 5 started in the same second, as did 1 and 4
+        let matches = vec![
+            process(3, 300, "alice"),
+            process(5, 300, "bob"),
+            process(2, 200, "bob"),
+            process(1, 100, "alice"),
+            process(4, 100, "alice"),
+        ];
+        let pids = |user, pick| {
+            pick_pkill_targets(matches.clone(), user, pick)
+                .iter()
+                .map(|p| p.pid)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(pids(None, PkillPick::Oldest), [1]);
+        assert_eq!(pids(None, PkillPick::Newest), [5]);
+        assert_eq!(pids(None, PkillPick::All), [3, 5, 2, 1, 4]);
+        assert_eq!(pids(None, PkillPick::Ask), [3, 5, 2, 1, 4]);
+
+        assert_eq!(pids(Some("alice"), PkillPick::Newest), [3]);
+        assert_eq!(pids(Some("bob"), PkillPick::Oldest), [2]);
+        assert_eq!(pids(Some("alice"), PkillPick::All), [3, 1, 4]);
+        assert!(pids(Some("carol"), PkillPick::Oldest).is_empty());
+
+        for pick in [
+            PkillPick::Oldest,
+            PkillPick::Newest,
+            PkillPick::All,
+            PkillPick::Ask,
+        ] {
+            assert!(pick_pkill_targets(Vec::new(), None, pick).is_empty());
+        }
+    }
+}
@@ -0,0 +1,117 @@
+/// Log setup: warnings go to stderr, `-v`/`--log-level`/`ZIRO_LOG` show more, and
+/// `--log-file` keeps a detailed copy for looking into failed kills and deletions later
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use std::fs::OpenOptions;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tracing_subscriber::filter::{EnvFilter, LevelFilter};
+use tracing_subscriber::prelude::*;
+
+/// Filter directives used when neither a flag nor `ZIRO_LOG` picks one
+const DEFAULT_FILTER: &str = "warn";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warn => "warn",
+            Self::Info => "info",
+            Self::Debug => "debug",
+            Self::Trace => "trace",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LogOptions {
+    /// Number of `-v` flags
+    pub verbosity: u8,
+    pub level: Option<LogLevel>,
+    pub file: Option<PathBuf>,
+}
+
+/// `--log-level` wins over `-v`, which wins over `ZIRO_LOG` (full `tracing` directives)
+fn filter_spec(opts: &LogOptions, env: Option<String>) -> String {
+    let from_verbosity = match opts.verbosity {
+        0 => None,
+        1 => Some(LogLevel::Info),
+        2 => Some(LogLevel::Debug),
+        _ => Some(LogLevel::Trace),
+    };
+    match opts.level.or(from_verbosity) {
+        Some(level) => level.as_str().to_string(),
+        None => env
+            .filter(|spec| !spec.trim().is_empty())
+            .unwrap_or_else(|| DEFAULT_FILTER.to_string()),
+    }
+}
+
+/// Install the global subscriber; the log file records at least debug messages
+pub fn init_logging(opts: &LogOptions) -> Result<()> {
+    let spec = filter_spec(opts, std::env::var("ZIRO_LOG").ok());
+    let filter =
+        EnvFilter::try_new(&spec).with_context(|| format!("Invalid log filter: {spec}"))?;
+    let file_level = match filter.max_level_hint() {
+        Some(LevelFilter::TRACE) => LevelFilter::TRACE,
+        _ => LevelFilter::DEBUG,
+    };
+
+    let stderr = tracing_subscriber::fmt::layer()
+        .with_writer(std::io::stderr)
+        .with_target(false)
+        .without_time()
+        .with_filter(filter);
+
+    let file = match &opts.file {
+        Some(path) => {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .with_context(|| format!("Failed to open log file: {}", path.display()))?;
+            Some(
+                tracing_subscriber::fmt::layer()
+                    .with_writer(Mutex::new(file))
+                    .with_ansi(false)
+                    .with_filter(file_level),
+            )
+        }
+        None => None,
+    };
+
+    tracing_subscriber::registry()
+        .with(stderr)
+        .with(file)
+        .try_init()
+        .context("Logging is already set up")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_spec() {
+        let opts = |verbosity, level| LogOptions {
+            verbosity,
+            level,
+            file: None,
+        };
+        let env = || Some("ziro::core=trace".to_string());
+        assert_eq!(filter_spec(&opts(0, None), None), "warn");
+        assert_eq!(filter_spec(&opts(0, None), env()), "ziro::core=trace");
+        assert_eq!(filter_spec(&opts(2, None), env()), "debug");
+        assert_eq!(filter_spec(&opts(5, None), None), "trace");
+        assert_eq!(filter_spec(&opts(2, Some(LogLevel::Error)), None), "error");
+    }
+}
@@ -1,7 +1,24 @@
 pub mod args;
+pub mod completions;
+pub mod config;
+pub mod doctor;
 pub mod handlers;
+pub mod logging;
+#[cfg(feature = "self-update")]
+pub mod self_update;
 
-pub use args::{Cli, Commands};
+pub use args::{
+    Cli, Commands, ConfigAction, ExportFormat, NetSort, OutputFormat, PortArg, SignalName,
+    SnapshotAction, SymlinkMode, TopSort, resolve_ports,
+};
+pub use config::Config;
+#[cfg(feature = "self-update")]
+pub use handlers::handle_self_update;
 pub use handlers::{
-    RemoveOptions, handle_find, handle_kill, handle_list, handle_remove, handle_top, handle_who,
+    ConnsArgs, KillOptions, NetArgs, PkillArgs, PortPolicy, PsArgs, ReapArgs, RemoveOptions,
+    TopArgs, handle_big, handle_check, handle_complete_ports, handle_completions, handle_config,
+    handle_conns, handle_doctor, handle_du, handle_find, handle_info, handle_kill, handle_list,
+    handle_net, handle_nice, handle_open, handle_pause, handle_pkill, handle_ps, handle_pstree,
+    handle_reap, handle_remove, handle_restore, handle_serve, handle_snapshot, handle_tmp,
+    handle_top, handle_unlock, handle_waitpid, handle_watch, handle_who,
 };
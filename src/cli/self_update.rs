@@ -0,0 +1,292 @@
+/// `ziro self-update`: fetch the latest GitHub release, check its SHA-256 against the
+/// published `.sha256` file and swap the running binary for it
+///
+/// Downloads go through `curl` (PowerShell on Windows) and archives through `unzip`/`tar`,
+/// like the other external tools ziro relies on.
+use crate::platform::command::{find_program, run_command_with_timeout};
+use anyhow::{Context, Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+const LATEST_RELEASE: &str = "https://api.github.com/repos/Protagonistss/ziro/releases/latest";
+
+/// Downloads are bigger than what `--command-timeout` is meant for
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+    html_url: String,
+    assets: Vec<Asset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Asset {
+    name: String,
+    browser_download_url: String,
+}
+
+/// What `--check` reports
+#[derive(Debug, Serialize)]
+pub struct UpdateCheck {
+    pub current: String,
+    pub latest: String,
+    pub update_available: bool,
+    pub url: String,
+    #[serde(skip)]
+    release: Option<Release>,
+}
+
+/// Compare the running version with the latest release
+pub fn check() -> Result<UpdateCheck> {
+    let body = download_text(LATEST_RELEASE).context("Failed to look up the latest release")?;
+    let release: Release =
+        serde_json::from_str(&body).context("Unexpected answer from the GitHub API")?;
+    let current = env!("CARGO_PKG_VERSION").to_string();
+    let latest = release.tag_name.trim_start_matches('v').to_string();
+    let update_available = is_newer(&latest, &current)?;
+    Ok(UpdateCheck {
+        current,
+        latest,
+        update_available,
+        url: release.html_url.clone(),
+        release: Some(release),
+    })
+}
+
+/// Download the release found by `check`, verify it and replace the running binary;
+/// returns where the binary was installed
+pub fn install(update: &UpdateCheck) -> Result<PathBuf> {
+    let release = update
+        .release
+        .as_ref()
+        .ok_or_else(|| anyhow!("No release to install"))?;
+    let name = asset_name()?;
+    let find = |name: &str| {
+        release
+            .assets
+            .iter()
+            .find(|asset| asset.name == name)
+            .ok_or_else(|| anyhow!("Release {} has no {name}", release.tag_name))
+    };
+    let archive_asset = find(name)?;
+    let checksum_asset = find(&format!("{name}.sha256"))?;
+
+    let work = std::env::temp_dir().join(format!("ziro-update-{}", std::process::id()));
+    std::fs::create_dir_all(&work)
+        .with_context(|| format!("Failed to create {}", work.display()))?;
+    let result = download_and_replace(archive_asset, checksum_asset, &work);
+    let _ = std::fs::remove_dir_all(&work);
+    result
+}
+
+fn download_and_replace(archive: &Asset, checksum: &Asset, work: &Path) -> Result<PathBuf> {
+    let archive_path = work.join(&archive.name);
+    download_file(&archive.browser_download_url, &archive_path)?;
+    // A file rather than text: PowerShell hands non-text downloads back as bytes
+    let checksum_path = work.join(&checksum.name);
+    download_file(&checksum.browser_download_url, &checksum_path)?;
+    let expected = std::fs::read_to_string(&checksum_path)
+        .with_context(|| format!("Failed to read {}", checksum_path.display()))?;
+    let expected = expected
+        .split_whitespace()
+        .next()
+        .filter(|hash| hash.len() == 64)
+        .ok_or_else(|| anyhow!("Malformed checksum file {}", checksum.name))?
+        .to_ascii_lowercase();
+    let bytes = std::fs::read(&archive_path)
+        .with_context(|| format!("Failed to read {}", archive_path.display()))?;
+    let actual = hex(&sha256(&bytes));
+    if actual != expected {
+        bail!(
+            "Checksum mismatch for {}: expected {expected}, got {actual}; nothing was replaced",
+            archive.name
+        );
+    }
+
+    extract(&archive_path, work)?;
+    let binary = work.join(if cfg!(windows) { "ziro.exe" } else { "ziro" });
+    if !binary.is_file() {
+        bail!("{} does not contain the ziro binary", archive.name);
+    }
+    replace_current_exe(&binary)
+}
+
+/// Release asset for this platform, as named by the release workflow
+fn asset_name() -> Result<&'static str> {
+    match (std::env::consts::OS, std::env::consts::ARCH) {
+        ("windows", "x86_64") => Ok("windows-x64.zip"),
+        ("linux", "x86_64") => Ok("linux-x64.zip"),
+        ("linux", "aarch64") => Ok("linux-aarch64.zip"),
+        ("macos", "aarch64") => Ok("macos-aarch64.zip"),
+        (os, arch) => bail!("No release builds for {os}/{arch}; update with cargo install ziro"),
+    }
+}
+
+/// `a` > `b` for dotted numeric versions (`0.0.27` > `0.0.26`)
+fn is_newer(a: &str, b: &str) -> Result<bool> {
+    let parse = |version: &str| -> Result<Vec<u64>> {
+        version
+            .split(['-', '+'])
+            .next()
+            .unwrap_or_default()
+            .split('.')
+            .map(|part| {
+                part.parse()
+                    .map_err(|_| anyhow!("Invalid version: {version}"))
+            })
+            .collect()
+    };
+    Ok(parse(a)? > parse(b)?)
+}
+
+fn download_command(url: &str, dest: Option<&Path>) -> Command {
+    if cfg!(windows) {
+        let mut script = format!(
+            "$ProgressPreference = 'SilentlyContinue'; Invoke-WebRequest -UseBasicParsing -Uri '{url}'"
+        );
+        match dest {
+            Some(dest) => script.push_str(&format!(" -OutFile '{}'", dest.display())),
+            None => script.push_str(" | Select-Object -ExpandProperty Content"),
+        }
+        let mut command = Command::new("powershell");
+        command.args(["-NoProfile", "-NonInteractive", "-Command", &script]);
+        command
+    } else {
+        let mut command = Command::new("curl");
+        command.args(["-fsSL", "-H", "User-Agent: ziro", url]);
+        if let Some(dest) = dest {
+            command.arg("-o").arg(dest);
+        }
+        command
+    }
+}
+
+fn download_text(url: &str) -> Result<String> {
+    let output = run_command_with_timeout(&mut download_command(url, None), DOWNLOAD_TIMEOUT)?;
+    if !output.status.success() {
+        bail!(
+            "Download of {url} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn download_file(url: &str, dest: &Path) -> Result<()> {
+    let output =
+        run_command_with_timeout(&mut download_command(url, Some(dest)), DOWNLOAD_TIMEOUT)?;
+    if !output.status.success() || !dest.is_file() {
+        bail!(
+            "Download of {url} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// `unzip` where installed, else `tar` (bsdtar reads zip files on macOS and Windows 10+)
+fn extract(archive: &Path, into: &Path) -> Result<()> {
+    let mut command = if find_program("unzip").is_some() {
+        let mut command = Command::new("unzip");
+        command.args(["-o", "-q"]).arg(archive).arg("-d").arg(into);
+        command
+    } else {
+        let mut command = Command::new("tar");
+        command.arg("-xf").arg(archive).arg("-C").arg(into);
+        command
+    };
+    let output = run_command_with_timeout(&mut command, DOWNLOAD_TIMEOUT)?;
+    if !output.status.success() {
+        bail!(
+            "Failed to unpack {}: {}",
+            archive.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Copy `new` next to the running binary, then rename it over it, so the binary is never
+/// half-written; Windows cannot replace a running executable, so it is moved aside first
+fn replace_current_exe(new: &Path) -> Result<PathBuf> {
+    let current = std::env::current_exe()
+        .and_then(|path| path.canonicalize())
+        .context("Cannot locate the running ziro binary")?;
+    let dir = current
+        .parent()
+        .ok_or_else(|| anyhow!("Cannot locate the running ziro binary"))?;
+    let staged = dir.join(".ziro-update");
+    std::fs::copy(new, &staged).with_context(|| {
+        format!(
+            "Failed to write to {} (try again with sudo, or as administrator)",
+            dir.display()
+        )
+    })?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o755))?;
+    }
+    // Left behind on Windows; the next update clears it
+    let aside = current.with_extension("old.exe");
+    if cfg!(windows) {
+        let _ = std::fs::remove_file(&aside);
+        std::fs::rename(&current, &aside)
+            .with_context(|| format!("Failed to move {} aside", current.display()))?;
+    }
+
+    if let Err(e) = std::fs::rename(&staged, &current) {
+        let _ = std::fs::remove_file(&staged);
+        if cfg!(windows) {
+            let _ = std::fs::rename(&aside, &current);
+        }
+        return Err(e).with_context(|| format!("Failed to replace {}", current.display()));
+    }
+    Ok(current)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn sha256(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(data).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sha256() {
+        assert_eq!(
+            hex(&sha256(b"")),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            hex(&sha256(b"abc")),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        // Two blocks
+        assert_eq!(
+            hex(&sha256(
+                b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"
+            )),
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+        );
+    }
+
+    #[test]
+    fn test_is_newer() {
+        assert!(is_newer("0.0.27", "0.0.26").unwrap());
+        assert!(is_newer("0.1.0", "0.0.99").unwrap());
+        assert!(!is_newer("0.0.26", "0.0.26").unwrap());
+        assert!(!is_newer("0.0.26-rc.1", "0.0.26").unwrap());
+        assert!(is_newer("v1", "0.0.1").is_err());
+    }
+}
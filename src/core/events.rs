@@ -0,0 +1,169 @@
+/// Newline-delimited JSON events, so other programs can follow ziro as an event source
+use crate::core::port::PortInfo;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Something that happened between two refreshes, tagged by `event` in the output
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    PortOpened {
+        port: u16,
+        pid: u32,
+        name: String,
+    },
+    PortClosed {
+        port: u16,
+        pid: u32,
+        name: String,
+    },
+    ProcKilled {
+        pid: u32,
+        name: Option<String>,
+        force: bool,
+    },
+    ThresholdCrossed {
+        pid: u32,
+        name: String,
+        reason: String,
+    },
+}
+
+#[derive(Serialize)]
+struct Record<'a> {
+    /// Unix time in milliseconds
+    timestamp: u128,
+    #[serde(flatten)]
+    event: &'a Event,
+}
+
+/// Write `event` as one JSON line and flush it, so readers see it right away
+pub fn write_event(out: &mut impl Write, event: &Event) -> io::Result<()> {
+    let record = Record {
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or_default(),
+        event,
+    };
+    serde_json::to_writer(&mut *out, &record)?;
+    writeln!(out)?;
+    out.flush()
+}
+
+/// Turns successive port listings into `port_opened` / `port_closed` events
+#[derive(Debug, Default)]
+pub struct PortWatcher {
+    /// `(port, pid)` → process name, from the previous listing; `None` before the first
+    known: Option<HashMap<(u16, u32), String>>,
+}
+
+impl PortWatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Events since the previous call; the first listing only sets the baseline
+    pub fn update(&mut self, ports: &[PortInfo]) -> Vec<Event> {
+        let current: HashMap<(u16, u32), String> = ports
+            .iter()
+            .map(|info| ((info.port, info.process.pid), info.process.name.clone()))
+            .collect();
+        let Some(previous) = self.known.replace(current.clone()) else {
+            return Vec::new();
+        };
+
+        let mut closed: Vec<_> = previous
+            .iter()
+            .filter(|(key, _)| !current.contains_key(key))
+            .collect();
+        let mut opened: Vec<_> = current
+            .iter()
+            .filter(|(key, _)| !previous.contains_key(key))
+            .collect();
+        closed.sort();
+        opened.sort();
+
+        let closed = closed
+            .into_iter()
+            .map(|(&(port, pid), name)| Event::PortClosed {
+                port,
+                pid,
+                name: name.clone(),
+            });
+        let opened = opened
+            .into_iter()
+            .map(|(&(port, pid), name)| Event::PortOpened {
+                port,
+                pid,
+                name: name.clone(),
+            });
+        closed.chain(opened).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::port::ProcessInfo;
+
+    fn listening(port: u16, pid: u32) -> PortInfo {
+        PortInfo {
+            port,
+            process: ProcessInfo {
+                pid,
+                name: format!("app{pid}"),
+                cmd: Vec::new(),
+                cpu_usage: 0.0,
+                memory: 0,
+            },
+        }
+    }
+
+    #[test]
+    fn test_port_watcher() {
+        let mut watcher = PortWatcher::new();
+        assert!(watcher.update(&[listening(3000, 1)]).is_empty());
+        assert_eq!(
+            watcher.update(&[listening(3000, 2), listening(8080, 3)]),
+            [
+                Event::PortClosed {
+                    port: 3000,
+                    pid: 1,
+                    name: "app1".to_string()
+                },
+                Event::PortOpened {
+                    port: 3000,
+                    pid: 2,
+                    name: "app2".to_string()
+                },
+                Event::PortOpened {
+                    port: 8080,
+                    pid: 3,
+                    name: "app3".to_string()
+                },
+            ]
+        );
+        assert!(
+            watcher
+                .update(&[listening(8080, 3), listening(3000, 2)])
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_write_event() {
+        let mut out = Vec::new();
+        let event = Event::ProcKilled {
+            pid: 42,
+            name: None,
+            force: true,
+        };
+        write_event(&mut out, &event).unwrap();
+        let line = String::from_utf8(out).unwrap();
+        assert!(line.ends_with("}\n") && line.lines().count() == 1);
+        assert!(line.contains("\"event\":\"proc_killed\",\"pid\":42"));
+    }
+}
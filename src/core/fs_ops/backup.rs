@@ -0,0 +1,89 @@
+/// Backup archive written while deleting
+use super::FileInfo;
+use anyhow::{Context, Result, anyhow};
+use ruzstd::encoding::{CompressionLevel, compress};
+use std::fs::File;
+use std::io::{BufWriter, PipeWriter, Write};
+use std::path::{Component, Path, PathBuf};
+use std::thread::{self, JoinHandle};
+
+/// Zstd-compressed tar archive that entries are streamed into right before deletion
+///
+/// Entries are stored under their absolute path without the root, so the archive
+/// can be restored with `tar --zstd -xf <archive> -C /`.
+pub struct BackupArchive {
+    path: PathBuf,
+    builder: tar::Builder<PipeWriter>,
+    compressor: JoinHandle<std::io::Result<()>>,
+}
+
+impl BackupArchive {
+    /// Create the archive file and start the background compressor
+    pub fn create(path: &Path) -> Result<Self> {
+        let file = File::create(path)
+            .with_context(|| format!("Failed to create backup archive: {}", path.display()))?;
+        let (reader, writer) = std::io::pipe().context("Failed to create backup pipe")?;
+
+        // The tar stream is produced on this thread and compressed on another
+        let compressor = thread::spawn(move || {
+            let mut out = BufWriter::new(file);
+            compress(reader, &mut out, CompressionLevel::Fastest);
+            out.flush()
+        });
+
+        let mut builder = tar::Builder::new(writer);
+        builder.follow_symlinks(false);
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            builder,
+            compressor,
+        })
+    }
+
+    /// Path of the archive file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append one entry (symlinks are stored as links, directories as headers only)
+    pub fn append(&mut self, file: &FileInfo) -> Result<()> {
+        let name = archive_name(&file.path)?;
+        self.builder
+            .append_path_with_name(&file.path, &name)
+            .with_context(|| format!("Failed to back up: {}", file.path.display()))
+    }
+
+    /// Write the tar trailer and wait for the compressor to flush the archive
+    pub fn finish(self) -> Result<()> {
+        let writer = self
+            .builder
+            .into_inner()
+            .context("Failed to finalize backup archive")?;
+        // Closing the pipe signals end of input to the compressor
+        drop(writer);
+
+        self.compressor
+            .join()
+            .map_err(|_| anyhow!("Backup compressor thread panicked"))?
+            .with_context(|| format!("Failed to write backup archive: {}", self.path.display()))
+    }
+}
+
+/// Build the in-archive name: absolute path with root/prefix stripped and `..` resolved
+fn archive_name(path: &Path) -> Result<PathBuf> {
+    let absolute = std::path::absolute(path)
+        .with_context(|| format!("Failed to resolve path: {}", path.display()))?;
+
+    let mut name = PathBuf::new();
+    for component in absolute.components() {
+        match component {
+            Component::Normal(part) => name.push(part),
+            Component::ParentDir => {
+                name.pop();
+            }
+            Component::Prefix(_) | Component::RootDir | Component::CurDir => {}
+        }
+    }
+    Ok(name)
+}
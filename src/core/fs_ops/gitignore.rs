@@ -0,0 +1,212 @@
+/// `.gitignore`-aware traversal built on the `ignore` crate's walker
+use super::{
+    CollectOptions, FileInfo, IgnoreFilter, ON_OTHER_FILESYSTEM, RemovalPlan, SymlinkPolicy,
+    collect_files_to_remove, device_id, follow_target, is_hidden,
+};
+use anyhow::{Result, bail};
+use ignore::WalkBuilder;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Entries under a root split by the ignore rules
+struct IgnoreWalk {
+    /// Entries kept by the rules, in walk order (parents before children)
+    kept: Vec<FileInfo>,
+    /// Top-most ignored entries (their contents were never visited)
+    ignored: Vec<PathBuf>,
+    /// Directories with something below them left out of `kept`
+    incomplete: HashSet<PathBuf>,
+}
+
+/// Collect a directory through the ignore rules, according to `opts.gitignore`
+pub(super) fn collect_dir(
+    root: &Path,
+    metadata: &fs::Metadata,
+    opts: &CollectOptions,
+    plan: &mut RemovalPlan,
+    follow: &mut Vec<PathBuf>,
+) -> Result<()> {
+    if opts.max_depth.is_some() {
+        bail!("--max-depth cannot be combined with gitignore filtering");
+    }
+
+    let walk = walk(root, metadata, opts, plan, follow)?;
+
+    match opts.gitignore {
+        IgnoreFilter::Off => unreachable!("gitignore walk requested without a filter"),
+        IgnoreFilter::SkipIgnored => {
+            let (dirs, files): (Vec<FileInfo>, Vec<FileInfo>) =
+                walk.kept.into_iter().partition(|f| f.is_dir);
+            plan.files.extend(files);
+            // Children before parents, and only directories whose whole subtree is scheduled
+            plan.files.extend(
+                dirs.into_iter()
+                    .rev()
+                    .filter(|d| !walk.incomplete.contains(&d.path)),
+            );
+            if !walk.incomplete.contains(root) {
+                plan.files.push(FileInfo {
+                    path: root.to_path_buf(),
+                    is_dir: true,
+                    size: 0,
+                    is_symlink: false,
+                    is_hidden: is_hidden(root, metadata),
+                    link_target: None,
+                });
+            }
+        }
+        IgnoreFilter::OnlyIgnored => {
+            let inner = CollectOptions {
+                gitignore: IgnoreFilter::Off,
+                ..opts.clone()
+            };
+            let ignored = collect_files_to_remove(&walk.ignored, &inner)?;
+            plan.files.extend(ignored.files);
+            plan.skipped.extend(ignored.skipped);
+            plan.hidden_skipped += ignored.hidden_skipped;
+            plan.left_out.extend(ignored.left_out);
+        }
+    }
+    Ok(())
+}
+
+fn walk(
+    root: &Path,
+    metadata: &fs::Metadata,
+    opts: &CollectOptions,
+    plan: &mut RemovalPlan,
+    follow: &mut Vec<PathBuf>,
+) -> Result<IgnoreWalk> {
+    let mut builder = WalkBuilder::new(root);
+    builder
+        .standard_filters(true)
+        .hidden(opts.skip_hidden)
+        .require_git(false)
+        .follow_links(false)
+        .same_file_system(opts.one_file_system);
+
+    let mut walk = IgnoreWalk {
+        kept: Vec::new(),
+        ignored: Vec::new(),
+        incomplete: HashSet::new(),
+    };
+    let mut seen: HashSet<PathBuf> = HashSet::new();
+    let mut dirs: Vec<PathBuf> = vec![root.to_path_buf()];
+
+    for entry in builder.build() {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                let path = error_path(&e).unwrap_or(root).to_path_buf();
+                if !opts.keep_going {
+                    return Err(anyhow::Error::new(e)
+                        .context(format!("Failed to walk directory: {}", root.display())));
+                }
+                mark_incomplete(&path, root, &mut walk.incomplete);
+                walk.incomplete.insert(path.clone());
+                plan.skipped.push((path, e.into()));
+                continue;
+            }
+        };
+        if entry.depth() == 0 {
+            continue;
+        }
+
+        let path = entry.path().to_path_buf();
+        let entry_meta = match entry.metadata() {
+            Ok(meta) => meta,
+            Err(e) if opts.keep_going => {
+                mark_incomplete(&path, root, &mut walk.incomplete);
+                plan.skipped.push((path, e.into()));
+                continue;
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let is_symlink = entry.path_is_symlink();
+        let is_dir = entry_meta.is_dir() && !is_symlink;
+        seen.insert(path.clone());
+
+        if is_symlink {
+            match opts.symlinks {
+                SymlinkPolicy::Skip => {
+                    mark_incomplete(&path, root, &mut walk.incomplete);
+                    continue;
+                }
+                SymlinkPolicy::Follow => follow.extend(follow_target(&path)),
+                SymlinkPolicy::KeepTarget => {}
+            }
+        }
+        if is_dir {
+            dirs.push(path.clone());
+        }
+
+        walk.kept.push(FileInfo {
+            is_hidden: is_hidden(&path, &entry_meta),
+            link_target: if is_symlink {
+                fs::read_link(&path).ok()
+            } else {
+                None
+            },
+            path,
+            is_dir,
+            size: if is_dir { 0 } else { entry_meta.len() },
+            is_symlink,
+        });
+    }
+
+    // Whatever a visited directory holds but the walker did not yield was filtered out
+    let root_dev = if opts.one_file_system {
+        device_id(root, metadata)
+    } else {
+        None
+    };
+    for dir in dirs {
+        let Ok(read_dir) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for child in read_dir.flatten() {
+            let path = child.path();
+            if seen.contains(&path) {
+                continue;
+            }
+            mark_incomplete(&path, root, &mut walk.incomplete);
+
+            let Ok(child_meta) = path.symlink_metadata() else {
+                continue;
+            };
+            if opts.skip_hidden && is_hidden(&path, &child_meta) {
+                plan.hidden_skipped += 1;
+            } else if root_dev.is_some()
+                && child_meta.is_dir()
+                && device_id(&path, &child_meta) != root_dev
+            {
+                plan.left_out.push((path, ON_OTHER_FILESYSTEM));
+            } else if !walk.incomplete.contains(&path) {
+                walk.ignored.push(path);
+            }
+        }
+    }
+
+    Ok(walk)
+}
+
+/// Mark every directory between `path` and `root` (inclusive) as incomplete
+fn mark_incomplete(path: &Path, root: &Path, incomplete: &mut HashSet<PathBuf>) {
+    for ancestor in path.ancestors().skip(1) {
+        if !ancestor.starts_with(root) {
+            break;
+        }
+        incomplete.insert(ancestor.to_path_buf());
+    }
+}
+
+fn error_path(err: &ignore::Error) -> Option<&Path> {
+    match err {
+        ignore::Error::WithPath { path, .. } => Some(path),
+        ignore::Error::WithDepth { err, .. } | ignore::Error::WithLineNumber { err, .. } => {
+            error_path(err)
+        }
+        _ => None,
+    }
+}
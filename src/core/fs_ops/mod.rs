@@ -1,211 +1,997 @@
+use crate::core::process::{FileLockProcess, inspect_file_locks};
+use crate::error::ZiroError;
+use crate::platform::elevation::{self, Refusal, Refused};
 use anyhow::{Context, Result, anyhow};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fs;
 use std::path::{Path, PathBuf};
-
-/// Windows deletion retry parameters
-#[cfg(target_os = "windows")]
-const RETRY_MAX_ATTEMPTS: u32 = 5;
-#[cfg(target_os = "windows")]
-const RETRY_INITIAL_WAIT_MS: u64 = 100;
-#[cfg(target_os = "windows")]
-const RETRY_MAX_WAIT_MS: u64 = 1000;
-
-/// Force kill all processes locking a file/directory (Windows only)
-#[cfg(target_os = "windows")]
-fn force_kill_lockers(path: &Path) {
-    use crate::core::process::{find_processes_by_file, kill_process_force};
-    if let Ok(pids) = find_processes_by_file(path) {
-        for pid in pids {
-            // Best-effort kill; ignore if process already gone
-            let _ = kill_process_force(pid);
-        }
+use std::thread;
+use std::time::{Duration, Instant};
+
+mod backup;
+mod gitignore;
+mod pending;
+mod temp;
+mod trash;
+mod usage;
+
+pub use backup::BackupArchive;
+pub use pending::{PENDING_DIR, PendingDelete, defer_locked, locked_entries};
+pub use temp::{TempEntry, TempStatus, scan_temp_dirs};
+pub use trash::{TRASH_JOURNAL_LEN, TrashJournal, TrashRecord, restore_from_trash};
+pub use usage::{
+    DiskUsage, LargeFile, LargestFiles, LargestFilter, UsageNode, disk_usage, largest_files,
+    parse_age,
+};
+
+/// Default deletion retry parameters
+pub const DEFAULT_RETRIES: u32 = 5;
+pub const DEFAULT_RETRY_DELAY_MS: u64 = 100;
+/// Backoff never waits longer than this (unless the initial delay is already longer)
+const RETRY_MAX_WAIT: Duration = Duration::from_millis(1000);
+
+/// Force kill the processes locking a file/directory, returning the PIDs terminated
+///
+/// Best effort: holders that cannot be found or killed are left for the next retry.
+fn kill_lockers(path: &Path) -> Vec<u32> {
+    use crate::core::process::{find_processes_by_file, kill_processes_force};
+
+    let me = std::process::id();
+    let pids: Vec<u32> = find_processes_by_file(path)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|&pid| pid != me)
+        .collect();
+    let killed: Vec<u32> = kill_processes_force(&pids)
+        .into_iter()
+        .filter(|report| report.result.is_ok())
+        .map(|report| report.pid)
+        .collect();
+    if !killed.is_empty() {
+        tracing::info!(path = %path.display(), ?killed, "killed locking processes");
     }
+    killed
 }
 
 #[derive(Debug, Clone)]
 pub struct FileInfo {
     pub path: PathBuf,
     pub is_dir: bool,
+    /// Bytes; for a directory, the total of the files collected below it
     pub size: u64,
     pub is_symlink: bool,
+    /// Dotfile, or hidden/system attribute on Windows
+    pub is_hidden: bool,
+    /// Where a symlink points (as stored in the link)
+    pub link_target: Option<PathBuf>,
+}
+
+/// Classified reason a deletion failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeletionError {
+    NotFound,
+    PermissionDenied,
+    FileLocked,
+    DirectoryNotEmpty,
+    Other,
+}
+
+impl DeletionError {
+    /// Classify a deletion error by the first IO error in its chain
+    pub fn classify(err: &anyhow::Error) -> Self {
+        use std::io::ErrorKind;
+
+        let Some(io_err) = err
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<std::io::Error>())
+        else {
+            return Self::Other;
+        };
+
+        // ERROR_SHARING_VIOLATION / ERROR_LOCK_VIOLATION surface as PermissionDenied on Windows
+        if cfg!(target_os = "windows") && matches!(io_err.raw_os_error(), Some(32) | Some(33)) {
+            return Self::FileLocked;
+        }
+
+        match io_err.kind() {
+            ErrorKind::NotFound => Self::NotFound,
+            ErrorKind::PermissionDenied => Self::PermissionDenied,
+            ErrorKind::DirectoryNotEmpty => Self::DirectoryNotEmpty,
+            ErrorKind::ResourceBusy | ErrorKind::ExecutableFileBusy => Self::FileLocked,
+            _ => Self::Other,
+        }
+    }
+
+    /// Whether waiting and trying again can make the deletion succeed
+    pub fn is_retryable(self) -> bool {
+        matches!(self, Self::FileLocked)
+    }
 }
 
 /// Validate that paths exist
 pub fn validate_paths(paths: &[PathBuf]) -> Result<()> {
     for path in paths {
         if !path.exists() {
-            return Err(anyhow!("Path does not exist: {}", path.display()));
+            return Err(
+                ZiroError::NotFound(format!("Path does not exist: {}", path.display())).into(),
+            );
         }
     }
     Ok(())
 }
 
+/// The first of `paths` that is, contains or lies inside one of `protected`, with that entry
+pub fn find_protected<'a>(
+    paths: &'a [PathBuf],
+    protected: &'a [PathBuf],
+) -> Option<(&'a Path, &'a Path)> {
+    let resolve = |path: &Path| real_location(path).or_else(|| std::path::absolute(path).ok());
+    let guards: Vec<(&Path, PathBuf)> = protected
+        .iter()
+        .filter_map(|guard| Some((guard.as_path(), resolve(guard)?)))
+        .collect();
+    paths.iter().find_map(|path| {
+        let target = resolve(path)?;
+        guards
+            .iter()
+            .find(|(_, guard)| target.starts_with(guard) || guard.starts_with(&target))
+            .map(|&(guard, _)| (path.as_path(), guard))
+    })
+}
+
+/// Parse a path list as produced by `find`/`fd`/`rg -l`
+///
+/// Entries are NUL-separated if the input contains any NUL byte (`find -print0`),
+/// newline-separated otherwise. Empty entries are ignored.
+pub fn parse_path_list(input: &[u8]) -> Vec<PathBuf> {
+    let separator = if input.contains(&0) { 0 } else { b'\n' };
+
+    input
+        .split(|&b| b == separator)
+        .map(|entry| {
+            if separator == b'\n' {
+                entry.strip_suffix(b"\r").unwrap_or(entry)
+            } else {
+                entry
+            }
+        })
+        .filter(|entry| !entry.is_empty())
+        .map(bytes_to_path)
+        .collect()
+}
+
+#[cfg(unix)]
+fn bytes_to_path(bytes: &[u8]) -> PathBuf {
+    use std::os::unix::ffi::OsStrExt;
+    PathBuf::from(std::ffi::OsStr::from_bytes(bytes))
+}
+
+#[cfg(not(unix))]
+fn bytes_to_path(bytes: &[u8]) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(bytes).into_owned())
+}
+
+/// How `.gitignore` rules affect collection
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IgnoreFilter {
+    /// Walk everything
+    #[default]
+    Off,
+    /// Leave ignored entries out
+    SkipIgnored,
+    /// Collect only ignored entries (build outputs, caches)
+    OnlyIgnored,
+}
+
+/// What to do with symlinks met during collection
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SymlinkPolicy {
+    /// Remove the link itself and leave its target alone
+    #[default]
+    KeepTarget,
+    /// Remove the link and also collect what it points to
+    Follow,
+    /// Leave symlinks (and the directories containing them) in place
+    Skip,
+}
+
+/// Options controlling how removal targets are collected
+#[derive(Debug, Clone, Default)]
+pub struct CollectOptions {
+    /// Descend into directories
+    pub recursive: bool,
+    /// Do not cross into directories mounted from another filesystem
+    pub one_file_system: bool,
+    /// Deepest level to collect (1 = direct children); directories with deeper contents are kept
+    pub max_depth: Option<usize>,
+    /// Record unreadable entries and keep walking instead of aborting
+    pub keep_going: bool,
+    /// Leave hidden entries below the given paths out of the plan
+    pub skip_hidden: bool,
+    /// Apply `.gitignore`/`.ignore` rules below the given paths
+    pub gitignore: IgnoreFilter,
+    pub symlinks: SymlinkPolicy,
+}
+
+/// Paths that could not be walked, with the reason
+pub type SkippedEntries = Vec<(PathBuf, anyhow::Error)>;
+
+/// Paths deliberately not collected, with the reason (e.g. `on a different filesystem`)
+pub type LeftOutEntries = Vec<(PathBuf, &'static str)>;
+
+/// Result of collection: what will be removed, and what could not be walked
+#[derive(Debug, Default)]
+pub struct RemovalPlan {
+    pub files: Vec<FileInfo>,
+    /// Entries skipped because of walk errors (only with `keep_going`)
+    pub skipped: SkippedEntries,
+    /// Hidden entries left out because of `skip_hidden`
+    pub hidden_skipped: usize,
+    /// Symlinks and mount points left out, for the caller to report
+    pub left_out: LeftOutEntries,
+    /// Targets collected through `SymlinkPolicy::Follow`, with the root whose walk led there
+    pub followed: Vec<(PathBuf, PathBuf)>,
+}
+
+impl RemovalPlan {
+    /// Where `path` was collected from: the root that led to it for a followed target,
+    /// `path` itself otherwise
+    pub fn origin_of<'a>(&'a self, path: &'a Path) -> &'a Path {
+        self.followed
+            .iter()
+            .find(|(target, _)| path.starts_with(target))
+            .map_or(path, |(_, root)| root.as_path())
+    }
+}
+
+/// Per-root state shared by the recursive walk
+struct WalkContext<'a> {
+    opts: &'a CollectOptions,
+    root_dev: Option<u64>,
+    /// Resolved targets of symlinks to collect next (`SymlinkPolicy::Follow`)
+    follow: Vec<PathBuf>,
+}
+
+impl WalkContext<'_> {
+    /// Under `keep_going`, record a walk error and return None; otherwise propagate it
+    fn tolerate<T>(
+        &self,
+        path: &Path,
+        result: Result<T>,
+        plan: &mut RemovalPlan,
+    ) -> Result<Option<T>> {
+        match result {
+            Ok(value) => Ok(Some(value)),
+            Err(e) if self.opts.keep_going => {
+                plan.skipped.push((path.to_path_buf(), e));
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Reason recorded for directories skipped by `one_file_system`
+const ON_OTHER_FILESYSTEM: &str = "on a different filesystem";
+
 /// Collect file/directory info for removal
-pub fn collect_files_to_remove(paths: &[PathBuf], recursive: bool) -> Result<Vec<FileInfo>> {
-    let mut files = Vec::new();
+pub fn collect_files_to_remove(paths: &[PathBuf], opts: &CollectOptions) -> Result<RemovalPlan> {
+    let mut plan = RemovalPlan::default();
+    // Each path to walk, with the root it was reached from
+    let mut pending: VecDeque<(PathBuf, PathBuf)> =
+        paths.iter().map(|p| (p.clone(), p.clone())).collect();
+    // Where the roots really live, so followed links into them are not collected twice
+    let mut collected: Vec<PathBuf> = paths.iter().filter_map(|p| real_location(p)).collect();
+
+    while let Some((path, origin)) = pending.pop_front() {
+        let mut follow = Vec::new();
+        collect_root(&path, opts, &mut plan, &mut follow)?;
+
+        for target in follow {
+            if !collected.iter().any(|root| target.starts_with(root)) {
+                collected.push(target.clone());
+                plan.followed.push((target.clone(), origin.clone()));
+                pending.push_back((target, origin.clone()));
+            }
+        }
+    }
 
-    for path in paths {
-        let metadata = path
-            .symlink_metadata()
-            .with_context(|| format!("Failed to get file metadata: {}", path.display()))?;
-        let is_symlink = metadata.file_type().is_symlink();
-        let is_dir = metadata.is_dir() && !is_symlink;
+    aggregate_dir_sizes(&mut plan.files);
+    Ok(plan)
+}
 
-        if is_dir {
-            if recursive {
-                collect_dir_files(path, &mut files)?;
-                files.push(FileInfo {
-                    path: path.clone(),
-                    is_dir: true,
-                    size: 0,
-                    is_symlink: false,
-                });
-            } else {
-                // Non-recursive mode: only allow empty directories
-                if path.read_dir()?.next().is_some() {
-                    return Err(anyhow!(
-                        "Directory requires -r/--recursive flag: {}",
-                        path.display()
-                    ));
-                }
-                files.push(FileInfo {
-                    path: path.clone(),
+/// Give each collected directory the total size of the entries collected below it
+fn aggregate_dir_sizes(files: &mut [FileInfo]) {
+    let mut order: Vec<usize> = (0..files.len()).collect();
+    // Deepest first, so a directory's total is complete before it is passed up
+    order.sort_by_key(|&i| std::cmp::Reverse(files[i].path.components().count()));
+
+    let mut below: HashMap<PathBuf, u64> = HashMap::new();
+    for i in order {
+        let file = &mut files[i];
+        if file.is_dir {
+            file.size = below.remove(&file.path).unwrap_or(0);
+        }
+        if let Some(parent) = file.path.parent() {
+            *below.entry(parent.to_path_buf()).or_default() += file.size;
+        }
+    }
+}
+
+/// Canonical location of a path without resolving the path itself if it is a symlink
+fn real_location(path: &Path) -> Option<PathBuf> {
+    let absolute = std::path::absolute(path).ok()?;
+    match (absolute.parent(), absolute.file_name()) {
+        (Some(parent), Some(name)) => Some(parent.canonicalize().ok()?.join(name)),
+        _ => absolute.canonicalize().ok(),
+    }
+}
+
+/// Resolve a symlink for `SymlinkPolicy::Follow` (broken links resolve to nothing)
+fn follow_target(path: &Path) -> Option<PathBuf> {
+    path.canonicalize().ok()
+}
+
+fn collect_root(
+    path: &Path,
+    opts: &CollectOptions,
+    plan: &mut RemovalPlan,
+    follow: &mut Vec<PathBuf>,
+) -> Result<()> {
+    let metadata = path
+        .symlink_metadata()
+        .with_context(|| format!("Failed to get file metadata: {}", path.display()))?;
+    let is_symlink = metadata.file_type().is_symlink();
+    let is_dir = metadata.is_dir() && !is_symlink;
+    // Explicitly named paths are never filtered, only flagged
+    let is_hidden = is_hidden(path, &metadata);
+
+    if is_symlink {
+        match opts.symlinks {
+            SymlinkPolicy::Skip => {
+                plan.left_out.push((path.to_path_buf(), "symlink"));
+                return Ok(());
+            }
+            SymlinkPolicy::Follow => follow.extend(follow_target(path)),
+            SymlinkPolicy::KeepTarget => {}
+        }
+    }
+
+    if is_dir {
+        if opts.recursive && opts.gitignore != IgnoreFilter::Off {
+            gitignore::collect_dir(path, &metadata, opts, plan, follow)?;
+        } else if opts.recursive {
+            let mut walk = WalkContext {
+                opts,
+                root_dev: if opts.one_file_system {
+                    device_id(path, &metadata)
+                } else {
+                    None
+                },
+                follow: Vec::new(),
+            };
+            // Keep the directory itself only if its whole subtree is scheduled
+            if collect_dir_files(path, 1, &mut walk, plan)? {
+                plan.files.push(FileInfo {
+                    path: path.to_path_buf(),
                     is_dir: true,
                     size: 0,
                     is_symlink: false,
+                    is_hidden,
+                    link_target: None,
                 });
             }
+            follow.append(&mut walk.follow);
         } else {
-            files.push(FileInfo {
-                path: path.clone(),
-                is_dir: false,
-                size: metadata.len(),
-                is_symlink,
+            // Non-recursive mode: only allow empty directories
+            if path.read_dir()?.next().is_some() {
+                return Err(anyhow!(
+                    "Directory requires -r/--recursive flag: {}",
+                    path.display()
+                ));
+            }
+            plan.files.push(FileInfo {
+                path: path.to_path_buf(),
+                is_dir: true,
+                size: 0,
+                is_symlink: false,
+                is_hidden,
+                link_target: None,
             });
         }
+    } else {
+        plan.files.push(FileInfo {
+            path: path.to_path_buf(),
+            is_dir: false,
+            size: metadata.len(),
+            is_symlink,
+            is_hidden,
+            link_target: if is_symlink {
+                fs::read_link(path).ok()
+            } else {
+                None
+            },
+        });
     }
 
-    Ok(files)
+    Ok(())
 }
 
 /// Recursively collect directory contents (does not follow symlinks)
-fn collect_dir_files(dir: &Path, files: &mut Vec<FileInfo>) -> Result<()> {
-    for entry in
-        fs::read_dir(dir).with_context(|| format!("Failed to read directory: {}", dir.display()))?
-    {
+///
+/// `depth` is the depth of the entries of `dir` (1 for the children of a root).
+/// Returns false if anything below `dir` was skipped, so `dir` itself must be kept.
+fn collect_dir_files(
+    dir: &Path,
+    depth: usize,
+    walk: &mut WalkContext,
+    plan: &mut RemovalPlan,
+) -> Result<bool> {
+    // `max_depth` 0: nothing below the root
+    if walk.opts.max_depth.is_some_and(|max| depth > max) {
+        return Ok(false);
+    }
+    let read_dir =
+        fs::read_dir(dir).with_context(|| format!("Failed to read directory: {}", dir.display()));
+    let Some(entries) = walk.tolerate(dir, read_dir, plan)? else {
+        return Ok(false);
+    };
+
+    let mut complete = true;
+
+    for entry in entries {
         let entry =
-            entry.with_context(|| format!("Failed to read directory entry: {}", dir.display()))?;
+            entry.with_context(|| format!("Failed to read directory entry: {}", dir.display()));
+        let Some(entry) = walk.tolerate(dir, entry, plan)? else {
+            complete = false;
+            continue;
+        };
         let path = entry.path();
         let metadata = path
             .symlink_metadata()
-            .with_context(|| format!("Failed to get file metadata: {}", path.display()))?;
+            .with_context(|| format!("Failed to get file metadata: {}", path.display()));
+        let Some(metadata) = walk.tolerate(&path, metadata, plan)? else {
+            complete = false;
+            continue;
+        };
         let is_symlink = metadata.file_type().is_symlink();
         let is_dir = metadata.is_dir() && !is_symlink;
+        let is_hidden = is_hidden(&path, &metadata);
+
+        if is_hidden && walk.opts.skip_hidden {
+            plan.hidden_skipped += 1;
+            complete = false;
+            continue;
+        }
+
+        if is_symlink {
+            match walk.opts.symlinks {
+                SymlinkPolicy::Skip => {
+                    complete = false;
+                    continue;
+                }
+                SymlinkPolicy::Follow => walk.follow.extend(follow_target(&path)),
+                SymlinkPolicy::KeepTarget => {}
+            }
+        }
 
         if is_dir {
-            collect_dir_files(&path, files)?;
-            files.push(FileInfo {
-                path,
-                is_dir: true,
-                size: 0,
-                is_symlink: false,
-            });
+            if walk.root_dev.is_some() && device_id(&path, &metadata) != walk.root_dev {
+                plan.left_out.push((path, ON_OTHER_FILESYSTEM));
+                complete = false;
+                continue;
+            }
+
+            // At the depth limit only empty directories can be removed
+            if walk.opts.max_depth.is_some_and(|max| depth >= max) {
+                let is_empty = path
+                    .read_dir()
+                    .map(|mut entries| entries.next().is_none())
+                    .with_context(|| format!("Failed to read directory: {}", path.display()));
+                if walk.tolerate(&path, is_empty, plan)? == Some(true) {
+                    plan.files.push(FileInfo {
+                        path,
+                        is_dir: true,
+                        size: 0,
+                        is_symlink: false,
+                        is_hidden,
+                        link_target: None,
+                    });
+                } else {
+                    complete = false;
+                }
+                continue;
+            }
+
+            if collect_dir_files(&path, depth + 1, walk, plan)? {
+                plan.files.push(FileInfo {
+                    path,
+                    is_dir: true,
+                    size: 0,
+                    is_symlink: false,
+                    is_hidden,
+                    link_target: None,
+                });
+            } else {
+                complete = false;
+            }
         } else {
-            files.push(FileInfo {
+            let link_target = if is_symlink {
+                fs::read_link(&path).ok()
+            } else {
+                None
+            };
+            plan.files.push(FileInfo {
                 path,
                 is_dir: false,
                 size: metadata.len(),
                 is_symlink,
+                is_hidden,
+                link_target,
             });
         }
     }
 
-    Ok(())
+    Ok(complete)
 }
 
-/// Execute deletion
-pub fn remove_files(files: &[FileInfo], dry_run: bool, anyway: bool) -> Vec<(PathBuf, Result<()>)> {
-    // Windows special handling: try bulk deletion
-    #[cfg(target_os = "windows")]
-    if let Some(results) = try_windows_bulk_remove(files, dry_run, anyway) {
-        return results;
-    }
+/// Whether an entry is hidden: a dotfile, or hidden/system attribute on Windows
+pub fn is_hidden(path: &Path, metadata: &fs::Metadata) -> bool {
+    let dotfile = path
+        .file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+    dotfile || has_hidden_attribute(metadata)
+}
 
-    // Generic individual deletion logic
-    remove_files_individually(files, dry_run, anyway)
+#[cfg(target_os = "windows")]
+fn has_hidden_attribute(metadata: &fs::Metadata) -> bool {
+    use std::os::windows::fs::MetadataExt;
+    use windows_sys::Win32::Storage::FileSystem::{FILE_ATTRIBUTE_HIDDEN, FILE_ATTRIBUTE_SYSTEM};
+
+    metadata.file_attributes() & (FILE_ATTRIBUTE_HIDDEN | FILE_ATTRIBUTE_SYSTEM) != 0
+}
+
+#[cfg(not(target_os = "windows"))]
+fn has_hidden_attribute(_metadata: &fs::Metadata) -> bool {
+    false
+}
+
+/// Identify the filesystem a directory lives on (`st_dev`)
+#[cfg(unix)]
+fn device_id(_path: &Path, metadata: &fs::Metadata) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    Some(metadata.dev())
 }
 
-/// Windows special handling: try bulk deletion of root directory
+/// Identify the volume a directory lives on (volume serial number)
 #[cfg(target_os = "windows")]
-fn try_windows_bulk_remove(
-    files: &[FileInfo],
-    dry_run: bool,
-    anyway: bool,
-) -> Option<Vec<(PathBuf, Result<()>)>> {
-    let root_dir = files.iter().find(|f| {
-        f.is_dir
-            && !files
-                .iter()
-                .any(|other| other.path != f.path && f.path.starts_with(&other.path))
-    })?;
+fn device_id(path: &Path, _metadata: &fs::Metadata) -> Option<u64> {
+    use std::os::windows::fs::OpenOptionsExt;
+    use std::os::windows::io::AsRawHandle;
+    use windows_sys::Win32::Storage::FileSystem::{
+        BY_HANDLE_FILE_INFORMATION, FILE_FLAG_BACKUP_SEMANTICS, GetFileInformationByHandle,
+    };
+
+    // Directories can only be opened with backup semantics; no access rights are needed
+    let handle = fs::OpenOptions::new()
+        .access_mode(0)
+        .custom_flags(FILE_FLAG_BACKUP_SEMANTICS)
+        .open(path)
+        .ok()?;
 
-    if dry_run {
-        return Some(vec![(root_dir.path.clone(), Ok(()))]);
+    let mut info: BY_HANDLE_FILE_INFORMATION = unsafe { std::mem::zeroed() };
+    if unsafe { GetFileInformationByHandle(handle.as_raw_handle(), &mut info) } == 0 {
+        return None;
     }
+    Some(u64::from(info.dwVolumeSerialNumber))
+}
 
-    // Try to use remove_dir_all to delete the entire directory tree, with retries
-    let mut wait_ms = RETRY_INITIAL_WAIT_MS;
-    let mut last_err = None;
-    let mut success = false;
+#[cfg(not(any(unix, target_os = "windows")))]
+fn device_id(_path: &Path, _metadata: &fs::Metadata) -> Option<u64> {
+    None
+}
 
-    for attempt in 0..=RETRY_MAX_ATTEMPTS {
-        match remove_dir_all_with_symlinks(&root_dir.path) {
-            Ok(_) => {
-                success = true;
-                break;
-            }
-            Err(e) => {
-                last_err = Some(e);
+/// Retry policy for entries that are temporarily locked
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Retries after the first failed attempt
+    pub retries: u32,
+    /// Wait before the first retry; doubles on each further retry
+    pub delay: Duration,
+}
 
-                let io_err = last_err
-                    .as_ref()
-                    .and_then(|e| e.downcast_ref::<std::io::Error>());
-                let should_retry = io_err.is_some_and(is_retryable_error);
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            retries: DEFAULT_RETRIES,
+            delay: Duration::from_millis(DEFAULT_RETRY_DELAY_MS),
+        }
+    }
+}
 
-                if !should_retry || attempt == RETRY_MAX_ATTEMPTS {
-                    break;
+impl RetryPolicy {
+    /// Run `op`, retrying with exponential backoff while it fails with a retryable error
+    ///
+    /// `on_retry` is called before each wait with the 1-based retry number.
+    fn run<T>(
+        &self,
+        mut op: impl FnMut() -> Result<T>,
+        mut on_retry: impl FnMut(u32),
+    ) -> Result<T> {
+        let max_wait = self.delay.max(RETRY_MAX_WAIT);
+        let mut wait = self.delay;
+        let mut attempt = 0;
+
+        loop {
+            match op() {
+                Ok(value) => return Ok(value),
+                Err(err)
+                    if attempt < self.retries && DeletionError::classify(&err).is_retryable() =>
+                {
+                    attempt += 1;
+                    on_retry(attempt);
+                    thread::sleep(wait);
+                    wait = (wait * 2).min(max_wait);
                 }
-
-                if anyway {
-                    force_kill_lockers(&root_dir.path);
+                Err(err) if attempt > 0 => {
+                    return Err(err.context(format!("Gave up after {attempt} retries")));
                 }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+/// Options controlling how collected entries are deleted
+#[derive(Debug, Clone, Default)]
+pub struct RemovalOptions {
+    /// Only report what would be deleted
+    pub dry_run: bool,
+    /// Kill processes that keep entries locked, then retry
+    pub anyway: bool,
+    /// Retry policy for locked entries
+    pub retry: RetryPolicy,
+    /// Stop at the first entry that fails to delete
+    pub fail_fast: bool,
+    /// Move top-level entries to the OS trash instead of deleting them
+    pub trash: bool,
+}
+
+/// How the removal of one entry ended
+#[derive(Debug)]
+pub enum RemovalOutcome {
+    /// Deleted, or with a dry run, would have been
+    Removed,
+    /// Moved to the OS trash
+    Trashed,
+    /// Not attempted: the run stopped at an earlier failure (`fail_fast`)
+    Skipped,
+    Failed {
+        kind: DeletionError,
+        error: anyhow::Error,
+    },
+}
+
+impl RemovalOutcome {
+    /// Whether the entry is gone from its path
+    pub fn is_done(&self) -> bool {
+        matches!(self, Self::Removed | Self::Trashed)
+    }
+
+    pub fn error(&self) -> Option<&anyhow::Error> {
+        match self {
+            Self::Failed { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
 
-                eprintln!("  Retrying ({}/{})...", attempt + 1, RETRY_MAX_ATTEMPTS);
+#[derive(Debug)]
+pub struct RemovalEntry {
+    pub path: PathBuf,
+    pub outcome: RemovalOutcome,
+    /// Processes killed to unlock the entry (`anyway`)
+    pub killed: Vec<u32>,
+}
 
-                std::thread::sleep(std::time::Duration::from_millis(wait_ms));
-                wait_ms = (wait_ms * 2).min(RETRY_MAX_WAIT_MS);
+impl RemovalEntry {
+    /// An attempt on `path` that ended with `result`, reported as `done` when it succeeded
+    ///
+    /// Refusals for lack of permissions get the reason attached.
+    fn settled(path: PathBuf, result: Result<()>, done: RemovalOutcome) -> Self {
+        let outcome = match result {
+            Ok(()) => done,
+            Err(e) => {
+                let error = explain_denied(&path, e);
+                RemovalOutcome::Failed {
+                    kind: DeletionError::classify(&error),
+                    error,
+                }
             }
+        };
+        Self {
+            path,
+            outcome,
+            killed: Vec::new(),
         }
     }
+}
+
+/// What `remove_files` did, entry by entry
+#[derive(Debug, Default)]
+pub struct RemovalReport {
+    pub entries: Vec<RemovalEntry>,
+    pub dry_run: bool,
+    /// Bytes in files that are gone, or would be with a dry run
+    pub bytes_freed: u64,
+    /// Non-directory entries counted in `bytes_freed`
+    pub files_freed: usize,
+    pub elapsed: Duration,
+}
+
+impl RemovalReport {
+    /// Entries removed or trashed
+    pub fn succeeded(&self) -> usize {
+        self.entries.iter().filter(|e| e.outcome.is_done()).count()
+    }
+
+    /// Entries left in place without an attempt
+    pub fn skipped(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|e| matches!(e.outcome, RemovalOutcome::Skipped))
+            .count()
+    }
+
+    /// Failed entries with their errors
+    pub fn failures(&self) -> impl Iterator<Item = (&Path, &anyhow::Error)> {
+        self.entries
+            .iter()
+            .filter_map(|e| Some((e.path.as_path(), e.outcome.error()?)))
+    }
+
+    /// Recount the bytes freed, e.g. after `defer_locked` settled more entries
+    ///
+    /// A file counts if it or any of its ancestors is gone, so a bulk directory removal
+    /// still accounts for its contents.
+    pub fn tally(&mut self, files: &[FileInfo]) {
+        let gone: HashSet<&Path> = self
+            .entries
+            .iter()
+            .filter(|e| e.outcome.is_done())
+            .map(|e| e.path.as_path())
+            .collect();
+        (self.bytes_freed, self.files_freed) = files
+            .iter()
+            .filter(|f| !f.is_dir && f.path.ancestors().any(|p| gone.contains(p)))
+            .fold((0, 0), |(bytes, count), f| (bytes + f.size, count + 1));
+    }
+}
 
-    if success {
-        Some(vec![(root_dir.path.clone(), Ok(()))])
+/// Execute deletion
+///
+/// With a backup archive, every entry is archived right before it is deleted; an entry
+/// that cannot be archived is left in place and reported as failed.
+pub fn remove_files(
+    files: &[FileInfo],
+    opts: &RemovalOptions,
+    backup: Option<&mut BackupArchive>,
+) -> RemovalReport {
+    let start = Instant::now();
+    let mut report = RemovalReport {
+        entries: remove_entries(files, opts, backup),
+        dry_run: opts.dry_run,
+        ..Default::default()
+    };
+    report.elapsed = start.elapsed();
+    report.tally(files);
+    report
+}
+
+fn remove_entries(
+    files: &[FileInfo],
+    opts: &RemovalOptions,
+    backup: Option<&mut BackupArchive>,
+) -> Vec<RemovalEntry> {
+    if opts.trash && !opts.dry_run {
+        return trash::trash_files(files);
+    }
+
+    // Windows special handling: bulk deletion of whole trees (never when archiving or
+    // previewing, it skips the per-entry walk)
+    #[cfg(target_os = "windows")]
+    if backup.is_none() && !opts.dry_run {
+        let (mut entries, rest) = bulk_remove_trees(files, opts, remove_dir_all_with_symlinks);
+        entries.extend(remove_files_individually(&rest, opts, None));
+        return entries;
+    }
+
+    // Generic individual deletion logic
+    remove_files_individually(files, opts, backup)
+}
+
+/// Attach the reason to a deletion of `path` refused for lack of permissions
+fn explain_denied(path: &Path, err: anyhow::Error) -> anyhow::Error {
+    if DeletionError::classify(&err) != DeletionError::PermissionDenied {
+        return err;
+    }
+    let action = err.to_string();
+    err.context(Refused {
+        action,
+        refusal: diagnose_denied(path),
+    })
+}
+
+/// Why deleting `path` is refused
+///
+/// Deleting an entry needs write access to its directory, and in a sticky directory such
+/// as `/tmp` it also takes owning the entry (or the directory).
+#[cfg(unix)]
+fn diagnose_denied(path: &Path) -> Refusal {
+    use std::os::unix::fs::MetadataExt;
+
+    // Still refused as root: an immutable or append-only file, or a read-only mount
+    if elevation::is_elevated() {
+        return Refusal::Protected;
+    }
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let (Ok(entry), Ok(dir)) = (fs::symlink_metadata(path), fs::metadata(parent)) else {
+        return Refusal::NeedsElevation;
+    };
+    let me = nix::unistd::geteuid().as_raw();
+    let sticky = dir.mode() & 0o1000 != 0;
+    let owner = if sticky && entry.uid() != me && dir.uid() != me {
+        entry.uid()
+    } else {
+        dir.uid()
+    };
+    match owner {
+        0 => Refusal::System,
+        // Our own directory without write permission
+        uid if uid == me => Refusal::NeedsElevation,
+        uid => Refusal::OtherUser(
+            nix::unistd::User::from_uid(nix::unistd::Uid::from_raw(uid))
+                .ok()
+                .flatten()
+                .map_or_else(|| uid.to_string(), |user| user.name),
+        ),
+    }
+}
+
+/// Why deleting `path` is refused; Windows ACLs are not worth walking for it
+#[cfg(not(unix))]
+fn diagnose_denied(_path: &Path) -> Refusal {
+    if elevation::is_elevated() {
+        Refusal::Protected
     } else {
-        eprintln!(
-            "  Bulk delete failed, trying individual deletion: {}",
-            last_err.unwrap_or_else(|| anyhow::anyhow!("Unknown error"))
+        Refusal::NeedsElevation
+    }
+}
+
+/// How long `verify_removed` waits before re-checking, so watchers get to react
+const VERIFY_SETTLE: Duration = Duration::from_millis(250);
+
+/// An entry reported as removed that exists again
+#[derive(Debug, Clone)]
+pub struct LingeringEntry {
+    pub path: PathBuf,
+    /// Processes holding the entry, or its parent directory when `via_parent`
+    pub holders: Vec<FileLockProcess>,
+    pub via_parent: bool,
+}
+
+/// Re-check successfully removed entries and name whoever may have recreated them
+pub fn verify_removed(report: &RemovalReport) -> Vec<LingeringEntry> {
+    let removed: Vec<&PathBuf> = report
+        .entries
+        .iter()
+        .filter(|e| e.outcome.is_done())
+        .map(|e| &e.path)
+        .collect();
+    if removed.is_empty() {
+        return Vec::new();
+    }
+    thread::sleep(VERIFY_SETTLE);
+
+    removed
+        .into_iter()
+        .filter(|path| path.symlink_metadata().is_ok())
+        .map(|path| {
+            let holders_of = |p: &Path| {
+                inspect_file_locks(&[p.to_path_buf()])
+                    .ok()
+                    .and_then(|infos| infos.into_iter().next())
+                    .map(|info| info.processes)
+                    .unwrap_or_default()
+            };
+
+            let holders = holders_of(path);
+            match path.parent() {
+                Some(parent) if holders.is_empty() => LingeringEntry {
+                    path: path.clone(),
+                    holders: holders_of(parent),
+                    via_parent: true,
+                },
+                _ => LingeringEntry {
+                    path: path.clone(),
+                    holders,
+                    via_parent: false,
+                },
+            }
+        })
+        .collect()
+}
+
+/// Delete each top-level directory of the plan in one call of `remove_tree`
+///
+/// Collection only keeps a directory whose whole subtree is scheduled, so a directory
+/// with no ancestor in the plan can go at once. Returns the trees removed and the
+/// entries left for individual deletion: everything outside them, and the contents of
+/// trees whose bulk removal failed.
+#[cfg(any(target_os = "windows", test))]
+fn bulk_remove_trees(
+    files: &[FileInfo],
+    opts: &RemovalOptions,
+    remove_tree: impl Fn(&Path) -> Result<()>,
+) -> (Vec<RemovalEntry>, Vec<FileInfo>) {
+    let dirs: HashSet<&Path> = files
+        .iter()
+        .filter(|f| f.is_dir)
+        .map(|f| f.path.as_path())
+        .collect();
+    let mut removed: HashSet<&Path> = HashSet::new();
+    let mut entries = Vec::new();
+
+    for root in files
+        .iter()
+        .filter(|f| f.is_dir && !f.path.ancestors().skip(1).any(|a| dirs.contains(a)))
+    {
+        let mut killed = Vec::new();
+        let result = opts.retry.run(
+            || remove_tree(&root.path),
+            |attempt| {
+                if opts.anyway {
+                    killed.extend(kill_lockers(&root.path));
+                }
+                tracing::warn!("retrying ({}/{})...", attempt, opts.retry.retries);
+            },
         );
-        None
+        match result {
+            Ok(()) => {
+                let mut entry =
+                    RemovalEntry::settled(root.path.clone(), Ok(()), RemovalOutcome::Removed);
+                entry.killed = killed;
+                entries.push(entry);
+                removed.insert(&root.path);
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "bulk delete of {} failed, trying individual deletion: {e}",
+                    root.path.display()
+                );
+            }
+        }
     }
+
+    let rest = files
+        .iter()
+        .filter(|f| !f.path.ancestors().any(|a| removed.contains(a)))
+        .cloned()
+        .collect();
+    (entries, rest)
 }
 
 /// Delete files individually (generic logic)
 fn remove_files_individually(
     files: &[FileInfo],
-    dry_run: bool,
-    anyway: bool,
-) -> Vec<(PathBuf, Result<()>)> {
-    let mut results = Vec::new();
+    opts: &RemovalOptions,
+    mut backup: Option<&mut BackupArchive>,
+) -> Vec<RemovalEntry> {
+    let mut entries = Vec::new();
 
     let mut sorted = files.to_vec();
     sorted.sort_by(|a, b| {
@@ -220,17 +1006,35 @@ fn remove_files_individually(
         }
     });
 
-    for file in sorted {
-        let result = if dry_run {
+    let mut sorted = sorted.into_iter();
+    for file in sorted.by_ref() {
+        let mut killed = Vec::new();
+        let result = if opts.dry_run {
             Ok(())
+        } else if let Some(archive) = backup.as_deref_mut()
+            && let Err(e) = archive.append(&file)
+        {
+            Err(e)
         } else {
-            remove_with_retry(&file, anyway)
+            remove_with_retry(&file, opts, &mut killed)
         };
 
-        results.push((file.path, result));
+        if let Err(e) = &result {
+            tracing::debug!(path = %file.path.display(), "delete failed: {e:#}");
+        }
+        let failed = result.is_err();
+        let mut entry = RemovalEntry::settled(file.path, result, RemovalOutcome::Removed);
+        entry.killed = killed;
+        entries.push(entry);
+        if failed && opts.fail_fast {
+            break;
+        }
     }
+    entries.extend(
+        sorted.map(|file| RemovalEntry::settled(file.path, Ok(()), RemovalOutcome::Skipped)),
+    );
 
-    results
+    entries
 }
 
 /// Delete directories containing symlinks on Windows
@@ -480,69 +1284,25 @@ fn remove_readonly_recursively(path: &Path) -> Result<()> {
     Ok(())
 }
 
-/// Determine if an IO error is retryable (Windows-specific)
-/// Retries are triggered only for:
-/// - PermissionDenied
-/// - Windows error code 32 (ERROR_SHARING_VIOLATION)
-/// - Windows error code 33 (ERROR_LOCK_VIOLATION)
-/// - Windows error code 5 (ERROR_ACCESS_DENIED)
-#[cfg(target_os = "windows")]
-fn is_retryable_error(e: &std::io::Error) -> bool {
-    match e.kind() {
-        std::io::ErrorKind::PermissionDenied => true,
-        _ => {
-            let os_code = e.raw_os_error();
-            matches!(os_code, Some(5) | Some(32) | Some(33))
-        }
-    }
-}
-
-/// File deletion with exponential backoff retry
-#[cfg(target_os = "windows")]
-fn remove_with_retry(file: &FileInfo, anyway: bool) -> Result<()> {
-    use std::thread;
-    use std::time::Duration;
-
-    let mut wait_ms = RETRY_INITIAL_WAIT_MS;
-
-    for attempt in 0..=RETRY_MAX_ATTEMPTS {
-        match remove_entry(file) {
-            Ok(()) => return Ok(()),
-            Err(err) => {
-                let io_err = err.downcast_ref::<std::io::Error>();
-                let should_retry = io_err.is_some_and(is_retryable_error);
-
-                if !should_retry || attempt == RETRY_MAX_ATTEMPTS {
-                    return Err(err.context(format!(
-                        "Deletion failed (after {} retries): {}",
-                        attempt,
-                        file.path.display()
-                    )));
-                }
-
-                eprintln!(
-                    "  Retrying ({}/{})... file may be in use: {}",
-                    attempt + 1,
-                    RETRY_MAX_ATTEMPTS,
-                    file.path.display()
-                );
-
-                if anyway {
-                    force_kill_lockers(&file.path);
-                }
-
-                thread::sleep(Duration::from_millis(wait_ms));
-                wait_ms = (wait_ms * 2).min(RETRY_MAX_WAIT_MS);
+/// File deletion with exponential backoff retry for locked entries
+/// Delete one entry; with `anyway`, lockers are killed before each retry and recorded
+/// in `killed`
+fn remove_with_retry(file: &FileInfo, opts: &RemovalOptions, killed: &mut Vec<u32>) -> Result<()> {
+    opts.retry.run(
+        || remove_entry(file),
+        |attempt| {
+            tracing::warn!(
+                "retrying ({}/{})... file may be in use: {}",
+                attempt,
+                opts.retry.retries,
+                file.path.display()
+            );
+
+            if opts.anyway {
+                killed.extend(kill_lockers(&file.path));
             }
-        }
-    }
-
-    unreachable!()
-}
-
-#[cfg(not(target_os = "windows"))]
-fn remove_with_retry(file: &FileInfo, _anyway: bool) -> Result<()> {
-    remove_entry(file)
+        },
+    )
 }
 
 fn remove_entry(file: &FileInfo) -> Result<()> {
@@ -550,7 +1310,19 @@ fn remove_entry(file: &FileInfo) -> Result<()> {
     #[cfg(target_os = "windows")]
     {
         if file.is_symlink {
-            // For symlinks, always use remove_file
+            use std::os::windows::fs::FileTypeExt;
+
+            // Directory symlinks are removed like empty directories, never through the target
+            if file
+                .path
+                .symlink_metadata()
+                .is_ok_and(|m| m.file_type().is_symlink_dir())
+            {
+                return fs::remove_dir(&file.path)
+                    .with_context(|| format!("Failed to delete symlink: {}", file.path.display()));
+            }
+
+            // For file symlinks, use remove_file
             // This deletes the link itself, not the target
             match fs::remove_file(&file.path) {
                 Ok(_) => return Ok(()),
@@ -607,3 +1379,574 @@ fn remove_entry(file: &FileInfo) -> Result<()> {
 
     result.with_context(|| format!("Deletion failed: {}", file.path.display()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_protected() {
+        let protected = vec![PathBuf::from("/srv/ziro-test/data")];
+        let hit = |path: &str| {
+            find_protected(&[PathBuf::from(path)], &protected)
+                .map(|(path, guard)| (path.to_path_buf(), guard.to_path_buf()))
+        };
+        assert!(hit("/srv/ziro-test/data").is_some());
+        assert!(hit("/srv/ziro-test").is_some());
+        assert!(hit("/srv/ziro-test/data/cache").is_some());
+        assert_eq!(hit("/srv/ziro-test/database"), None);
+        assert_eq!(hit("/srv/other"), None);
+    }
+
+    fn temp_tree(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!(
+            "ziro_fs_ops_{}_{}_{}",
+            name,
+            std::process::id(),
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos()
+        ));
+        fs::create_dir_all(root.join("sub/deeper")).unwrap();
+        fs::write(root.join("a.txt"), b"aaaa").unwrap();
+        fs::write(root.join("sub/b.txt"), b"bb").unwrap();
+        fs::write(root.join("sub/deeper/c.txt"), b"c").unwrap();
+        root
+    }
+
+    #[test]
+    fn test_parse_path_list_newlines() {
+        let paths = parse_path_list(b"./a.tmp\r\n./b c.tmp\n\n./d.tmp");
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("./a.tmp"),
+                PathBuf::from("./b c.tmp"),
+                PathBuf::from("./d.tmp")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_path_list_nul() {
+        let paths = parse_path_list(b"./a\nb.tmp\0./c.tmp\0");
+        assert_eq!(
+            paths,
+            vec![PathBuf::from("./a\nb.tmp"), PathBuf::from("./c.tmp")]
+        );
+    }
+
+    #[test]
+    fn test_classify_deletion_error() {
+        let err = anyhow::Error::from(std::io::Error::from(std::io::ErrorKind::NotFound))
+            .context("Deletion failed: x");
+        assert_eq!(DeletionError::classify(&err), DeletionError::NotFound);
+
+        let err = anyhow!("not an io error");
+        assert_eq!(DeletionError::classify(&err), DeletionError::Other);
+    }
+
+    #[test]
+    fn test_retry_policy_only_retries_locked_errors() {
+        let policy = RetryPolicy {
+            retries: 3,
+            delay: Duration::ZERO,
+        };
+
+        let mut calls = 0;
+        let result: Result<()> = policy.run(
+            || {
+                calls += 1;
+                Err(std::io::Error::from(std::io::ErrorKind::ResourceBusy).into())
+            },
+            |_| {},
+        );
+        assert!(result.is_err());
+        assert_eq!(calls, 4);
+
+        let mut calls = 0;
+        let result: Result<()> = policy.run(
+            || {
+                calls += 1;
+                Err(std::io::Error::from(std::io::ErrorKind::PermissionDenied).into())
+            },
+            |_| {},
+        );
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_tally_counts_descendants() {
+        let file = |path: &str, is_dir: bool, size: u64| FileInfo {
+            path: PathBuf::from(path),
+            is_dir,
+            size,
+            is_symlink: false,
+            is_hidden: false,
+            link_target: None,
+        };
+        let files = vec![
+            file("root/a", false, 10),
+            file("root/sub/b", false, 5),
+            file("root/sub", true, 0),
+            file("root", true, 0),
+            file("other", false, 7),
+        ];
+        // Bulk removal reports only the root directory
+        let mut report = RemovalReport {
+            entries: vec![
+                RemovalEntry::settled(PathBuf::from("root"), Ok(()), RemovalOutcome::Removed),
+                RemovalEntry::settled(
+                    PathBuf::from("other"),
+                    Err(anyhow!("locked")),
+                    RemovalOutcome::Removed,
+                ),
+            ],
+            ..Default::default()
+        };
+        report.tally(&files);
+
+        assert_eq!((report.bytes_freed, report.files_freed), (15, 2));
+        assert_eq!(report.succeeded(), 1);
+        assert_eq!(report.failures().count(), 1);
+    }
+
+    #[test]
+    fn test_fail_fast_skips_the_rest() {
+        let root = temp_tree("fail_fast");
+        let file = |path: PathBuf| FileInfo {
+            path,
+            is_dir: false,
+            size: 1,
+            is_symlink: false,
+            is_hidden: false,
+            link_target: None,
+        };
+        // Deepest first: the missing entry fails before a.txt is tried
+        let files = vec![file(root.join("a.txt")), file(root.join("sub/missing.txt"))];
+        let opts = RemovalOptions {
+            fail_fast: true,
+            retry: RetryPolicy {
+                retries: 0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let report = remove_files(&files, &opts, None);
+        assert!(matches!(
+            report.entries[0].outcome,
+            RemovalOutcome::Failed {
+                kind: DeletionError::NotFound,
+                ..
+            }
+        ));
+        assert!(matches!(report.entries[1].outcome, RemovalOutcome::Skipped));
+        assert_eq!((report.skipped(), report.bytes_freed), (1, 0));
+        assert!(root.join("a.txt").exists());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_dry_run_lists_every_entry() {
+        let root = temp_tree("dry_run");
+        let opts = CollectOptions {
+            recursive: true,
+            ..Default::default()
+        };
+        let plan = collect_files_to_remove(std::slice::from_ref(&root), &opts).unwrap();
+        let size_of = |path: &Path| plan.files.iter().find(|f| f.path == path).unwrap().size;
+        // Directories carry the total of what lies below them
+        assert_eq!(size_of(&root), 7);
+        assert_eq!(size_of(&root.join("sub")), 3);
+
+        let removal = RemovalOptions {
+            dry_run: true,
+            ..Default::default()
+        };
+        let report = remove_files(&plan.files, &removal, None);
+        // Every file and directory of the tree, not just its root
+        assert_eq!(report.entries.len(), plan.files.len());
+        assert_eq!((report.bytes_freed, report.files_freed), (7, 3));
+        assert!(root.join("sub/deeper/c.txt").exists());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_bulk_remove_trees_leaves_the_rest() {
+        let root = temp_tree("bulk");
+        fs::create_dir(root.join("empty")).unwrap();
+        let opts = CollectOptions {
+            recursive: true,
+            max_depth: Some(1),
+            ..Default::default()
+        };
+        // The root is incomplete: the plan holds a.txt and the empty directory only
+        let plan = collect_files_to_remove(std::slice::from_ref(&root), &opts).unwrap();
+
+        let (entries, rest) = bulk_remove_trees(&plan.files, &RemovalOptions::default(), |path| {
+            Ok(fs::remove_dir_all(path)?)
+        });
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].path, root.join("empty"));
+        let rest: Vec<&Path> = rest.iter().map(|f| f.path.as_path()).collect();
+        assert_eq!(rest, vec![root.join("a.txt").as_path()]);
+        assert!(root.join("a.txt").exists() && root.join("sub").exists());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_collect_unreadable_directory() {
+        use std::os::unix::fs::PermissionsExt;
+
+        // Root reads the directory anyway
+        if nix::unistd::geteuid().is_root() {
+            return;
+        }
+        let root = temp_tree("unreadable");
+        let locked = root.join("sub/deeper");
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o000)).unwrap();
+        let opts = |keep_going| CollectOptions {
+            recursive: true,
+            keep_going,
+            ..Default::default()
+        };
+
+        let plan = collect_files_to_remove(std::slice::from_ref(&root), &opts(true)).unwrap();
+        let skipped: Vec<&Path> = plan
+            .skipped
+            .iter()
+            .map(|(path, _)| path.as_path())
+            .collect();
+        assert_eq!(skipped, vec![locked.as_path()]);
+        // Its siblings are still collected
+        let collected = |path: PathBuf| plan.files.iter().any(|f| f.path == path);
+        assert!(collected(root.join("a.txt")) && collected(root.join("sub/b.txt")));
+
+        assert!(collect_files_to_remove(std::slice::from_ref(&root), &opts(false)).is_err());
+
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_collect_max_depth_keeps_deeper_structure() {
+        let root = temp_tree("depth");
+        fs::create_dir(root.join("empty")).unwrap();
+        let opts = |max_depth| CollectOptions {
+            recursive: true,
+            max_depth: Some(max_depth),
+            ..Default::default()
+        };
+
+        let plan = collect_files_to_remove(std::slice::from_ref(&root), &opts(1)).unwrap();
+        let mut paths: Vec<PathBuf> = plan.files.into_iter().map(|f| f.path).collect();
+        paths.sort();
+        // Only a.txt and the empty directory; sub/ has deeper contents, so root stays too
+        assert_eq!(paths, vec![root.join("a.txt"), root.join("empty")]);
+
+        let plan = collect_files_to_remove(std::slice::from_ref(&root), &opts(0)).unwrap();
+        assert!(plan.files.is_empty());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_disk_usage_aggregates_subdirectories() {
+        let root = temp_tree("du");
+
+        let usage = disk_usage(&root, &CollectOptions::default()).unwrap();
+        assert!(usage.skipped.is_empty());
+        let usage = usage.root;
+        assert_eq!(usage.size, 7);
+        assert_eq!(usage.file_count, 3);
+        // Largest first: a.txt (4) before sub/ (3)
+        assert_eq!(usage.children[0].path, root.join("a.txt"));
+        assert_eq!(usage.children[1].path, root.join("sub"));
+        assert_eq!(usage.children[1].size, 3);
+        assert_eq!(usage.children[1].children.len(), 2);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_largest_files_filters_by_extension() {
+        let root = temp_tree("big");
+        fs::write(root.join("sub/big.log"), b"0123456789").unwrap();
+
+        let filter = LargestFilter {
+            extensions: vec![".TXT".to_string()],
+            ..Default::default()
+        };
+        let found = largest_files(std::slice::from_ref(&root), 10, &filter)
+            .unwrap()
+            .files;
+        let paths: Vec<_> = found.iter().map(|f| f.file.path.clone()).collect();
+        assert_eq!(paths[0], root.join("a.txt"));
+        assert!(!paths.contains(&root.join("sub/big.log")));
+
+        let found = largest_files(std::slice::from_ref(&root), 1, &LargestFilter::default())
+            .unwrap()
+            .files;
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].file.path, root.join("sub/big.log"));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_scan_temp_dirs_respects_age() {
+        let root = temp_tree("tmp");
+
+        let entries = scan_temp_dirs(std::slice::from_ref(&root), Duration::from_secs(3600));
+        assert_eq!(entries.len(), 2);
+        assert!(entries.iter().all(|e| e.status == TempStatus::Recent));
+
+        let entries = scan_temp_dirs(std::slice::from_ref(&root), Duration::ZERO);
+        let sub = entries.iter().find(|e| e.path == root.join("sub")).unwrap();
+        assert_eq!(sub.status, TempStatus::Reclaimable);
+        assert_eq!(sub.size, 3);
+        // Subtree plus the directory itself
+        assert_eq!(sub.files.len(), 4);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_collect_skip_hidden_counts_skipped() {
+        let root = temp_tree("hidden");
+        fs::write(root.join(".env"), b"secret").unwrap();
+        fs::create_dir(root.join("sub/.cache")).unwrap();
+
+        let opts = CollectOptions {
+            recursive: true,
+            skip_hidden: true,
+            ..Default::default()
+        };
+        let plan = collect_files_to_remove(std::slice::from_ref(&root), &opts).unwrap();
+        assert_eq!(plan.hidden_skipped, 2);
+        assert!(plan.files.iter().all(|f| !f.is_hidden));
+        // Parents of skipped entries stay
+        assert!(
+            !plan
+                .files
+                .iter()
+                .any(|f| f.path == root || f.path == root.join("sub"))
+        );
+
+        let opts = CollectOptions {
+            recursive: true,
+            ..Default::default()
+        };
+        let plan = collect_files_to_remove(std::slice::from_ref(&root), &opts).unwrap();
+        assert_eq!(plan.hidden_skipped, 0);
+        assert_eq!(plan.files.iter().filter(|f| f.is_hidden).count(), 2);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_collect_gitignore_filters() {
+        let root = temp_tree("gitignore");
+        fs::write(root.join(".gitignore"), "sub/deeper/\n*.log\n").unwrap();
+        fs::write(root.join("build.log"), b"log").unwrap();
+
+        let opts = |gitignore| CollectOptions {
+            recursive: true,
+            gitignore,
+            ..Default::default()
+        };
+        let paths = |plan: RemovalPlan| -> Vec<PathBuf> {
+            let mut paths: Vec<_> = plan
+                .files
+                .into_iter()
+                .map(|f| f.path.strip_prefix(&root).unwrap().to_path_buf())
+                .collect();
+            paths.sort();
+            paths
+        };
+
+        let skip = collect_files_to_remove(
+            std::slice::from_ref(&root),
+            &opts(IgnoreFilter::SkipIgnored),
+        )
+        .unwrap();
+        assert_eq!(
+            paths(skip),
+            vec![
+                PathBuf::from(".gitignore"),
+                PathBuf::from("a.txt"),
+                PathBuf::from("sub/b.txt"),
+            ]
+        );
+
+        let only = collect_files_to_remove(
+            std::slice::from_ref(&root),
+            &opts(IgnoreFilter::OnlyIgnored),
+        )
+        .unwrap();
+        assert_eq!(
+            paths(only),
+            vec![
+                PathBuf::from("build.log"),
+                PathBuf::from("sub/deeper"),
+                PathBuf::from("sub/deeper/c.txt"),
+            ]
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_collect_symlink_policies() {
+        let root = temp_tree("symlinks");
+        let outside = temp_tree("symlinks_target");
+        std::os::unix::fs::symlink(&outside, root.join("link")).unwrap();
+
+        let opts = |symlinks| CollectOptions {
+            recursive: true,
+            symlinks,
+            ..Default::default()
+        };
+
+        let keep = collect_files_to_remove(
+            std::slice::from_ref(&root),
+            &opts(SymlinkPolicy::KeepTarget),
+        )
+        .unwrap();
+        let link = keep.files.iter().find(|f| f.is_symlink).unwrap();
+        assert_eq!(link.link_target.as_deref(), Some(outside.as_path()));
+        assert!(!keep.files.iter().any(|f| f.path.starts_with(&outside)));
+
+        let follow =
+            collect_files_to_remove(std::slice::from_ref(&root), &opts(SymlinkPolicy::Follow))
+                .unwrap();
+        let outside_real = outside.canonicalize().unwrap();
+        assert!(follow.files.iter().any(|f| f.path == outside_real));
+        assert!(follow.files.iter().any(|f| f.is_symlink));
+        // The target is outside the root but still belongs to it
+        assert_eq!(follow.origin_of(&outside_real.join("x")), root);
+        assert_eq!(follow.origin_of(&root.join("x")), root.join("x"));
+
+        let skip = collect_files_to_remove(std::slice::from_ref(&root), &opts(SymlinkPolicy::Skip))
+            .unwrap();
+        assert!(!skip.files.iter().any(|f| f.is_symlink));
+        // The directory holding the link is kept
+        assert!(!skip.files.iter().any(|f| f.path == root));
+
+        let _ = fs::remove_dir_all(&root);
+        let _ = fs::remove_dir_all(&outside);
+    }
+
+    #[test]
+    fn test_trash_journal_keeps_recent_records() {
+        let mut journal = TrashJournal::default();
+        for i in 0..TRASH_JOURNAL_LEN + 3 {
+            journal.push(TrashRecord {
+                time: i as i64,
+                paths: vec![PathBuf::from(format!("/tmp/{i}"))],
+            });
+        }
+        assert_eq!(journal.records.len(), TRASH_JOURNAL_LEN);
+        assert_eq!(journal.records[0].time, 3);
+
+        journal.forget(&[PathBuf::from("/tmp/3")]);
+        assert_eq!(journal.records.len(), TRASH_JOURNAL_LEN - 1);
+        assert_eq!(journal.records[0].time, 4);
+    }
+
+    #[test]
+    fn test_defer_locked_frees_path_and_parent() {
+        let root = temp_tree("pending");
+        let sub = root.join("sub");
+        let locked = sub.join("b.txt");
+        // Locked, then gone: it cannot be moved aside
+        let gone = root.join("gone.txt");
+        fs::remove_dir_all(sub.join("deeper")).unwrap();
+
+        let files = vec![
+            FileInfo {
+                path: locked.clone(),
+                is_dir: false,
+                size: 2,
+                is_symlink: false,
+                is_hidden: false,
+                link_target: None,
+            },
+            FileInfo {
+                path: sub.clone(),
+                is_dir: true,
+                size: 0,
+                is_symlink: false,
+                is_hidden: false,
+                link_target: None,
+            },
+        ];
+        let busy = || std::io::Error::from(std::io::ErrorKind::ResourceBusy);
+        let not_empty = std::io::Error::from(std::io::ErrorKind::DirectoryNotEmpty);
+        let mut report = RemovalReport {
+            entries: vec![
+                RemovalEntry::settled(
+                    locked.clone(),
+                    Err(anyhow::Error::from(busy())),
+                    RemovalOutcome::Removed,
+                ),
+                RemovalEntry::settled(
+                    gone.clone(),
+                    Err(anyhow::Error::from(busy())),
+                    RemovalOutcome::Removed,
+                ),
+                RemovalEntry::settled(
+                    sub.clone(),
+                    Err(anyhow::Error::from(not_empty)),
+                    RemovalOutcome::Removed,
+                ),
+            ],
+            ..Default::default()
+        };
+
+        let pending = defer_locked(&files, &mut report);
+        assert_eq!(pending.len(), 2);
+        assert!(pending[0].removed);
+        assert!(pending[1].moved_to.is_none());
+        assert_eq!(report.succeeded(), 2);
+        assert_eq!(report.failures().count(), 1);
+        assert_eq!(report.bytes_freed, 2);
+        assert!(!sub.exists());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_parse_age() {
+        assert_eq!(parse_age("90s").unwrap(), Duration::from_secs(90));
+        assert_eq!(parse_age("2h").unwrap(), Duration::from_secs(7200));
+        assert_eq!(parse_age("7d").unwrap(), Duration::from_secs(7 * 86400));
+        assert!(parse_age("7").is_err());
+        assert!(parse_age("3y").is_err());
+    }
+
+    #[test]
+    fn test_collect_one_file_system_same_device() {
+        let root = temp_tree("ofs");
+        let opts = CollectOptions {
+            recursive: true,
+            one_file_system: true,
+            ..Default::default()
+        };
+
+        let plan = collect_files_to_remove(std::slice::from_ref(&root), &opts).unwrap();
+        // 3 files + deeper + sub + root
+        assert_eq!(plan.files.len(), 6);
+        assert_eq!(plan.files.last().unwrap().path, root);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,179 @@
+/// Fallback for entries that stay locked: move them aside and delete them later
+use super::{DeletionError, FileInfo, RemovalEntry, RemovalOutcome, RemovalReport, remove_entry};
+use anyhow::{Context, Result, anyhow};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Directory (next to the locked entry) that locked entries are renamed into
+pub const PENDING_DIR: &str = ".ziro-pending-delete";
+
+/// A locked entry handled by `defer_locked`
+#[derive(Debug, Clone)]
+pub struct PendingDelete {
+    pub path: PathBuf,
+    /// Where the entry was renamed to; `None` if it could not be moved
+    pub moved_to: Option<PathBuf>,
+    /// Deleted right after the move (possible once the name is no longer in use)
+    pub removed: bool,
+    /// Scheduled for deletion on the next reboot (Windows only)
+    pub on_reboot: bool,
+}
+
+/// Entries whose removal failed because they are locked
+pub fn locked_entries(report: &RemovalReport) -> Vec<PathBuf> {
+    report
+        .entries
+        .iter()
+        .filter(|e| {
+            matches!(
+                e.outcome,
+                RemovalOutcome::Failed {
+                    kind: DeletionError::FileLocked,
+                    ..
+                }
+            )
+        })
+        .map(|e| e.path.clone())
+        .collect()
+}
+
+/// Rename locked entries into a pending-delete area so their paths are freed now
+///
+/// Entries moved out of the way are marked as removed in `report`, and directories
+/// that only failed because such an entry was still inside are retried. An entry that
+/// could not be moved stays failed, even when it is scheduled for deletion on reboot.
+pub fn defer_locked(files: &[FileInfo], report: &mut RemovalReport) -> Vec<PendingDelete> {
+    let pending: Vec<PendingDelete> = locked_entries(report)
+        .into_iter()
+        .map(|path| defer_entry(&path))
+        .collect();
+
+    for entry in &pending {
+        if entry.moved_to.is_some()
+            && let Some(removed) = report.entries.iter_mut().find(|e| e.path == entry.path)
+        {
+            removed.outcome = RemovalOutcome::Removed;
+        }
+    }
+
+    // Parents are retried deepest first, so a whole chain of directories can clear up
+    let mut retry: Vec<&FileInfo> = files
+        .iter()
+        .filter(|f| {
+            f.is_dir
+                && pending
+                    .iter()
+                    .any(|p| p.moved_to.is_some() && p.path.starts_with(&f.path))
+        })
+        .collect();
+    retry.sort_by_key(|f| std::cmp::Reverse(f.path.components().count()));
+    for dir in retry {
+        if let Some(entry) = report.entries.iter_mut().find(|e| e.path == dir.path)
+            && matches!(
+                entry.outcome,
+                RemovalOutcome::Failed {
+                    kind: DeletionError::DirectoryNotEmpty,
+                    ..
+                }
+            )
+        {
+            *entry =
+                RemovalEntry::settled(dir.path.clone(), remove_entry(dir), RemovalOutcome::Removed);
+        }
+    }
+    report.tally(files);
+
+    pending
+}
+
+fn defer_entry(path: &Path) -> PendingDelete {
+    match move_aside(path) {
+        Ok(moved_to) => {
+            let removed = remove_moved(&moved_to).is_ok();
+            let on_reboot = !removed && schedule_on_reboot(&moved_to).is_ok();
+            PendingDelete {
+                path: path.to_path_buf(),
+                moved_to: Some(moved_to),
+                removed,
+                on_reboot,
+            }
+        }
+        Err(e) => {
+            tracing::warn!("{e:#}");
+            PendingDelete {
+                path: path.to_path_buf(),
+                moved_to: None,
+                removed: false,
+                on_reboot: schedule_on_reboot(path).is_ok(),
+            }
+        }
+    }
+}
+
+/// Rename `path` into the pending-delete directory of its parent (same filesystem)
+fn move_aside(path: &Path) -> Result<PathBuf> {
+    let name = path
+        .file_name()
+        .ok_or_else(|| anyhow!("Cannot move {} aside", path.display()))?;
+    let parent = path.parent().unwrap_or(Path::new("."));
+    let area = parent.join(PENDING_DIR);
+    fs::create_dir_all(&area)
+        .with_context(|| format!("Failed to create directory: {}", area.display()))?;
+
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    let mut target_name = name.to_os_string();
+    target_name.push(format!(".{}-{stamp}", std::process::id()));
+    let target = area.join(target_name);
+
+    fs::rename(path, &target)
+        .with_context(|| format!("Failed to move {} to {}", path.display(), target.display()))?;
+    Ok(target)
+}
+
+fn remove_moved(path: &Path) -> std::io::Result<()> {
+    if path.symlink_metadata()?.is_dir() {
+        fs::remove_dir_all(path)?;
+    } else {
+        fs::remove_file(path)?;
+    }
+    // Leave no empty pending area behind
+    if let Some(area) = path.parent() {
+        let _ = fs::remove_dir(area);
+    }
+    Ok(())
+}
+
+/// Ask Windows to delete `path` (and everything below it) on the next reboot
+#[cfg(target_os = "windows")]
+fn schedule_on_reboot(path: &Path) -> Result<()> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::{MOVEFILE_DELAY_UNTIL_REBOOT, MoveFileExW};
+
+    // Contents must be registered before their directory; pending renames run in order
+    if path.symlink_metadata()?.is_dir() {
+        for entry in fs::read_dir(path)? {
+            let _ = schedule_on_reboot(&entry?.path());
+        }
+    }
+
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+    // SAFETY: `wide` is NUL-terminated and outlives the call; a null target means delete
+    let ok = unsafe { MoveFileExW(wide.as_ptr(), std::ptr::null(), MOVEFILE_DELAY_UNTIL_REBOOT) };
+    if ok == 0 {
+        return Err(std::io::Error::last_os_error())
+            .with_context(|| format!("Failed to schedule deletion: {}", path.display()));
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn schedule_on_reboot(path: &Path) -> Result<()> {
+    Err(anyhow!(
+        "Deleting on reboot is only supported on Windows: {}",
+        path.display()
+    ))
+}
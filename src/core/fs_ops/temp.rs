@@ -0,0 +1,161 @@
+/// Temp directory scanning for `ziro tmp`
+use super::{CollectOptions, FileInfo, collect_files_to_remove};
+use crate::core::process::is_file_locked;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Why a temp entry is (or is not) eligible for cleanup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TempStatus {
+    Reclaimable,
+    /// Modified more recently than the age threshold
+    Recent,
+    /// Held open by a process, or contains sockets/pipes
+    InUse,
+    /// Could not be fully read, so its age is unknown
+    Unreadable,
+}
+
+/// A top-level entry of a temp directory
+#[derive(Debug, Clone)]
+pub struct TempEntry {
+    pub path: PathBuf,
+    pub is_dir: bool,
+    pub size: u64,
+    /// Most recent modification anywhere inside the entry
+    pub modified: Option<SystemTime>,
+    pub owner: Option<String>,
+    pub status: TempStatus,
+    /// Everything to delete for this entry, in walker order
+    pub files: Vec<FileInfo>,
+}
+
+/// Scan the direct children of each temp root
+///
+/// Entries are only reclaimable when nothing inside them changed within `older_than`
+/// and no process holds them open.
+pub fn scan_temp_dirs(roots: &[PathBuf], older_than: Duration) -> Vec<TempEntry> {
+    let owners = OwnerNames::new();
+    let now = SystemTime::now();
+    let mut entries = Vec::new();
+
+    for root in roots {
+        let read_dir = match fs::read_dir(root) {
+            Ok(read_dir) => read_dir,
+            Err(e) => {
+                tracing::warn!("skipping {} ({})", root.display(), e);
+                continue;
+            }
+        };
+
+        for entry in read_dir.flatten() {
+            entries.push(scan_entry(&entry.path(), older_than, now, &owners));
+        }
+    }
+
+    entries.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+    entries
+}
+
+fn scan_entry(
+    path: &Path,
+    older_than: Duration,
+    now: SystemTime,
+    owners: &OwnerNames,
+) -> TempEntry {
+    let metadata = fs::symlink_metadata(path).ok();
+    let mut entry = TempEntry {
+        path: path.to_path_buf(),
+        is_dir: metadata.as_ref().is_some_and(|m| m.is_dir()),
+        size: 0,
+        modified: None,
+        owner: metadata.as_ref().and_then(|m| owners.owner_of(m)),
+        status: TempStatus::Unreadable,
+        files: Vec::new(),
+    };
+
+    let opts = CollectOptions {
+        recursive: true,
+        one_file_system: true,
+        keep_going: true,
+        ..Default::default()
+    };
+    let plan = match collect_files_to_remove(&[path.to_path_buf()], &opts) {
+        Ok(plan) if plan.skipped.is_empty() => plan,
+        _ => return entry,
+    };
+
+    let mut special = false;
+    for file in &plan.files {
+        let Ok(meta) = fs::symlink_metadata(&file.path) else {
+            return entry;
+        };
+        special |= is_special_file(&meta);
+        if !file.is_dir {
+            entry.size += file.size;
+        }
+        if let Ok(modified) = meta.modified() {
+            entry.modified = entry.modified.max(Some(modified));
+        }
+    }
+    entry.files = plan.files;
+
+    let age = entry
+        .modified
+        .and_then(|m| now.duration_since(m).ok())
+        .unwrap_or_default();
+    entry.status = if age < older_than {
+        TempStatus::Recent
+    } else if special || is_file_locked(path) {
+        TempStatus::InUse
+    } else {
+        TempStatus::Reclaimable
+    };
+    entry
+}
+
+#[cfg(unix)]
+fn is_special_file(meta: &fs::Metadata) -> bool {
+    use std::os::unix::fs::FileTypeExt;
+    let file_type = meta.file_type();
+    file_type.is_socket() || file_type.is_fifo()
+}
+
+#[cfg(not(unix))]
+fn is_special_file(_meta: &fs::Metadata) -> bool {
+    false
+}
+
+/// Resolves file owners to user names
+struct OwnerNames {
+    #[cfg(unix)]
+    users: sysinfo::Users,
+}
+
+impl OwnerNames {
+    fn new() -> Self {
+        Self {
+            #[cfg(unix)]
+            users: sysinfo::Users::new_with_refreshed_list(),
+        }
+    }
+
+    #[cfg(unix)]
+    fn owner_of(&self, meta: &fs::Metadata) -> Option<String> {
+        use std::os::unix::fs::MetadataExt;
+        let uid = meta.uid();
+        let name = uid
+            .to_string()
+            .parse::<sysinfo::Uid>()
+            .ok()
+            .and_then(|id| self.users.get_user_by_id(&id))
+            .map(|user| user.name().to_string());
+        Some(name.unwrap_or_else(|| uid.to_string()))
+    }
+
+    #[cfg(not(unix))]
+    fn owner_of(&self, _meta: &fs::Metadata) -> Option<String> {
+        None
+    }
+}
@@ -0,0 +1,178 @@
+/// Trash-based removal with a small undo journal
+use super::{FileInfo, RemovalEntry, RemovalOutcome, real_location};
+use crate::platform::dirs;
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Number of trash removals remembered for `restore`
+pub const TRASH_JOURNAL_LEN: usize = 20;
+
+const JOURNAL_FILE: &str = "trash-journal.json";
+
+/// One `remove --trash` run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashRecord {
+    /// Unix time (seconds) the entries were moved to the trash
+    pub time: i64,
+    /// Original locations of the trashed entries
+    pub paths: Vec<PathBuf>,
+}
+
+/// The most recent trash removals, oldest first
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TrashJournal {
+    pub records: Vec<TrashRecord>,
+}
+
+impl TrashJournal {
+    fn location() -> Result<PathBuf> {
+        dirs::state_dir()
+            .map(|dir| dir.join(JOURNAL_FILE))
+            .ok_or_else(|| anyhow!("Unable to determine the state directory for the trash journal"))
+    }
+
+    /// Load the journal; a missing journal is empty
+    pub fn load() -> Result<Self> {
+        let path = Self::location()?;
+        match fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .with_context(|| format!("Failed to parse trash journal: {}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => {
+                Err(e).with_context(|| format!("Failed to read trash journal: {}", path.display()))
+            }
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::location()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+        let json = serde_json::to_vec_pretty(self)?;
+        fs::write(&path, json)
+            .with_context(|| format!("Failed to write trash journal: {}", path.display()))
+    }
+
+    /// Remember a removal, dropping the oldest beyond `TRASH_JOURNAL_LEN`
+    pub fn push(&mut self, record: TrashRecord) {
+        self.records.push(record);
+        let excess = self.records.len().saturating_sub(TRASH_JOURNAL_LEN);
+        self.records.drain(..excess);
+    }
+
+    /// Drop restored paths, and records left empty
+    pub fn forget(&mut self, restored: &[PathBuf]) {
+        for record in &mut self.records {
+            record.paths.retain(|p| !restored.contains(p));
+        }
+        self.records.retain(|r| !r.paths.is_empty());
+    }
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+/// Move the top-level entries of `files` to the OS trash and journal them
+///
+/// Entries inside a trashed directory go with it, so only entries without an ancestor
+/// in `files` get a result.
+pub(super) fn trash_files(files: &[FileInfo]) -> Vec<RemovalEntry> {
+    let all: HashSet<&Path> = files.iter().map(|f| f.path.as_path()).collect();
+    let time = unix_now();
+
+    let entries: Vec<RemovalEntry> = files
+        .iter()
+        .filter(|f| !f.path.ancestors().skip(1).any(|a| all.contains(a)))
+        .map(|f| {
+            let result = trash::delete(&f.path)
+                .with_context(|| format!("Failed to move to trash: {}", f.path.display()));
+            RemovalEntry::settled(f.path.clone(), result, RemovalOutcome::Trashed)
+        })
+        .collect();
+
+    let paths: Vec<PathBuf> = entries
+        .iter()
+        .filter(|e| e.outcome.is_done())
+        .map(|e| real_location(&e.path).unwrap_or_else(|| e.path.clone()))
+        .collect();
+    if !paths.is_empty() {
+        let journaled = TrashJournal::load().and_then(|mut journal| {
+            journal.push(TrashRecord { time, paths });
+            journal.save()
+        });
+        if let Err(e) = journaled {
+            tracing::warn!("removal will not be restorable with 'ziro restore': {e:#}");
+        }
+    }
+
+    entries
+}
+
+/// Put trashed entries back at their original location
+///
+/// Only trash items deleted at or after `since` are considered; when a path was trashed
+/// several times the most recent copy wins. Returns the paths that were restored.
+#[cfg(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+))]
+pub fn restore_from_trash(paths: &[PathBuf], since: i64) -> Result<Vec<PathBuf>> {
+    use std::collections::HashMap;
+    use trash::TrashItem;
+
+    let items = trash::os_limited::list().context("Failed to list trash contents")?;
+    let mut chosen: HashMap<PathBuf, TrashItem> = HashMap::new();
+    for item in items {
+        let original = item.original_path();
+        // Trash timestamps are whole seconds and may be taken just before ours
+        if !paths.contains(&original) || item.time_deleted < since - 1 {
+            continue;
+        }
+        match chosen.get(&original) {
+            Some(existing) if existing.time_deleted >= item.time_deleted => {}
+            _ => {
+                chosen.insert(original, item);
+            }
+        }
+    }
+
+    let restored: Vec<PathBuf> = chosen.keys().cloned().collect();
+    if let Some(path) = restored.iter().find(|p| p.symlink_metadata().is_ok()) {
+        return Err(anyhow!(
+            "Cannot restore {}: something already exists at that path",
+            path.display()
+        ));
+    }
+    trash::os_limited::restore_all(chosen.into_values()).context("Failed to restore from trash")?;
+    Ok(restored)
+}
+
+#[cfg(not(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+)))]
+pub fn restore_from_trash(_paths: &[PathBuf], _since: i64) -> Result<Vec<PathBuf>> {
+    Err(anyhow!(
+        "Restoring from the trash is not supported on this platform, use the system trash (e.g. Finder's Put Back)"
+    ))
+}
@@ -0,0 +1,229 @@
+/// Disk usage aggregation on top of the removal walker
+use super::{
+    CollectOptions, FileInfo, IgnoreFilter, LeftOutEntries, SkippedEntries, collect_files_to_remove,
+};
+use anyhow::{Result, anyhow, bail};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Aggregated size of a file or directory subtree
+#[derive(Debug, Clone)]
+pub struct UsageNode {
+    pub path: PathBuf,
+    pub is_dir: bool,
+    /// Total bytes of all files at or below this entry
+    pub size: u64,
+    /// Number of non-directory entries at or below this entry
+    pub file_count: u64,
+    /// Children sorted by size, largest first
+    pub children: Vec<UsageNode>,
+}
+
+/// Result of `disk_usage`
+#[derive(Debug)]
+pub struct DiskUsage {
+    pub root: UsageNode,
+    /// Unreadable entries, not counted in the sizes
+    pub skipped: SkippedEntries,
+    /// Hidden entries left out because of `skip_hidden`
+    pub hidden_skipped: usize,
+    pub left_out: LeftOutEntries,
+}
+
+#[derive(Default)]
+struct NodeAcc {
+    is_dir: bool,
+    size: u64,
+    file_count: u64,
+}
+
+/// Walk `root` and aggregate sizes per entry
+///
+/// The walk is always recursive and unreadable entries never abort it;
+/// they are returned alongside the tree.
+pub fn disk_usage(root: &Path, opts: &CollectOptions) -> Result<DiskUsage> {
+    let opts = CollectOptions {
+        recursive: true,
+        keep_going: true,
+        ..opts.clone()
+    };
+    let plan = collect_files_to_remove(&[root.to_path_buf()], &opts)?;
+
+    let mut nodes: HashMap<PathBuf, NodeAcc> = HashMap::new();
+    nodes.entry(root.to_path_buf()).or_default().is_dir = root.is_dir();
+
+    for file in &plan.files {
+        let node = nodes.entry(file.path.clone()).or_default();
+        node.is_dir = file.is_dir;
+        if file.is_dir {
+            continue;
+        }
+        node.size += file.size;
+        node.file_count += 1;
+
+        // Directories left out of the plan (incomplete subtrees) still show up here
+        for ancestor in file.path.ancestors().skip(1) {
+            let acc = nodes.entry(ancestor.to_path_buf()).or_default();
+            acc.is_dir = true;
+            acc.size += file.size;
+            acc.file_count += 1;
+            if ancestor == root {
+                break;
+            }
+        }
+    }
+
+    let mut children: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
+    for path in nodes.keys() {
+        if path != root
+            && let Some(parent) = path.parent()
+        {
+            children
+                .entry(parent.to_path_buf())
+                .or_default()
+                .push(path.clone());
+        }
+    }
+
+    Ok(DiskUsage {
+        root: build_node(root, &mut nodes, &mut children),
+        skipped: plan.skipped,
+        hidden_skipped: plan.hidden_skipped,
+        left_out: plan.left_out,
+    })
+}
+
+fn build_node(
+    path: &Path,
+    nodes: &mut HashMap<PathBuf, NodeAcc>,
+    children: &mut HashMap<PathBuf, Vec<PathBuf>>,
+) -> UsageNode {
+    let acc = nodes.remove(path).unwrap_or_default();
+    let mut child_nodes: Vec<UsageNode> = children
+        .remove(path)
+        .unwrap_or_default()
+        .iter()
+        .map(|child| build_node(child, nodes, children))
+        .collect();
+    child_nodes.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+
+    UsageNode {
+        path: path.to_path_buf(),
+        is_dir: acc.is_dir,
+        size: acc.size,
+        file_count: acc.file_count,
+        children: child_nodes,
+    }
+}
+
+/// Filters applied by `largest_files`
+#[derive(Debug, Clone, Default)]
+pub struct LargestFilter {
+    /// Only keep files with one of these extensions (case-insensitive, no dot)
+    pub extensions: Vec<String>,
+    /// Only keep files not modified within this duration
+    pub older_than: Option<Duration>,
+    pub one_file_system: bool,
+    pub gitignore: IgnoreFilter,
+}
+
+/// A file found by `largest_files`
+#[derive(Debug, Clone)]
+pub struct LargeFile {
+    pub file: FileInfo,
+    pub modified: Option<SystemTime>,
+}
+
+/// Result of `largest_files`
+#[derive(Debug)]
+pub struct LargestFiles {
+    /// Largest first
+    pub files: Vec<LargeFile>,
+    /// Unreadable entries, not considered
+    pub skipped: SkippedEntries,
+    pub left_out: LeftOutEntries,
+}
+
+/// Find the `limit` largest files under `paths`, largest first
+pub fn largest_files(
+    paths: &[PathBuf],
+    limit: usize,
+    filter: &LargestFilter,
+) -> Result<LargestFiles> {
+    let opts = CollectOptions {
+        recursive: true,
+        one_file_system: filter.one_file_system,
+        keep_going: true,
+        gitignore: filter.gitignore,
+        ..Default::default()
+    };
+    let plan = collect_files_to_remove(paths, &opts)?;
+    let now = SystemTime::now();
+
+    let mut found: Vec<LargeFile> = plan
+        .files
+        .into_iter()
+        .filter(|f| !f.is_dir && matches_extension(&f.path, &filter.extensions))
+        .filter_map(|file| {
+            let modified = fs::symlink_metadata(&file.path)
+                .and_then(|m| m.modified())
+                .ok();
+            if let Some(min_age) = filter.older_than {
+                let age = modified.and_then(|m| now.duration_since(m).ok())?;
+                if age < min_age {
+                    return None;
+                }
+            }
+            Some(LargeFile { file, modified })
+        })
+        .collect();
+
+    found.sort_by(|a, b| {
+        b.file
+            .size
+            .cmp(&a.file.size)
+            .then_with(|| a.file.path.cmp(&b.file.path))
+    });
+    found.truncate(limit);
+    Ok(LargestFiles {
+        files: found,
+        skipped: plan.skipped,
+        left_out: plan.left_out,
+    })
+}
+
+fn matches_extension(path: &Path, extensions: &[String]) -> bool {
+    if extensions.is_empty() {
+        return true;
+    }
+    path.extension()
+        .map(|ext| ext.to_string_lossy())
+        .is_some_and(|ext| {
+            extensions
+                .iter()
+                .any(|want| want.trim_start_matches('.').eq_ignore_ascii_case(&ext))
+        })
+}
+
+/// Parse an age such as `30s`, `15m`, `12h`, `7d` or `2w`
+pub fn parse_age(s: &str) -> Result<Duration> {
+    let s = s.trim();
+    let split = s
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(|| anyhow!("Missing unit in '{s}' (use s, m, h, d or w)"))?;
+    let (value, unit) = s.split_at(split);
+    let value: u64 = value
+        .parse()
+        .map_err(|_| anyhow!("Invalid age '{s}', expected e.g. 7d"))?;
+    let secs = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => bail!("Unknown unit '{unit}' in '{s}' (use s, m, h, d or w)"),
+    };
+    Ok(Duration::from_secs(value.saturating_mul(secs)))
+}
@@ -1,4 +1,7 @@
+pub mod events;
 pub mod fs_ops;
 pub mod port;
 pub mod process;
+pub mod serve;
+pub mod snapshot;
 pub mod top;
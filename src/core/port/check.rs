@@ -0,0 +1,243 @@
+/// Health checks for deployment scripts (`ziro check`)
+///
+/// A port is healthy when it accepts a TCP connection and, if a path is given, answers a
+/// plain HTTP GET on it with a 2xx status. TLS is not spoken.
+use serde::Serialize;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// What to check and how patiently
+pub struct CheckOptions {
+    pub host: String,
+    pub port: u16,
+    /// HTTP path to GET, e.g. `/healthz`; `None` checks only that connections are accepted
+    pub http: Option<String>,
+    /// Attempts after the first before giving up
+    pub retries: u32,
+    /// Pause between attempts
+    pub interval: Duration,
+    /// How long one attempt may take, connecting and reading the answer each
+    pub timeout: Duration,
+}
+
+/// Why an attempt failed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "reason", content = "detail", rename_all = "snake_case")]
+pub enum CheckFailure {
+    /// The host name did not resolve
+    Unresolved(String),
+    /// Nothing accepted the connection in time
+    Unreachable(String),
+    /// Connected, but no readable HTTP status came back
+    BadResponse(String),
+    /// An HTTP answer outside 2xx
+    Status(u16),
+}
+
+impl std::fmt::Display for CheckFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unresolved(detail) => write!(f, "cannot resolve host: {detail}"),
+            Self::Unreachable(detail) => write!(f, "no connection: {detail}"),
+            Self::BadResponse(detail) => write!(f, "no HTTP answer: {detail}"),
+            Self::Status(status) => write!(f, "HTTP {status}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckAttempt {
+    /// 1 for the first attempt
+    pub attempt: u32,
+    /// HTTP status of a healthy answer; `None` for a TCP-only check or a failure
+    pub status: Option<u16>,
+    pub failure: Option<CheckFailure>,
+    pub elapsed_ms: u128,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckOutcome {
+    pub host: String,
+    pub port: u16,
+    pub http: Option<String>,
+    pub healthy: bool,
+    /// Every attempt made, the last one deciding
+    pub attempts: Vec<CheckAttempt>,
+    pub elapsed_ms: u128,
+}
+
+impl CheckOutcome {
+    /// Why the last attempt failed, `None` when healthy
+    pub fn failure(&self) -> Option<&CheckFailure> {
+        self.attempts.last()?.failure.as_ref()
+    }
+}
+
+/// Try until healthy or out of retries; `progress` sees each failed attempt that will be
+/// retried
+pub fn check_port(opts: &CheckOptions, mut progress: impl FnMut(&CheckAttempt)) -> CheckOutcome {
+    let started = Instant::now();
+    let mut attempts = Vec::new();
+    for attempt in 1..=opts.retries.saturating_add(1) {
+        let attempt_started = Instant::now();
+        let result = probe(opts);
+        let record = CheckAttempt {
+            attempt,
+            status: result.as_ref().ok().copied().flatten(),
+            failure: result.err(),
+            elapsed_ms: attempt_started.elapsed().as_millis(),
+        };
+        let healthy = record.failure.is_none();
+        attempts.push(record);
+        if healthy || attempt > opts.retries {
+            break;
+        }
+        progress(&attempts[attempts.len() - 1]);
+        thread::sleep(opts.interval);
+    }
+    CheckOutcome {
+        host: opts.host.clone(),
+        port: opts.port,
+        http: opts.http.clone(),
+        healthy: attempts.last().is_some_and(|a| a.failure.is_none()),
+        attempts,
+        elapsed_ms: started.elapsed().as_millis(),
+    }
+}
+
+/// Whether the port answers an HTTP request on `/` with any status at all
+pub fn speaks_http(host: &str, port: u16, timeout: Duration) -> bool {
+    let opts = CheckOptions {
+        host: host.to_string(),
+        port,
+        http: Some("/".to_string()),
+        retries: 0,
+        interval: Duration::ZERO,
+        timeout,
+    };
+    matches!(probe(&opts), Ok(_) | Err(CheckFailure::Status(_)))
+}
+
+/// One attempt: the HTTP status when a path is checked
+fn probe(opts: &CheckOptions) -> Result<Option<u16>, CheckFailure> {
+    let addrs: Vec<SocketAddr> = (opts.host.as_str(), opts.port)
+        .to_socket_addrs()
+        .map_err(|e| CheckFailure::Unresolved(e.to_string()))?
+        .collect();
+    // `localhost` may resolve to ::1 first while the server only listens on 127.0.0.1
+    let mut last_error = "no addresses".to_string();
+    let mut stream = None;
+    for addr in &addrs {
+        match TcpStream::connect_timeout(addr, opts.timeout) {
+            Ok(connected) => {
+                stream = Some(connected);
+                break;
+            }
+            Err(e) => last_error = e.to_string(),
+        }
+    }
+    let Some(stream) = stream else {
+        return Err(CheckFailure::Unreachable(last_error));
+    };
+    let Some(path) = &opts.http else {
+        return Ok(None);
+    };
+
+    let status = http_status(stream, &opts.host, opts.port, path, opts.timeout)
+        .map_err(|e| CheckFailure::BadResponse(e.to_string()))?;
+    if (200..300).contains(&status) {
+        Ok(Some(status))
+    } else {
+        Err(CheckFailure::Status(status))
+    }
+}
+
+/// Send a GET for `path` and read the status code off the answer
+fn http_status(
+    mut stream: TcpStream,
+    host: &str,
+    port: u16,
+    path: &str,
+    timeout: Duration,
+) -> std::io::Result<u16> {
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+    let path = if path.starts_with('/') {
+        path.to_string()
+    } else {
+        format!("/{path}")
+    };
+    // In one write: a server may answer and close once it has the request line
+    let request = format!(
+        "GET {path} HTTP/1.1\r\nHost: {host}:{port}\r\nUser-Agent: ziro\r\nAccept: */*\r\n\
+         Connection: close\r\n\r\n"
+    );
+    stream.write_all(request.as_bytes())?;
+    let mut status_line = String::new();
+    BufReader::new(stream).read_line(&mut status_line)?;
+    parse_status_line(&status_line).ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("unexpected answer {:?}", status_line.trim_end()),
+        )
+    })
+}
+
+/// `HTTP/1.1 204 No Content` -> 204
+fn parse_status_line(line: &str) -> Option<u16> {
+    let mut parts = line.split_whitespace();
+    parts
+        .next()
+        .filter(|version| version.starts_with("HTTP/"))?;
+    parts.next()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_parse_status_line() {
+        assert_eq!(parse_status_line("HTTP/1.1 204 No Content\r\n"), Some(204));
+        assert_eq!(parse_status_line("HTTP/1.0 503\r\n"), Some(503));
+        assert_eq!(parse_status_line("SSH-2.0-OpenSSH_9.6\r\n"), None);
+        assert_eq!(parse_status_line(""), None);
+    }
+
+    #[test]
+    fn test_check_port() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = thread::spawn(move || {
+            for status in ["503 Service Unavailable", "200 OK"] {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = [0u8; 1024];
+                let _ = stream.read(&mut request).unwrap();
+                write!(stream, "HTTP/1.1 {status}\r\nContent-Length: 0\r\n\r\n").unwrap();
+            }
+        });
+
+        let mut retried = 0;
+        let outcome = check_port(
+            &CheckOptions {
+                host: "127.0.0.1".to_string(),
+                port,
+                http: Some("healthz".to_string()),
+                retries: 3,
+                interval: Duration::ZERO,
+                timeout: Duration::from_secs(5),
+            },
+            |_| retried += 1,
+        );
+        server.join().unwrap();
+        assert!(outcome.healthy, "{outcome:?}");
+        assert_eq!(retried, 1);
+        assert_eq!(outcome.attempts.len(), 2);
+        assert_eq!(outcome.attempts[0].failure, Some(CheckFailure::Status(503)));
+        assert_eq!(outcome.attempts[1].status, Some(200));
+    }
+}
@@ -0,0 +1,126 @@
+/// Running port backends side by side
+///
+/// With the `async` feature each backend runs on tokio's blocking pool under its own
+/// timeout, so a slow tool costs the time of the slowest one instead of the sum, and a
+/// hanging one no more than its timeout. Without it, or when called from inside a tokio
+/// runtime (which cannot be blocked on), they run one after the other.
+use anyhow::Result;
+use std::time::Duration;
+
+/// One lookup, e.g. reading the connection table
+pub(super) struct Backend<T> {
+    /// Named in the timeout error
+    #[cfg_attr(not(feature = "async"), allow(dead_code))]
+    name: &'static str,
+    #[cfg_attr(not(feature = "async"), allow(dead_code))]
+    timeout: Duration,
+    run: Box<dyn FnOnce() -> Result<T> + Send>,
+}
+
+impl<T: Send + 'static> Backend<T> {
+    pub(super) fn new(
+        name: &'static str,
+        timeout: Duration,
+        run: impl FnOnce() -> Result<T> + Send + 'static,
+    ) -> Self {
+        Self {
+            name,
+            timeout,
+            run: Box::new(run),
+        }
+    }
+
+    fn run_now(self) -> Result<T> {
+        (self.run)()
+    }
+
+    #[cfg(feature = "async")]
+    async fn spawn(self) -> Result<T> {
+        let Self { name, timeout, run } = self;
+        match tokio::time::timeout(timeout, tokio::task::spawn_blocking(run)).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(e)) => Err(anyhow::anyhow!("{name} failed: {e}")),
+            Err(_) => Err(anyhow::anyhow!(
+                "{name} did not answer within {}s",
+                timeout.as_secs()
+            )),
+        }
+    }
+}
+
+/// Run both backends and wait for each, up to its timeout
+#[cfg(feature = "async")]
+pub(super) fn join<A: Send + 'static, B: Send + 'static>(
+    a: Backend<A>,
+    b: Backend<B>,
+) -> (Result<A>, Result<B>) {
+    if tokio::runtime::Handle::try_current().is_ok() {
+        return (a.run_now(), b.run_now());
+    }
+    let Ok(runtime) = tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+    else {
+        return (a.run_now(), b.run_now());
+    };
+    let results = runtime.block_on(async { tokio::join!(a.spawn(), b.spawn()) });
+    // A backend past its timeout is left to finish on its own
+    runtime.shutdown_background();
+    results
+}
+
+/// Run both backends, one after the other
+#[cfg(not(feature = "async"))]
+pub(super) fn join<A: Send + 'static, B: Send + 'static>(
+    a: Backend<A>,
+    b: Backend<B>,
+) -> (Result<A>, Result<B>) {
+    (a.run_now(), b.run_now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_join() {
+        let (a, b) = join(
+            Backend::new("first", Duration::from_secs(5), || Ok(1)),
+            Backend::new("second", Duration::from_secs(5), || {
+                Err::<(), _>(anyhow::anyhow!("broken"))
+            }),
+        );
+        assert_eq!(a.unwrap(), 1);
+        assert_eq!(b.unwrap_err().to_string(), "broken");
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_join_timeout() {
+        let started = std::time::Instant::now();
+        let (slow, fast) = join(
+            Backend::new("slow", Duration::from_millis(50), || {
+                std::thread::sleep(Duration::from_secs(2));
+                Ok(())
+            }),
+            Backend::new("fast", Duration::from_secs(5), || Ok(2)),
+        );
+        assert!(
+            slow.unwrap_err()
+                .to_string()
+                .contains("slow did not answer")
+        );
+        assert_eq!(fast.unwrap(), 2);
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_join_inside_runtime() {
+        let (a, b) = join(
+            Backend::new("first", Duration::from_secs(5), || Ok(1)),
+            Backend::new("second", Duration::from_secs(5), || Ok(2)),
+        );
+        assert_eq!((a.unwrap(), b.unwrap()), (1, 2));
+    }
+}
@@ -0,0 +1,380 @@
+/// Established TCP connections and their remote peers (`ziro conns`)
+use super::ProcessInfo;
+use crate::core::process;
+use crate::platform::dns;
+use anyhow::Result;
+use regex::Regex;
+use serde::Serialize;
+use std::net::{IpAddr, SocketAddr};
+
+/// One established connection of a process
+#[derive(Debug, Clone, Serialize)]
+pub struct Connection {
+    pub pid: u32,
+    pub name: String,
+    pub local: SocketAddr,
+    pub remote: SocketAddr,
+    /// Reverse DNS name of the remote address, when asked for and known
+    pub remote_host: Option<String>,
+}
+
+/// Which connections `list_connections` returns
+#[derive(Default)]
+pub struct ConnOptions {
+    /// Only processes whose name or command line matches
+    pub filter: Option<Regex>,
+    /// Only connections to this remote address
+    pub remote: Option<IpAddr>,
+    /// Look up host names of the remote addresses
+    pub resolve: bool,
+}
+
+/// Established connections with a known owner, by PID then remote address
+///
+/// Without privileges, other users' sockets have no visible owner and are left out.
+pub fn list_connections(opts: &ConnOptions) -> Result<Vec<Connection>> {
+    let rows = established()?;
+    let mut connections: Vec<Connection> = process::shared().with(|sys| {
+        rows.into_iter()
+            .filter(|(_, _, remote)| opts.remote.is_none_or(|ip| same_ip(remote.ip(), ip)))
+            .filter_map(|(pid, local, remote)| {
+                let info = ProcessInfo::lookup(sys, pid)?;
+                let matches = opts.filter.as_ref().is_none_or(|filter| {
+                    filter.is_match(&info.name) || filter.is_match(&info.cmd.join(" "))
+                });
+                matches.then_some(Connection {
+                    pid,
+                    name: info.name,
+                    local,
+                    remote,
+                    remote_host: None,
+                })
+            })
+            .collect()
+    });
+    connections.sort_by_key(|c| (c.pid, c.remote, c.local));
+
+    if opts.resolve {
+        let names = dns::reverse_lookup_all(connections.iter().map(|c| c.remote.ip()));
+        for connection in &mut connections {
+            connection.remote_host = names.get(&connection.remote.ip()).cloned();
+        }
+    }
+    Ok(connections)
+}
+
+/// Equal addresses, counting an IPv4-mapped IPv6 address (`::ffff:1.2.3.4`) as its IPv4 one
+fn same_ip(a: IpAddr, b: IpAddr) -> bool {
+    a.to_canonical() == b.to_canonical()
+}
+
+/// An address as the connection tables print it: `1.2.3.4:443`, `[::1]:443`,
+/// `::1:443` or `fe80::1%em0:443`; wildcards such as `*:*` give `None`
+#[cfg(any(not(target_os = "linux"), test))]
+fn parse_address(address: &str) -> Option<SocketAddr> {
+    if let Ok(addr) = address.parse() {
+        return Some(addr);
+    }
+    let (host, port) = address.rsplit_once(':')?;
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    // Zone indexes (`%em0`) name the local interface, not part of the address
+    let host = host.split('%').next()?;
+    Some(SocketAddr::new(host.parse().ok()?, port.parse().ok()?))
+}
+
+/// (PID, local, remote) of every established TCP connection
+#[cfg(target_os = "linux")]
+fn established() -> Result<Vec<(u32, SocketAddr, SocketAddr)>> {
+    let owners = super::socket_owners();
+    let mut rows = Vec::new();
+    for path in ["/proc/net/tcp", "/proc/net/tcp6"] {
+        let Ok(content) = std::fs::read_to_string(path) else {
+            continue;
+        };
+        for (local, remote, inode) in parse_proc_net_established(&content) {
+            if let Some(&pid) = owners.get(&inode) {
+                rows.push((pid, local, remote));
+            }
+        }
+    }
+    Ok(rows)
+}
+
+/// (local, remote, inode) of the ESTABLISHED rows of /proc/net/tcp[6]
+#[cfg(any(target_os = "linux", test))]
+fn parse_proc_net_established(content: &str) -> Vec<(SocketAddr, SocketAddr, u64)> {
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if *parts.get(3)? != "01" {
+                return None;
+            }
+            let inode = parts.get(9)?.parse::<u64>().ok().filter(|&i| i != 0)?;
+            Some((
+                parse_proc_address(parts.get(1)?)?,
+                parse_proc_address(parts.get(2)?)?,
+                inode,
+            ))
+        })
+        .collect()
+}
+
+/// `0100007F:1F90` or a 32-digit IPv6 form: the address is printed as 32-bit words in
+/// host byte order, the port in plain hex
+#[cfg(any(target_os = "linux", test))]
+fn parse_proc_address(field: &str) -> Option<SocketAddr> {
+    let (address, port) = field.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+    let mut bytes = Vec::with_capacity(16);
+    for i in (0..address.len()).step_by(8) {
+        let word = u32::from_str_radix(address.get(i..i + 8)?, 16).ok()?;
+        bytes.extend_from_slice(&word.to_ne_bytes());
+    }
+    let ip = match bytes.len() {
+        4 => IpAddr::from(<[u8; 4]>::try_from(bytes).ok()?),
+        16 => IpAddr::from(<[u8; 16]>::try_from(bytes).ok()?),
+        _ => return None,
+    };
+    Some(SocketAddr::new(ip, port))
+}
+
+#[cfg(target_os = "macos")]
+fn established() -> Result<Vec<(u32, SocketAddr, SocketAddr)>> {
+    use crate::platform::command::run_command;
+    use std::process::Command;
+
+    let output = run_command(Command::new("lsof").args(["-nP", "-iTCP", "-sTCP:ESTABLISHED"]))?;
+    Ok(parse_lsof_established(
+        &process::encoding::safe_command_output_to_string(&output.stdout),
+    ))
+}
+
+/// `node 4242 alice 21u IPv4 0x... 0t0 TCP 10.0.0.5:52000->140.82.112.3:443 (ESTABLISHED)`
+#[cfg(any(target_os = "macos", test))]
+fn parse_lsof_established(text: &str) -> Vec<(u32, SocketAddr, SocketAddr)> {
+    text.lines()
+        .skip(1)
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let pid = parts.get(1)?.parse::<u32>().ok()?;
+            let (local, remote) = parts.get(8)?.split_once("->")?;
+            Some((pid, parse_address(local)?, parse_address(remote)?))
+        })
+        .collect()
+}
+
+#[cfg(target_os = "windows")]
+fn established() -> Result<Vec<(u32, SocketAddr, SocketAddr)>> {
+    use crate::platform::command::run_command;
+    use std::process::Command;
+
+    let output = run_command(Command::new("netstat").args(["-ano"]))?;
+    Ok(parse_netstat_established(
+        &process::encoding::safe_command_output_to_string(&output.stdout),
+    ))
+}
+
+/// `  TCP    10.0.0.5:52000    140.82.112.3:443    ESTABLISHED    4242`
+#[cfg(any(target_os = "windows", test))]
+fn parse_netstat_established(text: &str) -> Vec<(u32, SocketAddr, SocketAddr)> {
+    text.lines()
+        .filter_map(
+            |line| match line.split_whitespace().collect::<Vec<_>>().as_slice() {
+                ["TCP", local, remote, "ESTABLISHED", pid] => Some((
+                    pid.parse().ok()?,
+                    parse_address(local)?,
+                    parse_address(remote)?,
+                )),
+                _ => None,
+            },
+        )
+        .collect()
+}
+
+#[cfg(any(target_os = "freebsd", target_os = "netbsd", target_os = "dragonfly"))]
+fn established() -> Result<Vec<(u32, SocketAddr, SocketAddr)>> {
+    use crate::platform::command::run_command;
+    use std::process::Command;
+
+    // -c: connected sockets only
+    let output = run_command(Command::new("sockstat").args(["-4", "-6", "-c", "-P", "tcp"]))?;
+    Ok(parse_sockstat_established(
+        &process::encoding::safe_command_output_to_string(&output.stdout),
+    ))
+}
+
+/// `alice node 4242 21 tcp4 10.0.0.5:52000 140.82.112.3:443`
+#[cfg(any(
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "dragonfly",
+    test
+))]
+fn parse_sockstat_established(text: &str) -> Vec<(u32, SocketAddr, SocketAddr)> {
+    text.lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let pid = parts.get(2)?.parse::<u32>().ok()?;
+            Some((
+                pid,
+                parse_address(parts.get(5)?)?,
+                parse_address(parts.get(6)?)?,
+            ))
+        })
+        .collect()
+}
+
+#[cfg(target_os = "openbsd")]
+fn established() -> Result<Vec<(u32, SocketAddr, SocketAddr)>> {
+    use crate::platform::command::run_command;
+    use std::process::Command;
+
+    let output = run_command(&mut Command::new("fstat"))?;
+    Ok(parse_fstat_established(
+        &process::encoding::safe_command_output_to_string(&output.stdout),
+    ))
+}
+
+/// `alice node 4242 6* internet stream tcp 0x0 10.0.0.5:52000 --> 140.82.112.3:443`
+#[cfg(any(target_os = "openbsd", test))]
+fn parse_fstat_established(text: &str) -> Vec<(u32, SocketAddr, SocketAddr)> {
+    text.lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let at = parts.iter().position(|p| p.starts_with("internet"))?;
+            if parts.get(at + 2) != Some(&"tcp") {
+                return None;
+            }
+            let pid = parts.get(2)?.parse::<u32>().ok()?;
+            let rest: Vec<&str> = parts[at + 3..]
+                .iter()
+                .copied()
+                .filter(|p| !p.starts_with("0x"))
+                .collect();
+            match rest.as_slice() {
+                [local, "<->" | "-->" | "<--", remote, ..] => {
+                    Some((pid, parse_address(local)?, parse_address(remote)?))
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(not(any(
+    target_os = "windows",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "dragonfly",
+    target_os = "openbsd"
+)))]
+fn established() -> Result<Vec<(u32, SocketAddr, SocketAddr)>> {
+    Err(anyhow::Error::msg(
+        "Network connection queries are not supported on the current operating system",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addr(s: &str) -> SocketAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_address() {
+        assert_eq!(parse_address("10.0.0.5:443"), Some(addr("10.0.0.5:443")));
+        assert_eq!(parse_address("[::1]:8080"), Some(addr("[::1]:8080")));
+        assert_eq!(parse_address("::1:8080"), Some(addr("[::1]:8080")));
+        assert_eq!(parse_address("fe80::1%em0:22"), Some(addr("[fe80::1]:22")));
+        assert_eq!(parse_address("*:*"), None);
+    }
+
+    #[test]
+    fn test_parse_proc_net_established() {
+        let content = "\
+  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 0100007F:1F90 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 111 1
+   1: 0500000A:CB20 03705C8C:01BB 01 00000000:00000000 00:00000000 00000000  1000        0 222 1
+";
+        let rows = parse_proc_net_established(content);
+        assert_eq!(rows.len(), 1);
+        let (local, remote, inode) = rows[0];
+        if cfg!(target_endian = "little") {
+            assert_eq!(local, addr("10.0.0.5:52000"));
+            assert_eq!(remote, addr("140.92.112.3:443"));
+        }
+        assert_eq!(inode, 222);
+        assert_eq!(
+            parse_proc_address("00000000000000000000000001000000:0050").map(|a| a.port()),
+            Some(80)
+        );
+    }
+
+    #[test]
+    fn test_parse_lsof_established() {
+        let text = "\
+COMMAND   PID  USER   FD   TYPE DEVICE SIZE/OFF NODE NAME
+node     4242 alice   21u  IPv4 0x1234      0t0  TCP 10.0.0.5:52000->140.82.112.3:443 (ESTABLISHED)
+curl     4343 alice    5u  IPv6 0x5678      0t0  TCP [::1]:50000->[::1]:8080 (ESTABLISHED)
+";
+        assert_eq!(
+            parse_lsof_established(text),
+            [
+                (4242, addr("10.0.0.5:52000"), addr("140.82.112.3:443")),
+                (4343, addr("[::1]:50000"), addr("[::1]:8080")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_netstat_established() {
+        let text = "\
+  Proto  Local Address          Foreign Address        State           PID
+  TCP    0.0.0.0:135            0.0.0.0:0              LISTENING       1000
+  TCP    10.0.0.5:52000         140.82.112.3:443       ESTABLISHED     4242
+  TCP    [::1]:50000            [::1]:8080             ESTABLISHED     4343
+  UDP    0.0.0.0:5353           *:*                                    2000
+";
+        assert_eq!(
+            parse_netstat_established(text),
+            [
+                (4242, addr("10.0.0.5:52000"), addr("140.82.112.3:443")),
+                (4343, addr("[::1]:50000"), addr("[::1]:8080")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_bsd_established() {
+        let sockstat = "\
+USER     COMMAND    PID   FD PROTO  LOCAL ADDRESS         FOREIGN ADDRESS
+alice    node       4242  21 tcp4   10.0.0.5:52000        140.82.112.3:443
+";
+        assert_eq!(
+            parse_sockstat_established(sockstat),
+            [(4242, addr("10.0.0.5:52000"), addr("140.82.112.3:443"))]
+        );
+        let fstat = "\
+USER     CMD          PID   FD MOUNT        INUM MODE         R/W    SZ|DV
+alice    node        4242    6* internet stream tcp 0x0 10.0.0.5:52000 --> 140.82.112.3:443
+www      httpd       1234    6* internet stream tcp 0x0 *:80
+";
+        assert_eq!(
+            parse_fstat_established(fstat),
+            [(4242, addr("10.0.0.5:52000"), addr("140.82.112.3:443"))]
+        );
+    }
+
+    #[test]
+    fn test_same_ip() {
+        let mapped: IpAddr = "::ffff:140.82.112.3".parse().unwrap();
+        assert!(same_ip(mapped, "140.82.112.3".parse().unwrap()));
+        assert!(!same_ip(mapped, "140.82.112.4".parse().unwrap()));
+    }
+}
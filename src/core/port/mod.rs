@@ -1,6 +1,26 @@
-use anyhow::Result;
+use crate::core::process;
+use anyhow::{Result, anyhow};
 use std::collections::HashMap;
-use sysinfo::{ProcessRefreshKind, RefreshKind, System};
+use std::io;
+use std::net::{IpAddr, TcpListener};
+use std::time::Duration;
+
+pub mod check;
+mod concurrent;
+pub mod conns;
+pub mod net;
+mod snapshot;
+
+use concurrent::Backend;
+
+/// How long the connection table tool (netstat, lsof, sockstat) may take
+const CONNECTIONS_TIMEOUT: Duration = Duration::from_secs(10);
+/// How long a Windows tool may take from inside WSL, where interop starts slowly
+const HOST_TIMEOUT: Duration = Duration::from_secs(15);
+/// How many ports `bind_free_port` tries before giving up
+const FREE_PORT_ATTEMPTS: u16 = 100;
+
+pub use snapshot::Snapshot;
 
 /// Process information
 #[derive(Debug, Clone)]
@@ -27,6 +47,61 @@ impl ProcessInfo {
             memory: process.memory(),
         }
     }
+
+    /// Look up `pid`, asking `ps` where sysinfo has no backend for the OS
+    fn lookup(sys: &sysinfo::System, pid: u32) -> Option<Self> {
+        match sys.process(sysinfo::Pid::from_u32(pid)) {
+            Some(process) => Some(Self::from_sysinfo(pid, process)),
+            None => Self::from_ps(pid),
+        }
+    }
+
+    #[cfg(any(target_os = "openbsd", target_os = "netbsd", target_os = "dragonfly"))]
+    fn from_ps(pid: u32) -> Option<Self> {
+        use crate::platform::command::run_command;
+        use std::process::Command;
+
+        let output = run_command(Command::new("ps").args([
+            "-o",
+            "rss=",
+            "-o",
+            "comm=",
+            "-o",
+            "args=",
+            "-p",
+            &pid.to_string(),
+        ]))
+        .ok()?;
+        parse_ps_line(
+            pid,
+            &process::encoding::safe_command_output_to_string(&output.stdout),
+        )
+    }
+
+    #[cfg(not(any(target_os = "openbsd", target_os = "netbsd", target_os = "dragonfly")))]
+    fn from_ps(_pid: u32) -> Option<Self> {
+        None
+    }
+}
+
+/// Parse `ps -o rss= -o comm= -o args=`: resident KiB, the name, then the command line
+#[cfg(any(
+    target_os = "openbsd",
+    target_os = "netbsd",
+    target_os = "dragonfly",
+    test
+))]
+fn parse_ps_line(pid: u32, line: &str) -> Option<ProcessInfo> {
+    let mut parts = line.split_whitespace();
+    let rss = parts.next()?.parse::<u64>().ok()?;
+    let name = parts.next()?.to_string();
+    Some(ProcessInfo {
+        pid,
+        name,
+        cmd: parts.map(str::to_string).collect(),
+        cpu_usage: 0.0,
+        memory: rss * 1024,
+    })
 }
 
 /// Port usage information
@@ -38,59 +113,127 @@ pub struct PortInfo {
 
 /// Find processes occupying multiple ports
 pub fn find_processes_by_ports(ports: &[u16]) -> Result<Vec<PortInfo>> {
-    let connections = get_network_connections()?;
-    let sys = System::new_with_specifics(
-        RefreshKind::new().with_processes(ProcessRefreshKind::everything()),
-    );
-
-    let mut result = Vec::new();
-
-    for &port in ports {
-        if let Some(&pid) = connections.get(&port)
-            && let Some(process) = sys.process(sysinfo::Pid::from_u32(pid))
-        {
-            let process_info = ProcessInfo::from_sysinfo(pid, process);
-            result.push(PortInfo {
-                port,
-                process: process_info,
-            });
-        }
-    }
-
-    Ok(result)
+    Ok(Snapshot::take()?.find(ports))
 }
 
 /// List all port usage
 pub fn list_all_ports() -> Result<Vec<PortInfo>> {
-    let connections = get_network_connections()?;
-    let sys = System::new_with_specifics(
-        RefreshKind::new().with_processes(ProcessRefreshKind::everything()),
-    );
+    Ok(Snapshot::take()?.list())
+}
 
-    let mut result = Vec::new();
+/// Every port in use here and, inside WSL, on the Windows host, both read at once
+pub fn list_with_host_ports() -> (Result<Vec<PortInfo>>, Result<Vec<PortInfo>>) {
+    concurrent::join(
+        Backend::new("port table", CONNECTIONS_TIMEOUT, list_all_ports),
+        Backend::new("Windows host ports", HOST_TIMEOUT, list_host_ports),
+    )
+}
 
-    for (port, pid) in connections {
-        if let Some(process) = sys.process(sysinfo::Pid::from_u32(pid)) {
-            let process_info = ProcessInfo::from_sysinfo(pid, process);
-            result.push(PortInfo {
-                port,
-                process: process_info,
-            });
+/// Listen on the first free port from `from` upward
+///
+/// Binding is the test, so the port cannot be taken between the check and its use.
+pub fn bind_free_port(ip: IpAddr, from: u16) -> Result<TcpListener> {
+    let last = from.saturating_add(FREE_PORT_ATTEMPTS - 1);
+    for port in from..=last {
+        match TcpListener::bind((ip, port)) {
+            Ok(listener) => return Ok(listener),
+            // Windows reports a port bound exclusively by another process as access denied
+            Err(e)
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::AddrInUse | io::ErrorKind::PermissionDenied
+                ) => {}
+            Err(e) => return Err(e.into()),
         }
     }
+    Err(anyhow!("No free port between {from} and {last}"))
+}
 
-    // Sort by port number
-    result.sort_by_key(|info| info.port);
+/// Ports held by one process, in ascending order
+pub fn ports_of_process(pid: u32) -> Result<Vec<u16>> {
+    let mut ports: Vec<u16> = get_network_connections()?
+        .into_iter()
+        .filter_map(|(port, owner)| (owner == pid).then_some(port))
+        .collect();
+    ports.sort_unstable();
+    Ok(ports)
+}
 
+/// Ports held on the Windows host, seen from inside WSL through `netstat.exe` and
+/// `tasklist.exe`; the PIDs are Windows PIDs and the commands are unknown
+pub fn list_host_ports() -> Result<Vec<PortInfo>> {
+    use crate::platform::{command::run_command, wsl};
+    use std::process::Command;
+
+    let (connections, tasks) = concurrent::join(
+        Backend::new("netstat.exe", HOST_TIMEOUT, || {
+            let output =
+                run_command(Command::new(wsl::windows_program("netstat.exe")).arg("-ano"))?;
+            parse_netstat_output(&output.stdout)
+        }),
+        Backend::new("tasklist.exe", HOST_TIMEOUT, || {
+            let output = run_command(
+                Command::new(wsl::windows_program("tasklist.exe")).args(["/FO", "CSV", "/NH"]),
+            )?;
+            Ok(parse_tasklist(
+                &process::encoding::safe_command_output_to_string(&output.stdout),
+            ))
+        }),
+    );
+    let connections = connections?;
+    // Without names the ports are still worth showing
+    let tasks = tasks.unwrap_or_default();
+
+    let mut result: Vec<PortInfo> = connections
+        .into_iter()
+        // PID 0 is the System Idle Process, which netstat names for TIME_WAIT rows
+        .filter(|&(_, pid)| pid != 0)
+        .map(|(port, pid)| {
+            let (name, memory) = tasks
+                .get(&pid)
+                .cloned()
+                .unwrap_or_else(|| ("unknown".to_string(), 0));
+            PortInfo {
+                port,
+                process: ProcessInfo {
+                    pid,
+                    name,
+                    cmd: Vec::new(),
+                    cpu_usage: 0.0,
+                    memory,
+                },
+            }
+        })
+        .collect();
+    result.sort_by_key(|info| info.port);
     Ok(result)
 }
 
+/// PID -> (image name, memory in bytes) from `tasklist /FO CSV /NH`, whose rows read
+/// `"node.exe","1234","Console","1","45,678 K"`
+fn parse_tasklist(text: &str) -> HashMap<u32, (String, u64)> {
+    text.lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.trim().trim_matches('"').split("\",\"").collect();
+            let pid = fields.get(1)?.parse::<u32>().ok()?;
+            // Grouped by the locale: 45,678 K or 45.678 K or 45 678 K
+            let kib: String = fields
+                .get(4)
+                .map(|memory| memory.chars().filter(char::is_ascii_digit).collect())
+                .unwrap_or_default();
+            let memory = kib.parse::<u64>().unwrap_or(0) * 1024;
+            Some((pid, (fields[0].to_string(), memory)))
+        })
+        .collect()
+}
+
 /// Get network connection information (port -> PID mapping)
 #[cfg(target_os = "windows")]
 fn get_network_connections() -> Result<HashMap<u16, u32>> {
+    use crate::platform::command::run_command;
     use std::process::Command;
 
-    let output = Command::new("netstat").args(["-ano"]).output()?;
+    let output = run_command(Command::new("netstat").args(["-ano"]))?;
 
     // Use simple string processing to avoid encoding conversion issues
     let connections = parse_netstat_output(&output.stdout)?;
@@ -99,12 +242,11 @@ fn get_network_connections() -> Result<HashMap<u16, u32>> {
 }
 
 /// Parse netstat output, extract port-to-PID mapping
-#[cfg(target_os = "windows")]
 fn parse_netstat_output(stdout: &[u8]) -> Result<HashMap<u16, u32>> {
     let mut connections = HashMap::new();
 
-    // Use lossy conversion directly to avoid complex encoding detection
-    let text = String::from_utf8_lossy(stdout);
+    // Localized Windows writes the headers in the console code page
+    let text = process::encoding::safe_command_output_to_string(stdout);
 
     for line in text.lines() {
         // Skip header lines and empty lines
@@ -226,9 +368,10 @@ fn find_pid_by_inode(inode: u64) -> Result<u32> {
 
 #[cfg(target_os = "macos")]
 fn get_network_connections() -> Result<HashMap<u16, u32>> {
+    use crate::platform::command::run_command;
     use std::process::Command;
 
-    let output = Command::new("lsof").args(["-i", "-n", "-P"]).output()?;
+    let output = run_command(Command::new("lsof").args(["-i", "-n", "-P"]))?;
 
     // Use simple string processing to avoid encoding conversion issues
     let connections = parse_lsof_output(&output.stdout)?;
@@ -241,8 +384,7 @@ fn get_network_connections() -> Result<HashMap<u16, u32>> {
 fn parse_lsof_output(stdout: &[u8]) -> Result<HashMap<u16, u32>> {
     let mut connections = HashMap::new();
 
-    // Use lossy conversion directly to avoid complex encoding detection
-    let text = String::from_utf8_lossy(stdout);
+    let text = process::encoding::safe_command_output_to_string(stdout);
 
     for line in text.lines().skip(1) {
         let parts: Vec<&str> = line.split_whitespace().collect();
@@ -267,8 +409,343 @@ fn parse_lsof_output(stdout: &[u8]) -> Result<HashMap<u16, u32>> {
     Ok(connections)
 }
 
-#[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+#[cfg(any(
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "dragonfly",
+    target_os = "openbsd"
+))]
 fn get_network_connections() -> Result<HashMap<u16, u32>> {
+    let mut connections = HashMap::new();
+    for (pid, port, state) in bsd_sockets()? {
+        // A listener wins over connections that happen to share its port
+        if state == SocketState::Listening || !connections.contains_key(&port) {
+            connections.insert(port, pid);
+        }
+    }
+    Ok(connections)
+}
+
+/// (PID, local port, state) of every IPv4/IPv6 socket, from `sockstat`
+#[cfg(any(target_os = "freebsd", target_os = "netbsd", target_os = "dragonfly"))]
+fn bsd_sockets() -> Result<Vec<(u32, u16, SocketState)>> {
+    use crate::platform::command::run_command;
+    use std::process::Command;
+
+    let output = run_command(Command::new("sockstat").args(["-4", "-6"]))?;
+    Ok(parse_sockstat_output(
+        &process::encoding::safe_command_output_to_string(&output.stdout),
+    ))
+}
+
+/// (PID, local port, state) of every IPv4/IPv6 socket, from `fstat`
+#[cfg(target_os = "openbsd")]
+fn bsd_sockets() -> Result<Vec<(u32, u16, SocketState)>> {
+    use crate::platform::command::run_command;
+    use std::process::Command;
+
+    let output = run_command(&mut Command::new("fstat"))?;
+    Ok(parse_fstat_sockets(
+        &process::encoding::safe_command_output_to_string(&output.stdout),
+    ))
+}
+
+/// Port of an address such as `*:8080`, `127.0.0.1:8080` or `fe80::1%em0:8080`
+#[cfg(any(
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "dragonfly",
+    target_os = "openbsd",
+    test
+))]
+fn address_port(address: &str) -> Option<u16> {
+    address.rsplit(':').next()?.parse().ok()
+}
+
+/// Parse `sockstat -4 -6`; a socket without a peer (`*:*`) is listening or bound
+///
+/// ```text
+/// USER     COMMAND    PID   FD PROTO  LOCAL ADDRESS         FOREIGN ADDRESS
+/// www      nginx      1234  6  tcp4   *:80                  *:*
+/// ```
+#[cfg(any(
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "dragonfly",
+    test
+))]
+fn parse_sockstat_output(text: &str) -> Vec<(u32, u16, SocketState)> {
+    text.lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let pid = parts.get(2)?.parse::<u32>().ok()?;
+            let port = address_port(parts.get(5)?)?;
+            let state = match *parts.get(6)? {
+                "*:*" => SocketState::Listening,
+                _ => SocketState::Established,
+            };
+            Some((pid, port, state))
+        })
+        .collect()
+}
+
+/// Parse the `internet` rows of OpenBSD's `fstat`; connected sockets name their
+/// peer after an arrow
+///
+/// ```text
+/// USER     CMD          PID   FD MOUNT        INUM MODE         R/W    SZ|DV
+/// www      httpd      1234     6* internet stream tcp 0x0 *:80
+/// ```
+#[cfg(any(target_os = "openbsd", test))]
+fn parse_fstat_sockets(text: &str) -> Vec<(u32, u16, SocketState)> {
+    text.lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let at = parts.iter().position(|p| p.starts_with("internet"))?;
+            let pid = parts.get(2)?.parse::<u32>().ok()?;
+            // Kernel address of the protocol control block, when fstat shows it
+            let rest: Vec<&str> = parts[at + 3..]
+                .iter()
+                .copied()
+                .filter(|p| !p.starts_with("0x"))
+                .collect();
+            let port = address_port(rest.first()?)?;
+            let state = match rest.get(1) {
+                Some(&("<->" | "-->" | "<--")) => SocketState::Established,
+                _ => SocketState::Listening,
+            };
+            Some((pid, port, state))
+        })
+        .collect()
+}
+
+#[cfg(not(any(
+    target_os = "windows",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "dragonfly",
+    target_os = "openbsd"
+)))]
+fn get_network_connections() -> Result<HashMap<u16, u32>> {
+    Err(anyhow::Error::msg(
+        "Network connection queries are not supported on the current operating system",
+    ))
+}
+
+/// Socket counts of one process
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SocketCounts {
+    /// Listening TCP sockets and bound UDP sockets
+    pub listening: usize,
+    /// Established TCP connections and connected UDP sockets
+    pub established: usize,
+}
+
+impl SocketCounts {
+    fn add(&mut self, state: SocketState) {
+        match state {
+            SocketState::Listening => self.listening += 1,
+            SocketState::Established => self.established += 1,
+            SocketState::Other => {}
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SocketState {
+    Listening,
+    Established,
+    /// Closing, waiting, ... not worth a column
+    // sockstat and fstat list no TCP states, so the BSDs never see one
+    #[cfg_attr(
+        any(
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "dragonfly",
+            target_os = "openbsd"
+        ),
+        allow(dead_code)
+    )]
+    Other,
+}
+
+/// Listening and established sockets of every process that has any
+#[cfg(target_os = "linux")]
+pub fn socket_counts() -> Result<HashMap<u32, SocketCounts>> {
+    use std::fs;
+
+    let owners = socket_owners();
+    let mut counts: HashMap<u32, SocketCounts> = HashMap::new();
+    for (path, udp) in [
+        ("/proc/net/tcp", false),
+        ("/proc/net/tcp6", false),
+        ("/proc/net/udp", true),
+        ("/proc/net/udp6", true),
+    ] {
+        let Ok(content) = fs::read_to_string(path) else {
+            continue;
+        };
+        for (state, inode) in parse_proc_net_states(&content, udp) {
+            if let Some(&pid) = owners.get(&inode) {
+                counts.entry(pid).or_default().add(state);
+            }
+        }
+    }
+    Ok(counts)
+}
+
+/// Socket inode -> owning PID, from one pass over every process's fds
+#[cfg(target_os = "linux")]
+fn socket_owners() -> HashMap<u64, u32> {
+    use std::fs;
+
+    let mut owners = HashMap::new();
+    let Ok(proc_dir) = fs::read_dir("/proc") else {
+        return owners;
+    };
+    for entry in proc_dir.flatten() {
+        let Some(pid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|s| s.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        let Ok(fds) = fs::read_dir(entry.path().join("fd")) else {
+            continue;
+        };
+        for fd in fds.flatten() {
+            if let Ok(link) = fs::read_link(fd.path())
+                && let Some(inode) = link
+                    .to_str()
+                    .and_then(|l| l.strip_prefix("socket:["))
+                    .and_then(|l| l.strip_suffix(']'))
+                    .and_then(|l| l.parse::<u64>().ok())
+            {
+                owners.insert(inode, pid);
+            }
+        }
+    }
+    owners
+}
+
+/// (state, inode) of each socket in /proc/net/{tcp,udp}[6]; sockets without an
+/// inode (TIME_WAIT) are skipped
+#[cfg(any(target_os = "linux", test))]
+fn parse_proc_net_states(content: &str, udp: bool) -> Vec<(SocketState, u64)> {
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let state = u8::from_str_radix(parts.get(3)?, 16).ok()?;
+            let inode = parts.get(9)?.parse::<u64>().ok().filter(|&i| i != 0)?;
+            let state = match state {
+                0x01 => SocketState::Established,
+                0x0A => SocketState::Listening,
+                // An unconnected UDP socket sits in CLOSE
+                0x07 if udp => SocketState::Listening,
+                _ => SocketState::Other,
+            };
+            Some((state, inode))
+        })
+        .collect()
+}
+
+/// Listening and established sockets of every process that has any
+#[cfg(target_os = "macos")]
+pub fn socket_counts() -> Result<HashMap<u32, SocketCounts>> {
+    use crate::platform::command::run_command;
+    use std::process::Command;
+
+    let output = run_command(Command::new("lsof").args(["-i", "-n", "-P"]))?;
+    Ok(parse_lsof_states(
+        &process::encoding::safe_command_output_to_string(&output.stdout),
+    ))
+}
+
+/// Count `lsof -i` rows per PID by the state in the last column; UDP rows have none
+#[cfg(any(target_os = "macos", test))]
+fn parse_lsof_states(text: &str) -> HashMap<u32, SocketCounts> {
+    let mut counts: HashMap<u32, SocketCounts> = HashMap::new();
+    for line in text.lines().skip(1) {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let Some(pid) = parts.get(1).and_then(|p| p.parse::<u32>().ok()) else {
+            continue;
+        };
+        let state = match (parts.get(7), parts.last()) {
+            (_, Some(&"(LISTEN)")) => SocketState::Listening,
+            (_, Some(&"(ESTABLISHED)")) => SocketState::Established,
+            (Some(&"UDP"), _) if parts.get(8).is_some_and(|n| n.contains("->")) => {
+                SocketState::Established
+            }
+            (Some(&"UDP"), _) => SocketState::Listening,
+            _ => SocketState::Other,
+        };
+        counts.entry(pid).or_default().add(state);
+    }
+    counts
+}
+
+/// Listening and established sockets of every process that has any
+#[cfg(target_os = "windows")]
+pub fn socket_counts() -> Result<HashMap<u32, SocketCounts>> {
+    use crate::platform::command::run_command;
+    use std::process::Command;
+
+    let output = run_command(Command::new("netstat").args(["-ano"]))?;
+    Ok(parse_netstat_states(
+        &process::encoding::safe_command_output_to_string(&output.stdout),
+    ))
+}
+
+/// Count `netstat -ano` rows per PID; UDP rows have no state column
+#[cfg(any(target_os = "windows", test))]
+fn parse_netstat_states(text: &str) -> HashMap<u32, SocketCounts> {
+    let mut counts: HashMap<u32, SocketCounts> = HashMap::new();
+    for line in text.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let state = match parts.as_slice() {
+            ["TCP", _, _, "LISTENING", _] => SocketState::Listening,
+            ["TCP", _, _, "ESTABLISHED", _] => SocketState::Established,
+            ["UDP", _, _, _] => SocketState::Listening,
+            ["TCP", _, _, _, _] => SocketState::Other,
+            _ => continue,
+        };
+        if let Some(pid) = parts.last().and_then(|p| p.parse::<u32>().ok()) {
+            counts.entry(pid).or_default().add(state);
+        }
+    }
+    counts
+}
+
+/// Listening and established sockets of every process that has any
+#[cfg(any(
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "dragonfly",
+    target_os = "openbsd"
+))]
+pub fn socket_counts() -> Result<HashMap<u32, SocketCounts>> {
+    let mut counts: HashMap<u32, SocketCounts> = HashMap::new();
+    for (pid, _, state) in bsd_sockets()? {
+        counts.entry(pid).or_default().add(state);
+    }
+    Ok(counts)
+}
+
+#[cfg(not(any(
+    target_os = "windows",
+    target_os = "linux",
+    target_os = "macos",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "dragonfly",
+    target_os = "openbsd"
+)))]
+pub fn socket_counts() -> Result<HashMap<u32, SocketCounts>> {
     Err(anyhow::Error::msg(
         "Network connection queries are not supported on the current operating system",
     ))
@@ -278,7 +755,6 @@ fn get_network_connections() -> Result<HashMap<u16, u32>> {
 mod tests {
     use super::*;
 
-    #[cfg(target_os = "windows")]
     #[test]
     fn test_parse_netstat_output() {
         let input = b"\
@@ -296,7 +772,6 @@ Active Connections
         assert_eq!(result.len(), 3);
     }
 
-    #[cfg(target_os = "windows")]
     #[test]
     fn test_parse_netstat_skips_headers() {
         let input = b"\
@@ -307,7 +782,6 @@ Active Connections
         assert!(result.is_empty());
     }
 
-    #[cfg(target_os = "windows")]
     #[test]
     fn test_parse_netstat_empty() {
         let result = parse_netstat_output(b"").unwrap();
@@ -350,4 +824,127 @@ python  67890   user   22u  IPv6 0xabcde 0t0 TCP 127.0.0.1:3000 (LISTEN)
             parse_lsof_output(b"COMMAND PID USER FD TYPE DEVICE SIZE/OFF NODE NAME\n").unwrap();
         assert!(result.is_empty());
     }
+
+    #[test]
+    fn test_parse_proc_net_states() {
+        let input = "\
+  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 00000000:1F90 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 111 1 0 100 0 0 10 0
+   1: 0100007F:1F90 0100007F:D2A4 01 00000000:00000000 00:00000000 00000000  1000        0 222 1 0 20 4 30 10 -1
+   2: 0100007F:D2A5 0100007F:1F90 06 00000000:00000000 03:00000000 00000000     0        0 0 3 0
+";
+        assert_eq!(
+            parse_proc_net_states(input, false),
+            vec![
+                (SocketState::Listening, 111),
+                (SocketState::Established, 222)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_socket_states() {
+        let lsof = "\
+COMMAND   PID USER   FD   TYPE DEVICE SIZE/OFF NODE NAME
+node    12345 user   21u  IPv4 0x1234      0t0  TCP *:8080 (LISTEN)
+node    12345 user   22u  IPv4 0x1235      0t0  TCP 127.0.0.1:8080->127.0.0.1:50000 (ESTABLISHED)
+mDNS      200 user    5u  IPv4 0x1236      0t0  UDP *:5353
+";
+        let counts = parse_lsof_states(lsof);
+        assert_eq!(
+            counts[&12345],
+            SocketCounts {
+                listening: 1,
+                established: 1
+            }
+        );
+        assert_eq!(counts[&200].listening, 1);
+
+        let netstat = "\
+  Proto  Local Address          Foreign Address        State           PID
+  TCP    0.0.0.0:8080           0.0.0.0:0              LISTENING       1234
+  TCP    127.0.0.1:8080         127.0.0.1:50000        ESTABLISHED     1234
+  TCP    127.0.0.1:50001        127.0.0.1:8080         TIME_WAIT       0
+  UDP    0.0.0.0:5353           *:*                                    200
+";
+        let counts = parse_netstat_states(netstat);
+        assert_eq!(
+            counts[&1234],
+            SocketCounts {
+                listening: 1,
+                established: 1
+            }
+        );
+        assert_eq!(counts[&200].listening, 1);
+    }
+
+    #[test]
+    fn test_parse_tasklist() {
+        let input = "\
+\"System Idle Process\",\"0\",\"Services\",\"0\",\"8 K\"
+\"node.exe\",\"1234\",\"Console\",\"1\",\"45,678 K\"
+\"svchost.exe\",\"5678\",\"Services\",\"0\",\"12.345 K\"
+";
+        let tasks = parse_tasklist(input);
+        assert_eq!(tasks[&1234], ("node.exe".to_string(), 45_678 * 1024));
+        assert_eq!(tasks[&5678].1, 12_345 * 1024);
+        assert_eq!(tasks[&0].0, "System Idle Process");
+    }
+
+    #[test]
+    fn test_parse_ps_line() {
+        let info = parse_ps_line(42, " 2048 node     node server.js --port 3000\n").unwrap();
+        assert_eq!(info.name, "node");
+        assert_eq!(info.cmd, ["node", "server.js", "--port", "3000"]);
+        assert_eq!(info.memory, 2048 * 1024);
+        assert!(parse_ps_line(42, "").is_none());
+    }
+
+    #[test]
+    fn test_parse_bsd_sockets() {
+        let sockstat = "\
+USER     COMMAND    PID   FD PROTO  LOCAL ADDRESS         FOREIGN ADDRESS
+www      nginx      1234  6  tcp4   *:80                  *:*
+www      nginx      1234  7  tcp6   *:80                  *:*
+alice    node       5678  21 tcp4   127.0.0.1:3000        127.0.0.1:50000
+root     syslogd    600   7  udp6   ::1:514               *:*
+?        ?          ?     ?  tcp4   10.0.0.2:22           10.0.0.9:60000
+";
+        assert_eq!(
+            parse_sockstat_output(sockstat),
+            vec![
+                (1234, 80, SocketState::Listening),
+                (1234, 80, SocketState::Listening),
+                (5678, 3000, SocketState::Established),
+                (600, 514, SocketState::Listening),
+            ]
+        );
+
+        let fstat = "\
+USER     CMD          PID   FD MOUNT        INUM MODE         R/W    SZ|DV
+www      httpd      1234     6* internet stream tcp 0xffff800000a1b2c3 *:80
+alice    node       5678    21* internet stream tcp 0x0 127.0.0.1:3000 <-> 127.0.0.1:50000
+root     syslogd     600     5* internet6 dgram udp [::1]:514
+alice    node       5678    wd /home      1234 drwxr-xr-x     r     512 /home/alice
+";
+        assert_eq!(
+            parse_fstat_sockets(fstat),
+            vec![
+                (1234, 80, SocketState::Listening),
+                (5678, 3000, SocketState::Established),
+                (600, 514, SocketState::Listening),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bind_free_port_skips_taken() {
+        let localhost = IpAddr::from([127, 0, 0, 1]);
+        let taken = TcpListener::bind((localhost, 0)).unwrap();
+        let taken_port = taken.local_addr().unwrap().port();
+        let Ok(listener) = bind_free_port(localhost, taken_port) else {
+            return;
+        };
+        assert!(listener.local_addr().unwrap().port() > taken_port);
+    }
 }
@@ -0,0 +1,226 @@
+/// Per-process network statistics (`ziro net`)
+///
+/// Socket counts come from the same tables as `top --net`. Traffic rates need a per-process
+/// byte counter, which only macOS offers without kernel tracing (`nettop`); elsewhere the
+/// rate columns stay empty.
+use super::concurrent::{self, Backend};
+use super::{CONNECTIONS_TIMEOUT, ProcessInfo, Snapshot, SocketCounts, socket_counts};
+use crate::core::process;
+use anyhow::Result;
+use regex::Regex;
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// One process with network activity
+#[derive(Debug, Clone, Serialize)]
+pub struct NetProcess {
+    pub pid: u32,
+    pub name: String,
+    /// Listening TCP sockets and bound UDP sockets
+    pub listening: usize,
+    /// Established TCP connections and connected UDP sockets
+    pub established: usize,
+    /// Ports the process holds, ascending
+    pub ports: Vec<u16>,
+    /// Bytes received per second, where the platform can tell
+    pub rx_rate: Option<f64>,
+    /// Bytes sent per second, where the platform can tell
+    pub tx_rate: Option<f64>,
+}
+
+/// Column `net_stats` orders by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NetSortKey {
+    /// Established connections, most first
+    #[default]
+    Connections,
+    /// Listening sockets, most first
+    Listening,
+    /// Receive rate, highest first
+    Rx,
+    /// Send rate, highest first
+    Tx,
+    Pid,
+    Name,
+}
+
+impl NetSortKey {
+    fn compare(self, a: &NetProcess, b: &NetProcess) -> Ordering {
+        let rate = |r: Option<f64>| r.unwrap_or(0.0);
+        match self {
+            Self::Connections => b.established.cmp(&a.established),
+            Self::Listening => b.listening.cmp(&a.listening),
+            Self::Rx => rate(b.rx_rate).total_cmp(&rate(a.rx_rate)),
+            Self::Tx => rate(b.tx_rate).total_cmp(&rate(a.tx_rate)),
+            Self::Pid => a.pid.cmp(&b.pid),
+            Self::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+        }
+        .then(a.pid.cmp(&b.pid))
+    }
+}
+
+/// Which processes `net_stats` returns, and in what order
+#[derive(Default)]
+pub struct NetOptions {
+    /// Only processes whose name or command line matches
+    pub filter: Option<Regex>,
+    /// Only processes with a listening socket
+    pub listening_only: bool,
+    pub sort: NetSortKey,
+    pub reverse: bool,
+    pub limit: Option<usize>,
+}
+
+/// Every process holding a socket, with its counts, ports and traffic rates
+pub fn net_stats(opts: &NetOptions) -> Result<Vec<NetProcess>> {
+    // nettop samples for a second, long enough to read the socket tables meanwhile
+    let (counts, rates) = concurrent::join(
+        Backend::new("socket table", CONNECTIONS_TIMEOUT, socket_counts),
+        Backend::new("traffic counters", CONNECTIONS_TIMEOUT, || {
+            Ok(traffic_rates())
+        }),
+    );
+    let (counts, rates) = (counts?, rates.ok().flatten());
+    let snapshot = Snapshot::take()?;
+
+    let mut processes: Vec<NetProcess> = process::shared().with(|sys| {
+        counts
+            .iter()
+            .filter_map(|(&pid, &sockets)| {
+                let info = ProcessInfo::lookup(sys, pid)?;
+                let matches = opts.filter.as_ref().is_none_or(|filter| {
+                    filter.is_match(&info.name) || filter.is_match(&info.cmd.join(" "))
+                });
+                matches.then(|| entry(info, sockets, snapshot.ports_of(pid), &rates))
+            })
+            .collect()
+    });
+    processes.retain(|p| !opts.listening_only || p.listening > 0);
+    processes.sort_by(|a, b| {
+        let order = opts.sort.compare(a, b);
+        if opts.reverse { order.reverse() } else { order }
+    });
+    if let Some(limit) = opts.limit {
+        processes.truncate(limit);
+    }
+    Ok(processes)
+}
+
+fn entry(
+    info: ProcessInfo,
+    sockets: SocketCounts,
+    ports: Vec<u16>,
+    rates: &Option<HashMap<u32, (f64, f64)>>,
+) -> NetProcess {
+    // A process absent from the rate table moved no bytes while it was sampled
+    let (rx_rate, tx_rate) = match rates {
+        Some(rates) => {
+            let (rx, tx) = rates.get(&info.pid).copied().unwrap_or_default();
+            (Some(rx), Some(tx))
+        }
+        None => (None, None),
+    };
+    NetProcess {
+        pid: info.pid,
+        name: info.name,
+        listening: sockets.listening,
+        established: sockets.established,
+        ports,
+        rx_rate,
+        tx_rate,
+    }
+}
+
+/// PID -> (received, sent) bytes per second over one second, from `nettop`
+#[cfg(target_os = "macos")]
+fn traffic_rates() -> Option<HashMap<u32, (f64, f64)>> {
+    use crate::platform::command::run_command;
+    use std::process::Command;
+
+    // Two samples a second apart; in delta mode the second one holds the bytes moved
+    let output = run_command(Command::new("nettop").args([
+        "-P",
+        "-x",
+        "-d",
+        "-L",
+        "2",
+        "-s",
+        "1",
+        "-J",
+        "bytes_in,bytes_out",
+    ]))
+    .ok()?;
+    Some(parse_nettop(
+        &process::encoding::safe_command_output_to_string(&output.stdout),
+    ))
+}
+
+#[cfg(not(target_os = "macos"))]
+fn traffic_rates() -> Option<HashMap<u32, (f64, f64)>> {
+    None
+}
+
+/// Parse `nettop -P -L 2 -J bytes_in,bytes_out` CSV, keeping the last sample: each sample
+/// starts with a `time,,bytes_in,bytes_out,` header, and rows name processes as `name.pid`
+#[cfg(any(target_os = "macos", test))]
+fn parse_nettop(text: &str) -> HashMap<u32, (f64, f64)> {
+    let mut rates = HashMap::new();
+    for line in text.lines() {
+        let fields: Vec<&str> = line.split(',').collect();
+        if fields.first() == Some(&"time") {
+            rates.clear();
+            continue;
+        }
+        let Some(pid) = fields
+            .get(1)
+            .and_then(|process| process.rsplit_once('.'))
+            .and_then(|(_, pid)| pid.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        let bytes = |i: usize| fields.get(i).and_then(|b| b.trim().parse::<f64>().ok());
+        if let (Some(rx), Some(tx)) = (bytes(2), bytes(3)) {
+            rates.insert(pid, (rx, tx));
+        }
+    }
+    rates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_nettop() {
+        let text = "\
+time,,bytes_in,bytes_out,
+22:05:18.925417,node.4242,1000,2000,
+22:05:18.925417,Google Chrome H.311,50,7,
+time,,bytes_in,bytes_out,
+22:05:19.931002,node.4242,300,40,
+22:05:19.931002,launchd.1,0,0,
+";
+        let rates = parse_nettop(text);
+        assert_eq!(rates.len(), 2);
+        assert_eq!(rates[&4242], (300.0, 40.0));
+        assert_eq!(rates[&1], (0.0, 0.0));
+    }
+
+    #[test]
+    fn test_net_sort() {
+        let process = |pid: u32, established: usize, rx: f64| NetProcess {
+            pid,
+            name: format!("p{pid}"),
+            listening: 0,
+            established,
+            ports: Vec::new(),
+            rx_rate: Some(rx),
+            tx_rate: None,
+        };
+        let (a, b) = (process(1, 5, 10.0), process(2, 9, 1.0));
+        assert_eq!(NetSortKey::Connections.compare(&a, &b), Ordering::Greater);
+        assert_eq!(NetSortKey::Rx.compare(&a, &b), Ordering::Less);
+        assert_eq!(NetSortKey::Pid.compare(&a, &b), Ordering::Less);
+    }
+}
@@ -0,0 +1,130 @@
+/// One look at ports and processes shared by every step of a command
+///
+/// Reading the connection table shells out to netstat / lsof / ss on most platforms, so a
+/// command takes it once, together with the processes holding ports and the parent links
+/// of the process table, instead of every lookup reading them again.
+use super::concurrent::{self, Backend};
+use super::{CONNECTIONS_TIMEOUT, PortInfo, ProcessInfo, get_network_connections};
+use crate::core::process::{self, ChildProcess, ProcessTree};
+use anyhow::Result;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// How long refreshing the process table may take
+const PROCESSES_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The connection table and the process table as of one moment
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    /// Port -> PID of the process holding it
+    connections: HashMap<u16, u32>,
+    /// Every process holding a port
+    holders: HashMap<u32, ProcessInfo>,
+    tree: ProcessTree,
+}
+
+impl Snapshot {
+    /// Read both tables; the process table refresh overlaps the connection table tool
+    /// (with the `async` feature)
+    pub fn take() -> Result<Self> {
+        let (connections, tree) = concurrent::join(
+            Backend::new(
+                "connection table",
+                CONNECTIONS_TIMEOUT,
+                get_network_connections,
+            ),
+            Backend::new("process table", PROCESSES_TIMEOUT, || {
+                Ok(process::shared().with(ProcessTree::read))
+            }),
+        );
+        let (connections, tree) = (connections?, tree?);
+        // Still fresh from the refresh above
+        let holders = process::shared().with(|sys| {
+            connections
+                .values()
+                .filter_map(|&pid| Some((pid, ProcessInfo::lookup(sys, pid)?)))
+                .collect()
+        });
+        Ok(Self {
+            connections,
+            holders,
+            tree,
+        })
+    }
+
+    /// Processes holding any of `ports`, in the order given; free ports are left out
+    pub fn find(&self, ports: &[u16]) -> Vec<PortInfo> {
+        ports
+            .iter()
+            .filter_map(|&port| self.port_info(port))
+            .collect()
+    }
+
+    /// Every port in use, ascending
+    pub fn list(&self) -> Vec<PortInfo> {
+        let mut result: Vec<PortInfo> = self
+            .connections
+            .keys()
+            .filter_map(|&port| self.port_info(port))
+            .collect();
+        result.sort_by_key(|info| info.port);
+        result
+    }
+
+    /// Ports held by one process, ascending
+    pub fn ports_of(&self, pid: u32) -> Vec<u16> {
+        let mut ports: Vec<u16> = self
+            .connections
+            .iter()
+            .filter_map(|(&port, &owner)| (owner == pid).then_some(port))
+            .collect();
+        ports.sort_unstable();
+        ports
+    }
+
+    /// Every descendant of `pid`, depth-first with siblings in PID order
+    pub fn descendants(&self, pid: u32) -> Vec<ChildProcess> {
+        self.tree.descendants(pid)
+    }
+
+    fn port_info(&self, port: u16) -> Option<PortInfo> {
+        let pid = self.connections.get(&port)?;
+        Some(PortInfo {
+            port,
+            process: self.holders.get(pid)?.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_lookups() {
+        let holder = |pid: u32| ProcessInfo {
+            pid,
+            name: format!("p{pid}"),
+            cmd: Vec::new(),
+            cpu_usage: 0.0,
+            memory: 0,
+        };
+        // Port 9000's holder exited before it could be looked up
+        let snapshot = Snapshot {
+            connections: HashMap::from([(8080, 10), (3000, 10), (5432, 20), (9000, 30)]),
+            holders: HashMap::from([(10, holder(10)), (20, holder(20))]),
+            tree: ProcessTree::default(),
+        };
+
+        let found: Vec<(u16, u32)> = snapshot
+            .find(&[5432, 9000, 1234, 8080])
+            .iter()
+            .map(|info| (info.port, info.process.pid))
+            .collect();
+        assert_eq!(found, [(5432, 20), (8080, 10)]);
+        let listed: Vec<u16> = snapshot.list().iter().map(|info| info.port).collect();
+        assert_eq!(listed, [3000, 5432, 8080]);
+        assert_eq!(snapshot.ports_of(10), [3000, 8080]);
+        assert!(snapshot.descendants(10).is_empty());
+    }
+}
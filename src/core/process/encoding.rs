@@ -1,44 +1,81 @@
 /// Encoding conversion module
 /// Handles encoding conversion for command output
-pub fn safe_command_output_to_string(stdout: &[u8]) -> String {
-    if let Ok(text) = std::str::from_utf8(stdout) {
-        return text.to_string();
-    }
+use crate::platform::encoding::legacy_codepages;
+use encoding_rs::Encoding;
 
-    #[cfg(target_os = "windows")]
-    {
-        if let Some(text) = try_decode_as_gbk(stdout) {
-            return text;
-        }
+/// Text of a tool's output: UTF-8 when valid, otherwise decoded with the first of the
+/// system's legacy code pages (GBK, Big5, Windows-125x, ...) that fits it
+pub fn safe_command_output_to_string(stdout: &[u8]) -> String {
+    decode_with_codepages(stdout, &legacy_codepages())
+}
 
-        if let Some(text) = try_decode_as_windows_1252(stdout) {
-            return text;
-        }
+fn decode_with_codepages(data: &[u8], codepages: &[u32]) -> String {
+    if let Ok(text) = std::str::from_utf8(data) {
+        return text.to_string();
     }
+    codepages
+        .iter()
+        .filter_map(|&cp| encoding_for_codepage(cp))
+        .find_map(|encoding| {
+            let (text, had_errors) = encoding.decode_without_bom_handling(data);
+            (!had_errors).then(|| text.into_owned())
+        })
+        .unwrap_or_else(|| String::from_utf8_lossy(data).into_owned())
+}
 
-    String::from_utf8_lossy(stdout).to_string()
+/// The decoder for a Windows code page; OEM pages without one (437, 850) read as
+/// Windows-1252, which agrees with them on ASCII
+pub fn encoding_for_codepage(cp: u32) -> Option<&'static Encoding> {
+    let encoding = match cp {
+        936 => encoding_rs::GBK,
+        54936 => encoding_rs::GB18030,
+        950 => encoding_rs::BIG5,
+        932 => encoding_rs::SHIFT_JIS,
+        949 => encoding_rs::EUC_KR,
+        866 => encoding_rs::IBM866,
+        874 => encoding_rs::WINDOWS_874,
+        1250 => encoding_rs::WINDOWS_1250,
+        1251 => encoding_rs::WINDOWS_1251,
+        1252 | 437 | 850 => encoding_rs::WINDOWS_1252,
+        1253 => encoding_rs::WINDOWS_1253,
+        1254 => encoding_rs::WINDOWS_1254,
+        1255 => encoding_rs::WINDOWS_1255,
+        1256 => encoding_rs::WINDOWS_1256,
+        1257 => encoding_rs::WINDOWS_1257,
+        1258 => encoding_rs::WINDOWS_1258,
+        _ => return None,
+    };
+    Some(encoding)
 }
 
-#[cfg(target_os = "windows")]
-fn try_decode_as_gbk(data: &[u8]) -> Option<String> {
-    use encoding_rs::GBK;
-    let (text, _, had_errors) = GBK.decode(data);
-    if had_errors {
-        return None;
-    }
-    let s = text.to_string();
-    if s.contains('\u{FFFD}') {
-        return None;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bytes(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
     }
-    Some(s)
-}
 
-#[cfg(target_os = "windows")]
-fn try_decode_as_windows_1252(data: &[u8]) -> Option<String> {
-    use encoding_rs::WINDOWS_1252;
-    let (text, _, had_errors) = WINDOWS_1252.decode(data);
-    if had_errors {
-        return None;
+    #[test]
+    fn test_decode_with_codepages() {
+        assert_eq!(decode_with_codepages("协议".as_bytes(), &[936]), "协议");
+        // "协议 监听" in GBK, "協定 接聽" in Big5, "Прослушивание" in Windows-1251
+        assert_eq!(
+            decode_with_codepages(&bytes("d0add2e920bce0ccfd"), &[936]),
+            "协议 监听"
+        );
+        assert_eq!(
+            decode_with_codepages(&bytes("a8f3a97720b1b5c5a5"), &[950]),
+            "協定 接聽"
+        );
+        assert_eq!(
+            decode_with_codepages(&bytes("cff0eef1ebf3f8e8e2e0ede8e5"), &[65001, 1251]),
+            "Прослушивание"
+        );
+        // Nothing fits: replacement characters rather than an error
+        assert_eq!(decode_with_codepages(&[b'a', 0xff], &[]), "a\u{FFFD}");
     }
-    Some(text.to_string())
 }
@@ -0,0 +1,163 @@
+/// Everything ziro can find out about one process (`ziro info`)
+use crate::core::port;
+use crate::error::ZiroError;
+use anyhow::{Result, bail};
+use serde::Serialize;
+use std::path::PathBuf;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System, Users};
+
+use super::open_files;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub name: String,
+    pub parent: Option<u32>,
+    /// Owning user name (or numeric id when it has no name)
+    pub user: Option<String>,
+    pub status: String,
+    /// Unix time (seconds) the process started
+    pub start_time: u64,
+    /// Seconds since the process started
+    pub run_time: u64,
+    pub cmd: Vec<String>,
+    pub exe: Option<PathBuf>,
+    pub cwd: Option<PathBuf>,
+    /// `KEY=value` entries; only collected on request
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub environ: Option<Vec<String>>,
+    pub memory: MemoryBreakdown,
+    pub cpu: f32,
+    /// Ports the process listens on or is connected from, ascending
+    pub ports: Vec<u16>,
+    pub open_files: Vec<PathBuf>,
+}
+
+/// Memory figures in bytes; the split of resident memory is Linux only
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MemoryBreakdown {
+    pub resident: u64,
+    pub virtual_bytes: u64,
+    /// Heap, stacks and other private pages
+    pub anonymous: Option<u64>,
+    /// Mapped files, including the executable and libraries
+    pub file_backed: Option<u64>,
+    pub shared: Option<u64>,
+    pub swap: Option<u64>,
+}
+
+/// Look up one process; the environment is only read with `with_env`
+pub fn inspect_process(pid: u32, with_env: bool) -> Result<ProcessInfo> {
+    let mut system = System::new();
+    let sysinfo_pid = Pid::from_u32(pid);
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[sysinfo_pid]),
+        ProcessRefreshKind::everything(),
+    );
+    // CPU usage is the difference between two refreshes
+    std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[sysinfo_pid]),
+        ProcessRefreshKind::everything(),
+    );
+    let Some(process) = system.process(sysinfo_pid) else {
+        bail!(ZiroError::NotFound(format!("No process with PID {pid}")));
+    };
+
+    let users = Users::new_with_refreshed_list();
+    let lossy = |items: &[std::ffi::OsString]| {
+        items
+            .iter()
+            .map(|s| s.to_string_lossy().into_owned())
+            .collect::<Vec<_>>()
+    };
+
+    let mut memory = memory_breakdown(pid);
+    memory.resident = process.memory();
+    memory.virtual_bytes = process.virtual_memory();
+
+    Ok(ProcessInfo {
+        pid,
+        name: process.name().to_string_lossy().into_owned(),
+        parent: process.parent().map(|p| p.as_u32()),
+        user: process.user_id().map(|uid| {
+            users
+                .get_user_by_id(uid)
+                .map(|user| user.name().to_string())
+                .unwrap_or_else(|| uid.to_string())
+        }),
+        status: process.status().to_string(),
+        start_time: process.start_time(),
+        run_time: process.run_time(),
+        cmd: lossy(process.cmd()),
+        exe: process.exe().map(|p| p.to_path_buf()),
+        cwd: process.cwd().map(|p| p.to_path_buf()),
+        environ: with_env.then(|| lossy(process.environ())),
+        memory,
+        cpu: process.cpu_usage(),
+        // Port lookup needs netstat/lsof elsewhere; a failure just leaves the list empty
+        ports: port::ports_of_process(pid).unwrap_or_default(),
+        open_files: open_files(pid),
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn memory_breakdown(pid: u32) -> MemoryBreakdown {
+    std::fs::read_to_string(format!("/proc/{pid}/status"))
+        .map(|status| parse_proc_status(&status))
+        .unwrap_or_default()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn memory_breakdown(_pid: u32) -> MemoryBreakdown {
+    MemoryBreakdown::default()
+}
+
+/// Read the `Rss*` and `VmSwap` lines of /proc/PID/status, e.g. `RssAnon:    1234 kB`
+#[cfg(any(target_os = "linux", test))]
+fn parse_proc_status(status: &str) -> MemoryBreakdown {
+    let mut memory = MemoryBreakdown::default();
+    for line in status.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let Some(kb) = value
+            .trim()
+            .strip_suffix("kB")
+            .and_then(|kb| kb.trim().parse::<u64>().ok())
+        else {
+            continue;
+        };
+        let bytes = Some(kb * 1024);
+        match key {
+            "RssAnon" => memory.anonymous = bytes,
+            "RssFile" => memory.file_backed = bytes,
+            "RssShmem" => memory.shared = bytes,
+            "VmSwap" => memory.swap = bytes,
+            _ => {}
+        }
+    }
+    memory
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_proc_status() {
+        let status = "Name:\tbash\nVmRSS:\t    4096 kB\nRssAnon:\t    1024 kB\n\
+                      RssFile:\t    2048 kB\nRssShmem:\t       0 kB\nVmSwap:\t      16 kB\n";
+        assert_eq!(
+            parse_proc_status(status),
+            MemoryBreakdown {
+                resident: 0,
+                virtual_bytes: 0,
+                anonymous: Some(1024 * 1024),
+                file_backed: Some(2048 * 1024),
+                shared: Some(0),
+                swap: Some(16 * 1024),
+            }
+        );
+    }
+}
@@ -0,0 +1,144 @@
+/// Windows tree kills through a Job Object: every process of the tree joins one job,
+/// which is then terminated as a whole
+///
+/// A process started by a job member joins the job too, so a wrapper such as `cmd.exe`
+/// cannot leave the real server behind by spawning it while its siblings are killed.
+use super::tree::walk_descendants;
+use anyhow::{Result, bail};
+use std::collections::HashSet;
+use windows_sys::Win32::Foundation::{CloseHandle, FILETIME, HANDLE, INVALID_HANDLE_VALUE};
+use windows_sys::Win32::System::Diagnostics::ToolHelp::{
+    CreateToolhelp32Snapshot, PROCESSENTRY32W, Process32FirstW, Process32NextW, TH32CS_SNAPPROCESS,
+};
+use windows_sys::Win32::System::JobObjects::{
+    AssignProcessToJobObject, CreateJobObjectW, TerminateJobObject,
+};
+use windows_sys::Win32::System::Threading::{
+    GetProcessTimes, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION, PROCESS_SET_QUOTA,
+    PROCESS_TERMINATE,
+};
+
+/// Children started before their parent joined are not in the job; walking the tree
+/// again picks them up, a few times at most
+const MAX_PASSES: usize = 4;
+
+/// Terminate `root` and everything below it at once, returning the PIDs that were in
+/// the job; fails when `root` itself could not join
+pub(super) fn terminate_tree(root: u32) -> Result<Vec<u32>> {
+    // SAFETY: plain Win32 calls on handles owned and closed here
+    unsafe {
+        let job = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+        if job.is_null() {
+            bail!(
+                "Failed to create a job object: {}",
+                std::io::Error::last_os_error()
+            );
+        }
+
+        let result = fill_job(job, root).and_then(|members| {
+            if TerminateJobObject(job, 1) == 0 {
+                bail!(
+                    "Failed to terminate the job of process {root}: {}",
+                    std::io::Error::last_os_error()
+                );
+            }
+            Ok(members)
+        });
+        CloseHandle(job);
+        result
+    }
+}
+
+/// Put `root` and its descendants into `job`, parents first
+unsafe fn fill_job(job: HANDLE, root: u32) -> Result<Vec<u32>> {
+    let Some((handle, root_started)) = (unsafe { open_member(root) }) else {
+        bail!(
+            "Failed to open process {root}: {}",
+            std::io::Error::last_os_error()
+        );
+    };
+    let joined = unsafe { AssignProcessToJobObject(job, handle) } != 0;
+    unsafe { CloseHandle(handle) };
+    if !joined {
+        bail!(
+            "Process {root} could not join a job object: {}",
+            std::io::Error::last_os_error()
+        );
+    }
+
+    let mut members = vec![root];
+    let mut tried: HashSet<u32> = HashSet::from([root]);
+    for _ in 0..MAX_PASSES {
+        let table = process_table();
+        let fresh: Vec<u32> = walk_descendants(root, &table)
+            .into_iter()
+            .map(|child| child.pid)
+            .filter(|pid| tried.insert(*pid))
+            .collect();
+        if fresh.is_empty() {
+            break;
+        }
+        for pid in fresh {
+            let Some((handle, started)) = (unsafe { open_member(pid) }) else {
+                continue;
+            };
+            // A parent PID can outlive its process and be reused; a real child is younger
+            if started >= root_started && unsafe { AssignProcessToJobObject(job, handle) } != 0 {
+                members.push(pid);
+            }
+            unsafe { CloseHandle(handle) };
+        }
+    }
+    Ok(members)
+}
+
+/// A handle good for joining and terminating `pid`, and its creation time
+unsafe fn open_member(pid: u32) -> Option<(HANDLE, u64)> {
+    let handle = unsafe {
+        OpenProcess(
+            PROCESS_SET_QUOTA | PROCESS_TERMINATE | PROCESS_QUERY_LIMITED_INFORMATION,
+            0,
+            pid,
+        )
+    };
+    if handle.is_null() {
+        return None;
+    }
+    let empty = FILETIME {
+        dwLowDateTime: 0,
+        dwHighDateTime: 0,
+    };
+    let (mut created, mut exited, mut kernel, mut user) = (empty, empty, empty, empty);
+    if unsafe { GetProcessTimes(handle, &mut created, &mut exited, &mut kernel, &mut user) } == 0 {
+        unsafe { CloseHandle(handle) };
+        return None;
+    }
+    let started = (u64::from(created.dwHighDateTime) << 32) | u64::from(created.dwLowDateTime);
+    Some((handle, started))
+}
+
+/// (pid, parent, name) of every process, fresh from a Toolhelp32 snapshot
+fn process_table() -> Vec<(u32, u32, String)> {
+    let mut table = Vec::new();
+    // SAFETY: the snapshot handle is checked and closed; the entry's size is set as required
+    unsafe {
+        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0);
+        if snapshot == INVALID_HANDLE_VALUE {
+            return table;
+        }
+        let mut entry: PROCESSENTRY32W = std::mem::zeroed();
+        entry.dwSize = std::mem::size_of::<PROCESSENTRY32W>() as u32;
+        let mut ok = Process32FirstW(snapshot, &mut entry);
+        while ok != 0 {
+            // Names are not needed to walk the tree
+            table.push((
+                entry.th32ProcessID,
+                entry.th32ParentProcessID,
+                String::new(),
+            ));
+            ok = Process32NextW(snapshot, &mut entry);
+        }
+        CloseHandle(snapshot);
+    }
+    table
+}
@@ -1,7 +1,9 @@
 use super::encoding::safe_command_output_to_string;
 /// File lock detection module
+use crate::platform::command::run_command;
 use anyhow::Result;
 use std::path::{Path, PathBuf};
+use std::process::Command;
 
 #[derive(Debug, Clone)]
 pub struct FileLockProcess {
@@ -36,93 +38,82 @@ pub fn is_file_locked(path: &Path) -> bool {
 }
 
 /// Windows file lock detection
+///
+/// Restart Manager reports the exact holders; when it finds none (or is unavailable),
+/// a write-open probe catches sharing violations it cannot see (e.g. kernel handles).
 #[cfg(target_os = "windows")]
 fn is_file_locked_windows(path: &Path) -> bool {
     use std::fs::OpenOptions;
-    use std::io::ErrorKind;
 
-    // For directories, use a different detection method
+    if let Ok(pids) = find_processes_with_restart_manager(&restart_manager_targets(path))
+        && !pids.is_empty()
+    {
+        return true;
+    }
+
     if path.is_dir() {
-        return is_directory_locked(path);
+        return false;
     }
 
-    // Try to open file in write mode, but analyze error type more precisely
+    // Opening for write does not modify the file; only sharing/lock violations count as locked
     match OpenOptions::new().write(true).create(false).open(path) {
         Ok(_) => false,
-        Err(e) => match e.kind() {
-            ErrorKind::PermissionDenied => check_file_locking_status(path),
-            ErrorKind::NotFound => false,
-            _ => {
-                eprintln!(
-                    "Warning: file open failed, may be in use: {} - {}",
-                    path.display(),
-                    e
-                );
-                true
-            }
-        },
+        Err(e) => matches!(e.raw_os_error(), Some(32) | Some(33)),
     }
 }
 
 /// Unix file lock detection
 #[cfg(not(target_os = "windows"))]
 fn is_file_locked_unix(path: &Path) -> bool {
+    #[cfg(target_os = "linux")]
+    if let Ok(pids) = find_processes_with_procfs(path) {
+        return !pids.is_empty();
+    }
+
+    // lsof is a package on the BSDs; the base system's fstat answers without it
+    #[cfg(any(
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "dragonfly",
+        target_os = "openbsd"
+    ))]
+    if crate::platform::command::find_program("lsof").is_none() {
+        return find_processes_with_fstat(path).is_ok_and(|pids| !pids.is_empty());
+    }
+
     let path_str = match path.to_str() {
         Some(s) => s,
         None => return false,
     };
 
-    match std::process::Command::new("lsof").arg(path_str).output() {
+    match run_command(Command::new("lsof").arg(path_str)) {
         Ok(output) => output.status.success(),
         Err(_) => false,
     }
 }
 
-/// Windows-specific: check if a directory is locked
+/// Maximum number of directory entries registered with Restart Manager per query
 #[cfg(target_os = "windows")]
-fn is_directory_locked(path: &Path) -> bool {
-    // First check basic directory access permissions
-    match std::fs::read_dir(path) {
-        Ok(entries) => {
-            // Use RestartManager to register directory path detection
-            if let Ok(pids) = find_processes_with_restart_manager(path) {
-                if !pids.is_empty() {
-                    return true;
-                }
-            }
+const RM_MAX_DIR_ENTRIES: usize = 256;
 
-            // Sample first batch of child files in directory for lock detection
-            for entry in entries.take(10).flatten() {
-                let child = entry.path();
-                let metadata = match child.symlink_metadata() {
-                    Ok(m) => m,
-                    Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => return true,
-                    Err(_) => continue,
-                };
-                if !metadata.is_dir() && !metadata.file_type().is_symlink() {
-                    if let Ok(pids) = find_processes_with_restart_manager(&child) {
-                        if !pids.is_empty() {
-                            return true;
-                        }
-                    }
-                }
-            }
-            false
-        }
-        Err(e) => match e.kind() {
-            std::io::ErrorKind::PermissionDenied => {
-                eprintln!(
-                    "Warning: directory access denied, may be locked: {}",
-                    path.display()
-                );
-                true
-            }
-            _ => {
-                eprintln!("Warning: directory read failed: {} - {}", path.display(), e);
-                true
-            }
-        },
+/// Resources to register for a path: the path itself, plus the files directly inside a directory
+#[cfg(target_os = "windows")]
+fn restart_manager_targets(path: &Path) -> Vec<PathBuf> {
+    let mut targets = vec![path.to_path_buf()];
+
+    if path.is_dir()
+        && let Ok(entries) = std::fs::read_dir(path)
+    {
+        targets.extend(
+            entries
+                .flatten()
+                .filter(|entry| entry.file_type().is_ok_and(|t| t.is_file()))
+                .map(|entry| entry.path())
+                .take(RM_MAX_DIR_ENTRIES),
+        );
     }
+
+    targets
 }
 
 /// Escape path as PowerShell single-quoted string (' → '')
@@ -131,67 +122,32 @@ fn ps_escape(s: &str) -> String {
     s.replace('\'', "''")
 }
 
-/// Windows-specific: more precise file lock status check
-#[cfg(target_os = "windows")]
-fn check_file_locking_status(path: &Path) -> bool {
-    use std::fs::OpenOptions;
-
-    // Method 1: try opening in read-only mode
-    if OpenOptions::new().read(true).open(path).is_ok() {
-        // Can open read-only but not write, most likely locked
-        return true;
-    }
-
-    // Method 2: try opening in append mode (doesn't modify content but needs write permission)
-    match OpenOptions::new().append(true).open(path) {
-        Ok(_) => false,
-        Err(e) => match e.kind() {
-            std::io::ErrorKind::PermissionDenied => true,
-            _ => {
-                // Other errors, use PowerShell as supplementary detection
-                let escaped = ps_escape(&path.to_string_lossy());
-                match std::process::Command::new("powershell")
-                    .args([
-                        "-Command",
-                        &format!(
-                            "Get-Process | Where-Object {{$_.Modules.FileName -eq '{escaped}'}} | Measure-Object"
-                        ),
-                    ])
-                    .output()
-                {
-                    Ok(output) if output.status.success() => {
-                        let output_str = String::from_utf8_lossy(&output.stdout);
-                        !output_str.trim().contains("0")
-                    }
-                    _ => true,
-                }
-            }
-        },
-    }
-}
-
-/// Windows-specific: use RestartManager API to find processes holding a file
+/// Windows-specific: use RestartManager API to find processes holding any of the given files
 /// This is the same API used by Windows Explorer, providing precise file handle detection
 #[cfg(target_os = "windows")]
-fn find_processes_with_restart_manager(path: &Path) -> Result<Vec<u32>> {
+fn find_processes_with_restart_manager(paths: &[PathBuf]) -> Result<Vec<u32>> {
+    use anyhow::anyhow;
     use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Foundation::{ERROR_MORE_DATA, ERROR_SUCCESS};
     use windows_sys::Win32::System::RestartManager::*;
 
-    let mut pids = Vec::new();
-
-    let wide_path: Vec<u16> = path
-        .as_os_str()
-        .encode_wide()
-        .chain(std::iter::once(0))
+    let wide_paths: Vec<Vec<u16>> = paths
+        .iter()
+        .map(|path| {
+            path.as_os_str()
+                .encode_wide()
+                .chain(std::iter::once(0))
+                .collect()
+        })
         .collect();
+    let path_ptrs: Vec<*const u16> = wide_paths.iter().map(|w| w.as_ptr()).collect();
 
     let mut session_handle: u32 = 0;
-    let mut session_key: [u16; 1] = [0];
+    let mut session_key = [0u16; CCH_RM_SESSION_KEY as usize + 1];
 
     let result = unsafe { RmStartSession(&mut session_handle, 0, session_key.as_mut_ptr()) };
-
-    if result != 0 {
-        return Ok(pids);
+    if result != ERROR_SUCCESS {
+        return Err(anyhow!("RmStartSession failed (error {result})"));
     }
 
     // RAII guard to ensure session cleanup
@@ -212,116 +168,151 @@ fn find_processes_with_restart_manager(path: &Path) -> Result<Vec<u32>> {
     let result = unsafe {
         RmRegisterResources(
             session_handle,
-            1,
-            &wide_path.as_ptr(),
+            path_ptrs.len() as u32,
+            path_ptrs.as_ptr(),
             0,
             std::ptr::null(),
             0,
             std::ptr::null(),
         )
     };
-
-    if result != 0 {
-        return Ok(pids);
-    }
-
-    let mut proc_info_needed: u32 = 0;
-    let mut proc_info_count: u32 = 0;
-    let mut reboot_reasons: u32 = 0;
-
-    let result = unsafe {
-        RmGetList(
-            session_handle,
-            &mut proc_info_needed,
-            &mut proc_info_count,
-            std::ptr::null_mut(),
-            &mut reboot_reasons,
-        )
-    };
-
-    if result != 233 && result != 0 {
-        return Ok(pids);
+    if result != ERROR_SUCCESS {
+        return Err(anyhow!("RmRegisterResources failed (error {result})"));
     }
 
-    if proc_info_needed == 0 {
-        return Ok(pids);
+    let mut process_info: Vec<RM_PROCESS_INFO> = Vec::new();
+    loop {
+        let mut proc_info_needed: u32 = 0;
+        let mut proc_info_count = process_info.len() as u32;
+        let mut reboot_reasons: u32 = 0;
+        let buffer = if process_info.is_empty() {
+            std::ptr::null_mut()
+        } else {
+            process_info.as_mut_ptr()
+        };
+
+        let result = unsafe {
+            RmGetList(
+                session_handle,
+                &mut proc_info_needed,
+                &mut proc_info_count,
+                buffer,
+                &mut reboot_reasons,
+            )
+        };
+
+        match result {
+            ERROR_SUCCESS => {
+                process_info.truncate(proc_info_count as usize);
+                break;
+            }
+            // Buffer too small (or the holder list grew between calls), retry with the new size
+            ERROR_MORE_DATA => {
+                process_info = vec![unsafe { std::mem::zeroed() }; proc_info_needed as usize];
+            }
+            _ => return Err(anyhow!("RmGetList failed (error {result})")),
+        }
     }
 
-    let mut process_info: Vec<RM_PROCESS_INFO> =
-        vec![unsafe { std::mem::zeroed() }; proc_info_needed as usize];
-    proc_info_count = proc_info_needed;
+    let mut pids: Vec<u32> = process_info
+        .iter()
+        .map(|info| info.Process.dwProcessId)
+        .filter(|&pid| pid != 0)
+        .collect();
+    pids.sort_unstable();
+    pids.dedup();
 
-    let result = unsafe {
-        RmGetList(
-            session_handle,
-            &mut proc_info_needed,
-            &mut proc_info_count,
-            process_info.as_mut_ptr(),
-            &mut reboot_reasons,
-        )
-    };
+    Ok(pids)
+}
 
-    if result != 0 {
-        return Ok(pids);
+/// Find processes locking a specified file
+#[cfg(target_os = "windows")]
+pub fn find_processes_by_file(path: &Path) -> Result<Vec<u32>> {
+    if !path.exists() {
+        return Ok(Vec::new());
     }
 
-    for info in process_info.iter().take(proc_info_count as usize) {
-        let pid = info.Process.dwProcessId;
-        if pid != 0 && !pids.contains(&pid) {
-            pids.push(pid);
-        }
+    match find_processes_with_restart_manager(&restart_manager_targets(path)) {
+        Ok(pids) => Ok(pids),
+        // Restart Manager unavailable (e.g. restricted session): fall back to PowerShell
+        Err(_) => find_processes_with_powershell(&path.to_string_lossy()),
     }
-
-    Ok(pids)
 }
 
 /// Find processes locking a specified file
-#[cfg(target_os = "windows")]
+#[cfg(not(target_os = "windows"))]
 pub fn find_processes_by_file(path: &Path) -> Result<Vec<u32>> {
-    let mut pids = Vec::new();
-
     if !path.exists() {
-        return Ok(pids);
+        return Ok(Vec::new());
     }
 
-    let path_str = path.to_string_lossy();
+    // /proc answers natively on Linux; lsof is only needed when procfs is unusable
+    #[cfg(target_os = "linux")]
+    if let Ok(pids) = find_processes_with_procfs(path) {
+        return Ok(pids);
+    }
 
-    // Method 1 (preferred): use RestartManager API for precise file handle detection
-    if let Ok(rm_pids) = find_processes_with_restart_manager(path) {
-        pids.extend(rm_pids);
+    #[cfg(any(
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "dragonfly",
+        target_os = "openbsd"
+    ))]
+    if crate::platform::command::find_program("lsof").is_none() {
+        return find_processes_with_fstat(path);
     }
 
-    // Method 2: use PowerShell to find (compatibility fallback)
-    if let Ok(ps_pids) = find_processes_with_powershell(&path_str) {
-        for pid in ps_pids {
-            if !pids.contains(&pid) {
-                pids.push(pid);
-            }
+    find_processes_with_lsof(path)
+}
+
+/// Find processes with `path` open by asking the BSD base system's fstat
+#[cfg(any(
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "dragonfly",
+    target_os = "openbsd"
+))]
+fn find_processes_with_fstat(path: &Path) -> Result<Vec<u32>> {
+    let output = run_command(Command::new("fstat").arg(path))?;
+    Ok(parse_fstat_pids(&safe_command_output_to_string(
+        &output.stdout,
+    )))
+}
+
+/// PIDs in the third column of `fstat` rows, in order and without repeats
+#[cfg(any(
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "dragonfly",
+    target_os = "openbsd",
+    test
+))]
+fn parse_fstat_pids(output: &str) -> Vec<u32> {
+    let mut pids: Vec<u32> = Vec::new();
+    for line in output.lines() {
+        if let Some(pid) = line
+            .split_whitespace()
+            .nth(2)
+            .and_then(|p| p.parse::<u32>().ok())
+            && !pids.contains(&pid)
+        {
+            pids.push(pid);
         }
     }
-
-    Ok(pids)
+    pids
 }
 
-/// Find processes locking a specified file
+/// Find locking processes by asking lsof
 #[cfg(not(target_os = "windows"))]
-pub fn find_processes_by_file(path: &Path) -> Result<Vec<u32>> {
+fn find_processes_with_lsof(path: &Path) -> Result<Vec<u32>> {
     let mut pids = Vec::new();
 
-    if !path.exists() {
-        return Ok(pids);
-    }
-
     let path_str = match path.to_str() {
         Some(s) => s,
         None => return Ok(pids),
     };
 
-    if let Ok(output) = std::process::Command::new("lsof")
-        .arg("-t")
-        .arg(path_str)
-        .output()
-    {
+    if let Ok(output) = run_command(Command::new("lsof").arg("-t").arg(path_str)) {
         if output.status.success() {
             let output_str = safe_command_output_to_string(&output.stdout);
             for line in output_str.lines() {
@@ -335,6 +326,162 @@ pub fn find_processes_by_file(path: &Path) -> Result<Vec<u32>> {
     Ok(pids)
 }
 
+/// Linux-specific: find processes holding a path via /proc
+///
+/// A process counts as a holder if it has an open fd or a memory mapping on the path
+/// (or anything below it, for directories), or holds an advisory lock listed in /proc/locks.
+#[cfg(target_os = "linux")]
+fn find_processes_with_procfs(path: &Path) -> Result<Vec<u32>> {
+    use std::fs;
+    use std::os::unix::fs::MetadataExt;
+
+    let target = fs::canonicalize(path)?;
+    let metadata = fs::metadata(&target)?;
+    let (major, minor) = split_dev(metadata.dev());
+    let inode = metadata.ino();
+
+    let mut pids: Vec<u32> = fs::read_to_string("/proc/locks")
+        .map(|content| parse_proc_locks(&content))
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|lock| lock.major == major && lock.minor == minor && lock.inode == inode)
+        .map(|lock| lock.pid)
+        .collect();
+
+    for entry in fs::read_dir("/proc")?.flatten() {
+        let Some(pid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|name| name.parse::<u32>().ok())
+        else {
+            continue;
+        };
+
+        if process_holds_path(pid, &target) {
+            pids.push(pid);
+        }
+    }
+
+    pids.sort_unstable();
+    pids.dedup();
+    Ok(pids)
+}
+
+/// Check a single process's open fds and memory maps for the target path
+#[cfg(target_os = "linux")]
+fn process_holds_path(pid: u32, target: &Path) -> bool {
+    use std::fs;
+
+    let proc_dir = PathBuf::from(format!("/proc/{pid}"));
+
+    if let Ok(fds) = fs::read_dir(proc_dir.join("fd")) {
+        for fd in fds.flatten() {
+            if let Ok(link) = fs::read_link(fd.path())
+                && link.starts_with(target)
+            {
+                return true;
+            }
+        }
+    }
+
+    if let Ok(maps) = fs::read_to_string(proc_dir.join("maps")) {
+        // The pathname column is the only one that can contain '/'
+        return maps
+            .lines()
+            .filter_map(|line| line.find('/').map(|i| Path::new(line[i..].trim_end())))
+            .any(|mapped| mapped.starts_with(target));
+    }
+
+    false
+}
+
+/// Files a process has open, sorted; sockets, pipes and the like are left out
+#[cfg(target_os = "linux")]
+pub fn open_files(pid: u32) -> Vec<PathBuf> {
+    let Ok(fds) = std::fs::read_dir(format!("/proc/{pid}/fd")) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = fds
+        .flatten()
+        .filter_map(|fd| std::fs::read_link(fd.path()).ok())
+        // Anonymous objects read as `socket:[1234]`, `pipe:[5678]`, ...
+        .filter(|link| link.is_absolute())
+        .collect();
+    files.sort();
+    files.dedup();
+    files
+}
+
+/// Files a process has open, sorted, as reported by lsof
+#[cfg(all(unix, not(target_os = "linux")))]
+pub fn open_files(pid: u32) -> Vec<PathBuf> {
+    match run_command(Command::new("lsof").args(["-Fn", "-p", &pid.to_string()])) {
+        Ok(output) => parse_lsof_names(&safe_command_output_to_string(&output.stdout)),
+        Err(_) => Vec::new(),
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn open_files(_pid: u32) -> Vec<PathBuf> {
+    Vec::new()
+}
+
+/// Paths from `lsof -Fn` output, where each name field is a line starting with `n`
+#[cfg(any(all(unix, not(target_os = "linux")), test))]
+fn parse_lsof_names(output: &str) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = output
+        .lines()
+        .filter_map(|line| line.strip_prefix('n'))
+        .filter(|name| name.starts_with('/'))
+        .map(PathBuf::from)
+        .collect();
+    files.sort();
+    files.dedup();
+    files
+}
+
+/// A lock entry from /proc/locks
+#[cfg(any(target_os = "linux", test))]
+#[derive(Debug, PartialEq)]
+struct ProcLock {
+    pid: u32,
+    major: u32,
+    minor: u32,
+    inode: u64,
+}
+
+/// Parse /proc/locks, e.g. `1: POSIX  ADVISORY  WRITE 1234 08:02:131090 0 EOF`
+///
+/// Blocked waiters (`->`) are included; OFD locks without an owner (pid -1) are skipped.
+#[cfg(any(target_os = "linux", test))]
+fn parse_proc_locks(content: &str) -> Vec<ProcLock> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace().skip(1).filter(|f| *f != "->");
+            let pid = fields.nth(3)?.parse::<u32>().ok()?;
+            let mut id = fields.next()?.split(':');
+            let major = u32::from_str_radix(id.next()?, 16).ok()?;
+            let minor = u32::from_str_radix(id.next()?, 16).ok()?;
+            let inode = id.next()?.parse::<u64>().ok()?;
+            Some(ProcLock {
+                pid,
+                major,
+                minor,
+                inode,
+            })
+        })
+        .collect()
+}
+
+/// Split a Linux `dev_t` into (major, minor) as glibc's gnu_dev_major/minor do
+#[cfg(target_os = "linux")]
+fn split_dev(dev: u64) -> (u32, u32) {
+    let major = ((dev >> 8) & 0xfff) | ((dev >> 32) & !0xfff);
+    let minor = (dev & 0xff) | ((dev >> 12) & !0xff);
+    (major as u32, minor as u32)
+}
+
 /// Windows-specific: use PowerShell to find locking processes
 #[cfg(target_os = "windows")]
 fn find_processes_with_powershell(path_str: &str) -> Result<Vec<u32>> {
@@ -354,10 +501,7 @@ fn find_processes_with_powershell(path_str: &str) -> Result<Vec<u32>> {
     ];
 
     for command in powershell_commands {
-        match std::process::Command::new("powershell")
-            .args(["-Command", &command])
-            .output()
-        {
+        match run_command(Command::new("powershell").args(["-NoProfile", "-Command", &command])) {
             Ok(output) => {
                 if output.status.success() {
                     let output_str = safe_command_output_to_string(&output.stdout);
@@ -382,3 +526,66 @@ fn find_processes_with_powershell(path_str: &str) -> Result<Vec<u32>> {
 
     Ok(pids)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_proc_locks() {
+        let input = "\
+1: POSIX  ADVISORY  WRITE 1234 08:02:131090 0 EOF
+1: -> POSIX  ADVISORY  WRITE 5678 08:02:131090 0 EOF
+2: FLOCK  ADVISORY  WRITE 910 00:1a:2310 0 EOF
+3: OFDLCK ADVISORY  READ  -1 00:06:9 0 EOF
+";
+        let locks = parse_proc_locks(input);
+        assert_eq!(locks.len(), 3);
+        assert_eq!(
+            locks[0],
+            ProcLock {
+                pid: 1234,
+                major: 8,
+                minor: 2,
+                inode: 131090
+            }
+        );
+        assert_eq!(locks[1].pid, 5678);
+        assert_eq!((locks[2].major, locks[2].minor), (0, 0x1a));
+    }
+
+    #[test]
+    fn test_parse_fstat_pids() {
+        let input = "\
+USER     CMD          PID   FD MOUNT      INUM MODE         SZ|DV R/W NAME
+alice    vim         4321    4 /home    123456 -rw-r--r--     812  rw  /home/alice/notes.txt
+alice    vim         4321   wd /home    123400 drwxr-xr-x     512  r   /home/alice
+root     tail         600    3 /home    123456 -rw-r--r--     812  r   /home/alice/notes.txt
+";
+        assert_eq!(parse_fstat_pids(input), vec![4321, 600]);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_procfs_finds_own_open_file() {
+        let file_path =
+            std::env::temp_dir().join(format!("ziro_procfs_test_{}", std::process::id()));
+        let file = std::fs::File::create(&file_path).unwrap();
+
+        let pids = find_processes_with_procfs(&file_path).unwrap();
+        assert!(pids.contains(&std::process::id()));
+
+        drop(file);
+        let _ = std::fs::remove_file(&file_path);
+    }
+
+    #[test]
+    fn test_parse_lsof_names() {
+        let output =
+            "p1234\nfcwd\nn/home/user\nf3\nn/tmp/b.log\nf4\nnlocalhost:8080\nf5\nn/tmp/b.log\n";
+        assert_eq!(
+            parse_lsof_names(output),
+            vec![PathBuf::from("/home/user"), PathBuf::from("/tmp/b.log")]
+        );
+    }
+}
@@ -1,92 +1,241 @@
+use crate::error::ZiroError;
 use anyhow::{Result, anyhow};
+use std::collections::HashSet;
 use std::path::PathBuf;
 use std::thread;
-use std::time::Duration;
-use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, RefreshKind, System};
+use std::time::{Duration, Instant};
+use sysinfo::Pid;
 
 pub mod encoding;
+mod info;
+#[cfg(windows)]
+mod job;
 pub mod lock;
+mod native;
+mod priority;
+mod reap;
+mod refusal;
+mod suspend;
+mod system;
+mod tree;
+mod wait;
 
-pub use lock::{FileLockInfo, FileLockProcess, find_processes_by_file, is_file_locked};
+pub use info::{MemoryBreakdown, ProcessInfo, inspect_process};
+pub use lock::{FileLockInfo, FileLockProcess, find_processes_by_file, is_file_locked, open_files};
+pub use native::{KillSignal, process_exists, send_signal};
+pub use priority::set_priority;
+pub use reap::{INIT_PID, ReapReport, StrayProcess, ZombieParent, find_strays, nudge_parent};
+pub use suspend::{pause_process, resume_process};
+pub use system::{SystemHandle, shared};
+pub use tree::{ChildProcess, ProcessTree, descendants};
+pub use wait::{WaitOptions, WaitOutcome, WaitedProcess, wait_for_exit};
 
-fn create_process_system() -> System {
-    System::new_with_specifics(RefreshKind::new().with_processes(ProcessRefreshKind::everything()))
+/// Kill the process with the given PID
+pub fn kill_process(pid: u32) -> Result<()> {
+    let result = send_signal(pid, KillSignal::Kill);
+    shared().invalidate();
+    result
 }
 
-fn refresh_processes(sys: &mut System) {
-    sys.refresh_processes_specifics(ProcessesToUpdate::All, ProcessRefreshKind::everything());
+/// Kill multiple processes
+pub fn kill_processes(pids: &[u32]) -> Vec<(u32, Result<()>)> {
+    signal_processes(pids, KillSignal::Kill)
 }
 
-/// Kill the process with the given PID
-pub fn kill_process(pid: u32) -> Result<()> {
-    let sys = create_process_system();
-    let pid_obj = sysinfo::Pid::from_u32(pid);
+/// Send `signal` to multiple processes without waiting for them
+///
+/// One result per distinct PID, in the order given; a process already gone counts as done.
+pub fn signal_processes(pids: &[u32], signal: KillSignal) -> Vec<(u32, Result<()>)> {
+    let results = distinct(pids)
+        .into_iter()
+        .map(|pid| (pid, already_gone_is_done(send_signal(pid, signal))))
+        .collect();
+    shared().invalidate();
+    results
+}
 
-    if let Some(process) = sys.process(pid_obj) {
-        if process.kill() {
-            Ok(())
-        } else {
-            Err(anyhow!(
-                "Failed to kill process {pid} (administrator privileges may be required)"
-            ))
+/// Kill whole process trees: `pids` lists the `roots` followed by their descendants
+///
+/// On Windows each root's tree is first terminated at once through a Job Object, so
+/// children spawned in the meantime die too; whatever could not join is signalled alone.
+/// Results are as for `signal_processes`.
+pub fn kill_trees(roots: &[u32], pids: &[u32], signal: KillSignal) -> Vec<(u32, Result<()>)> {
+    let terminated = terminate_trees(&distinct(roots));
+    let results = distinct(pids)
+        .into_iter()
+        .map(|pid| {
+            let result = if terminated.contains(&pid) {
+                Ok(())
+            } else {
+                already_gone_is_done(send_signal(pid, signal))
+            };
+            (pid, result)
+        })
+        .collect();
+    shared().invalidate();
+    results
+}
+
+/// Force kill whole process trees and wait until they are gone; see `kill_trees`
+pub fn kill_trees_force(roots: &[u32], pids: &[u32]) -> Vec<KillReport> {
+    let terminated = terminate_trees(&distinct(roots));
+    force_kill(pids, &terminated)
+}
+
+/// `pids` without repeats, first occurrences kept in order; several ports of one request
+/// often belong to the same process
+fn distinct(pids: &[u32]) -> Vec<u32> {
+    let mut seen = HashSet::new();
+    pids.iter()
+        .copied()
+        .filter(|&pid| seen.insert(pid))
+        .collect()
+}
+
+/// A kill whose target exited in the meantime got what it asked for
+fn already_gone_is_done(result: Result<()>) -> Result<()> {
+    match result {
+        Err(e) if matches!(e.downcast_ref::<ZiroError>(), Some(ZiroError::NotFound(_))) => Ok(()),
+        result => result,
+    }
+}
+
+/// PIDs whose job objects were terminated
+#[cfg(windows)]
+fn terminate_trees(roots: &[u32]) -> HashSet<u32> {
+    let mut terminated = HashSet::new();
+    for &root in roots {
+        match job::terminate_tree(root) {
+            Ok(members) => terminated.extend(members),
+            Err(e) => tracing::debug!(root, "job object kill failed, killing one by one: {e:#}"),
         }
-    } else {
-        Err(anyhow!("Process {pid} does not exist"))
     }
+    terminated
 }
 
-/// Kill multiple processes
-pub fn kill_processes(pids: &[u32]) -> Vec<(u32, Result<()>)> {
-    pids.iter().map(|&pid| (pid, kill_process(pid))).collect()
+/// Signals reach the members of a tree one by one
+#[cfg(not(windows))]
+fn terminate_trees(_roots: &[u32]) -> HashSet<u32> {
+    HashSet::new()
+}
+
+/// First pause between checks of force-killed processes; doubles up to `VERIFY_MAX_DELAY`
+const VERIFY_FIRST_DELAY: Duration = Duration::from_millis(10);
+const VERIFY_MAX_DELAY: Duration = Duration::from_millis(400);
+/// Survivors get the signal again this often
+const RESEND_EVERY: Duration = Duration::from_secs(1);
+/// Give up on processes still running after this long
+const VERIFY_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Outcome of force-killing one process
+#[derive(Debug)]
+pub struct KillReport {
+    pub pid: u32,
+    pub result: Result<()>,
+    /// Time until the process was seen gone (or until giving up on it)
+    pub elapsed: Duration,
 }
 
-/// Force kill the process with the given PID (multiple attempts)
+/// Force kill the process with the given PID, waiting until it is gone
 pub fn kill_process_force(pid: u32) -> Result<()> {
-    let mut sys = create_process_system();
-    let pid_obj = sysinfo::Pid::from_u32(pid);
+    kill_processes_force(&[pid])
+        .pop()
+        .map_or(Ok(()), |report| report.result)
+}
 
-    if sys.process(pid_obj).is_none() {
-        return Ok(());
-    }
+/// Force kill multiple processes and wait until they are gone
+///
+/// Every PID is signalled up front and then all of them are checked together,
+/// so the wait is as long as the slowest process rather than the sum of all.
+/// One report per distinct PID, in the order given.
+pub fn kill_processes_force(pids: &[u32]) -> Vec<KillReport> {
+    force_kill(pids, &HashSet::new())
+}
 
-    for attempt in 1..=3 {
-        if let Some(process) = sys.process(pid_obj) {
-            if process.kill() {
-                thread::sleep(Duration::from_millis(500));
-                refresh_processes(&mut sys);
-                if !sys.processes().contains_key(&pid_obj) {
-                    return Ok(());
-                }
-            } else if attempt == 3 {
-                return Err(anyhow!(
-                    "Failed to force kill process {pid} (administrator privileges may be required)"
-                ));
+/// Force kill `pids`, of which `signalled` are already dying, and wait until they are gone
+fn force_kill(pids: &[u32], signalled: &HashSet<u32>) -> Vec<KillReport> {
+    let pids = &distinct(pids);
+    let start = Instant::now();
+    let mut reports: Vec<Option<KillReport>> = pids.iter().map(|_| None).collect();
+    let mut pending: Vec<usize> = Vec::new();
+
+    for (i, &pid) in pids.iter().enumerate() {
+        if !process_exists(pid) {
+            reports[i] = Some(KillReport {
+                pid,
+                result: Ok(()),
+                elapsed: start.elapsed(),
+            });
+            continue;
+        }
+        if signalled.contains(&pid) {
+            pending.push(i);
+            continue;
+        }
+        match send_signal(pid, KillSignal::Kill) {
+            Ok(()) => pending.push(i),
+            // Gone between the check and the signal
+            Err(_) if !process_exists(pid) => {
+                reports[i] = Some(KillReport {
+                    pid,
+                    result: Ok(()),
+                    elapsed: start.elapsed(),
+                })
+            }
+            // Waiting would not change a refused signal
+            Err(e) => {
+                reports[i] = Some(KillReport {
+                    pid,
+                    result: Err(e),
+                    elapsed: start.elapsed(),
+                })
             }
-        } else {
-            return Ok(());
         }
+    }
+
+    let mut delay = VERIFY_FIRST_DELAY;
+    let mut last_send = Instant::now();
+    while !pending.is_empty() && start.elapsed() < VERIFY_TIMEOUT {
+        thread::sleep(delay);
+        delay = (delay * 2).min(VERIFY_MAX_DELAY);
 
-        if attempt < 3 {
-            thread::sleep(Duration::from_millis(1000));
-            refresh_processes(&mut sys);
+        pending.retain(|&i| {
+            let pid = pids[i];
+            if process_exists(pid) {
+                return true;
+            }
+            reports[i] = Some(KillReport {
+                pid,
+                result: Ok(()),
+                elapsed: start.elapsed(),
+            });
+            false
+        });
+        if last_send.elapsed() >= RESEND_EVERY {
+            for &i in &pending {
+                let _ = send_signal(pids[i], KillSignal::Kill);
+            }
+            last_send = Instant::now();
         }
     }
+    for i in pending {
+        let pid = pids[i];
+        tracing::debug!(pid, "still running after {:?} of SIGKILL", VERIFY_TIMEOUT);
+        reports[i] = Some(KillReport {
+            pid,
+            result: Err(anyhow!(
+                "Force kill of process {pid} failed, the process may still be running"
+            )),
+            elapsed: start.elapsed(),
+        });
+    }
 
-    Err(anyhow!(
-        "Force kill of process {pid} failed, the process may still be running"
-    ))
-}
-
-/// Force kill multiple processes
-pub fn kill_processes_force(pids: &[u32]) -> Vec<(u32, Result<()>)> {
-    pids.iter()
-        .map(|&pid| (pid, kill_process_force(pid)))
-        .collect()
+    shared().invalidate();
+    reports.into_iter().flatten().collect()
 }
 
 /// Check file lock status
 pub fn inspect_file_locks(paths: &[PathBuf]) -> Result<Vec<FileLockInfo>> {
-    let sys = create_process_system();
     let mut results = Vec::new();
 
     for path in paths {
@@ -95,25 +244,27 @@ pub fn inspect_file_locks(paths: &[PathBuf]) -> Result<Vec<FileLockInfo>> {
         pids.sort_unstable();
         pids.dedup();
 
-        let mut processes = Vec::new();
-        for pid in pids {
-            if let Some(process) = sys.process(sysinfo::Pid::from_u32(pid)) {
-                let name = process.name().to_string_lossy().to_string();
-                let cmd = process
-                    .cmd()
-                    .iter()
-                    .map(|s| s.to_string_lossy().to_string())
-                    .collect::<Vec<String>>()
-                    .join(" ");
-                processes.push(FileLockProcess { pid, name, cmd });
-            } else {
-                processes.push(FileLockProcess {
-                    pid,
-                    name: "unknown".to_string(),
-                    cmd: String::new(),
-                });
-            }
-        }
+        let processes: Vec<FileLockProcess> = shared().with(|sys| {
+            pids.iter()
+                .map(|&pid| match sys.process(Pid::from_u32(pid)) {
+                    Some(process) => FileLockProcess {
+                        pid,
+                        name: process.name().to_string_lossy().to_string(),
+                        cmd: process
+                            .cmd()
+                            .iter()
+                            .map(|s| s.to_string_lossy().to_string())
+                            .collect::<Vec<String>>()
+                            .join(" "),
+                    },
+                    None => FileLockProcess {
+                        pid,
+                        name: "unknown".to_string(),
+                        cmd: String::new(),
+                    },
+                })
+                .collect()
+        });
 
         if !processes.is_empty() {
             locked = true;
@@ -160,4 +311,18 @@ mod tests {
 
         let _ = fs::remove_file(&file_path);
     }
+
+    #[test]
+    fn test_batch_kill_dedups_and_accepts_gone() {
+        assert_eq!(distinct(&[30, 10, 30, 20, 10]), [30, 10, 20]);
+
+        // No process can have this PID, as if it had exited after being listed
+        let pid = u32::MAX;
+        let results = signal_processes(&[pid, pid], KillSignal::Kill);
+        assert_eq!(results.len(), 1);
+        assert!(results[0].1.is_ok());
+        let reports = kill_processes_force(&[pid, pid]);
+        assert_eq!(reports.len(), 1);
+        assert!(reports[0].result.is_ok());
+    }
 }
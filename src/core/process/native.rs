@@ -0,0 +1,181 @@
+/// Signalling single processes with direct OS calls
+///
+/// `sysinfo` can only signal processes it has listed, which costs a process table
+/// refresh per kill; these go straight to `kill(2)` / `TerminateProcess`.
+use crate::error::ZiroError;
+use anyhow::{Result, anyhow};
+
+/// How a process is asked to stop
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KillSignal {
+    /// SIGTERM; the process may clean up first (Windows has no equivalent and terminates)
+    Terminate,
+    /// SIGKILL
+    Kill,
+}
+
+/// Whether a process with this PID is running
+///
+/// On Linux a zombie counts as gone: it has exited and only waits for its parent.
+#[cfg(unix)]
+pub fn process_exists(pid: u32) -> bool {
+    use nix::errno::Errno;
+
+    let Some(target) = unix_pid(pid) else {
+        return false;
+    };
+    // Signal 0 only checks; EPERM means it exists but belongs to someone else
+    matches!(
+        nix::sys::signal::kill(target, None),
+        Ok(()) | Err(Errno::EPERM)
+    ) && !is_zombie(pid)
+}
+
+#[cfg(target_os = "linux")]
+fn is_zombie(pid: u32) -> bool {
+    std::fs::read_to_string(format!("/proc/{pid}/stat"))
+        .ok()
+        .and_then(|stat| stat_state(&stat))
+        == Some('Z')
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn is_zombie(_pid: u32) -> bool {
+    false
+}
+
+/// State letter of a /proc/PID/stat line, e.g. `1234 (my (app)) Z 1 ...`
+#[cfg(any(target_os = "linux", test))]
+fn stat_state(stat: &str) -> Option<char> {
+    // The name may itself contain parentheses, so look after the last one
+    stat[stat.rfind(')')? + 1..].trim_start().chars().next()
+}
+
+/// Send `signal` to one process
+#[cfg(unix)]
+pub fn send_signal(pid: u32, signal: KillSignal) -> Result<()> {
+    use nix::sys::signal::Signal;
+
+    let signal = match signal {
+        KillSignal::Terminate => Signal::SIGTERM,
+        KillSignal::Kill => Signal::SIGKILL,
+    };
+    raw_signal(pid, signal)
+}
+
+/// `kill(2)` with the errors ziro reports for it
+#[cfg(unix)]
+pub(super) fn raw_signal(pid: u32, signal: nix::sys::signal::Signal) -> Result<()> {
+    use nix::errno::Errno;
+
+    let Some(target) = unix_pid(pid) else {
+        return Err(anyhow!(ZiroError::NotFound(format!(
+            "Process {pid} does not exist"
+        ))));
+    };
+    tracing::debug!(pid, "sending {}", signal.as_str());
+    let sent = nix::sys::signal::kill(target, signal);
+    if let Err(e) = sent {
+        tracing::debug!(pid, "{} failed: {e}", signal.as_str());
+    }
+    match sent {
+        Ok(()) => Ok(()),
+        Err(Errno::ESRCH) => Err(anyhow!(ZiroError::NotFound(format!(
+            "Process {pid} does not exist"
+        )))),
+        // Keep the OS error in the chain so callers can tell missing privileges apart
+        Err(Errno::EPERM) => Err(super::refusal::refused(
+            pid,
+            std::io::Error::from(Errno::EPERM),
+            "signal",
+        )),
+        Err(e) => Err(anyhow!("Failed to signal process {pid}: {e}")),
+    }
+}
+
+/// PIDs 0 and above `i32::MAX` would address process groups, never a single process
+#[cfg(unix)]
+fn unix_pid(pid: u32) -> Option<nix::unistd::Pid> {
+    i32::try_from(pid)
+        .ok()
+        .filter(|&pid| pid > 0)
+        .map(nix::unistd::Pid::from_raw)
+}
+
+#[cfg(windows)]
+pub fn process_exists(pid: u32) -> bool {
+    use windows_sys::Win32::Foundation::{CloseHandle, ERROR_ACCESS_DENIED, STILL_ACTIVE};
+    use windows_sys::Win32::System::Threading::{
+        GetExitCodeProcess, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    // SAFETY: plain Win32 calls on a handle we own and close
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if handle.is_null() {
+            // Access denied still means there is a process to deny access to
+            return std::io::Error::last_os_error().raw_os_error()
+                == Some(ERROR_ACCESS_DENIED as i32);
+        }
+        let mut code = 0u32;
+        let ok = GetExitCodeProcess(handle, &mut code);
+        CloseHandle(handle);
+        // Handles to exited processes stay valid until every one is closed
+        ok != 0 && code == STILL_ACTIVE as u32
+    }
+}
+
+#[cfg(windows)]
+pub fn send_signal(pid: u32, _signal: KillSignal) -> Result<()> {
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::Threading::{OpenProcess, PROCESS_TERMINATE, TerminateProcess};
+
+    tracing::debug!(pid, "terminating");
+    // SAFETY: plain Win32 calls on a handle we own and close
+    unsafe {
+        let handle = OpenProcess(PROCESS_TERMINATE, 0, pid);
+        if handle.is_null() {
+            return Err(open_failed(pid, "signal"));
+        }
+        let ok = TerminateProcess(handle, 1);
+        CloseHandle(handle);
+        if ok == 0 {
+            return Err(anyhow!(
+                "Failed to signal process {pid}: {}",
+                std::io::Error::last_os_error()
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// The error for an `OpenProcess` call on `pid` that just failed, for a caller that
+/// was about to `action` it
+#[cfg(windows)]
+pub(super) fn open_failed(pid: u32, action: &str) -> anyhow::Error {
+    use windows_sys::Win32::Foundation::{ERROR_ACCESS_DENIED, ERROR_INVALID_PARAMETER};
+
+    let error = std::io::Error::last_os_error();
+    match error.raw_os_error() {
+        // What OpenProcess reports for a PID nobody has
+        Some(code) if code == ERROR_INVALID_PARAMETER as i32 => {
+            anyhow!(ZiroError::NotFound(format!("Process {pid} does not exist")))
+        }
+        Some(code) if code == ERROR_ACCESS_DENIED as i32 => {
+            super::refusal::refused(pid, error, action)
+        }
+        _ => anyhow!("Failed to {action} process {pid}: {error}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stat_state() {
+        assert_eq!(stat_state("1234 (my (app)) Z 1 1234 1234"), Some('Z'));
+        assert_eq!(stat_state("1 (init) S 0 1 1"), Some('S'));
+        assert_eq!(stat_state("garbage"), None);
+    }
+}
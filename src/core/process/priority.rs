@@ -0,0 +1,76 @@
+/// Scheduling priority of running processes (`ziro nice`)
+use anyhow::{Result, anyhow};
+
+use super::shared;
+
+/// Lowest and highest niceness accepted, as on Unix
+const NICE_RANGE: std::ops::RangeInclusive<i32> = -20..=19;
+
+/// Set a process's niceness (-20 highest priority, 19 lowest)
+///
+/// Raising priority (a lower value than before) usually needs root or administrator rights.
+/// Windows only has priority classes, so the value is mapped onto the nearest one.
+pub fn set_priority(pid: u32, nice: i32) -> Result<()> {
+    if !NICE_RANGE.contains(&nice) {
+        return Err(anyhow!("Niceness must be between -20 and 19, got {nice}"));
+    }
+    let result = renice(pid, nice);
+    shared().invalidate();
+    result
+}
+
+#[cfg(unix)]
+fn renice(pid: u32, nice: i32) -> Result<()> {
+    // SAFETY: setpriority takes plain integers and only reports failure through errno;
+    // `who` is an `id_t` on most systems but a `c_int` on the BSDs
+    let status = unsafe { libc::setpriority(libc::PRIO_PROCESS, pid as _, nice) };
+    if status == 0 {
+        return Ok(());
+    }
+    let error = std::io::Error::last_os_error();
+    Err(match error.raw_os_error() {
+        Some(libc::ESRCH) => anyhow!(crate::error::ZiroError::NotFound(format!(
+            "Process {pid} does not exist"
+        ))),
+        Some(libc::EPERM | libc::EACCES) => {
+            super::refusal::refused(pid, error, "change the priority of")
+        }
+        _ => anyhow!("Failed to change the priority of process {pid}: {error}"),
+    })
+}
+
+#[cfg(windows)]
+fn renice(pid: u32, nice: i32) -> Result<()> {
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::Threading::{
+        ABOVE_NORMAL_PRIORITY_CLASS, BELOW_NORMAL_PRIORITY_CLASS, HIGH_PRIORITY_CLASS,
+        IDLE_PRIORITY_CLASS, NORMAL_PRIORITY_CLASS, OpenProcess, PROCESS_SET_INFORMATION,
+        SetPriorityClass,
+    };
+
+    let class = match nice {
+        15.. => IDLE_PRIORITY_CLASS,
+        5..=14 => BELOW_NORMAL_PRIORITY_CLASS,
+        -4..=4 => NORMAL_PRIORITY_CLASS,
+        -14..=-5 => ABOVE_NORMAL_PRIORITY_CLASS,
+        // Realtime can starve the whole system, so it is never chosen
+        _ => HIGH_PRIORITY_CLASS,
+    };
+
+    // SAFETY: plain Win32 calls on a handle we own and close
+    unsafe {
+        let handle = OpenProcess(PROCESS_SET_INFORMATION, 0, pid);
+        if handle.is_null() {
+            return Err(super::native::open_failed(pid, "change the priority of"));
+        }
+        let ok = SetPriorityClass(handle, class);
+        CloseHandle(handle);
+        if ok == 0 {
+            return Err(anyhow!(
+                "Failed to change the priority of process {pid}: {}",
+                std::io::Error::last_os_error()
+            ));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,197 @@
+/// Finding zombies and orphaned processes (`ziro reap`)
+///
+/// A zombie has exited but stays in the process table until its parent collects the
+/// exit status, so it is the parent that has to be signalled (or killed, letting init
+/// adopt and reap it). Orphans are still running after their parent died.
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::time::Duration;
+use sysinfo::ProcessStatus;
+
+use super::shared;
+
+/// PID of init, which adopts orphans on Unix
+pub const INIT_PID: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StrayProcess {
+    pub pid: u32,
+    pub name: String,
+    /// Seconds since the process started
+    pub run_time: u64,
+}
+
+/// A parent together with the zombies it has not reaped
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ZombieParent {
+    pub pid: u32,
+    pub name: String,
+    pub zombies: Vec<StrayProcess>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ReapReport {
+    /// Ordered by zombie count, most first
+    pub zombies: Vec<ZombieParent>,
+    /// Processes whose parent died, longest-running first
+    pub orphans: Vec<StrayProcess>,
+}
+
+impl ReapReport {
+    pub fn zombie_count(&self) -> usize {
+        self.zombies.iter().map(|parent| parent.zombies.len()).sum()
+    }
+}
+
+/// One process table row as far as reaping is concerned
+struct Row {
+    pid: u32,
+    parent: Option<u32>,
+    name: String,
+    zombie: bool,
+    run_time: u64,
+    /// Not the leader of its own session, i.e. not a daemon that detached on purpose
+    detached: bool,
+}
+
+/// Zombies, and orphans running for at least `min_age`
+pub fn find_strays(min_age: Duration) -> ReapReport {
+    let rows: Vec<Row> = shared().with(|sys| {
+        sys.processes()
+            .iter()
+            .filter(|(_, process)| process.thread_kind().is_none())
+            .map(|(pid, process)| Row {
+                pid: pid.as_u32(),
+                parent: process.parent().map(|p| p.as_u32()),
+                name: process.name().to_string_lossy().into_owned(),
+                zombie: process.status() == ProcessStatus::Zombie,
+                run_time: process.run_time(),
+                detached: detached(pid.as_u32()),
+            })
+            .collect()
+    });
+    collect_strays(&rows, min_age.as_secs())
+}
+
+fn collect_strays(rows: &[Row], min_age: u64) -> ReapReport {
+    let pids: HashSet<u32> = rows.iter().map(|row| row.pid).collect();
+    let name_of = |pid: u32| {
+        rows.iter()
+            .find(|row| row.pid == pid)
+            .map_or_else(|| "?".to_string(), |row| row.name.clone())
+    };
+    let stray = |row: &Row| StrayProcess {
+        pid: row.pid,
+        name: row.name.clone(),
+        run_time: row.run_time,
+    };
+
+    let mut report = ReapReport::default();
+    for row in rows {
+        let Some(parent) = row.parent else {
+            continue;
+        };
+        if row.zombie {
+            match report.zombies.iter_mut().find(|p| p.pid == parent) {
+                Some(group) => group.zombies.push(stray(row)),
+                None => report.zombies.push(ZombieParent {
+                    pid: parent,
+                    name: name_of(parent),
+                    zombies: vec![stray(row)],
+                }),
+            }
+        } else if (parent == INIT_PID || !pids.contains(&parent))
+            && row.detached
+            && row.run_time >= min_age
+        {
+            report.orphans.push(stray(row));
+        }
+    }
+
+    for group in &mut report.zombies {
+        group.zombies.sort_by_key(|z| z.pid);
+    }
+    report.zombies.sort_by(|a, b| {
+        b.zombies
+            .len()
+            .cmp(&a.zombies.len())
+            .then(a.pid.cmp(&b.pid))
+    });
+    report
+        .orphans
+        .sort_by(|a, b| b.run_time.cmp(&a.run_time).then(a.pid.cmp(&b.pid)));
+    report
+}
+
+/// Daemons start their own session; anything else adopted by init lost its parent
+#[cfg(unix)]
+fn detached(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    // SAFETY: getsid only reads the process table
+    let sid = unsafe { libc::getsid(pid) };
+    sid > 0 && sid != pid
+}
+
+/// Windows has no sessions in the Unix sense and never reparents
+#[cfg(windows)]
+fn detached(_pid: u32) -> bool {
+    true
+}
+
+/// Ask a parent to collect its exited children (SIGCHLD)
+#[cfg(unix)]
+pub fn nudge_parent(pid: u32) -> Result<()> {
+    super::native::raw_signal(pid, nix::sys::signal::Signal::SIGCHLD)
+}
+
+#[cfg(windows)]
+pub fn nudge_parent(_pid: u32) -> Result<()> {
+    anyhow::bail!("Windows has no zombie processes to reap")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(pid: u32, parent: u32, zombie: bool, detached: bool) -> Row {
+        Row {
+            pid,
+            parent: Some(parent),
+            name: format!("p{pid}"),
+            zombie,
+            run_time: pid.into(),
+            detached,
+        }
+    }
+
+    #[test]
+    fn test_collect_strays() {
+        let rows = vec![
+            row(1, 0, false, false),
+            row(10, 1, false, false),
+            row(11, 10, true, false),
+            row(12, 10, true, false),
+            row(13, 1, true, false),
+            row(300, 1, false, true),
+            row(400, 99, false, true),
+            row(50, 1, false, true),
+        ];
+
+        let report = collect_strays(&rows, 100);
+        let zombies: Vec<(u32, usize)> = report
+            .zombies
+            .iter()
+            .map(|p| (p.pid, p.zombies.len()))
+            .collect();
+        assert_eq!(zombies, [(10, 2), (1, 1)]);
+        assert_eq!(report.zombies[0].name, "p10");
+        assert_eq!(report.zombie_count(), 3);
+
+        // 10 is a daemon, 50 too young; 400's parent is gone
+        let orphans: Vec<u32> = report.orphans.iter().map(|o| o.pid).collect();
+        assert_eq!(orphans, [400, 300]);
+    }
+}
@@ -0,0 +1,72 @@
+/// Working out why the OS refused to act on a process
+///
+/// A refusal from `kill(2)` or `OpenProcess` only says "permission denied"; the owner of
+/// the process and whether ziro already runs elevated tell the likely reasons apart.
+use crate::platform::elevation::{self, Refusal, Refused};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind, Users};
+
+/// The error for a refused attempt to `action` process `pid` (e.g. "signal"), naming
+/// the reason; the OS error stays in the chain
+pub(super) fn refused(pid: u32, error: std::io::Error, action: &str) -> anyhow::Error {
+    anyhow::Error::new(error).context(Refused {
+        action: format!("Failed to {action} process {pid}"),
+        refusal: diagnose(pid),
+    })
+}
+
+/// Why acting on `pid` is refused
+fn diagnose(pid: u32) -> Refusal {
+    // The Idle and System pseudo-processes cannot be touched by anyone
+    if elevation::is_elevated() || (cfg!(windows) && matches!(pid, 0 | 4)) {
+        return Refusal::Protected;
+    }
+
+    let target = Pid::from_u32(pid);
+    let me = sysinfo::get_current_pid().ok();
+    let pids: Vec<Pid> = std::iter::once(target).chain(me).collect();
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&pids),
+        ProcessRefreshKind::new().with_user(UpdateKind::Always),
+    );
+    let owner_of = |pid: Pid| system.process(pid).and_then(|p| p.user_id()).cloned();
+
+    match (owner_of(target), me.and_then(owner_of)) {
+        (Some(owner), mine) if Some(&owner) != mine.as_ref() => {
+            let id = (*owner).to_string();
+            if is_system_account(&id) {
+                Refusal::System
+            } else {
+                let users = Users::new_with_refreshed_list();
+                Refusal::OtherUser(
+                    users
+                        .get_user_by_id(&owner)
+                        .map_or(id, |user| user.name().to_string()),
+                )
+            }
+        }
+        // Windows hides the owner of processes ziro may not open, which are mostly
+        // services and elevated programs
+        _ => Refusal::NeedsElevation,
+    }
+}
+
+/// root, or the SYSTEM, LOCAL SERVICE and NETWORK SERVICE accounts
+fn is_system_account(id: &str) -> bool {
+    matches!(id, "0" | "S-1-5-18" | "S-1-5-19" | "S-1-5-20")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_system_account() {
+        assert!(is_system_account("0"));
+        assert!(is_system_account("S-1-5-18"));
+        assert!(!is_system_account("1000"));
+        assert!(!is_system_account(
+            "S-1-5-21-1004336348-1177238915-682003330-1001"
+        ));
+    }
+}
@@ -0,0 +1,69 @@
+/// Freezing and thawing processes (`ziro pause` / `ziro resume`)
+use anyhow::Result;
+
+use super::shared;
+
+/// Stop a process from being scheduled until it is resumed
+pub fn pause_process(pid: u32) -> Result<()> {
+    let result = suspend(pid, true);
+    shared().invalidate();
+    result
+}
+
+/// Let a paused process run again
+pub fn resume_process(pid: u32) -> Result<()> {
+    let result = suspend(pid, false);
+    shared().invalidate();
+    result
+}
+
+/// SIGSTOP / SIGCONT
+#[cfg(unix)]
+fn suspend(pid: u32, pause: bool) -> Result<()> {
+    use nix::sys::signal::Signal;
+
+    let signal = if pause {
+        Signal::SIGSTOP
+    } else {
+        Signal::SIGCONT
+    };
+    super::native::raw_signal(pid, signal)
+}
+
+/// NtSuspendProcess / NtResumeProcess, which act on every thread of the process at once
+#[cfg(windows)]
+fn suspend(pid: u32, pause: bool) -> Result<()> {
+    use anyhow::anyhow;
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows_sys::Win32::System::Threading::{OpenProcess, PROCESS_SUSPEND_RESUME};
+
+    #[link(name = "ntdll")]
+    unsafe extern "system" {
+        fn NtSuspendProcess(process: HANDLE) -> i32;
+        fn NtResumeProcess(process: HANDLE) -> i32;
+    }
+
+    // SAFETY: plain Win32 calls on a handle we own and close
+    unsafe {
+        let handle = OpenProcess(PROCESS_SUSPEND_RESUME, 0, pid);
+        if handle.is_null() {
+            return Err(super::native::open_failed(
+                pid,
+                if pause { "pause" } else { "resume" },
+            ));
+        }
+        let status = if pause {
+            NtSuspendProcess(handle)
+        } else {
+            NtResumeProcess(handle)
+        };
+        CloseHandle(handle);
+        if status < 0 {
+            return Err(anyhow!(
+                "Failed to {} process {pid} (NTSTATUS {status:#x})",
+                if pause { "pause" } else { "resume" }
+            ));
+        }
+    }
+    Ok(())
+}
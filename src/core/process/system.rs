@@ -0,0 +1,81 @@
+/// Process table shared by everything that looks up processes by PID
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, RefreshKind, System, UpdateKind};
+
+/// A full refresh older than this is redone before the next lookup
+const MAX_AGE: Duration = Duration::from_millis(500);
+
+/// Lazily refreshed `System` shared across the process and port modules
+///
+/// Building a `System` and scanning every process costs hundreds of milliseconds,
+/// so batch operations reuse one table and only re-check the PIDs they touch.
+pub struct SystemHandle {
+    inner: Mutex<Inner>,
+}
+
+struct Inner {
+    system: System,
+    refreshed: Option<Instant>,
+}
+
+/// The process-wide handle
+pub fn shared() -> &'static SystemHandle {
+    static SHARED: OnceLock<SystemHandle> = OnceLock::new();
+    SHARED.get_or_init(SystemHandle::new)
+}
+
+fn refresh_kind() -> ProcessRefreshKind {
+    ProcessRefreshKind::new()
+        .with_memory()
+        .with_cpu()
+        .with_cmd(UpdateKind::OnlyIfNotSet)
+        .with_exe(UpdateKind::OnlyIfNotSet)
+}
+
+impl SystemHandle {
+    fn new() -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                system: System::new_with_specifics(RefreshKind::new()),
+                refreshed: None,
+            }),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        // A panic while holding the lock leaves the table usable, just possibly stale
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Run `f` against a process table refreshed within the last `MAX_AGE`
+    pub fn with<T>(&self, f: impl FnOnce(&System) -> T) -> T {
+        let mut inner = self.lock();
+        if inner.refreshed.is_none_or(|at| at.elapsed() > MAX_AGE) {
+            inner
+                .system
+                .refresh_processes_specifics(ProcessesToUpdate::All, refresh_kind());
+            inner.refreshed = Some(Instant::now());
+        }
+        f(&inner.system)
+    }
+
+    /// Re-read a single process; returns false once it has exited
+    pub fn refresh_pid(&self, pid: u32) -> bool {
+        let mut inner = self.lock();
+        let updated = inner.system.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&[Pid::from_u32(pid)]),
+            refresh_kind(),
+        );
+        if updated == 0 {
+            // Dead entries are only dropped by a full refresh
+            inner.refreshed = None;
+        }
+        updated > 0
+    }
+
+    /// Force a full refresh on the next lookup (e.g. after spawning or killing processes)
+    pub fn invalidate(&self) {
+        self.lock().refreshed = None;
+    }
+}
@@ -0,0 +1,117 @@
+/// Descendants of a process, for killing whole process trees
+use std::collections::{HashMap, HashSet};
+use sysinfo::System;
+
+use super::shared;
+
+/// A process below another one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChildProcess {
+    pub pid: u32,
+    pub name: String,
+    /// 1 for direct children, 2 for grandchildren, ...
+    pub depth: usize,
+}
+
+/// Every descendant of `pid`, depth-first with siblings in PID order
+pub fn descendants(pid: u32) -> Vec<ChildProcess> {
+    shared().with(ProcessTree::read).descendants(pid)
+}
+
+/// Parent links of every process, as of one process table refresh
+#[derive(Debug, Clone, Default)]
+pub struct ProcessTree {
+    /// (pid, parent, name) rows
+    rows: Vec<(u32, u32, String)>,
+}
+
+impl ProcessTree {
+    pub fn read(sys: &System) -> Self {
+        let rows = sys
+            .processes()
+            .iter()
+            // Threads are listed under their process but die with it
+            .filter(|(_, process)| process.thread_kind().is_none())
+            .filter_map(|(child, process)| {
+                let parent = process.parent()?;
+                Some((
+                    child.as_u32(),
+                    parent.as_u32(),
+                    process.name().to_string_lossy().into_owned(),
+                ))
+            })
+            .collect();
+        Self { rows }
+    }
+
+    /// Every descendant of `pid`, depth-first with siblings in PID order
+    pub fn descendants(&self, pid: u32) -> Vec<ChildProcess> {
+        walk_descendants(pid, &self.rows)
+    }
+}
+
+/// `table` holds (pid, parent, name) rows
+pub(super) fn walk_descendants(root: u32, table: &[(u32, u32, String)]) -> Vec<ChildProcess> {
+    let mut children: HashMap<u32, Vec<(u32, &str)>> = HashMap::new();
+    for (pid, parent, name) in table {
+        if pid != parent {
+            children.entry(*parent).or_default().push((*pid, name));
+        }
+    }
+    for siblings in children.values_mut() {
+        siblings.sort_unstable();
+    }
+
+    let mut found = Vec::new();
+    // PID reuse can make the parent links loop
+    let mut seen: HashSet<u32> = HashSet::from([root]);
+    let mut stack: Vec<(u32, &str, usize)> = children
+        .get(&root)
+        .into_iter()
+        .flatten()
+        .rev()
+        .map(|&(pid, name)| (pid, name, 1))
+        .collect();
+    while let Some((pid, name, depth)) = stack.pop() {
+        if !seen.insert(pid) {
+            continue;
+        }
+        found.push(ChildProcess {
+            pid,
+            name: name.to_string(),
+            depth,
+        });
+        if let Some(kids) = children.get(&pid) {
+            stack.extend(
+                kids.iter()
+                    .rev()
+                    .map(|&(child, name)| (child, name, depth + 1)),
+            );
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_walk_descendants() {
+        let row = |pid: u32, parent: u32| (pid, parent, format!("p{pid}"));
+        let table = vec![
+            row(10, 1),
+            row(12, 10),
+            row(11, 10),
+            row(13, 11),
+            row(20, 1),
+        ];
+
+        let found: Vec<(u32, usize)> = walk_descendants(10, &table)
+            .iter()
+            .map(|c| (c.pid, c.depth))
+            .collect();
+        assert_eq!(found, [(11, 1), (13, 2), (12, 1)]);
+        assert!(walk_descendants(20, &table).is_empty());
+    }
+}
@@ -0,0 +1,190 @@
+use crate::error::ZiroError;
+/// Waiting for processes to exit (`ziro waitpid`)
+use anyhow::{Result, bail};
+use regex::Regex;
+use serde::Serialize;
+use std::thread;
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessStatus, ProcessesToUpdate, System, UpdateKind};
+
+/// How often the watched processes are re-checked
+const POLL: Duration = Duration::from_millis(250);
+
+/// Which processes to wait for and for how long
+pub struct WaitOptions {
+    pub pids: Vec<u32>,
+    /// Also every process whose name or command line matches when the wait starts
+    pub name: Option<Regex>,
+    /// Give up after this long; `None` waits forever
+    pub timeout: Option<Duration>,
+    /// How often `progress` is called while processes remain
+    pub status_interval: Duration,
+}
+
+/// A process being waited for
+#[derive(Debug, Clone, Serialize)]
+pub struct WaitedProcess {
+    pub pid: u32,
+    pub name: String,
+    /// Tells a reused PID apart from the process we started waiting for
+    #[serde(skip)]
+    start_time: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WaitOutcome {
+    /// Processes that exited while waiting, in the order they went
+    pub exited: Vec<WaitedProcess>,
+    /// Processes still running when the timeout hit (empty unless timed out)
+    pub remaining: Vec<WaitedProcess>,
+    pub timed_out: bool,
+    pub elapsed_ms: u128,
+}
+
+impl WaitOutcome {
+    /// An error when the timeout hit with processes still running
+    pub fn ensure_finished(&self) -> Result<()> {
+        if self.timed_out {
+            bail!(crate::tr!(
+                "Timed out with {} process(es) still running",
+                self.remaining.len()
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Block until every selected process has exited or the timeout passes
+///
+/// `progress` gets the elapsed time and the processes still running, once per
+/// `status_interval`. PIDs that do not exist when the wait starts are an error.
+pub fn wait_for_exit(
+    opts: &WaitOptions,
+    mut progress: impl FnMut(Duration, &[WaitedProcess]),
+) -> Result<WaitOutcome> {
+    let refresh = ProcessRefreshKind::new().with_cmd(UpdateKind::OnlyIfNotSet);
+    let mut system = System::new();
+    system.refresh_processes_specifics(ProcessesToUpdate::All, refresh);
+
+    let mut waiting: Vec<WaitedProcess> = Vec::new();
+    for &pid in &opts.pids {
+        match system.process(Pid::from_u32(pid)) {
+            Some(process) if !exited(process.status()) => waiting.push(WaitedProcess {
+                pid,
+                name: process.name().to_string_lossy().into_owned(),
+                start_time: process.start_time(),
+            }),
+            _ => bail!(ZiroError::NotFound(format!("No process with PID {pid}"))),
+        }
+    }
+    if let Some(name) = &opts.name {
+        let own_pid = sysinfo::get_current_pid().ok();
+        for (pid, process) in system.processes() {
+            let cmd = process
+                .cmd()
+                .iter()
+                .map(|s| s.to_string_lossy())
+                .collect::<Vec<_>>()
+                .join(" ");
+            let matches = name.is_match(&process.name().to_string_lossy()) || name.is_match(&cmd);
+            if matches
+                && Some(*pid) != own_pid
+                && process.thread_kind().is_none()
+                && !exited(process.status())
+                && !waiting.iter().any(|w| w.pid == pid.as_u32())
+            {
+                waiting.push(WaitedProcess {
+                    pid: pid.as_u32(),
+                    name: process.name().to_string_lossy().into_owned(),
+                    start_time: process.start_time(),
+                });
+            }
+        }
+    }
+    waiting.sort_by_key(|w| w.pid);
+
+    let start = Instant::now();
+    let mut exited_list = Vec::new();
+    let mut last_status = start;
+    loop {
+        let pids: Vec<Pid> = waiting.iter().map(|w| Pid::from_u32(w.pid)).collect();
+        system.refresh_processes_specifics(ProcessesToUpdate::Some(&pids), refresh);
+        let (gone, alive): (Vec<_>, Vec<_>) = waiting.into_iter().partition(|w| {
+            system
+                .process(Pid::from_u32(w.pid))
+                .is_none_or(|p| p.start_time() != w.start_time || exited(p.status()))
+        });
+        exited_list.extend(gone);
+        waiting = alive;
+
+        let elapsed = start.elapsed();
+        if waiting.is_empty() {
+            break;
+        }
+        if opts.timeout.is_some_and(|timeout| elapsed >= timeout) {
+            return Ok(WaitOutcome {
+                exited: exited_list,
+                remaining: waiting,
+                timed_out: true,
+                elapsed_ms: elapsed.as_millis(),
+            });
+        }
+        if last_status.elapsed() >= opts.status_interval {
+            progress(elapsed, &waiting);
+            last_status = Instant::now();
+        }
+        thread::sleep(POLL);
+    }
+
+    Ok(WaitOutcome {
+        exited: exited_list,
+        remaining: Vec::new(),
+        timed_out: false,
+        elapsed_ms: start.elapsed().as_millis(),
+    })
+}
+
+/// Zombies have exited; only their parent has not collected them yet
+fn exited(status: ProcessStatus) -> bool {
+    matches!(status, ProcessStatus::Zombie | ProcessStatus::Dead)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    fn wait_on(pid: u32, timeout: Duration) -> WaitOutcome {
+        let opts = WaitOptions {
+            pids: vec![pid],
+            name: None,
+            timeout: Some(timeout),
+            status_interval: Duration::from_secs(60),
+        };
+        wait_for_exit(&opts, |_, _| {}).unwrap()
+    }
+
+    #[test]
+    fn test_wait_for_exited_child() {
+        let mut child = Command::new("sleep").arg("0.3").spawn().unwrap();
+        // Its zombie counts as gone before it is collected
+        let outcome = wait_on(child.id(), Duration::from_secs(10));
+        child.wait().unwrap();
+        assert!(!outcome.timed_out);
+        assert_eq!(outcome.exited.len(), 1);
+        assert_eq!(outcome.exited[0].pid, child.id());
+        assert!(outcome.ensure_finished().is_ok());
+    }
+
+    #[test]
+    fn test_wait_timeout_is_an_error() {
+        let mut child = Command::new("sleep").arg("30").spawn().unwrap();
+        let outcome = wait_on(child.id(), Duration::from_millis(300));
+        child.kill().unwrap();
+        child.wait().unwrap();
+        assert!(outcome.timed_out);
+        assert!(outcome.exited.is_empty());
+        assert_eq!(outcome.remaining[0].pid, child.id());
+        assert!(outcome.ensure_finished().is_err());
+    }
+}
@@ -0,0 +1,442 @@
+/// A small static file server for sharing a directory on the LAN (`ziro serve`)
+///
+/// GET and HEAD only, one thread per connection and no keep-alive. Request paths are
+/// confined to the served directory: `..` is refused, and so are symlinks leading out of it.
+use crate::core::port;
+use crate::error::ZiroError;
+use anyhow::{Context, Result, anyhow};
+use signal_hook::consts::TERM_SIGNALS;
+use signal_hook::flag;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+/// First port tried when none is given
+pub const DEFAULT_PORT: u16 = 8000;
+/// How often the accept loop checks for Ctrl+C
+const POLL: Duration = Duration::from_millis(100);
+/// How long a client may take to send its request
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+/// Longest request head read; anything after it is ignored
+const MAX_HEAD: u64 = 16 * 1024;
+
+/// What to serve, and where
+pub struct ServeOptions {
+    pub root: PathBuf,
+    /// Exact port; without it the first free one from `DEFAULT_PORT` up
+    pub port: Option<u16>,
+}
+
+/// One answered request, for the access log
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub peer: SocketAddr,
+    pub method: String,
+    pub path: String,
+    pub status: u16,
+}
+
+/// A bound server, not yet answering
+pub struct Server {
+    root: PathBuf,
+    listener: TcpListener,
+}
+
+impl Server {
+    /// Bind on every interface; a taken `--port` is reported with the process holding it
+    pub fn bind(opts: &ServeOptions) -> Result<Self> {
+        let root = fs::canonicalize(&opts.root)
+            .with_context(|| format!("Cannot serve {}", opts.root.display()))?;
+        if !root.is_dir() {
+            return Err(ZiroError::Usage(format!("{} is not a directory", root.display())).into());
+        }
+        let any = IpAddr::V4(Ipv4Addr::UNSPECIFIED);
+        let listener = match opts.port {
+            Some(port) => TcpListener::bind((any, port)).map_err(|e| port_taken(port, e))?,
+            None => port::bind_free_port(any, DEFAULT_PORT)?,
+        };
+        Ok(Self { root, listener })
+    }
+
+    pub fn port(&self) -> u16 {
+        self.listener.local_addr().map_or(0, |addr| addr.port())
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Where the server can be reached: localhost, then each LAN IPv4 address
+    pub fn urls(&self) -> Vec<String> {
+        let mut ips: Vec<IpAddr> = sysinfo::Networks::new_with_refreshed_list()
+            .values()
+            .flat_map(|network| network.ip_networks().iter().map(|net| net.addr))
+            .filter(|ip| match ip {
+                IpAddr::V4(v4) => !v4.is_loopback() && !v4.is_link_local(),
+                IpAddr::V6(_) => false,
+            })
+            .collect();
+        ips.sort_unstable();
+        ips.dedup();
+        std::iter::once("localhost".to_string())
+            .chain(ips.iter().map(IpAddr::to_string))
+            .map(|host| format!("http://{host}:{}/", self.port()))
+            .collect()
+    }
+
+    /// Answer requests until Ctrl+C; the listener is dropped on return, freeing the port
+    pub fn run(self, log: impl Fn(&Request) + Send + Sync + 'static) -> Result<()> {
+        let stop = Arc::new(AtomicBool::new(false));
+        for &signal in TERM_SIGNALS {
+            // A second Ctrl+C exits at once, even with a download in flight
+            let _ = flag::register_conditional_shutdown(signal, 130, Arc::clone(&stop));
+            let _ = flag::register(signal, Arc::clone(&stop));
+        }
+        self.listener.set_nonblocking(true)?;
+        let root: Arc<Path> = self.root.into();
+        let log = Arc::new(log);
+
+        while !stop.load(Ordering::SeqCst) {
+            match self.listener.accept() {
+                Ok((stream, peer)) => {
+                    let (root, log) = (Arc::clone(&root), Arc::clone(&log));
+                    thread::spawn(move || {
+                        if let Ok(Some(request)) = answer(stream, peer, &root) {
+                            log(&request);
+                        }
+                    });
+                }
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => thread::sleep(POLL),
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The bind error for an explicit port, naming its holder when it can be found
+fn port_taken(port: u16, error: io::Error) -> anyhow::Error {
+    if error.kind() != io::ErrorKind::AddrInUse {
+        return anyhow!("Cannot listen on port {port}: {error}");
+    }
+    let holder = port::find_processes_by_ports(&[port])
+        .ok()
+        .and_then(|found| found.into_iter().next())
+        .map(|info| format!(" by {} (PID {})", info.process.name, info.process.pid))
+        .unwrap_or_default();
+    ZiroError::Usage(format!(
+        "Port {port} is already in use{holder}; leave out --port to pick a free one"
+    ))
+    .into()
+}
+
+/// Read one request and answer it; `None` when the client sent no usable request
+fn answer(stream: TcpStream, peer: SocketAddr, root: &Path) -> io::Result<Option<Request>> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?.take(MAX_HEAD));
+    let Some((method, target)) = read_head(&mut reader)? else {
+        return Ok(None);
+    };
+    let mut response = Response {
+        stream,
+        head_only: method == "HEAD",
+    };
+    let status = match method.as_str() {
+        "GET" | "HEAD" => serve_target(&mut response, root, &target)?,
+        _ => response.send(
+            405,
+            "text/plain",
+            &[("Allow", "GET, HEAD")],
+            b"Method not allowed\n",
+        )?,
+    };
+    Ok(Some(Request {
+        peer,
+        method,
+        path: target,
+        status,
+    }))
+}
+
+/// Method and target of the request line; the headers are read and ignored
+fn read_head(reader: &mut impl BufRead) -> io::Result<Option<(String, String)>> {
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 || line.trim_end().is_empty() {
+            break;
+        }
+    }
+    let mut parts = request_line.split_whitespace();
+    Ok(match (parts.next(), parts.next()) {
+        (Some(method), Some(target)) => Some((method.to_string(), target.to_string())),
+        _ => None,
+    })
+}
+
+fn serve_target(response: &mut Response, root: &Path, target: &str) -> io::Result<u16> {
+    let path = target.split(['?', '#']).next().unwrap_or("/");
+    let Some(file) = percent_decode(path).and_then(|decoded| resolve(root, &decoded)) else {
+        return response.send(404, "text/plain", &[], b"Not found\n");
+    };
+
+    if file.is_dir() {
+        // Relative links in the page need the trailing slash
+        if !path.ends_with('/') {
+            return response.send(301, "text/plain", &[("Location", &format!("{path}/"))], b"");
+        }
+        let index = file.join("index.html");
+        if !index.is_file() {
+            let page = listing(&file, path)?;
+            return response.send(200, "text/html; charset=utf-8", &[], page.as_bytes());
+        }
+        return response.send_file(&index);
+    }
+    response.send_file(&file)
+}
+
+/// The file under `root` a decoded request path names, if it stays inside `root`
+fn resolve(root: &Path, path: &str) -> Option<PathBuf> {
+    let mut resolved = root.to_path_buf();
+    for segment in path.split('/').filter(|s| !s.is_empty() && *s != ".") {
+        // One plain name per segment: no `..`, drive letters or backslash-separated parts
+        let mut components = Path::new(segment).components();
+        match (components.next(), components.next()) {
+            (Some(Component::Normal(name)), None) => resolved.push(name),
+            _ => return None,
+        }
+    }
+    let resolved = fs::canonicalize(resolved).ok()?;
+    resolved.starts_with(root).then_some(resolved)
+}
+
+/// `%20`-style escapes decoded; `None` for broken escapes or non-UTF-8 results
+fn percent_decode(path: &str) -> Option<String> {
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+/// A name escaped for use in a URL path
+fn percent_encode(name: &str) -> String {
+    name.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// An HTML index of `dir`: directories first, then files, each alphabetical
+fn listing(dir: &Path, path: &str) -> io::Result<String> {
+    let mut entries: Vec<(bool, String)> = fs::read_dir(dir)?
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let is_dir = entry.path().is_dir();
+            Some((!is_dir, entry.file_name().to_string_lossy().into_owned()))
+        })
+        .collect();
+    entries.sort_by_key(|(is_file, name)| (*is_file, name.to_lowercase()));
+
+    let title = html_escape(&percent_decode(path).unwrap_or_else(|| path.to_string()));
+    let mut page = format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{title}</title></head>\n\
+         <body><h1>{title}</h1>\n<ul>\n"
+    );
+    if path != "/" {
+        page.push_str("<li><a href=\"../\">../</a></li>\n");
+    }
+    for (is_file, name) in entries {
+        let slash = if is_file { "" } else { "/" };
+        page.push_str(&format!(
+            "<li><a href=\"{}{slash}\">{}{slash}</a></li>\n",
+            percent_encode(&name),
+            html_escape(&name)
+        ));
+    }
+    page.push_str("</ul></body></html>\n");
+    Ok(page)
+}
+
+/// Content type by file extension, for what a browser shows rather than downloads
+fn content_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "html" | "htm" => "text/html; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "js" | "mjs" => "text/javascript; charset=utf-8",
+        "json" => "application/json",
+        "txt" | "md" | "log" | "csv" => "text/plain; charset=utf-8",
+        "xml" => "application/xml",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "ico" => "image/x-icon",
+        "pdf" => "application/pdf",
+        "wasm" => "application/wasm",
+        "mp3" => "audio/mpeg",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        _ => "application/octet-stream",
+    }
+}
+
+struct Response {
+    stream: TcpStream,
+    /// HEAD: the headers of a GET, without the body
+    head_only: bool,
+}
+
+impl Response {
+    fn send(
+        &mut self,
+        status: u16,
+        content_type: &str,
+        headers: &[(&str, &str)],
+        body: &[u8],
+    ) -> io::Result<u16> {
+        self.write_head(status, content_type, headers, body.len() as u64)?;
+        if !self.head_only {
+            self.stream.write_all(body)?;
+        }
+        Ok(status)
+    }
+
+    fn send_file(&mut self, path: &Path) -> io::Result<u16> {
+        let Ok(mut file) = File::open(path) else {
+            return self.send(403, "text/plain", &[], b"Forbidden\n");
+        };
+        let length = file.metadata()?.len();
+        self.write_head(200, content_type(path), &[], length)?;
+        if !self.head_only {
+            io::copy(&mut file, &mut self.stream)?;
+        }
+        Ok(200)
+    }
+
+    fn write_head(
+        &mut self,
+        status: u16,
+        content_type: &str,
+        headers: &[(&str, &str)],
+        length: u64,
+    ) -> io::Result<()> {
+        let reason = match status {
+            200 => "OK",
+            301 => "Moved Permanently",
+            403 => "Forbidden",
+            404 => "Not Found",
+            _ => "Method Not Allowed",
+        };
+        let mut head = format!(
+            "HTTP/1.1 {status} {reason}\r\nContent-Type: {content_type}\r\n\
+             Content-Length: {length}\r\nConnection: close\r\n"
+        );
+        for (name, value) in headers {
+            head.push_str(&format!("{name}: {value}\r\n"));
+        }
+        head.push_str("\r\n");
+        self.stream.write_all(head.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percent_coding() {
+        assert_eq!(percent_decode("/a%20b/%E4%BD%A0").unwrap(), "/a b/你");
+        assert!(percent_decode("/bad%2").is_none());
+        assert!(percent_decode("/bad%zz").is_none());
+        assert_eq!(percent_encode("a b#你"), "a%20b%23%E4%BD%A0");
+    }
+
+    #[test]
+    fn test_resolve_stays_inside_root() {
+        let dir = std::env::temp_dir().join(format!("ziro-serve-{}", std::process::id()));
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::write(dir.join("sub/file.txt"), "hi").unwrap();
+        let root = fs::canonicalize(&dir).unwrap();
+
+        assert_eq!(
+            resolve(&root, "/sub/./file.txt").unwrap(),
+            root.join("sub").join("file.txt")
+        );
+        assert_eq!(resolve(&root, "/").unwrap(), root);
+        assert!(resolve(&root, "/sub/../../etc/passwd").is_none());
+        assert!(resolve(&root, "/missing").is_none());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_answer_request() {
+        let dir = std::env::temp_dir().join(format!("ziro-serve-http-{}", std::process::id()));
+        fs::create_dir_all(dir.join("docs")).unwrap();
+        fs::write(dir.join("docs/a b.txt"), "hello").unwrap();
+        let root = fs::canonicalize(&dir).unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let fetch = |request: &str| {
+            let mut client = TcpStream::connect(addr).unwrap();
+            client.write_all(request.as_bytes()).unwrap();
+            let (stream, peer) = listener.accept().unwrap();
+            let answered = answer(stream, peer, &root).unwrap().unwrap();
+            let mut reply = String::new();
+            client.read_to_string(&mut reply).unwrap();
+            (answered.status, reply)
+        };
+
+        let (status, reply) = fetch("GET /docs/a%20b.txt HTTP/1.1\r\nHost: x\r\n\r\n");
+        assert_eq!(status, 200);
+        assert!(reply.contains("Content-Type: text/plain; charset=utf-8"));
+        assert!(reply.ends_with("\r\n\r\nhello"));
+
+        let (status, reply) = fetch("GET /docs HTTP/1.1\r\n\r\n");
+        assert_eq!(status, 301);
+        assert!(reply.contains("Location: /docs/\r\n"));
+
+        let (status, reply) = fetch("GET /docs/ HTTP/1.1\r\n\r\n");
+        assert_eq!(status, 200);
+        assert!(reply.contains("<a href=\"a%20b.txt\">a b.txt</a>"));
+
+        assert_eq!(fetch("GET /../x HTTP/1.1\r\n\r\n").0, 404);
+        assert_eq!(fetch("POST / HTTP/1.1\r\n\r\n").0, 405);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
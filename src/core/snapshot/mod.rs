@@ -0,0 +1,228 @@
+/// Process table snapshots for before/after comparisons (`ziro snapshot`)
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use sysinfo::{ProcessRefreshKind, RefreshKind, System, UpdateKind, Users};
+
+const FORMAT: &str = "ziro-snapshot";
+const VERSION: u32 = 1;
+
+/// The process table at one point in time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    format: String,
+    version: u32,
+    /// Unix time (seconds) the snapshot was taken
+    pub taken_at: u64,
+    pub processes: Vec<ProcessEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessEntry {
+    pub pid: u32,
+    pub name: String,
+    pub cmd: String,
+    pub memory_bytes: u64,
+    pub user: Option<String>,
+    /// Unix time (seconds) the process started; tells a reused PID apart
+    pub start_time: u64,
+    pub parent: Option<u32>,
+}
+
+/// A process present in both snapshots whose memory changed
+#[derive(Debug, Clone, Serialize)]
+pub struct MemoryChange {
+    /// The process as of the newer snapshot
+    pub process: ProcessEntry,
+    pub before_bytes: u64,
+    pub delta_bytes: i64,
+}
+
+/// What changed between two snapshots
+#[derive(Debug, Clone, Serialize)]
+pub struct SnapshotDiff {
+    pub before_taken_at: u64,
+    pub after_taken_at: u64,
+    /// Processes only in the newer snapshot, largest first
+    pub started: Vec<ProcessEntry>,
+    /// Processes only in the older snapshot, largest first
+    pub exited: Vec<ProcessEntry>,
+    /// Processes whose memory moved by at least the threshold, biggest change first
+    pub changed: Vec<MemoryChange>,
+    pub total_before_bytes: u64,
+    pub total_after_bytes: u64,
+}
+
+impl Snapshot {
+    /// Read the live process table
+    pub fn capture() -> Self {
+        let refresh = ProcessRefreshKind::new()
+            .with_memory()
+            .with_cmd(UpdateKind::Always)
+            .with_user(UpdateKind::Always);
+        let system = System::new_with_specifics(RefreshKind::new().with_processes(refresh));
+        let users = Users::new_with_refreshed_list();
+        let own_pid = sysinfo::get_current_pid().ok();
+
+        let mut processes: Vec<ProcessEntry> = system
+            .processes()
+            .iter()
+            // Threads repeat their process's memory, and ziro itself is just noise
+            .filter(|(pid, process)| process.thread_kind().is_none() && Some(**pid) != own_pid)
+            .map(|(pid, process)| ProcessEntry {
+                pid: pid.as_u32(),
+                name: process.name().to_string_lossy().into_owned(),
+                cmd: process
+                    .cmd()
+                    .iter()
+                    .map(|s| s.to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                memory_bytes: process.memory(),
+                user: process.user_id().map(|uid| {
+                    users
+                        .get_user_by_id(uid)
+                        .map(|user| user.name().to_string())
+                        .unwrap_or_else(|| uid.to_string())
+                }),
+                start_time: process.start_time(),
+                parent: process.parent().map(|p| p.as_u32()),
+            })
+            .collect();
+        processes.sort_by_key(|p| p.pid);
+
+        Self {
+            format: FORMAT.to_string(),
+            version: VERSION,
+            taken_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            processes,
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let file = File::create(path)
+            .with_context(|| format!("Failed to create snapshot: {}", path.display()))?;
+        let mut out = BufWriter::new(file);
+        serde_json::to_writer(&mut out, self)?;
+        writeln!(out)?;
+        out.flush()
+            .with_context(|| format!("Failed to write snapshot: {}", path.display()))
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let file = File::open(path)
+            .with_context(|| format!("Failed to open snapshot: {}", path.display()))?;
+        let snapshot: Self = serde_json::from_reader(BufReader::new(file))
+            .with_context(|| format!("Not a ziro snapshot: {}", path.display()))?;
+        if snapshot.format != FORMAT || snapshot.version != VERSION {
+            bail!(
+                "Unsupported snapshot {} ({} v{})",
+                path.display(),
+                snapshot.format,
+                snapshot.version
+            );
+        }
+        Ok(snapshot)
+    }
+
+    /// Compare against a newer snapshot; memory changes smaller than `min_delta` are left out
+    pub fn diff(&self, after: &Snapshot, min_delta: u64) -> SnapshotDiff {
+        // PIDs get reused, so a process is only the same one if it also started at the same time
+        let key = |p: &ProcessEntry| (p.pid, p.start_time);
+        let before: HashMap<(u32, u64), &ProcessEntry> =
+            self.processes.iter().map(|p| (key(p), p)).collect();
+        let after_keys: HashSet<(u32, u64)> = after.processes.iter().map(key).collect();
+
+        let mut started = Vec::new();
+        let mut changed = Vec::new();
+        for process in &after.processes {
+            match before.get(&key(process)) {
+                None => started.push(process.clone()),
+                Some(old) => {
+                    let delta = process.memory_bytes as i64 - old.memory_bytes as i64;
+                    if delta != 0 && delta.unsigned_abs() >= min_delta {
+                        changed.push(MemoryChange {
+                            process: process.clone(),
+                            before_bytes: old.memory_bytes,
+                            delta_bytes: delta,
+                        });
+                    }
+                }
+            }
+        }
+        let mut exited: Vec<ProcessEntry> = self
+            .processes
+            .iter()
+            .filter(|p| !after_keys.contains(&key(p)))
+            .cloned()
+            .collect();
+
+        started.sort_by_key(|p| Reverse(p.memory_bytes));
+        exited.sort_by_key(|p| Reverse(p.memory_bytes));
+        changed.sort_by_key(|c| Reverse(c.delta_bytes.unsigned_abs()));
+
+        SnapshotDiff {
+            before_taken_at: self.taken_at,
+            after_taken_at: after.taken_at,
+            started,
+            exited,
+            changed,
+            total_before_bytes: self.processes.iter().map(|p| p.memory_bytes).sum(),
+            total_after_bytes: after.processes.iter().map(|p| p.memory_bytes).sum(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(pid: u32, start_time: u64, memory_bytes: u64) -> ProcessEntry {
+        ProcessEntry {
+            pid,
+            name: format!("p{pid}"),
+            cmd: String::new(),
+            memory_bytes,
+            user: None,
+            start_time,
+            parent: None,
+        }
+    }
+
+    fn snapshot(processes: Vec<ProcessEntry>) -> Snapshot {
+        Snapshot {
+            format: FORMAT.to_string(),
+            version: VERSION,
+            taken_at: 0,
+            processes,
+        }
+    }
+
+    #[test]
+    fn test_diff() {
+        let before = snapshot(vec![entry(1, 10, 100), entry(2, 10, 200), entry(3, 10, 50)]);
+        // PID 3 was reused by a new process
+        let after = snapshot(vec![entry(1, 10, 150), entry(3, 20, 70), entry(4, 30, 10)]);
+
+        let diff = before.diff(&after, 0);
+        let pids = |entries: &[ProcessEntry]| entries.iter().map(|p| p.pid).collect::<Vec<_>>();
+        assert_eq!(pids(&diff.started), [3, 4]);
+        assert_eq!(pids(&diff.exited), [2, 3]);
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].delta_bytes, 50);
+        assert_eq!(
+            (diff.total_before_bytes, diff.total_after_bytes),
+            (350, 230)
+        );
+
+        assert!(before.diff(&after, 64).changed.is_empty());
+    }
+}
@@ -0,0 +1,239 @@
+/// Threshold watchdog for `top --alert-mem` / `--alert-cpu`
+use super::ProcessView;
+use crate::platform::notify;
+use std::cmp::Reverse;
+use std::collections::HashSet;
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+use std::thread;
+
+/// Memory limit of `--alert-mem`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MemoryThreshold {
+    Bytes(u64),
+    /// Share of total memory, 0-100
+    Percent(f64),
+}
+
+/// What counts as an alert and what happens when a process crosses it
+#[derive(Debug, Clone, Default)]
+pub struct AlertOptions {
+    pub memory: Option<MemoryThreshold>,
+    /// CPU usage in percent (may exceed 100 on multi-core systems)
+    pub cpu: Option<f32>,
+    /// Ring the terminal bell
+    pub beep: bool,
+    /// Send a desktop notification
+    pub notify: bool,
+    /// Shell command to run, with `ZIRO_ALERT_*` variables describing the process
+    pub on_alert: Option<String>,
+}
+
+impl AlertOptions {
+    pub fn is_enabled(&self) -> bool {
+        self.memory.is_some() || self.cpu.is_some()
+    }
+}
+
+/// Parse `--alert-mem`: a percentage (`80%`) or a size (`512M`, `2G`, `1048576`)
+pub fn parse_memory_threshold(s: &str) -> Result<MemoryThreshold, String> {
+    let s = s.trim();
+    if let Some(pct) = s.strip_suffix('%') {
+        let pct: f64 = pct
+            .trim()
+            .parse()
+            .map_err(|_| format!("invalid percentage: {s}"))?;
+        if !(0.0..=100.0).contains(&pct) {
+            return Err(format!("percentage out of range: {s}"));
+        }
+        return Ok(MemoryThreshold::Percent(pct));
+    }
+    parse_size(s).map(MemoryThreshold::Bytes)
+}
+
+/// Parse a byte size with an optional binary suffix (`K`, `M`, `G`, `T`, `B` optional)
+pub fn parse_size(s: &str) -> Result<u64, String> {
+    let upper = s.trim().to_ascii_uppercase();
+    let digits = upper.strip_suffix('B').unwrap_or(&upper);
+    let (number, unit) = match digits.char_indices().last() {
+        Some((i, 'K')) => (&digits[..i], 1u64 << 10),
+        Some((i, 'M')) => (&digits[..i], 1 << 20),
+        Some((i, 'G')) => (&digits[..i], 1 << 30),
+        Some((i, 'T')) => (&digits[..i], 1 << 40),
+        _ => (digits, 1),
+    };
+    let value: f64 = number
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid size: {s}"))?;
+    if !value.is_finite() || value < 0.0 {
+        return Err(format!("invalid size: {s}"));
+    }
+    Ok((value * unit as f64) as u64)
+}
+
+pub(super) struct Watchdog {
+    opts: AlertOptions,
+    /// PIDs over a threshold at the last check; alerts fire on crossing, not every refresh
+    active: HashSet<u32>,
+    /// Processes that crossed a threshold at the last check, biggest first
+    crossed: Vec<Crossing>,
+}
+
+pub(super) struct Crossing {
+    pub(super) pid: u32,
+    pub(super) name: String,
+    pub(super) reason: String,
+    memory: u64,
+}
+
+impl Watchdog {
+    pub(super) fn new(opts: AlertOptions) -> Self {
+        Self {
+            opts,
+            active: HashSet::new(),
+            crossed: Vec::new(),
+        }
+    }
+
+    pub(super) fn crossed(&self) -> &[Crossing] {
+        &self.crossed
+    }
+
+    fn memory_reason(&self, process: &ProcessView) -> Option<String> {
+        let memory_over = match self.opts.memory? {
+            MemoryThreshold::Bytes(limit) => process.memory_bytes >= limit,
+            MemoryThreshold::Percent(limit) => process.memory_percent >= limit,
+        };
+        if memory_over {
+            let mb = process.memory_bytes as f64 / (1 << 20) as f64;
+            return Some(format!("memory {mb:.1} MB"));
+        }
+        None
+    }
+
+    fn cpu_reason(&self, process: &ProcessView) -> Option<String> {
+        let limit = self.opts.cpu?;
+        (process.cpu >= limit).then(|| format!("cpu {:.1}%", process.cpu))
+    }
+
+    /// Flag offending rows; with `fire`, run the alert actions for processes that just
+    /// crossed a threshold and return a summary of them
+    pub(super) fn check(&mut self, processes: &mut [ProcessView], fire: bool) -> Option<String> {
+        let mut active = HashSet::new();
+        let mut crossed = Vec::new();
+        for process in processes.iter_mut() {
+            // Threads repeat their process's memory and CPU
+            if process.is_thread {
+                continue;
+            }
+            let reasons: Vec<String> = [self.memory_reason(process), self.cpu_reason(process)]
+                .into_iter()
+                .flatten()
+                .collect();
+            process.alert = !reasons.is_empty();
+            if !process.alert {
+                continue;
+            }
+            active.insert(process.pid);
+            if !self.active.contains(&process.pid) {
+                crossed.push(Crossing {
+                    pid: process.pid,
+                    name: process.name.clone(),
+                    reason: reasons.join(", "),
+                    memory: process.memory_bytes,
+                });
+            }
+        }
+        self.active = active;
+        // Lead with the biggest offender
+        crossed.sort_by_key(|crossing| Reverse(crossing.memory));
+        self.crossed = crossed;
+
+        if !fire || self.crossed.is_empty() {
+            return None;
+        }
+        for crossing in &self.crossed {
+            self.fire(crossing.pid, &crossing.name, &crossing.reason);
+        }
+        if self.opts.beep {
+            let mut stdout = io::stdout();
+            let _ = write!(stdout, "\x07");
+            let _ = stdout.flush();
+        }
+
+        let Crossing {
+            pid, name, reason, ..
+        } = &self.crossed[0];
+        let mut summary = format!("Alert: {name} ({pid}) {reason}");
+        if self.crossed.len() > 1 {
+            summary.push_str(&format!(" and {} more", self.crossed.len() - 1));
+        }
+        Some(summary)
+    }
+
+    fn fire(&self, pid: u32, name: &str, reason: &str) {
+        if self.opts.notify {
+            notify::desktop_notify(
+                "ziro top alert",
+                &format!("{name} (PID {pid}) is over the limit: {reason}"),
+            );
+        }
+        if let Some(command) = &self.opts.on_alert {
+            run_hook(command, pid, name, reason);
+        }
+    }
+}
+
+/// Run the `--on-alert` command in the background; its output would garble the view
+fn run_hook(command: &str, pid: u32, name: &str, reason: &str) {
+    let mut shell = if cfg!(target_os = "windows") {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    let spawned = shell
+        .arg(command)
+        .env("ZIRO_ALERT_PID", pid.to_string())
+        .env("ZIRO_ALERT_NAME", name)
+        .env("ZIRO_ALERT_REASON", reason)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    if let Ok(mut child) = spawned {
+        // Reap in the background so long sessions don't collect zombies
+        thread::spawn(move || child.wait());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_memory_threshold() {
+        assert_eq!(
+            parse_memory_threshold("25%"),
+            Ok(MemoryThreshold::Percent(25.0))
+        );
+        assert_eq!(
+            parse_memory_threshold("512M"),
+            Ok(MemoryThreshold::Bytes(512 << 20))
+        );
+        assert_eq!(
+            parse_memory_threshold("1.5gb"),
+            Ok(MemoryThreshold::Bytes(3 << 29))
+        );
+        assert_eq!(
+            parse_memory_threshold("4096"),
+            Ok(MemoryThreshold::Bytes(4096))
+        );
+        assert!(parse_memory_threshold("120%").is_err());
+        assert!(parse_memory_threshold("lots").is_err());
+    }
+}
@@ -0,0 +1,131 @@
+/// Control group lookups for `top --containers` / `--cgroup` (Linux)
+use super::ProcessView;
+use std::collections::HashMap;
+
+/// Cgroup path of `pid`, e.g. `/system.slice/docker-4f1c….scope`
+#[cfg(target_os = "linux")]
+pub(super) fn cgroup_of(pid: u32) -> Option<String> {
+    let content = std::fs::read_to_string(format!("/proc/{pid}/cgroup")).ok()?;
+    parse_proc_cgroup(&content)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(super) fn cgroup_of(_pid: u32) -> Option<String> {
+    None
+}
+
+/// Pick the path out of `/proc/<pid>/cgroup`: the unified (v2) hierarchy, or the
+/// memory controller's on v1 hosts
+#[cfg(any(target_os = "linux", test))]
+fn parse_proc_cgroup(content: &str) -> Option<String> {
+    let entries: Vec<(&str, &str)> = content
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, ':');
+            let _id = fields.next()?;
+            Some((fields.next()?, fields.next()?))
+        })
+        .collect();
+    entries
+        .iter()
+        .find(|(controllers, _)| controllers.split(',').any(|c| c == "memory"))
+        .or_else(|| {
+            entries
+                .iter()
+                .find(|(controllers, _)| controllers.is_empty())
+        })
+        .map(|(_, path)| path.to_string())
+}
+
+/// Memory limit of the cgroup at `path`; `None` when unlimited or unknown
+#[cfg(target_os = "linux")]
+fn memory_limit(path: &str) -> Option<u64> {
+    let read = |file: String| {
+        std::fs::read_to_string(file)
+            .ok()
+            .and_then(|s| s.trim().parse::<u64>().ok())
+    };
+    // v2 writes "max" when unlimited, which fails to parse
+    read(format!("/sys/fs/cgroup{path}/memory.max"))
+        // v1 reports "unlimited" as a huge page-aligned number
+        .or_else(|| read(format!("/sys/fs/cgroup/memory{path}/memory.limit_in_bytes")))
+        .filter(|&limit| limit < 1 << 60)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn memory_limit(_path: &str) -> Option<u64> {
+    None
+}
+
+/// Turn `memory_percent` into a share of each process's cgroup memory limit;
+/// processes in unlimited cgroups keep their share of host RAM
+pub(super) fn relative_to_limits(processes: &mut [ProcessView]) {
+    let mut limits: HashMap<String, Option<u64>> = HashMap::new();
+    for process in processes {
+        let Some(path) = &process.cgroup else {
+            continue;
+        };
+        let limit = *limits
+            .entry(path.clone())
+            .or_insert_with(|| memory_limit(path));
+        if let Some(limit) = limit.filter(|&limit| limit > 0) {
+            process.memory_percent = process.memory_bytes as f64 / limit as f64 * 100.0;
+        }
+    }
+}
+
+/// Short name of a cgroup: the container ID for container runtimes, the last
+/// path component otherwise
+pub fn container_label(path: &str) -> String {
+    let last = path
+        .rsplit('/')
+        .find(|part| !part.is_empty())
+        .unwrap_or("/");
+    let unit = last.strip_suffix(".scope").unwrap_or(last);
+    let id = ["docker-", "cri-containerd-", "crio-", "libpod-"]
+        .iter()
+        .find_map(|prefix| unit.strip_prefix(prefix))
+        .unwrap_or(unit);
+    if id.len() >= 32 && id.chars().all(|c| c.is_ascii_hexdigit()) {
+        id[..12].to_string()
+    } else {
+        last.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_proc_cgroup() {
+        assert_eq!(
+            parse_proc_cgroup("0::/user.slice/user-1000.slice/session-2.scope\n"),
+            Some("/user.slice/user-1000.slice/session-2.scope".to_string())
+        );
+        let v1 = "12:cpu,cpuacct:/docker/abc\n4:memory:/docker/abc\n0::/\n";
+        assert_eq!(parse_proc_cgroup(v1), Some("/docker/abc".to_string()));
+        assert_eq!(parse_proc_cgroup(""), None);
+    }
+
+    #[test]
+    fn test_container_label() {
+        let id = "4f1c0a9e2b7d".repeat(6);
+        assert_eq!(
+            container_label(&format!("/system.slice/docker-{id}.scope")),
+            "4f1c0a9e2b7d"
+        );
+        assert_eq!(
+            container_label(&format!(
+                "/kubepods.slice/kubepods-pod1.slice/cri-containerd-{id}.scope"
+            )),
+            "4f1c0a9e2b7d"
+        );
+        assert_eq!(container_label(&format!("/docker/{id}")), "4f1c0a9e2b7d");
+        assert_eq!(
+            container_label("/system.slice/sshd.service"),
+            "sshd.service"
+        );
+        assert_eq!(container_label("/"), "/");
+    }
+}
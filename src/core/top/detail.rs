@@ -0,0 +1,212 @@
+/// `top --pid`: one process followed in detail
+use super::input::{self, InputEvent, KeyInput, TopAction};
+use super::schedule::Scheduler;
+use super::screen::{AltScreenGuard, Interrupt};
+use super::{
+    ProcessView, SystemSummary, TopNotice, TopOptions, collect_processes, open_fd_count,
+    terminal_width,
+};
+use crate::core::{port, process};
+use crate::error::ZiroError;
+use crate::platform::term;
+use anyhow::{Context, Result, bail};
+use std::io::{self, IsTerminal};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, RefreshKind, System, Users};
+
+/// Number of refreshes kept for the sparklines
+const HISTORY: usize = 60;
+
+/// Everything the detail view knows about the tracked process
+pub struct ProcessDetail {
+    pub process: ProcessView,
+    /// Memory of recent refreshes, oldest first
+    pub memory_history: Vec<u64>,
+    /// CPU usage of recent refreshes, oldest first
+    pub cpu_history: Vec<f32>,
+    /// Direct child processes, largest memory first
+    pub children: Vec<ProcessView>,
+    /// Ports the process listens on or is connected from
+    pub ports: Vec<u16>,
+    pub open_files: Vec<PathBuf>,
+    /// The process is gone; the rest is its last sample
+    pub exited: bool,
+}
+
+/// Rendering options for the detail view
+pub struct DetailRenderOptions {
+    pub summary: SystemSummary,
+    pub refresh: u64,
+    pub interval: f32,
+    /// Maximum entries per list (children, ports, files)
+    pub limit: usize,
+    /// Terminal width in columns; `None` when output isn't a terminal
+    pub width: Option<usize>,
+    pub incremental: bool,
+    pub batch: bool,
+    pub paused: bool,
+    /// Keyboard controls are active
+    pub interactive: bool,
+    pub notice: Option<TopNotice>,
+}
+
+/// Push onto a fixed-size history, dropping the oldest sample
+fn push_history<T>(history: &mut Vec<T>, value: T) {
+    if history.len() == HISTORY {
+        history.remove(0);
+    }
+    history.push(value);
+}
+
+pub fn run_detail(
+    pid: u32,
+    opts: TopOptions,
+    render: fn(&ProcessDetail, &DetailRenderOptions, &mut Vec<String>) -> io::Result<()>,
+) -> Result<()> {
+    let process_refresh = ProcessRefreshKind::everything();
+    let mut system = System::new_with_specifics(RefreshKind::new().with_processes(process_refresh));
+    let users = Users::new_with_refreshed_list();
+
+    // Initial refresh to establish baseline CPU usage
+    system.refresh_processes_specifics(ProcessesToUpdate::All, process_refresh);
+    system.refresh_memory();
+    system.refresh_cpu_usage();
+    if system.process(sysinfo::Pid::from_u32(pid)).is_none() {
+        bail!(ZiroError::NotFound(format!("No process with PID {pid}")));
+    }
+    let mut last_sample = Instant::now();
+
+    let profile = term::global_profile();
+    let live_view = !opts.once && !opts.batch;
+    let interrupt = Interrupt::install();
+    let _guard = (live_view && profile.alt_screen).then(AltScreenGuard::new);
+    let keys = if live_view && io::stdin().is_terminal() {
+        KeyInput::new().ok()
+    } else {
+        None
+    };
+
+    let interval = Duration::from_secs_f32(opts.interval);
+    let mut detail: Option<ProcessDetail> = None;
+    let mut summary = SystemSummary::default();
+    let mut notice = None;
+    let mut paused = false;
+    let mut last_frame: Vec<String> = Vec::new();
+    let mut last_width = None;
+    let mut schedule = Scheduler::new(interval);
+
+    if !opts.once {
+        interrupt.sleep(Duration::from_millis(100));
+    }
+
+    loop {
+        let exited = detail.as_ref().is_some_and(|d| d.exited);
+        if !paused && !exited && schedule.due() {
+            schedule.start();
+
+            system.refresh_processes_specifics(ProcessesToUpdate::All, process_refresh);
+            system.refresh_memory();
+            let snapshot = collect_processes(&system, &users, last_sample.elapsed(), false, true);
+            last_sample = Instant::now();
+            system.refresh_cpu_usage();
+            summary = SystemSummary::sample(&system);
+            detail = sample_detail(pid, snapshot, detail.take());
+            if detail.is_none() {
+                bail!("Process {pid} has exited");
+            }
+            if detail.as_ref().is_some_and(|d| d.exited) {
+                notice = Some(TopNotice::Error(format!("Process {pid} has exited")));
+            }
+        }
+        let Some(current) = &detail else {
+            break;
+        };
+
+        let width = if opts.batch { None } else { terminal_width() };
+        if width != last_width {
+            last_frame.clear();
+        }
+        let render_opts = DetailRenderOptions {
+            summary: summary.clone(),
+            refresh: schedule.tick(),
+            interval: opts.interval,
+            limit: opts.limit.max(1),
+            width,
+            incremental: live_view && profile.incremental,
+            batch: opts.batch,
+            paused,
+            interactive: keys.is_some(),
+            notice: notice.clone(),
+        };
+        match render(current, &render_opts, &mut last_frame) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::BrokenPipe => break,
+            Err(e) => return Err(e).context("Failed to draw top"),
+        }
+        last_width = width;
+
+        // Without a keyboard nobody is left to read the final state
+        let done = opts.once
+            || opts.iterations.is_some_and(|n| schedule.tick() >= n)
+            || (current.exited && keys.is_none());
+        if done {
+            break;
+        }
+
+        let event = schedule.wait(paused || current.exited, keys.as_ref(), &interrupt)?;
+        if interrupt.requested() {
+            break;
+        }
+        if let Some(InputEvent::Key(key)) = event {
+            notice = None;
+            match input::action_for(key) {
+                Some(TopAction::Quit) => break,
+                Some(TopAction::TogglePause) => paused = !paused,
+                _ => {}
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Pick the tracked process out of a fresh snapshot and extend its history; once
+/// it is gone, the previous sample marked as exited
+fn sample_detail(
+    pid: u32,
+    snapshot: Vec<ProcessView>,
+    previous: Option<ProcessDetail>,
+) -> Option<ProcessDetail> {
+    let mut children: Vec<ProcessView> = snapshot
+        .iter()
+        .filter(|p| p.parent == Some(pid) && !p.is_thread)
+        .cloned()
+        .collect();
+    children.sort_by(|a, b| b.memory_bytes.cmp(&a.memory_bytes).then(a.pid.cmp(&b.pid)));
+
+    let Some(mut process) = snapshot.into_iter().find(|p| p.pid == pid) else {
+        return previous.map(|last| ProcessDetail {
+            exited: true,
+            ..last
+        });
+    };
+    process.open_fds = open_fd_count(pid);
+
+    let (mut memory_history, mut cpu_history) = previous
+        .map(|d| (d.memory_history, d.cpu_history))
+        .unwrap_or_default();
+    push_history(&mut memory_history, process.memory_bytes);
+    push_history(&mut cpu_history, process.cpu);
+
+    Some(ProcessDetail {
+        process,
+        memory_history,
+        cpu_history,
+        children,
+        // Unsupported platforms simply show no ports
+        ports: port::ports_of_process(pid).unwrap_or_default(),
+        open_files: process::open_files(pid),
+        exited: false,
+    })
+}
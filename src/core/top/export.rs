@@ -0,0 +1,147 @@
+/// Machine-readable snapshots for `top --export`
+use super::ProcessView;
+use crate::core::events::{self, Event};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Row format of exported snapshots
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// One JSON object per line
+    Json,
+    /// Comma-separated values with a header row
+    Csv,
+    /// Only what happened: ports opening and closing, kills and crossed thresholds
+    Events,
+}
+
+/// Where `top` exports snapshots
+#[derive(Debug, Clone)]
+pub struct ExportTarget {
+    pub format: ExportFormat,
+    /// File to append to; stdout (instead of the live view) when `None`
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Serialize)]
+struct ExportRow<'a> {
+    /// Unix time in milliseconds
+    timestamp: u128,
+    pid: u32,
+    name: &'a str,
+    memory_bytes: u64,
+    memory_percent: f64,
+    cpu_percent: f32,
+    read_bytes_per_sec: f64,
+    write_bytes_per_sec: f64,
+}
+
+const CSV_HEADER: &str = "timestamp,pid,name,memory_bytes,memory_percent,cpu_percent,read_bytes_per_sec,write_bytes_per_sec";
+
+pub(super) struct Exporter {
+    format: ExportFormat,
+    out: Box<dyn Write>,
+}
+
+impl Exporter {
+    pub(super) fn open(target: &ExportTarget) -> Result<Self> {
+        let out: Box<dyn Write> = match &target.path {
+            Some(path) => {
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .with_context(|| format!("Failed to open export file: {}", path.display()))?;
+                let fresh = file.metadata().map(|m| m.len() == 0).unwrap_or(true);
+                let mut out = BufWriter::new(file);
+                // Appending to an existing CSV keeps its header
+                if fresh && target.format == ExportFormat::Csv {
+                    writeln!(out, "{CSV_HEADER}")?;
+                }
+                Box::new(out)
+            }
+            None => {
+                let mut out = io::stdout();
+                if target.format == ExportFormat::Csv {
+                    writeln!(out, "{CSV_HEADER}")?;
+                }
+                Box::new(out)
+            }
+        };
+        Ok(Self {
+            format: target.format,
+            out,
+        })
+    }
+
+    pub(super) fn wants_events(&self) -> bool {
+        self.format == ExportFormat::Events
+    }
+
+    /// Append events as JSON lines; other formats leave them out
+    pub(super) fn write_events(&mut self, events: &[Event]) -> io::Result<()> {
+        if !self.wants_events() {
+            return Ok(());
+        }
+        for event in events {
+            events::write_event(&mut self.out, event)?;
+        }
+        Ok(())
+    }
+
+    /// Append one snapshot, all rows sharing the same timestamp
+    pub(super) fn write_snapshot(&mut self, processes: &[ProcessView]) -> io::Result<()> {
+        if self.wants_events() {
+            return Ok(());
+        }
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or_default();
+
+        for process in processes {
+            let row = ExportRow {
+                timestamp,
+                pid: process.pid,
+                name: &process.name,
+                memory_bytes: process.shown_memory(),
+                memory_percent: process.memory_percent,
+                cpu_percent: process.cpu,
+                read_bytes_per_sec: process.read_rate,
+                write_bytes_per_sec: process.write_rate,
+            };
+            match self.format {
+                ExportFormat::Json => {
+                    serde_json::to_writer(&mut self.out, &row)?;
+                    writeln!(self.out)?;
+                }
+                ExportFormat::Csv => writeln!(
+                    self.out,
+                    "{},{},{},{},{:.2},{:.2},{:.0},{:.0}",
+                    row.timestamp,
+                    row.pid,
+                    csv_field(row.name),
+                    row.memory_bytes,
+                    row.memory_percent,
+                    row.cpu_percent,
+                    row.read_bytes_per_sec,
+                    row.write_bytes_per_sec
+                )?,
+                ExportFormat::Events => unreachable!("events are written by write_events"),
+            }
+        }
+        self.out.flush()
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
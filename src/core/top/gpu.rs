@@ -0,0 +1,83 @@
+/// Per-process GPU usage for `top --gpu` (NVIDIA, behind the `gpu` feature)
+use anyhow::Result;
+use std::collections::HashMap;
+
+/// GPU figures of one process, over all devices
+#[derive(Debug, Clone, Copy, Default)]
+pub(super) struct GpuUsage {
+    pub memory_bytes: u64,
+    /// Busiest streaming-multiprocessor utilization since the previous sample, in percent
+    pub utilization: Option<u32>,
+}
+
+#[cfg(feature = "gpu")]
+pub(super) struct GpuSampler {
+    nvml: nvml_wrapper::Nvml,
+    /// Timestamp of the newest utilization sample seen, in microseconds
+    last_seen: u64,
+}
+
+#[cfg(feature = "gpu")]
+impl GpuSampler {
+    pub(super) fn new() -> Result<Self> {
+        use anyhow::Context;
+
+        let nvml = nvml_wrapper::Nvml::init()
+            .context("Failed to load NVML (is an NVIDIA driver installed?)")?;
+        Ok(Self { nvml, last_seen: 0 })
+    }
+
+    pub(super) fn sample(&mut self) -> HashMap<u32, GpuUsage> {
+        use nvml_wrapper::enums::device::UsedGpuMemory;
+
+        let mut usage: HashMap<u32, GpuUsage> = HashMap::new();
+        let mut newest = self.last_seen;
+        for index in 0..self.nvml.device_count().unwrap_or(0) {
+            let Ok(device) = self.nvml.device_by_index(index) else {
+                continue;
+            };
+
+            // A process doing both compute and graphics is listed twice
+            let mut memory: HashMap<u32, u64> = HashMap::new();
+            let compute = device.running_compute_processes().unwrap_or_default();
+            let graphics = device.running_graphics_processes().unwrap_or_default();
+            for process in compute.into_iter().chain(graphics) {
+                let used = match process.used_gpu_memory {
+                    UsedGpuMemory::Used(bytes) => bytes,
+                    // Windows (WDDM) never reports per-process memory
+                    UsedGpuMemory::Unavailable => 0,
+                };
+                let entry = memory.entry(process.pid).or_default();
+                *entry = (*entry).max(used);
+            }
+            for (pid, bytes) in memory {
+                usage.entry(pid).or_default().memory_bytes += bytes;
+            }
+
+            // Only samples newer than the previous refresh; older GPUs don't support this
+            if let Ok(samples) = device.process_utilization_stats(self.last_seen) {
+                for sample in samples {
+                    newest = newest.max(sample.timestamp);
+                    let entry = usage.entry(sample.pid).or_default();
+                    entry.utilization = Some(entry.utilization.unwrap_or(0).max(sample.sm_util));
+                }
+            }
+        }
+        self.last_seen = newest;
+        usage
+    }
+}
+
+#[cfg(not(feature = "gpu"))]
+pub(super) struct GpuSampler;
+
+#[cfg(not(feature = "gpu"))]
+impl GpuSampler {
+    pub(super) fn new() -> Result<Self> {
+        anyhow::bail!("GPU columns need a ziro built with `--features gpu`")
+    }
+
+    pub(super) fn sample(&mut self) -> HashMap<u32, GpuUsage> {
+        HashMap::new()
+    }
+}
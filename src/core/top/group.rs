@@ -0,0 +1,160 @@
+/// Per-application rows for `top --group`
+use super::{ProcessView, SortKey};
+use std::collections::{HashMap, HashSet};
+
+/// Fold processes sharing an executable name into one row, ordered by `sort`
+///
+/// A group row sums the memory, CPU, I/O and counts of its members and has
+/// `group_size` set; its members follow it (at depth 1) when its name is in
+/// `expanded`. Names with a single process stay plain rows. Threads repeat their
+/// process's figures and are left out.
+pub(super) fn group_order(
+    processes: Vec<ProcessView>,
+    sort: SortKey,
+    reverse: bool,
+    expanded: &HashSet<String>,
+) -> Vec<ProcessView> {
+    let mut by_name: HashMap<String, Vec<ProcessView>> = HashMap::new();
+    for process in processes.into_iter().filter(|p| !p.is_thread) {
+        by_name
+            .entry(process.name.clone())
+            .or_default()
+            .push(process);
+    }
+
+    let mut groups: Vec<(ProcessView, Vec<ProcessView>)> = by_name
+        .into_values()
+        .map(|mut members| {
+            if members.len() == 1 {
+                (members.pop().expect("one member"), Vec::new())
+            } else {
+                members.sort_by(|a, b| sort.compare(a, b, reverse));
+                (summarize(&members), members)
+            }
+        })
+        .collect();
+    groups.sort_by(|(a, _), (b, _)| sort.compare(a, b, reverse));
+
+    let mut rows = Vec::new();
+    for (row, members) in groups {
+        let open = expanded.contains(&row.name);
+        rows.push(row);
+        if open {
+            rows.extend(members.into_iter().map(|mut member| {
+                member.depth = 1;
+                member
+            }));
+        }
+    }
+    rows
+}
+
+/// One row standing for all of `members`
+fn summarize(members: &[ProcessView]) -> ProcessView {
+    // The biggest member speaks for the group where figures can't be added up
+    let lead = members
+        .iter()
+        .max_by_key(|p| p.memory_bytes)
+        .expect("groups are not empty");
+    let same_user = members.iter().all(|p| p.user == lead.user);
+    let sum_counts = |count: fn(&ProcessView) -> Option<usize>| {
+        members.iter().filter_map(count).reduce(|a, b| a + b)
+    };
+
+    ProcessView {
+        pid: members.iter().map(|p| p.pid).min().unwrap_or(lead.pid),
+        name: lead.name.clone(),
+        memory_bytes: members.iter().map(|p| p.memory_bytes).sum(),
+        memory_percent: members.iter().map(|p| p.memory_percent).sum(),
+        cpu: members.iter().map(|p| p.cpu).sum(),
+        cmd: lead.cmd.clone(),
+        state: lead.state,
+        threads: sum_counts(|p| p.threads),
+        open_fds: sum_counts(|p| p.open_fds),
+        user: if same_user { lead.user.clone() } else { None },
+        cgroup: members
+            .iter()
+            .all(|p| p.cgroup == lead.cgroup)
+            .then(|| lead.cgroup.clone())
+            .flatten(),
+        gpu_memory: members
+            .iter()
+            .filter_map(|p| p.gpu_memory)
+            .reduce(|a, b| a + b),
+        gpu_util: members.iter().filter_map(|p| p.gpu_util).max(),
+        listening: sum_counts(|p| p.listening),
+        connections: sum_counts(|p| p.connections),
+        start_time: members.iter().map(|p| p.start_time).min().unwrap_or(0),
+        parent: None,
+        is_thread: false,
+        depth: 0,
+        tree_memory: None,
+        read_rate: members.iter().map(|p| p.read_rate).sum(),
+        write_rate: members.iter().map(|p| p.write_rate).sum(),
+        alert: members.iter().any(|p| p.alert),
+        memory_delta: members
+            .iter()
+            .filter_map(|p| p.memory_delta)
+            .reduce(|a, b| a + b),
+        group_size: Some(members.len()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: u32, name: &str, memory_bytes: u64) -> ProcessView {
+        ProcessView {
+            pid,
+            name: name.to_string(),
+            memory_bytes,
+            memory_percent: 0.0,
+            cpu: 1.0,
+            cmd: String::new(),
+            state: 'S',
+            threads: None,
+            open_fds: None,
+            user: None,
+            cgroup: None,
+            gpu_memory: None,
+            gpu_util: None,
+            listening: None,
+            connections: None,
+            start_time: 0,
+            parent: None,
+            is_thread: false,
+            depth: 0,
+            tree_memory: None,
+            read_rate: 0.0,
+            write_rate: 0.0,
+            alert: false,
+            memory_delta: None,
+            group_size: None,
+        }
+    }
+
+    #[test]
+    fn test_group_order() {
+        let processes = vec![
+            process(10, "chrome", 300),
+            process(11, "chrome", 200),
+            process(20, "sshd", 400),
+            process(12, "chrome", 100),
+        ];
+
+        let rows = group_order(processes.clone(), SortKey::Memory, false, &HashSet::new());
+        let summary: Vec<_> = rows
+            .iter()
+            .map(|p| (p.name.as_str(), p.memory_bytes, p.group_size))
+            .collect();
+        assert_eq!(summary, [("chrome", 600, Some(3)), ("sshd", 400, None)]);
+        assert_eq!(rows[0].pid, 10);
+        assert_eq!(rows[0].cpu, 3.0);
+
+        let expanded = HashSet::from(["chrome".to_string()]);
+        let rows = group_order(processes, SortKey::Memory, false, &expanded);
+        let order: Vec<_> = rows.iter().map(|p| (p.pid, p.depth)).collect();
+        assert_eq!(order, [(10, 0), (10, 1), (11, 1), (12, 1), (20, 0)]);
+    }
+}
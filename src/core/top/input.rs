@@ -0,0 +1,140 @@
+/// Keyboard controls for the interactive top view
+use super::SortKey;
+use anyhow::Result;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::terminal;
+use std::time::Duration;
+
+/// Something the user asked for with a key press
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TopAction {
+    Sort(SortKey),
+    /// Flip the sort direction
+    Reverse,
+    ToggleTree,
+    /// Fold processes by name
+    ToggleGroup,
+    /// Show or hide the members of the selected group
+    Expand,
+    /// Show or hide the disk I/O columns
+    ToggleIo,
+    /// Show or hide the memory delta column
+    ToggleDelta,
+    /// Move the selection by this many rows
+    Scroll(isize),
+    /// Move the selection by this many pages
+    Page(isize),
+    Home,
+    TogglePause,
+    /// Ask for a PID to kill (defaults to the selected row)
+    Kill {
+        force: bool,
+    },
+    /// Ask for a new nice value for the selected process
+    Renice,
+    /// Edit the process filter
+    Filter,
+    /// Copy the selected PID to the clipboard
+    Copy,
+    Quit,
+}
+
+/// Key press while a prompt is open
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptKey {
+    Char(char),
+    Backspace,
+    Submit,
+    Cancel,
+}
+
+/// Terminal event the view reacts to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputEvent {
+    Key(KeyEvent),
+    /// The terminal changed size
+    Resize,
+}
+
+/// Raw-mode keyboard reader; restores the terminal mode on drop
+pub struct KeyInput {
+    _private: (),
+}
+
+impl KeyInput {
+    pub fn new() -> Result<Self> {
+        super::screen::install_panic_hook();
+        terminal::enable_raw_mode()?;
+        Ok(Self { _private: () })
+    }
+
+    /// Wait up to `timeout` for a key press or resize; `None` on timeout or other events
+    pub fn next_event(&self, timeout: Duration) -> Result<Option<InputEvent>> {
+        if !event::poll(timeout)? {
+            return Ok(None);
+        }
+        match event::read()? {
+            Event::Key(key) if key.kind != KeyEventKind::Release => Ok(Some(InputEvent::Key(key))),
+            Event::Resize(..) => Ok(Some(InputEvent::Resize)),
+            _ => Ok(None),
+        }
+    }
+}
+
+impl Drop for KeyInput {
+    fn drop(&mut self) {
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Binding for a key in the normal view
+pub fn action_for(key: KeyEvent) -> Option<TopAction> {
+    // Raw mode swallows SIGINT, so Ctrl+C has to be handled as a key
+    if key.modifiers.contains(KeyModifiers::CONTROL) {
+        return matches!(key.code, KeyCode::Char('c')).then_some(TopAction::Quit);
+    }
+
+    let action = match key.code {
+        KeyCode::Char('m' | 'M') => TopAction::Sort(SortKey::Memory),
+        KeyCode::Char('c' | 'C') => TopAction::Sort(SortKey::Cpu),
+        KeyCode::Char('p' | 'P') => TopAction::Sort(SortKey::Pid),
+        KeyCode::Char('n' | 'N') => TopAction::Sort(SortKey::Name),
+        KeyCode::Char('s' | 'S') => TopAction::Sort(SortKey::Start),
+        KeyCode::Char('o' | 'O') => TopAction::Sort(SortKey::Io),
+        KeyCode::Char('r' | 'R') => TopAction::Reverse,
+        KeyCode::Char('t' | 'T') => TopAction::ToggleTree,
+        KeyCode::Char('g' | 'G') => TopAction::ToggleGroup,
+        KeyCode::Enter => TopAction::Expand,
+        KeyCode::Char('i' | 'I') => TopAction::ToggleIo,
+        KeyCode::Char('d' | 'D') => TopAction::ToggleDelta,
+        KeyCode::Char('q' | 'Q') | KeyCode::Esc => TopAction::Quit,
+        KeyCode::Char(' ') => TopAction::TogglePause,
+        KeyCode::Char('k') => TopAction::Kill { force: false },
+        KeyCode::Char('K') => TopAction::Kill { force: true },
+        KeyCode::Char('e' | 'E') => TopAction::Renice,
+        KeyCode::Char('/') => TopAction::Filter,
+        KeyCode::Char('y' | 'Y') => TopAction::Copy,
+        KeyCode::Up => TopAction::Scroll(-1),
+        KeyCode::Down => TopAction::Scroll(1),
+        KeyCode::PageUp => TopAction::Page(-1),
+        KeyCode::PageDown => TopAction::Page(1),
+        KeyCode::Home => TopAction::Home,
+        _ => return None,
+    };
+    Some(action)
+}
+
+/// Binding for a key while a prompt is open
+pub fn prompt_key(key: KeyEvent) -> Option<PromptKey> {
+    if key.modifiers.contains(KeyModifiers::CONTROL) {
+        return matches!(key.code, KeyCode::Char('c')).then_some(PromptKey::Cancel);
+    }
+
+    match key.code {
+        KeyCode::Char(c) => Some(PromptKey::Char(c)),
+        KeyCode::Backspace => Some(PromptKey::Backspace),
+        KeyCode::Enter => Some(PromptKey::Submit),
+        KeyCode::Esc => Some(PromptKey::Cancel),
+        _ => None,
+    }
+}
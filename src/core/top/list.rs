@@ -0,0 +1,109 @@
+/// One-shot process listings for `ziro ps` and `ziro pstree`
+use super::tree::{ProcessNode, build_forest};
+use super::{ProcessView, SortKey, collect_processes, matches_filter, resolve_user};
+use crate::error::ZiroError;
+use anyhow::{Result, bail};
+use regex::Regex;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, RefreshKind, System, Users};
+
+/// Which processes `list_processes` returns, and in what order
+#[derive(Default)]
+pub struct ListOptions {
+    /// Only processes whose name or command line matches
+    pub filter: Option<Regex>,
+    /// Only processes of this user (`me` for the current one)
+    pub user: Option<String>,
+    /// Only processes using at least this much memory, in bytes
+    pub min_memory: Option<u64>,
+    /// Only processes started at least this long ago
+    pub older_than: Option<Duration>,
+    /// Only processes started less than this long ago
+    pub newer_than: Option<Duration>,
+    pub sort: SortKey,
+    pub reverse: bool,
+    pub limit: Option<usize>,
+}
+
+/// Sample every process once, filtered and sorted
+pub fn list_processes(opts: &ListOptions) -> Result<Vec<ProcessView>> {
+    let user = resolve_user(opts.user.clone())?;
+    let users = Users::new_with_refreshed_list();
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let mut processes: Vec<ProcessView> = sample(&users)
+        .into_iter()
+        .filter(|p| opts.filter.as_ref().is_none_or(|f| matches_filter(f, p)))
+        .filter(|p| user.is_none() || p.user == user)
+        .filter(|p| opts.min_memory.is_none_or(|min| p.memory_bytes >= min))
+        .filter(|p| within_age(p.start_time, now, opts.older_than, opts.newer_than))
+        .collect();
+    processes.sort_by(|a, b| opts.sort.compare(a, b, opts.reverse));
+    if let Some(limit) = opts.limit {
+        processes.truncate(limit);
+    }
+    Ok(processes)
+}
+
+/// Whether a process started at `start_time` (seconds since the epoch) is at least
+/// `older_than` and less than `newer_than` old at `now`
+fn within_age(
+    start_time: u64,
+    now: u64,
+    older_than: Option<Duration>,
+    newer_than: Option<Duration>,
+) -> bool {
+    let age = now.saturating_sub(start_time);
+    older_than.is_none_or(|min| age >= min.as_secs())
+        && newer_than.is_none_or(|max| age < max.as_secs())
+}
+
+/// The process hierarchy below `root`, or the whole of it
+pub fn process_tree(root: Option<u32>) -> Result<Vec<ProcessNode>> {
+    let users = Users::new_with_refreshed_list();
+    let forest = build_forest(sample(&users), root);
+    if let Some(pid) = root
+        && forest.is_empty()
+    {
+        bail!(ZiroError::NotFound(format!("No process with PID {pid}")));
+    }
+    Ok(forest)
+}
+
+/// Every process except threads, with CPU usage measured over a short interval
+fn sample(users: &Users) -> Vec<ProcessView> {
+    let refresh = ProcessRefreshKind::everything();
+    let mut system = System::new_with_specifics(RefreshKind::new().with_processes(refresh));
+    let start = Instant::now();
+    // CPU usage is the difference between two refreshes
+    thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+    system.refresh_processes_specifics(ProcessesToUpdate::All, refresh);
+
+    collect_processes(&system, users, start.elapsed(), false, false)
+        .into_iter()
+        // Threads repeat their process's memory and CPU
+        .filter(|p| !p.is_thread)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_within_age() {
+        let hour = Duration::from_secs(3600);
+        let now = 100_000;
+        // Started two hours ago
+        let started = now - 7200;
+        assert!(within_age(started, now, None, None));
+        assert!(within_age(started, now, Some(hour), None));
+        assert!(!within_age(started, now, Some(3 * hour), None));
+        assert!(within_age(started, now, None, Some(3 * hour)));
+        assert!(!within_age(started, now, None, Some(hour)));
+        assert!(within_age(started, now, Some(hour), Some(3 * hour)));
+    }
+}
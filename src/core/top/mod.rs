@@ -1,145 +1,344 @@
-use crate::platform::term::{self, TerminalProfile};
-#[cfg(target_os = "windows")]
-use crate::platform::term::{
-    is_powershell_core, is_windows_powershell_legacy, is_windows_terminal_or_conemu,
-};
-use anyhow::Result;
-use std::io::{self, Write};
-use std::thread;
-use std::time::{Duration, Instant};
-use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, RefreshKind, System};
-
-/// Check whether alternate screen should be used (improved version)
-fn should_use_alt_screen(profile: &TerminalProfile) -> bool {
-    if !profile.alt_screen {
-        return false;
-    }
-
-    // Use improved terminal detection logic
-    #[cfg(target_os = "windows")]
-    {
-        // Windows Terminal explicitly supports alternate screen
-        if std::env::var("WT_SESSION").is_ok() {
-            return true;
-        }
-
-        // VSCode terminal supports alternate screen
-        if let Ok(term_program) = std::env::var("TERM_PROGRAM") {
-            if term_program.to_lowercase().contains("vscode") {
-                return true;
-            }
-        }
-
-        // ConEmu and other modern terminals with ANSI support
-        if std::env::var("ConEmuANSI").is_ok() || std::env::var("ANSICON").is_ok() {
-            return true;
-        }
-
-        // PowerShell Core usually supports alternate screen
-        if is_powershell_core() {
-            return true;
-        }
-
-        // Directly detect Warp terminal and other modern terminals
-        if let Ok(term_program) = std::env::var("TERM_PROGRAM") {
-            let term_program = term_program.to_lowercase();
-            if term_program.contains("warp") || term_program.contains("warpterminal") {
-                return true;
-            }
-        }
+mod alert;
+mod cgroup;
+mod detail;
+mod export;
+mod gpu;
+mod group;
+mod input;
+mod list;
+mod record;
+mod schedule;
+mod screen;
+mod tree;
+mod watch;
 
-        // TERM variable detection - support xterm-256color and other modern terminals
-        if let Ok(term) = std::env::var("TERM") {
-            let term = term.to_lowercase();
-            if term.contains("xterm-256color")
-                || term.contains("xterm")
-                || term.contains("256color")
-            {
-                return true;
-            }
-        }
+use crate::core::events::{Event, PortWatcher};
+use crate::core::{port, process};
+use crate::platform::{clipboard, term};
+use alert::Watchdog;
+pub use alert::{AlertOptions, MemoryThreshold, parse_memory_threshold, parse_size};
+use anyhow::{Context, Result};
+pub use cgroup::container_label;
+use crossterm::event::KeyEvent;
+pub use detail::{DetailRenderOptions, ProcessDetail, run_detail};
+use export::Exporter;
+pub use export::{ExportFormat, ExportTarget};
+use gpu::GpuSampler;
+use input::{InputEvent, KeyInput, PromptKey, TopAction};
+pub use list::{ListOptions, list_processes, process_tree};
+use record::{Recorder, Replay};
+use regex::{Regex, RegexBuilder};
+use schedule::Scheduler;
+use screen::{AltScreenGuard, Interrupt};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::io::{self, IsTerminal};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use sysinfo::{
+    ProcessRefreshKind, ProcessStatus, ProcessesToUpdate, RefreshKind, System, UpdateKind, Users,
+};
+pub use tree::ProcessNode;
+pub use watch::{WatchFrame, WatchOptions, WatchRenderOptions, run_watch};
 
-        // Windows PowerShell 5.1 only supports alternate screen in specific environments
-        if is_windows_powershell_legacy() {
-            // Only use alternate screen in Windows Terminal or ConEmu
-            return is_windows_terminal_or_conemu();
-        }
+/// Top subcommand options
+pub struct TopOptions {
+    pub interval: f32,
+    pub limit: usize,
+    pub show_cpu: bool,
+    pub show_cmd: bool,
+    /// Show disk read/write rates
+    pub show_io: bool,
+    /// Show memory change since the previous refresh
+    pub show_delta: bool,
+    /// Show the thread count of each process
+    pub show_threads: bool,
+    /// Show the number of open file descriptors (Linux)
+    pub show_fds: bool,
+    /// Show the container or cgroup of each process (Linux)
+    pub show_containers: bool,
+    /// Show GPU memory and utilization (needs the `gpu` feature)
+    pub show_gpu: bool,
+    /// Show listening sockets and established connections of each process
+    pub show_net: bool,
+    /// Memory percentages against each process's cgroup limit instead of host RAM
+    pub cgroup_limit: bool,
+    pub once: bool,
+    /// Plain frames for logging: no colors, alternate screen or keyboard controls
+    pub batch: bool,
+    /// Stop after this many refreshes
+    pub iterations: Option<u64>,
+    /// Show processes under their parents
+    pub tree: bool,
+    /// In tree mode, count children's memory in their parent's row
+    pub aggregate: bool,
+    /// Fold processes with the same executable name into one row
+    pub group: bool,
+    pub sort: SortKey,
+    /// Flip the natural direction of `sort`
+    pub reverse: bool,
+    /// Only show processes whose name or command line matches
+    pub filter: Option<Regex>,
+    /// Only show processes owned by this user (`me` for the current one)
+    pub user: Option<String>,
+    /// Only show zombie processes
+    pub zombies: bool,
+    /// Only show processes whose cgroup path contains this
+    pub cgroup: Option<String>,
+    /// Hide processes using less memory than this, in bytes
+    pub min_memory: Option<u64>,
+    /// Hide processes using less CPU than this, in percent
+    pub min_cpu: Option<f32>,
+    /// Append each refresh as JSON/CSV rows
+    pub export: Option<ExportTarget>,
+    /// Save every refresh to this session file
+    pub record: Option<PathBuf>,
+    /// Play back a session file instead of sampling the system
+    pub replay: Option<PathBuf>,
+    /// Replay speed multiplier
+    pub speed: f32,
+    /// Thresholds that highlight rows and trigger alert actions
+    pub alerts: AlertOptions,
+    /// Follow this process in the detail view instead of listing all of them
+    pub pid: Option<u32>,
+}
 
-        // Default: don't use alternate screen to avoid issues
-        false
+/// Parse a percentage, with or without the `%` sign
+pub fn parse_percent(s: &str) -> Result<f32, String> {
+    let s = s.trim();
+    let value: f32 = s
+        .strip_suffix('%')
+        .unwrap_or(s)
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid percentage: {s}"))?;
+    if !value.is_finite() || value < 0.0 {
+        return Err(format!("invalid percentage: {s}"));
     }
+    Ok(value)
+}
 
-    // Non-Windows systems usually support alternate screen
-    #[cfg(not(target_os = "windows"))]
-    true
+/// Build a case-insensitive process filter
+pub fn parse_filter(pattern: &str) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(pattern).case_insensitive(true).build()
 }
 
-/// Guard that restores terminal state on drop (including panics)
-struct AltScreenGuard {
-    active: bool,
+fn matches_filter(filter: &Regex, process: &ProcessView) -> bool {
+    filter.is_match(&process.name) || filter.is_match(&process.cmd)
 }
 
-impl AltScreenGuard {
-    fn new() -> Self {
-        enter_alternate_screen();
-        Self { active: true }
-    }
+/// Name of the user running ziro
+fn current_user(users: &Users) -> Option<String> {
+    let pid = sysinfo::get_current_pid().ok()?;
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[pid]),
+        ProcessRefreshKind::new().with_user(UpdateKind::Always),
+    );
+    let from_process = system
+        .process(pid)
+        .and_then(|process| process.user_id())
+        .and_then(|uid| users.get_user_by_id(uid))
+        .map(|user| user.name().to_string());
+    from_process.or_else(|| {
+        std::env::var("USER")
+            .or_else(|_| std::env::var("USERNAME"))
+            .ok()
+    })
 }
 
-impl Drop for AltScreenGuard {
-    fn drop(&mut self) {
-        if self.active {
-            exit_alternate_screen();
-        }
+/// `user` with `me` replaced by the name of the user running ziro
+pub fn resolve_user(user: Option<String>) -> Result<Option<String>> {
+    match user {
+        Some(user) if user == "me" => current_user(&Users::new_with_refreshed_list())
+            .context("Cannot determine the current user")
+            .map(Some),
+        user => Ok(user),
     }
 }
 
-/// Safely enter alternate screen
-fn enter_alternate_screen() {
-    // Clear screen and move to top first
-    print!("\x1b[2J\x1b[H");
+/// Column the process list is ordered by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortKey {
+    #[default]
+    Memory,
+    Cpu,
+    Pid,
+    Name,
+    /// Start time, newest first
+    Start,
+    /// Disk reads plus writes per second
+    Io,
+    /// GPU memory
+    Gpu,
+}
 
-    // Try to enter alternate screen
-    print!("\x1b[?1049h");
+impl SortKey {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Memory => "memory",
+            Self::Cpu => "cpu",
+            Self::Pid => "pid",
+            Self::Name => "name",
+            Self::Start => "start",
+            Self::Io => "io",
+            Self::Gpu => "gpu",
+        }
+    }
 
-    // Hide cursor
-    print!("\x1b[?25l");
+    /// Whether the natural order is largest (or newest) first
+    pub fn descending(self) -> bool {
+        matches!(
+            self,
+            Self::Memory | Self::Cpu | Self::Start | Self::Io | Self::Gpu
+        )
+    }
 
-    let _ = io::stdout().flush();
+    /// Order in the natural direction, or the opposite one with `reverse`; ties go by PID
+    fn compare(self, a: &ProcessView, b: &ProcessView, reverse: bool) -> Ordering {
+        let order = match self {
+            Self::Memory => a.shown_memory().cmp(&b.shown_memory()),
+            Self::Cpu => a.cpu.total_cmp(&b.cpu),
+            Self::Pid => a.pid.cmp(&b.pid),
+            Self::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+            Self::Start => a.start_time.cmp(&b.start_time),
+            Self::Io => (a.read_rate + a.write_rate).total_cmp(&(b.read_rate + b.write_rate)),
+            Self::Gpu => a.gpu_memory.unwrap_or(0).cmp(&b.gpu_memory.unwrap_or(0)),
+        };
+        let order = if self.descending() != reverse {
+            order.reverse()
+        } else {
+            order
+        };
+        order.then_with(|| a.pid.cmp(&b.pid))
+    }
 }
 
-/// Safely exit alternate screen
-fn exit_alternate_screen() {
-    // Show cursor
-    print!("\x1b[?25h");
-
-    // Exit alternate screen
-    print!("\x1b[?1049l");
-
-    let _ = io::stdout().flush();
+/// Machine-wide figures shown above the process list
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SystemSummary {
+    pub total_memory: u64,
+    pub used_memory: u64,
+    #[serde(default)]
+    pub total_swap: u64,
+    #[serde(default)]
+    pub used_swap: u64,
+    /// 1, 5 and 15 minute load averages (Unix only)
+    #[serde(default)]
+    pub load_average: Option<[f64; 3]>,
+    /// Usage of each logical core, in percent
+    #[serde(default)]
+    pub core_usage: Vec<f32>,
 }
 
-/// Top subcommand options
-pub struct TopOptions {
-    pub interval: f32,
-    pub limit: usize,
-    pub show_cpu: bool,
-    pub show_cmd: bool,
-    pub once: bool,
+impl SystemSummary {
+    fn sample(system: &System) -> Self {
+        let load_average = cfg!(unix).then(|| {
+            let load = System::load_average();
+            [load.one, load.five, load.fifteen]
+        });
+        Self {
+            total_memory: system.total_memory(),
+            used_memory: system.used_memory(),
+            total_swap: system.total_swap(),
+            used_swap: system.used_swap(),
+            load_average,
+            core_usage: system.cpus().iter().map(|cpu| cpu.cpu_usage()).collect(),
+        }
+    }
 }
 
 /// Rendering options for top display
 pub struct TopRenderOptions {
-    pub total_memory: u64,
-    pub used_memory: u64,
+    pub summary: SystemSummary,
     pub refresh: u64,
     pub interval: f32,
     pub show_cpu: bool,
     pub show_cmd: bool,
+    pub show_io: bool,
+    pub show_delta: bool,
+    pub show_threads: bool,
+    pub show_fds: bool,
+    pub show_containers: bool,
+    pub show_gpu: bool,
+    pub show_net: bool,
+    /// Mem% is relative to cgroup limits
+    pub cgroup_limit: bool,
+    /// Terminal width in columns; `None` when output isn't a terminal
+    pub width: Option<usize>,
     pub incremental: bool,
+    /// Plain, timestamped frames for logs
+    pub batch: bool,
+    pub sort: SortKey,
+    pub reverse: bool,
+    /// Number of processes scrolled past
+    pub offset: usize,
+    /// Number of processes before scrolling/truncation
+    pub total_processes: usize,
+    pub paused: bool,
+    /// Rows come from a recording
+    pub replay: bool,
+    /// Keyboard controls are active
+    pub interactive: bool,
+    /// Highlighted row, relative to the displayed slice
+    pub selected: Option<usize>,
+    /// Open prompt: (label, typed text)
+    pub prompt: Option<(String, String)>,
+    pub notice: Option<TopNotice>,
+    /// Active name/command line filter
+    pub filter: Option<String>,
+    /// Only processes of this user are listed
+    pub user: Option<String>,
+    /// Only processes in matching cgroups are listed
+    pub cgroup: Option<String>,
+    /// Processes below these are hidden
+    pub min_memory: Option<u64>,
+    pub min_cpu: Option<f32>,
+    /// Zombie processes on the whole system
+    pub zombies: usize,
+    /// Groups showing their members in `--group` mode
+    pub expanded: HashSet<String>,
+}
+
+/// One-line feedback shown until the next key press
+#[derive(Debug, Clone)]
+pub enum TopNotice {
+    Info(String),
+    Error(String),
+}
+
+/// What an open prompt asks for
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum PromptKind {
+    /// Opened with `k`/`K`
+    Kill { force: bool },
+    /// Opened with `/`
+    Filter,
+    /// Opened with `e` on a process row
+    Renice { pid: u32 },
+}
+
+impl PromptKind {
+    fn label(self) -> String {
+        match self {
+            Self::Kill { force: false } => "Kill PID".to_string(),
+            Self::Kill { force: true } => "Force kill PID".to_string(),
+            Self::Filter => "Filter (regex)".to_string(),
+            Self::Renice { pid } => format!("Nice value for PID {pid} (-20..19)"),
+        }
+    }
+}
+
+struct Prompt {
+    kind: PromptKind,
+    input: String,
+    /// Still showing the current value; the first typed character replaces it
+    prefilled: bool,
 }
 
 /// Process info for display
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcessView {
     pub pid: u32,
     pub name: String,
@@ -147,19 +346,431 @@ pub struct ProcessView {
     pub memory_percent: f64,
     pub cpu: f32,
     pub cmd: String,
+    /// State code as in `ps`: R running, S sleeping, D uninterruptible, Z zombie, T stopped...
+    #[serde(default = "unknown_state")]
+    pub state: char,
+    /// Thread count; `None` where the platform doesn't report it
+    #[serde(default)]
+    pub threads: Option<usize>,
+    /// Open file descriptors; `None` when unknown or not collected
+    #[serde(default)]
+    pub open_fds: Option<usize>,
+    /// Owning user name (or numeric id when it has no name)
+    #[serde(default)]
+    pub user: Option<String>,
+    /// Control group path (Linux); `None` when unknown or not collected
+    #[serde(default)]
+    pub cgroup: Option<String>,
+    /// GPU memory in bytes; `None` when not on a GPU or not collected
+    #[serde(default)]
+    pub gpu_memory: Option<u64>,
+    /// GPU utilization in percent
+    #[serde(default)]
+    pub gpu_util: Option<u32>,
+    /// Listening TCP / bound UDP sockets; `None` when not collected
+    #[serde(default)]
+    pub listening: Option<usize>,
+    /// Established connections; `None` when not collected
+    #[serde(default)]
+    pub connections: Option<usize>,
+    /// Unix time (seconds) the process started
+    pub start_time: u64,
+    pub parent: Option<u32>,
+    /// A thread listed as its own task (Linux); shares its parent's memory
+    pub is_thread: bool,
+    /// Nesting level in tree mode (0 otherwise)
+    #[serde(skip)]
+    pub depth: usize,
+    /// Memory of the whole subtree when aggregating in tree mode
+    #[serde(skip)]
+    pub tree_memory: Option<u64>,
+    /// Disk bytes read per second since the previous refresh
+    pub read_rate: f64,
+    /// Disk bytes written per second since the previous refresh
+    pub write_rate: f64,
+    /// Over an `--alert-mem` / `--alert-cpu` threshold
+    #[serde(skip)]
+    pub alert: bool,
+    /// Memory change since the previous refresh; `None` for new processes
+    #[serde(skip)]
+    pub memory_delta: Option<i64>,
+    /// Number of processes folded into this row by `--group`
+    #[serde(skip)]
+    pub group_size: Option<usize>,
+}
+
+impl ProcessView {
+    /// Memory shown in the row: the subtree total when aggregating, otherwise its own
+    pub fn shown_memory(&self) -> u64 {
+        self.tree_memory.unwrap_or(self.memory_bytes)
+    }
+}
+
+/// What the keyboard can change between frames
+#[derive(Default)]
+struct ViewState {
+    sort: SortKey,
+    reverse: bool,
+    tree: bool,
+    group: bool,
+    /// Names of the groups showing their members
+    expanded: HashSet<String>,
+    show_cpu: bool,
+    show_io: bool,
+    show_delta: bool,
+    /// Number of rows scrolled past
+    offset: usize,
+    /// Index of the highlighted row in the sorted list
+    selected: usize,
+    paused: bool,
+    prompt: Option<Prompt>,
+    notice: Option<TopNotice>,
+    filter: Option<Regex>,
+    user: Option<String>,
+    zombies: bool,
+    cgroup: Option<String>,
+    min_memory: Option<u64>,
+    min_cpu: Option<f32>,
+    /// Showing a recording; the PIDs are not live
+    replay: bool,
+    /// Happened since the last frame, for `--export events`
+    events: Vec<Event>,
+}
+
+/// What the main loop should do after a key press
+enum KeyOutcome {
+    Redraw,
+    /// Something changed on the system or in the filter, refresh before redrawing
+    Refresh,
+    Quit,
+}
+
+impl ViewState {
+    /// Keep the selection inside the list and the visible window around it
+    fn clamp(&mut self, len: usize, page: usize) {
+        self.selected = self.selected.min(len.saturating_sub(1));
+        if self.selected < self.offset {
+            self.offset = self.selected;
+        } else if self.selected >= self.offset + page {
+            self.offset = self.selected + 1 - page;
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent, processes: &[ProcessView], page: usize) -> KeyOutcome {
+        self.notice = None;
+
+        if let Some(prompt) = &mut self.prompt {
+            let accepts = |c: char| match prompt.kind {
+                PromptKind::Kill { .. } => c.is_ascii_digit(),
+                PromptKind::Filter => !c.is_control(),
+                PromptKind::Renice { .. } => c.is_ascii_digit() || c == '-',
+            };
+            match input::prompt_key(key) {
+                Some(PromptKey::Char(c)) if accepts(c) => {
+                    if prompt.prefilled {
+                        prompt.input.clear();
+                        prompt.prefilled = false;
+                    }
+                    prompt.input.push(c);
+                }
+                Some(PromptKey::Backspace) => {
+                    prompt.input.pop();
+                    prompt.prefilled = false;
+                }
+                Some(PromptKey::Submit) => {
+                    let prompt = self.prompt.take().expect("prompt is open");
+                    match prompt.kind {
+                        PromptKind::Kill { force } => {
+                            let (notice, killed) = kill_from_prompt(&prompt.input, force);
+                            self.notice = Some(notice);
+                            if let Some(pid) = killed {
+                                let name = processes.iter().find(|p| p.pid == pid);
+                                self.events.push(Event::ProcKilled {
+                                    pid,
+                                    name: name.map(|p| p.name.clone()),
+                                    force,
+                                });
+                            }
+                        }
+                        PromptKind::Filter => {
+                            self.apply_filter(&prompt.input);
+                            return KeyOutcome::Redraw;
+                        }
+                        PromptKind::Renice { pid } => {
+                            self.notice = Some(renice_from_prompt(pid, &prompt.input));
+                        }
+                    }
+                    return KeyOutcome::Refresh;
+                }
+                Some(PromptKey::Cancel) => self.prompt = None,
+                _ => {}
+            }
+            return KeyOutcome::Redraw;
+        }
+
+        let Some(action) = input::action_for(key) else {
+            return KeyOutcome::Redraw;
+        };
+        match action {
+            TopAction::Sort(key) => {
+                self.sort = key;
+                self.selected = 0;
+                // Sorting by a hidden column would be confusing
+                self.show_cpu |= key == SortKey::Cpu;
+                self.show_io |= key == SortKey::Io;
+            }
+            TopAction::Scroll(rows) => self.selected = self.selected.saturating_add_signed(rows),
+            TopAction::Page(pages) => {
+                self.selected = self.selected.saturating_add_signed(pages * page as isize)
+            }
+            TopAction::Reverse => {
+                self.reverse = !self.reverse;
+                self.selected = 0;
+            }
+            TopAction::ToggleIo => self.show_io = !self.show_io,
+            TopAction::ToggleDelta => self.show_delta = !self.show_delta,
+            TopAction::ToggleTree => {
+                self.tree = !self.tree;
+                self.group = false;
+                self.selected = 0;
+            }
+            TopAction::ToggleGroup => {
+                self.group = !self.group;
+                self.tree = false;
+                self.selected = 0;
+            }
+            TopAction::Expand => {
+                // Members carry their group's name, so this collapses from any row
+                if self.group
+                    && let Some(process) = processes.get(self.selected)
+                    && !self.expanded.remove(&process.name)
+                    && process.group_size.is_some()
+                {
+                    self.expanded.insert(process.name.clone());
+                }
+            }
+            TopAction::Home => self.selected = 0,
+            TopAction::TogglePause => self.paused = !self.paused,
+            TopAction::Kill { .. } if self.replay => {
+                self.notice = Some(TopNotice::Error(
+                    "Killing is not available while replaying".to_string(),
+                ));
+            }
+            TopAction::Renice if self.replay => {
+                self.notice = Some(TopNotice::Error(
+                    "Changing priority is not available while replaying".to_string(),
+                ));
+            }
+            TopAction::Renice => {
+                match processes
+                    .get(self.selected)
+                    .filter(|p| p.group_size.is_none())
+                {
+                    Some(process) => {
+                        self.prompt = Some(Prompt {
+                            kind: PromptKind::Renice { pid: process.pid },
+                            input: "10".to_string(),
+                            prefilled: true,
+                        })
+                    }
+                    None => {
+                        self.notice = Some(TopNotice::Error(
+                            "Select a process to change its priority".to_string(),
+                        ))
+                    }
+                }
+            }
+            TopAction::Kill { force } => {
+                // A group row has no single PID to offer
+                let input = processes
+                    .get(self.selected)
+                    .filter(|p| p.group_size.is_none())
+                    .map(|p| p.pid.to_string())
+                    .unwrap_or_default();
+                self.prompt = Some(Prompt {
+                    kind: PromptKind::Kill { force },
+                    input,
+                    prefilled: true,
+                });
+            }
+            TopAction::Copy => {
+                self.notice = Some(
+                    match processes
+                        .get(self.selected)
+                        .filter(|p| p.group_size.is_none())
+                    {
+                        Some(process) => match clipboard::copy(&process.pid.to_string()) {
+                            Ok(()) => TopNotice::Info(format!("Copied PID {}", process.pid)),
+                            Err(e) => TopNotice::Error(format!("{e:#}")),
+                        },
+                        None => TopNotice::Error("Select a process to copy its PID".to_string()),
+                    },
+                );
+            }
+            TopAction::Filter => {
+                self.prompt = Some(Prompt {
+                    kind: PromptKind::Filter,
+                    input: self
+                        .filter
+                        .as_ref()
+                        .map(|f| f.as_str().to_string())
+                        .unwrap_or_default(),
+                    prefilled: true,
+                });
+            }
+            TopAction::Quit => return KeyOutcome::Quit,
+        }
+        KeyOutcome::Redraw
+    }
+
+    /// The processes of `snapshot` that pass the filters, in display order; the
+    /// view shows `limit` of them from `offset` on
+    fn rows(&self, snapshot: &[ProcessView], aggregate: bool) -> Vec<ProcessView> {
+        let mut processes: Vec<ProcessView> =
+            snapshot.iter().filter(|p| self.shows(p)).cloned().collect();
+        if self.tree {
+            processes = tree::tree_order(processes, self.sort, self.reverse, aggregate);
+        } else if self.group {
+            processes = group::group_order(processes, self.sort, self.reverse, &self.expanded);
+        } else {
+            processes.sort_by(|a, b| self.sort.compare(a, b, self.reverse));
+        }
+        processes
+    }
+
+    /// Whether `process` passes the active filters
+    fn shows(&self, process: &ProcessView) -> bool {
+        self.filter
+            .as_ref()
+            .is_none_or(|f| matches_filter(f, process))
+            && (!self.zombies || process.state == 'Z')
+            && self
+                .user
+                .as_ref()
+                .is_none_or(|user| process.user.as_ref() == Some(user))
+            && self.cgroup.as_ref().is_none_or(|name| {
+                process
+                    .cgroup
+                    .as_ref()
+                    .is_some_and(|path| path.contains(name.as_str()))
+            })
+            && self
+                .min_memory
+                .is_none_or(|min| process.shown_memory() >= min)
+            && self.min_cpu.is_none_or(|min| process.cpu >= min)
+    }
+
+    /// Replace the filter; an empty pattern clears it
+    fn apply_filter(&mut self, pattern: &str) {
+        if pattern.is_empty() {
+            self.filter = None;
+        } else {
+            match parse_filter(pattern) {
+                Ok(filter) => self.filter = Some(filter),
+                Err(e) => {
+                    // Syntax errors span several lines; the last one names the problem
+                    let reason = e.to_string().lines().last().unwrap_or_default().to_string();
+                    self.notice = Some(TopNotice::Error(format!(
+                        "Invalid filter '{pattern}': {reason}"
+                    )));
+                    return;
+                }
+            }
+        }
+        self.selected = 0;
+    }
+}
+
+/// The notice to show, and the PID when the signal went out
+fn kill_from_prompt(input: &str, force: bool) -> (TopNotice, Option<u32>) {
+    let Ok(pid) = input.parse::<u32>() else {
+        return (TopNotice::Error(format!("Invalid PID: '{input}'")), None);
+    };
+    let result = if force {
+        process::kill_process_force(pid)
+    } else {
+        process::kill_process(pid)
+    };
+    match result {
+        Ok(()) => (
+            TopNotice::Info(format!("Sent kill to PID {pid}")),
+            Some(pid),
+        ),
+        Err(e) => (TopNotice::Error(format!("{e:#}")), None),
+    }
+}
+
+fn renice_from_prompt(pid: u32, input: &str) -> TopNotice {
+    let Ok(nice) = input.parse::<i32>() else {
+        return TopNotice::Error(format!("Invalid nice value: '{input}'"));
+    };
+    match process::set_priority(pid, nice) {
+        Ok(()) => TopNotice::Info(format!("Set PID {pid} to nice {nice}")),
+        Err(e) => TopNotice::Error(format!("{e:#}")),
+    }
+}
+
+/// Where the rows come from
+enum Source {
+    Live {
+        system: Box<System>,
+        users: Users,
+        last_sample: Instant,
+        recorder: Option<Recorder>,
+    },
+    Replay {
+        replay: Replay,
+        speed: f32,
+        finished: bool,
+    },
 }
 
 pub fn run_top(
     opts: TopOptions,
-    render: fn(&[ProcessView], &TopRenderOptions, &mut Vec<String>),
+    render: fn(&[ProcessView], &TopRenderOptions, &mut Vec<String>) -> io::Result<()>,
 ) -> Result<()> {
     let process_refresh = ProcessRefreshKind::everything();
-    let mut system = System::new_with_specifics(RefreshKind::new().with_processes(process_refresh));
+    let mut source = match &opts.replay {
+        Some(path) => Source::Replay {
+            replay: Replay::open(path)?,
+            speed: opts.speed,
+            finished: false,
+        },
+        None => Source::Live {
+            system: Box::new(System::new_with_specifics(
+                RefreshKind::new().with_processes(process_refresh),
+            )),
+            users: Users::new_with_refreshed_list(),
+            last_sample: Instant::now(),
+            recorder: opts
+                .record
+                .as_deref()
+                .map(|path| Recorder::create(path, opts.interval))
+                .transpose()?,
+        },
+    };
+    let user = resolve_user(opts.user)?;
+    let mut gpu = match &source {
+        Source::Live { .. } if opts.show_gpu => Some(GpuSampler::new()?),
+        _ => None,
+    };
+    let display_interval = match &source {
+        Source::Replay { replay, .. } => replay.interval,
+        Source::Live { .. } => opts.interval,
+    };
+
+    // Exporting to stdout replaces the live view
+    let mut exporter = opts.export.as_ref().map(Exporter::open).transpose()?;
+    let headless = opts.export.as_ref().is_some_and(|e| e.path.is_none());
 
     // Decide whether to use alternate screen / incremental refresh based on terminal capabilities
     let profile = term::global_profile();
-    let use_alt_screen = !opts.once && should_use_alt_screen(&profile);
-    let incremental = !opts.once && profile.incremental;
+    let live_view = !opts.once && !headless && !opts.batch;
+    let use_alt_screen = live_view && profile.alt_screen;
+    let incremental = live_view && profile.incremental;
+
+    // Ctrl+C ends the loop so the guards below can restore the terminal
+    let interrupt = Interrupt::install();
 
     // Enter alternate screen to avoid polluting scroll history (not needed for once mode)
     let _guard = if use_alt_screen {
@@ -168,104 +779,492 @@ pub fn run_top(
         None
     };
 
-    let mut tick: u64 = 0;
+    // Keyboard controls need a terminal on stdin; otherwise keep the plain refresh loop
+    let keys = if live_view && io::stdin().is_terminal() {
+        KeyInput::new().ok()
+    } else {
+        None
+    };
+
+    let limit = opts.limit.max(1);
+    let interval = Duration::from_secs_f32(opts.interval);
+    let mut state = ViewState {
+        sort: opts.sort,
+        reverse: opts.reverse,
+        tree: opts.tree,
+        group: opts.group,
+        expanded: HashSet::new(),
+        // Hiding rows by a hidden column would be confusing
+        show_cpu: opts.show_cpu || opts.min_cpu.is_some(),
+        show_io: opts.show_io,
+        show_delta: opts.show_delta,
+        offset: 0,
+        selected: 0,
+        paused: false,
+        prompt: None,
+        notice: None,
+        filter: opts.filter,
+        user,
+        zombies: opts.zombies,
+        cgroup: opts.cgroup,
+        min_memory: opts.min_memory,
+        min_cpu: opts.min_cpu,
+        replay: opts.replay.is_some(),
+        events: Vec::new(),
+    };
+
+    let mut alerts = opts.alerts;
+    // A bell in exported output would corrupt it
+    alerts.beep &= !headless;
+    let mut watchdog = alerts.is_enabled().then(|| Watchdog::new(alerts));
+    // Recorded sessions have no ports to watch
+    let mut ports = (exporter.as_ref().is_some_and(Exporter::wants_events)
+        && opts.replay.is_none())
+    .then(PortWatcher::new);
+
     let mut last_frame: Vec<String> = Vec::new();
+    let mut last_width = None;
+    // Every process from the latest refresh; filtering and ordering happen per frame
+    let mut snapshot: Vec<ProcessView> = Vec::new();
+    // Per-PID memory of the previous refresh, for the delta column
+    let mut previous_memory: HashMap<u32, u64> = HashMap::new();
+    let mut summary = SystemSummary::default();
+    let mut schedule = Scheduler::new(interval);
+    let mut force_refresh = true;
 
-    // Initial refresh to establish baseline CPU usage
-    system.refresh_processes_specifics(ProcessesToUpdate::All, process_refresh);
-    system.refresh_memory();
+    if let Source::Live {
+        system,
+        last_sample,
+        ..
+    } = &mut source
+    {
+        // Initial refresh to establish baseline CPU usage
+        system.refresh_processes_specifics(ProcessesToUpdate::All, process_refresh);
+        system.refresh_memory();
+        system.refresh_cpu_usage();
+        *last_sample = Instant::now();
 
-    // Wait a short time for better CPU usage calculation
-    if !opts.once {
-        thread::sleep(Duration::from_millis(100));
+        // Wait a short time for better CPU usage calculation
+        if !opts.once {
+            interrupt.sleep(Duration::from_millis(100));
+        }
     }
 
     loop {
-        tick = tick.wrapping_add(1);
-        let start = Instant::now();
+        let refreshed = force_refresh || (!state.paused && schedule.due());
+        if refreshed {
+            force_refresh = false;
+            let sampled = match &mut source {
+                Source::Live {
+                    system,
+                    users,
+                    last_sample,
+                    recorder,
+                } => {
+                    schedule.start();
 
-        // Use smarter refresh strategy
-        system.refresh_processes_specifics(ProcessesToUpdate::All, process_refresh);
-        system.refresh_memory();
+                    system.refresh_processes_specifics(ProcessesToUpdate::All, process_refresh);
+                    system.refresh_memory();
+                    let read_cgroups =
+                        opts.show_containers || opts.cgroup_limit || state.cgroup.is_some();
+                    snapshot = collect_processes(
+                        system,
+                        users,
+                        last_sample.elapsed(),
+                        opts.show_fds,
+                        read_cgroups,
+                    );
+                    if opts.cgroup_limit {
+                        cgroup::relative_to_limits(&mut snapshot);
+                    }
+                    // Unsupported platforms leave the columns empty
+                    if opts.show_net
+                        && let Ok(counts) = port::socket_counts()
+                    {
+                        for process in &mut snapshot {
+                            let sockets = counts.get(&process.pid).copied().unwrap_or_default();
+                            process.listening = Some(sockets.listening);
+                            process.connections = Some(sockets.established);
+                        }
+                    }
+                    if let Some(gpu) = &mut gpu {
+                        let usage = gpu.sample();
+                        for process in &mut snapshot {
+                            if let Some(usage) = usage.get(&process.pid) {
+                                process.gpu_memory = Some(usage.memory_bytes);
+                                process.gpu_util = usage.utilization;
+                            }
+                        }
+                    }
+                    *last_sample = Instant::now();
+                    system.refresh_cpu_usage();
+                    summary = SystemSummary::sample(system);
 
-        let total_memory = system.total_memory();
-        let used_memory = system.used_memory();
-
-        let mut processes: Vec<ProcessView> = system
-            .processes()
-            .iter()
-            .map(|(pid, process)| {
-                let cmd = process
-                    .cmd()
-                    .iter()
-                    .map(|s| s.to_string_lossy())
-                    .collect::<Vec<_>>()
-                    .join(" ");
-
-                let memory = process.memory();
-                let memory_percent = if total_memory > 0 {
-                    (memory as f64 / total_memory as f64) * 100.0
-                } else {
-                    0.0
-                };
-
-                // CPU usage calculation - use more stable values
-                let cpu_usage = process.cpu_usage();
-
-                ProcessView {
-                    pid: pid.as_u32(),
-                    name: process.name().to_string_lossy().into_owned(),
-                    memory_bytes: memory,
-                    memory_percent,
-                    cpu: cpu_usage,
-                    cmd,
+                    if let Some(recorder) = recorder {
+                        recorder.write(&summary, &snapshot)?;
+                    }
+                    true
                 }
-            })
-            .collect();
-
-        // Sort by memory usage, but factor in CPU usage weight
-        if opts.show_cpu {
-            processes.sort_by(|a, b| {
-                let score_a = a.memory_bytes as f64 * 0.7 + a.cpu as f64 * 1000.0 * 0.3;
-                let score_b = b.memory_bytes as f64 * 0.7 + b.cpu as f64 * 1000.0 * 0.3;
-                let score_a = if score_a.is_nan() { 0.0 } else { score_a };
-                let score_b = if score_b.is_nan() { 0.0 } else { score_b };
-                score_b
-                    .partial_cmp(&score_a)
-                    .unwrap_or(std::cmp::Ordering::Equal)
-            });
-        } else {
-            processes.sort_by(|a, b| b.memory_bytes.cmp(&a.memory_bytes));
+                Source::Replay {
+                    replay,
+                    speed,
+                    finished,
+                } => match replay.next_frame()? {
+                    Some(frame) => {
+                        let gap = replay
+                            .gap_after(frame.elapsed_ms)?
+                            .map_or(interval, Duration::from_millis);
+                        schedule.start_with_gap(gap.div_f32(*speed));
+                        snapshot = frame.processes;
+                        summary = frame.summary;
+                        true
+                    }
+                    // Without a keyboard there is nothing left to look at
+                    None if keys.is_none() => break,
+                    None => {
+                        if !*finished {
+                            state.notice = Some(TopNotice::Info("End of recording".to_string()));
+                        }
+                        *finished = true;
+                        state.paused = true;
+                        false
+                    }
+                },
+            };
+
+            if sampled {
+                set_memory_deltas(&mut snapshot, &mut previous_memory);
+            }
+
+            // Recorded processes are gone; only highlight them
+            let fire = matches!(source, Source::Live { .. });
+            if let Some(watchdog) = &mut watchdog
+                && let Some(summary) = watchdog.check(&mut snapshot, fire)
+            {
+                state.notice = Some(TopNotice::Error(summary));
+            }
+            if fire && let Some(watchdog) = &watchdog {
+                state
+                    .events
+                    .extend(watchdog.crossed().iter().map(|c| Event::ThresholdCrossed {
+                        pid: c.pid,
+                        name: c.name.clone(),
+                        reason: c.reason.clone(),
+                    }));
+            }
+            // A failed listing is retried at the next refresh
+            if sampled
+                && let Some(ports) = &mut ports
+                && let Ok(listing) = port::list_all_ports()
+            {
+                state.events.extend(ports.update(&listing));
+            }
         }
 
-        processes.truncate(opts.limit.max(1));
+        let processes = state.rows(&snapshot, opts.aggregate);
+        state.clamp(processes.len(), limit);
+        let end = (state.offset + limit).min(processes.len());
+
+        let events = std::mem::take(&mut state.events);
+        if let Some(exporter) = &mut exporter {
+            let top_rows = &processes[..limit.min(processes.len())];
+            let written = if refreshed {
+                exporter.write_snapshot(top_rows)
+            } else {
+                Ok(())
+            };
+            match written.and_then(|()| exporter.write_events(&events)) {
+                Ok(()) => {}
+                // The reader went away (e.g. `| head`), nothing left to do
+                Err(e) if e.kind() == io::ErrorKind::BrokenPipe => break,
+                Err(e) => return Err(e).context("Failed to write export"),
+            }
+        }
 
+        let done = opts.once || opts.iterations.is_some_and(|n| schedule.tick() >= n);
+        if headless {
+            if done {
+                break;
+            }
+            schedule.wait(false, None, &interrupt)?;
+            if interrupt.requested() {
+                break;
+            }
+            continue;
+        }
+
+        let width = if opts.batch { None } else { terminal_width() };
+        // After a resize the previous frame no longer matches the screen
+        if width != last_width {
+            last_frame.clear();
+        }
         let render_opts = TopRenderOptions {
-            total_memory,
-            used_memory,
-            refresh: tick,
-            interval: opts.interval,
-            show_cpu: opts.show_cpu,
+            summary: summary.clone(),
+            refresh: schedule.tick(),
+            interval: display_interval,
+            show_cpu: state.show_cpu,
             show_cmd: opts.show_cmd,
+            show_io: state.show_io,
+            show_delta: state.show_delta,
+            show_threads: opts.show_threads,
+            show_fds: opts.show_fds,
+            show_containers: opts.show_containers,
+            show_gpu: opts.show_gpu,
+            show_net: opts.show_net,
+            cgroup_limit: opts.cgroup_limit,
+            width,
             incremental,
+            batch: opts.batch,
+            sort: state.sort,
+            reverse: state.reverse,
+            offset: state.offset,
+            total_processes: processes.len(),
+            paused: state.paused,
+            replay: state.replay,
+            interactive: keys.is_some(),
+            selected: keys.is_some().then(|| state.selected - state.offset),
+            prompt: state
+                .prompt
+                .as_ref()
+                .map(|p| (p.kind.label(), p.input.clone())),
+            notice: state.notice.clone(),
+            filter: state.filter.as_ref().map(|f| f.as_str().to_string()),
+            user: state.user.clone(),
+            cgroup: state.cgroup.clone(),
+            min_memory: state.min_memory,
+            min_cpu: state.min_cpu,
+            zombies: snapshot.iter().filter(|p| p.state == 'Z').count(),
+            expanded: state.expanded.clone(),
         };
 
-        render(&processes, &render_opts, &mut last_frame);
+        match render(&processes[state.offset..end], &render_opts, &mut last_frame) {
+            Ok(()) => {}
+            // Piped into something that stopped reading (e.g. `--batch | head`)
+            Err(e) if e.kind() == io::ErrorKind::BrokenPipe => break,
+            Err(e) => return Err(e).context("Failed to draw top"),
+        }
+        last_width = width;
 
-        if opts.once {
+        if done {
             break;
         }
 
-        // More precise refresh timing control
-        let elapsed = start.elapsed();
-        let target_duration = Duration::from_secs_f32(opts.interval);
-
-        if elapsed < target_duration {
-            let remaining = target_duration - elapsed;
-            thread::sleep(remaining);
+        // Wait for the next refresh; a key press redraws right away
+        let event = schedule.wait(state.paused, keys.as_ref(), &interrupt)?;
+        if interrupt.requested() {
+            break;
+        }
+        match event {
+            Some(InputEvent::Key(key)) => match state.handle_key(key, &processes, limit) {
+                KeyOutcome::Redraw => {}
+                KeyOutcome::Refresh => force_refresh = true,
+                KeyOutcome::Quit => break,
+            },
+            // Redraw at the new size right away
+            Some(InputEvent::Resize) | None => {}
         }
     }
 
-    // Guard auto-drops and restores terminal here
+    // Guards auto-drop and restore the terminal here
     Ok(())
 }
+
+/// Width of the terminal stdout is attached to
+fn terminal_width() -> Option<usize> {
+    if !io::stdout().is_terminal() {
+        return None;
+    }
+    crossterm::terminal::size()
+        .ok()
+        .map(|(columns, _)| columns as usize)
+        .filter(|&columns| columns > 0)
+}
+
+/// Open file descriptors of `pid`; needs permission to read its `/proc` entry
+#[cfg(target_os = "linux")]
+fn open_fd_count(pid: u32) -> Option<usize> {
+    std::fs::read_dir(format!("/proc/{pid}/fd"))
+        .ok()
+        .map(|entries| entries.count())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn open_fd_count(_pid: u32) -> Option<usize> {
+    None
+}
+
+fn unknown_state() -> char {
+    '?'
+}
+
+/// One-letter state code, following `ps`
+fn state_code(status: ProcessStatus) -> char {
+    match status {
+        ProcessStatus::Run => 'R',
+        ProcessStatus::Sleep => 'S',
+        ProcessStatus::Idle => 'I',
+        ProcessStatus::UninterruptibleDiskSleep => 'D',
+        ProcessStatus::Zombie => 'Z',
+        ProcessStatus::Stop => 'T',
+        ProcessStatus::Tracing => 't',
+        ProcessStatus::Dead => 'X',
+        ProcessStatus::Wakekill => 'K',
+        ProcessStatus::Waking => 'W',
+        ProcessStatus::Parked => 'P',
+        ProcessStatus::LockBlocked => 'L',
+        _ => unknown_state(),
+    }
+}
+
+/// Fill in `memory_delta` against the previous refresh and remember this one
+fn set_memory_deltas(snapshot: &mut [ProcessView], previous: &mut HashMap<u32, u64>) {
+    for process in snapshot.iter_mut() {
+        process.memory_delta = previous
+            .get(&process.pid)
+            .map(|&before| process.memory_bytes as i64 - before as i64);
+    }
+    *previous = snapshot.iter().map(|p| (p.pid, p.memory_bytes)).collect();
+}
+
+/// Build the rows; `elapsed` is the time since the previous refresh, for I/O rates
+fn collect_processes(
+    system: &System,
+    users: &Users,
+    elapsed: Duration,
+    count_fds: bool,
+    read_cgroups: bool,
+) -> Vec<ProcessView> {
+    let total_memory = system.total_memory();
+    let secs = elapsed.as_secs_f64().max(0.001);
+
+    system
+        .processes()
+        .iter()
+        .map(|(pid, process)| {
+            let cmd = process
+                .cmd()
+                .iter()
+                .map(|s| s.to_string_lossy())
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            let disk = process.disk_usage();
+            let memory = process.memory();
+            let memory_percent = if total_memory > 0 {
+                (memory as f64 / total_memory as f64) * 100.0
+            } else {
+                0.0
+            };
+
+            ProcessView {
+                pid: pid.as_u32(),
+                name: process.name().to_string_lossy().into_owned(),
+                memory_bytes: memory,
+                memory_percent,
+                cpu: process.cpu_usage(),
+                cmd,
+                state: state_code(process.status()),
+                // Linux lists the other threads as tasks; elsewhere the count is unknown
+                threads: process.tasks().map(|tasks| tasks.len() + 1),
+                open_fds: if count_fds {
+                    open_fd_count(pid.as_u32())
+                } else {
+                    None
+                },
+                user: process.user_id().map(|uid| {
+                    users
+                        .get_user_by_id(uid)
+                        .map(|user| user.name().to_string())
+                        .unwrap_or_else(|| uid.to_string())
+                }),
+                cgroup: if read_cgroups {
+                    cgroup::cgroup_of(pid.as_u32())
+                } else {
+                    None
+                },
+                gpu_memory: None,
+                gpu_util: None,
+                listening: None,
+                connections: None,
+                start_time: process.start_time(),
+                parent: process.parent().map(|p| p.as_u32()),
+                is_thread: process.thread_kind().is_some(),
+                depth: 0,
+                tree_memory: None,
+                read_rate: disk.read_bytes as f64 / secs,
+                write_rate: disk.written_bytes as f64 / secs,
+                alert: false,
+                memory_delta: None,
+                group_size: None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: u32, memory_bytes: u64, cpu: f32) -> ProcessView {
+        ProcessView {
+            pid,
+            name: format!("p{pid}"),
+            memory_bytes,
+            memory_percent: 0.0,
+            cpu,
+            cmd: String::new(),
+            state: 'S',
+            threads: None,
+            open_fds: None,
+            user: None,
+            cgroup: None,
+            gpu_memory: None,
+            gpu_util: None,
+            listening: None,
+            connections: None,
+            start_time: 0,
+            parent: None,
+            is_thread: false,
+            depth: 0,
+            tree_memory: None,
+            read_rate: 0.0,
+            write_rate: 0.0,
+            alert: false,
+            memory_delta: None,
+            group_size: None,
+        }
+    }
+
+    #[test]
+    fn test_thresholds_apply_before_limit() {
+        let snapshot = vec![
+            process(1, 500, 1.0),
+            process(2, 400, 50.0),
+            process(3, 300, 0.5),
+            process(4, 100, 90.0),
+        ];
+        let limit = 2;
+        let top = |state: &ViewState| {
+            let rows = state.rows(&snapshot, false);
+            rows[..limit.min(rows.len())]
+                .iter()
+                .map(|p| p.pid)
+                .collect::<Vec<_>>()
+        };
+
+        // The busiest process is too small, so the next ones fill the rows
+        let mut state = ViewState {
+            sort: SortKey::Cpu,
+            min_memory: Some(250),
+            ..Default::default()
+        };
+        assert_eq!(top(&state), [2, 1]);
+
+        // The largest process is idle; both busy ones still show
+        state = ViewState {
+            min_cpu: Some(10.0),
+            ..Default::default()
+        };
+        assert_eq!(top(&state), [2, 4]);
+    }
+}
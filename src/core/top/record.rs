@@ -0,0 +1,146 @@
+/// `top --record` / `--replay` session files
+///
+/// A session is JSON lines: a header, then one frame per refresh holding every
+/// process (unfiltered), so a replay can still filter, sort and tree the data.
+use super::{ProcessView, SystemSummary};
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Lines, Write};
+use std::path::Path;
+use std::time::Instant;
+
+const FORMAT: &str = "ziro-top";
+const VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct Header {
+    format: String,
+    version: u32,
+    /// Refresh interval of the recording, in seconds
+    interval: f32,
+}
+
+/// One recorded refresh
+#[derive(Deserialize)]
+pub(super) struct Frame {
+    /// Milliseconds since the recording started
+    pub elapsed_ms: u64,
+    #[serde(flatten)]
+    pub summary: SystemSummary,
+    pub processes: Vec<ProcessView>,
+}
+
+#[derive(Serialize)]
+struct FrameRef<'a> {
+    elapsed_ms: u64,
+    #[serde(flatten)]
+    summary: &'a SystemSummary,
+    processes: &'a [ProcessView],
+}
+
+pub(super) struct Recorder {
+    out: BufWriter<File>,
+    start: Instant,
+}
+
+impl Recorder {
+    pub(super) fn create(path: &Path, interval: f32) -> Result<Self> {
+        let file = File::create(path)
+            .with_context(|| format!("Failed to create recording: {}", path.display()))?;
+        let mut out = BufWriter::new(file);
+        let header = Header {
+            format: FORMAT.to_string(),
+            version: VERSION,
+            interval,
+        };
+        serde_json::to_writer(&mut out, &header)?;
+        writeln!(out)?;
+        Ok(Self {
+            out,
+            start: Instant::now(),
+        })
+    }
+
+    pub(super) fn write(
+        &mut self,
+        summary: &SystemSummary,
+        processes: &[ProcessView],
+    ) -> Result<()> {
+        let frame = FrameRef {
+            elapsed_ms: self.start.elapsed().as_millis() as u64,
+            summary,
+            processes,
+        };
+        serde_json::to_writer(&mut self.out, &frame)?;
+        writeln!(self.out)?;
+        // Flush every frame so an interrupted session is still readable
+        self.out.flush().context("Failed to write recording")
+    }
+}
+
+pub(super) struct Replay {
+    lines: Lines<BufReader<File>>,
+    /// Frame read ahead by `gap_after`
+    pending: Option<Frame>,
+    /// Refresh interval of the recording, in seconds
+    pub interval: f32,
+}
+
+impl Replay {
+    pub(super) fn open(path: &Path) -> Result<Self> {
+        let file = File::open(path)
+            .with_context(|| format!("Failed to open recording: {}", path.display()))?;
+        let mut lines = BufReader::new(file).lines();
+        let header: Header = match lines.next() {
+            Some(line) => serde_json::from_str(&line?)
+                .with_context(|| format!("Not a ziro top recording: {}", path.display()))?,
+            None => bail!("Empty recording: {}", path.display()),
+        };
+        if header.format != FORMAT || header.version != VERSION {
+            bail!(
+                "Unsupported recording {} ({} v{})",
+                path.display(),
+                header.format,
+                header.version
+            );
+        }
+        Ok(Self {
+            lines,
+            pending: None,
+            interval: header.interval,
+        })
+    }
+
+    /// The next frame, or `None` at the end of the recording
+    pub(super) fn next_frame(&mut self) -> Result<Option<Frame>> {
+        match self.pending.take() {
+            Some(frame) => Ok(Some(frame)),
+            None => self.read_frame(),
+        }
+    }
+
+    /// Recorded milliseconds between the frame at `elapsed_ms` and the next one
+    pub(super) fn gap_after(&mut self, elapsed_ms: u64) -> Result<Option<u64>> {
+        if self.pending.is_none() {
+            self.pending = self.read_frame()?;
+        }
+        Ok(self
+            .pending
+            .as_ref()
+            .map(|next| next.elapsed_ms.saturating_sub(elapsed_ms)))
+    }
+
+    fn read_frame(&mut self) -> Result<Option<Frame>> {
+        for line in self.lines.by_ref() {
+            let line = line.context("Failed to read recording")?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            return serde_json::from_str(&line)
+                .map(Some)
+                .context("Corrupt frame in recording");
+        }
+        Ok(None)
+    }
+}
@@ -0,0 +1,100 @@
+/// Refresh timing shared by the live views: when the next refresh is due and waiting for it
+use super::input::{InputEvent, KeyInput};
+use super::screen::{Interrupt, POLL};
+use anyhow::Result;
+use std::time::{Duration, Instant};
+
+/// Counts refreshes and keeps the time of the next one
+pub(super) struct Scheduler {
+    interval: Duration,
+    next: Instant,
+    tick: u64,
+}
+
+impl Scheduler {
+    /// The first refresh is due right away
+    pub(super) fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            next: Instant::now(),
+            tick: 0,
+        }
+    }
+
+    pub(super) fn due(&self) -> bool {
+        Instant::now() >= self.next
+    }
+
+    /// Count a refresh and schedule the next one after the interval
+    pub(super) fn start(&mut self) {
+        self.start_with_gap(self.interval);
+    }
+
+    /// Count a refresh and schedule the next one `gap` from now
+    pub(super) fn start_with_gap(&mut self, gap: Duration) {
+        self.tick = self.tick.wrapping_add(1);
+        self.next = Instant::now() + gap;
+    }
+
+    /// Refreshes started so far
+    pub(super) fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// Wait for the next refresh (a whole interval while paused); a key press or resize
+    /// ends the wait early and is returned
+    pub(super) fn wait(
+        &self,
+        paused: bool,
+        keys: Option<&KeyInput>,
+        interrupt: &Interrupt,
+    ) -> Result<Option<InputEvent>> {
+        let wait = if paused {
+            self.interval
+        } else {
+            self.next.saturating_duration_since(Instant::now())
+        };
+        match keys {
+            Some(keys) => wait_for_input(keys, wait, interrupt),
+            None => {
+                interrupt.sleep(wait);
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// Wait up to `wait` for input, checking for interrupts in between
+fn wait_for_input(
+    keys: &KeyInput,
+    wait: Duration,
+    interrupt: &Interrupt,
+) -> Result<Option<InputEvent>> {
+    let deadline = Instant::now() + wait;
+    loop {
+        let left = deadline.saturating_duration_since(Instant::now());
+        if interrupt.requested() || left.is_zero() {
+            return Ok(None);
+        }
+        if let Some(event) = keys.next_event(left.min(POLL))? {
+            return Ok(Some(event));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scheduler() {
+        let mut schedule = Scheduler::new(Duration::from_secs(60));
+        assert!(schedule.due());
+        schedule.start();
+        assert_eq!(schedule.tick(), 1);
+        assert!(!schedule.due());
+        schedule.start_with_gap(Duration::ZERO);
+        assert_eq!(schedule.tick(), 2);
+        assert!(schedule.due());
+    }
+}
@@ -0,0 +1,100 @@
+/// Terminal ownership for the live view: alternate screen, interrupts and panics
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::execute;
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use signal_hook::consts::TERM_SIGNALS;
+use signal_hook::flag;
+use std::io;
+use std::panic;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Once};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often waits check for an interrupt
+pub(super) const POLL: Duration = Duration::from_millis(100);
+
+/// Set while the alternate screen is shown
+static ALT_SCREEN: AtomicBool = AtomicBool::new(false);
+
+/// Alternate screen for the live view; restores the terminal on drop
+pub(super) struct AltScreenGuard;
+
+impl AltScreenGuard {
+    pub(super) fn new() -> Self {
+        install_panic_hook();
+        // crossterm falls back to the console API on terminals without VT support
+        let _ = execute!(
+            io::stdout(),
+            EnterAlternateScreen,
+            Clear(ClearType::All),
+            MoveTo(0, 0),
+            Hide
+        );
+        ALT_SCREEN.store(true, Ordering::SeqCst);
+        Self
+    }
+}
+
+impl Drop for AltScreenGuard {
+    fn drop(&mut self) {
+        restore_terminal();
+    }
+}
+
+/// Leave raw mode and the alternate screen and show the cursor again
+pub(super) fn restore_terminal() {
+    if terminal::is_raw_mode_enabled().unwrap_or(false) {
+        let _ = terminal::disable_raw_mode();
+    }
+    if ALT_SCREEN.swap(false, Ordering::SeqCst) {
+        let _ = execute!(io::stdout(), Show, LeaveAlternateScreen);
+    }
+}
+
+/// Give the terminal back before the panic message is printed; release builds
+/// abort on panic, so guards never get to run
+pub(super) fn install_panic_hook() {
+    static HOOK: Once = Once::new();
+    HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            restore_terminal();
+            previous(info);
+        }));
+    });
+}
+
+/// Ctrl+C and termination requests, turned into a clean exit from the refresh loop
+pub(super) struct Interrupt {
+    requested: Arc<AtomicBool>,
+}
+
+impl Interrupt {
+    /// Catch termination signals; a second one exits immediately
+    pub(super) fn install() -> Self {
+        let requested = Arc::new(AtomicBool::new(false));
+        for &signal in TERM_SIGNALS {
+            // The shutdown check must run before the flag is set, so register it first
+            let _ = flag::register_conditional_shutdown(signal, 130, Arc::clone(&requested));
+            let _ = flag::register(signal, Arc::clone(&requested));
+        }
+        Self { requested }
+    }
+
+    pub(super) fn requested(&self) -> bool {
+        self.requested.load(Ordering::SeqCst)
+    }
+
+    /// Sleep for `duration`, waking early on an interrupt
+    pub(super) fn sleep(&self, duration: Duration) {
+        let deadline = Instant::now() + duration;
+        while !self.requested() {
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                break;
+            }
+            thread::sleep(left.min(POLL));
+        }
+    }
+}
@@ -0,0 +1,236 @@
+/// Parent/child ordering for `top --tree`
+use super::{ProcessView, SortKey};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Reorder `processes` depth-first under their parents, siblings ordered by `sort`
+///
+/// Sets `depth` on every row; with `aggregate`, `tree_memory` holds the memory of
+/// the whole subtree (threads excluded). Processes whose parent is not listed (or
+/// filtered out) are roots.
+pub(super) fn tree_order(
+    mut processes: Vec<ProcessView>,
+    sort: SortKey,
+    reverse: bool,
+    aggregate: bool,
+) -> Vec<ProcessView> {
+    let index: HashMap<u32, usize> = processes
+        .iter()
+        .enumerate()
+        .map(|(i, p)| (p.pid, i))
+        .collect();
+
+    let mut children: Vec<Vec<usize>> = vec![Vec::new(); processes.len()];
+    let mut roots = Vec::new();
+    for (i, process) in processes.iter().enumerate() {
+        match process.parent.and_then(|ppid| index.get(&ppid)) {
+            Some(&parent) if parent != i => children[parent].push(i),
+            _ => roots.push(i),
+        }
+    }
+
+    for process in &mut processes {
+        process.tree_memory = None;
+    }
+    if aggregate {
+        let mut visited = vec![false; processes.len()];
+        for i in 0..processes.len() {
+            subtree_memory(i, &children, &mut processes, &mut visited);
+        }
+    }
+
+    let by_sort = |a: &usize, b: &usize| sort.compare(&processes[*a], &processes[*b], reverse);
+    roots.sort_by(by_sort);
+    for siblings in &mut children {
+        siblings.sort_by(by_sort);
+    }
+
+    let mut order = Vec::with_capacity(processes.len());
+    let mut visited = vec![false; processes.len()];
+    for &root in &roots {
+        walk(root, 0, &children, &mut visited, &mut order);
+    }
+    // Parent cycles (PID reuse) leave rows unreachable from any root
+    for i in 0..processes.len() {
+        if !visited[i] {
+            walk(i, 0, &children, &mut visited, &mut order);
+        }
+    }
+
+    let mut slots: Vec<Option<ProcessView>> = processes.into_iter().map(Some).collect();
+    order
+        .into_iter()
+        .filter_map(|(i, depth)| {
+            let mut process = slots[i].take()?;
+            process.depth = depth;
+            Some(process)
+        })
+        .collect()
+}
+
+fn walk(
+    i: usize,
+    depth: usize,
+    children: &[Vec<usize>],
+    visited: &mut [bool],
+    order: &mut Vec<(usize, usize)>,
+) {
+    if std::mem::replace(&mut visited[i], true) {
+        return;
+    }
+    order.push((i, depth));
+    for &child in &children[i] {
+        walk(child, depth + 1, children, visited, order);
+    }
+}
+
+fn subtree_memory(
+    i: usize,
+    children: &[Vec<usize>],
+    processes: &mut [ProcessView],
+    visited: &mut [bool],
+) -> u64 {
+    if let Some(total) = processes[i].tree_memory {
+        return total;
+    }
+    if std::mem::replace(&mut visited[i], true) {
+        return 0;
+    }
+    let mut total = processes[i].memory_bytes;
+    for &child in &children[i] {
+        let memory = subtree_memory(child, children, processes, visited);
+        // Threads report their process's memory; counting them would double it
+        if !processes[child].is_thread {
+            total += memory;
+        }
+    }
+    processes[i].tree_memory = Some(total);
+    total
+}
+
+/// A process with its descendants, for `ziro pstree`
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessNode {
+    #[serde(flatten)]
+    pub process: ProcessView,
+    /// Ordered by PID
+    pub children: Vec<ProcessNode>,
+}
+
+/// Nest `processes` under their parents, from `root` or from every process
+/// whose parent is not listed
+pub(super) fn build_forest(processes: Vec<ProcessView>, root: Option<u32>) -> Vec<ProcessNode> {
+    let index: HashMap<u32, usize> = processes
+        .iter()
+        .enumerate()
+        .map(|(i, p)| (p.pid, i))
+        .collect();
+
+    let mut children: Vec<Vec<usize>> = vec![Vec::new(); processes.len()];
+    let mut roots = Vec::new();
+    for (i, process) in processes.iter().enumerate() {
+        match process.parent.and_then(|ppid| index.get(&ppid)) {
+            Some(&parent) if parent != i => children[parent].push(i),
+            _ => roots.push(i),
+        }
+    }
+    for siblings in &mut children {
+        siblings.sort_by_key(|&i| processes[i].pid);
+    }
+    roots.sort_by_key(|&i| processes[i].pid);
+
+    let mut slots: Vec<Option<ProcessView>> = processes.into_iter().map(Some).collect();
+    match root {
+        Some(pid) => index
+            .get(&pid)
+            .and_then(|&i| nest(i, &children, &mut slots))
+            .into_iter()
+            .collect(),
+        None => {
+            let mut forest: Vec<ProcessNode> = roots
+                .into_iter()
+                .filter_map(|i| nest(i, &children, &mut slots))
+                .collect();
+            // Parent cycles (PID reuse) leave processes unreachable from any root
+            for i in 0..slots.len() {
+                if let Some(node) = nest(i, &children, &mut slots) {
+                    forest.push(node);
+                }
+            }
+            forest
+        }
+    }
+}
+
+fn nest(
+    i: usize,
+    children: &[Vec<usize>],
+    slots: &mut [Option<ProcessView>],
+) -> Option<ProcessNode> {
+    let process = slots[i].take()?;
+    let children = children[i]
+        .iter()
+        .filter_map(|&child| nest(child, children, slots))
+        .collect();
+    Some(ProcessNode { process, children })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: u32, parent: Option<u32>) -> ProcessView {
+        ProcessView {
+            pid,
+            name: format!("p{pid}"),
+            memory_bytes: 0,
+            memory_percent: 0.0,
+            cpu: 0.0,
+            cmd: String::new(),
+            state: 'S',
+            threads: None,
+            open_fds: None,
+            user: None,
+            cgroup: None,
+            gpu_memory: None,
+            gpu_util: None,
+            listening: None,
+            connections: None,
+            start_time: 0,
+            parent,
+            is_thread: false,
+            depth: 0,
+            tree_memory: None,
+            read_rate: 0.0,
+            write_rate: 0.0,
+            alert: false,
+            memory_delta: None,
+            group_size: None,
+        }
+    }
+
+    fn shape(nodes: &[ProcessNode]) -> String {
+        nodes
+            .iter()
+            .map(|n| format!("{}[{}]", n.process.pid, shape(&n.children)))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    #[test]
+    fn test_build_forest() {
+        let processes = vec![
+            process(5, Some(1)),
+            process(1, None),
+            process(3, Some(1)),
+            process(7, Some(3)),
+            // Parent not listed
+            process(9, Some(100)),
+        ];
+
+        let forest = build_forest(processes.clone(), None);
+        assert_eq!(shape(&forest), "1[3[7[]],5[]],9[]");
+        assert_eq!(shape(&build_forest(processes.clone(), Some(3))), "3[7[]]");
+        assert!(build_forest(processes, Some(42)).is_empty());
+    }
+}
@@ -0,0 +1,151 @@
+/// `ziro watch`: a command re-run on an interval, its output redrawn in place
+use super::input::{self, InputEvent, KeyInput, TopAction};
+use super::schedule::Scheduler;
+use super::screen::{AltScreenGuard, Interrupt};
+use super::terminal_width;
+use crate::platform::{command, term};
+use anyhow::{Context, Result};
+use std::ffi::OsString;
+use std::io::{self, IsTerminal};
+use std::process::Command;
+use std::time::{Duration, SystemTime};
+
+/// What to run and how often
+pub struct WatchOptions {
+    pub program: OsString,
+    pub args: Vec<OsString>,
+    /// Extra environment for the command
+    pub env: Vec<(OsString, OsString)>,
+    /// The command as typed, for the header
+    pub label: String,
+    /// Seconds between the end of one run and the start of the next
+    pub interval: f32,
+    /// Stop after this many runs
+    pub iterations: Option<u64>,
+}
+
+/// Outcome of the latest run
+pub struct WatchFrame {
+    /// Standard output followed by standard error
+    pub output: String,
+    /// Exit code; `None` when killed by a signal or it could not run
+    pub code: Option<i32>,
+    /// Why the command could not run (or was stopped)
+    pub error: Option<String>,
+    pub finished_at: SystemTime,
+}
+
+/// Rendering options for the watch view
+pub struct WatchRenderOptions {
+    pub label: String,
+    pub interval: f32,
+    pub refresh: u64,
+    /// Terminal size; `None` when output isn't a terminal
+    pub width: Option<usize>,
+    pub height: Option<usize>,
+    pub incremental: bool,
+    pub paused: bool,
+    /// Keyboard controls are active
+    pub interactive: bool,
+}
+
+pub fn run_watch(
+    opts: WatchOptions,
+    render: fn(&WatchFrame, &WatchRenderOptions, &mut Vec<String>) -> io::Result<()>,
+) -> Result<()> {
+    let profile = term::global_profile();
+    // Piped into a log, frames are appended one after another
+    let live_view = io::stdout().is_terminal();
+    let interrupt = Interrupt::install();
+    let _guard = (live_view && profile.alt_screen).then(AltScreenGuard::new);
+    let keys = if io::stdin().is_terminal() {
+        KeyInput::new().ok()
+    } else {
+        None
+    };
+
+    let mut schedule = Scheduler::new(Duration::from_secs_f32(opts.interval));
+    let mut frame: Option<WatchFrame> = None;
+    let mut paused = false;
+    let mut last_frame: Vec<String> = Vec::new();
+    let mut last_size = None;
+
+    loop {
+        if !paused && schedule.due() {
+            frame = Some(run_once(&opts));
+            // Counted from the end of the run, so a slow command never runs back to back
+            schedule.start();
+            if interrupt.requested() {
+                break;
+            }
+        }
+        let Some(current) = &frame else {
+            break;
+        };
+
+        let size = terminal_size();
+        if size != last_size {
+            last_frame.clear();
+        }
+        let render_opts = WatchRenderOptions {
+            label: opts.label.clone(),
+            interval: opts.interval,
+            refresh: schedule.tick(),
+            width: size.map(|(width, _)| width),
+            height: size.map(|(_, height)| height),
+            incremental: live_view && profile.incremental,
+            paused,
+            interactive: keys.is_some(),
+        };
+        match render(current, &render_opts, &mut last_frame) {
+            Ok(()) => {}
+            Err(e) if e.kind() == io::ErrorKind::BrokenPipe => break,
+            Err(e) => return Err(e).context("Failed to draw watch"),
+        }
+        last_size = size;
+
+        if opts.iterations.is_some_and(|n| schedule.tick() >= n) {
+            break;
+        }
+        let event = schedule.wait(paused, keys.as_ref(), &interrupt)?;
+        if interrupt.requested() {
+            break;
+        }
+        if let Some(InputEvent::Key(key)) = event {
+            match input::action_for(key) {
+                Some(TopAction::Quit) => break,
+                Some(TopAction::TogglePause) => paused = !paused,
+                _ => {}
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Run the command once, collecting its output; `--command-timeout` stops a hung run
+fn run_once(opts: &WatchOptions) -> WatchFrame {
+    let mut command = Command::new(&opts.program);
+    command.args(&opts.args).envs(opts.env.iter().cloned());
+    let (output, code, error) = match command::run_command(&mut command) {
+        Ok(output) => {
+            let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+            text.push_str(&String::from_utf8_lossy(&output.stderr));
+            (text, output.status.code(), None)
+        }
+        Err(e) => (String::new(), None, Some(format!("{e:#}"))),
+    };
+    WatchFrame {
+        output,
+        code,
+        error,
+        finished_at: SystemTime::now(),
+    }
+}
+
+/// Columns and rows of the terminal stdout is attached to
+fn terminal_size() -> Option<(usize, usize)> {
+    let width = terminal_width()?;
+    let (_, rows) = crossterm::terminal::size().ok()?;
+    Some((width, rows as usize))
+}
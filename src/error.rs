@@ -0,0 +1,140 @@
+//! Failure classes and the exit codes scripts can branch on
+//!
+//! | code | meaning |
+//! |------|---------|
+//! | 0 | success |
+//! | 1 | any other failure |
+//! | 2 | usage error: bad or missing arguments |
+//! | 3 | not found: no such port holder, process or path |
+//! | 4 | permission denied |
+//! | 5 | partially failed: some items of a batch could not be handled |
+//!
+//! ```no_run
+//! if let Err(e) = ziro::ports::find(&[3000]) {
+//!     std::process::exit(ziro::error::exit_code(&e));
+//! }
+//! ```
+use std::fmt;
+
+pub const SUCCESS: i32 = 0;
+pub const FAILURE: i32 = 1;
+pub const USAGE: i32 = 2;
+pub const NOT_FOUND: i32 = 3;
+pub const PERMISSION_DENIED: i32 = 4;
+pub const PARTIAL_FAILURE: i32 = 5;
+
+/// A failure with a known class; anything else in an `anyhow::Error` counts as generic
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ZiroError {
+    /// Arguments that cannot work together or are missing
+    Usage(String),
+    /// The port holder, process or path asked about does not exist
+    NotFound(String),
+    /// The OS refused for lack of privileges
+    PermissionDenied(String),
+    /// Some items of a batch failed
+    PartialFailure(String),
+}
+
+impl ZiroError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::Usage(_) => USAGE,
+            Self::NotFound(_) => NOT_FOUND,
+            Self::PermissionDenied(_) => PERMISSION_DENIED,
+            Self::PartialFailure(_) => PARTIAL_FAILURE,
+        }
+    }
+
+    /// The error for a batch of `total` items of which `failures` failed, `None` if none did
+    ///
+    /// When every item failed for the same reason (not found, permission denied) that class
+    /// is kept; otherwise it is a partial failure. The message reads "`n` of `total` `what`".
+    pub fn batch<'a>(
+        total: usize,
+        failures: impl IntoIterator<Item = &'a anyhow::Error>,
+        what: &str,
+    ) -> Option<Self> {
+        let codes: Vec<i32> = failures.into_iter().map(exit_code).collect();
+        let first = *codes.first()?;
+        let what = crate::ui::i18n::translate(what).unwrap_or(what);
+        let message = crate::tr!("{} of {} {}", codes.len(), total, what);
+        let uniform = codes.len() == total && codes.iter().all(|&code| code == first);
+        Some(match first {
+            NOT_FOUND if uniform => Self::NotFound(message),
+            PERMISSION_DENIED if uniform => Self::PermissionDenied(message),
+            _ => Self::PartialFailure(message),
+        })
+    }
+}
+
+impl fmt::Display for ZiroError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Usage(message)
+            | Self::NotFound(message)
+            | Self::PermissionDenied(message)
+            | Self::PartialFailure(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for ZiroError {}
+
+/// The exit code for `err`: the first classified cause in its chain, where an OS
+/// "permission denied" counts as `PERMISSION_DENIED`, or `FAILURE`
+pub fn exit_code(err: &anyhow::Error) -> i32 {
+    err.chain()
+        .find_map(|cause| {
+            if let Some(error) = cause.downcast_ref::<ZiroError>() {
+                return Some(error.exit_code());
+            }
+            cause
+                .downcast_ref::<std::io::Error>()
+                .filter(|io_err| io_err.kind() == std::io::ErrorKind::PermissionDenied)
+                .map(|_| PERMISSION_DENIED)
+        })
+        .unwrap_or(FAILURE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::{Context, anyhow};
+
+    #[test]
+    fn test_exit_code() {
+        assert_eq!(exit_code(&anyhow!("boom")), FAILURE);
+        assert_eq!(
+            exit_code(&anyhow!(ZiroError::NotFound("gone".into())).context("while killing")),
+            NOT_FOUND
+        );
+        let refused: anyhow::Result<()> =
+            Err(std::io::Error::from(std::io::ErrorKind::PermissionDenied).into());
+        assert_eq!(
+            exit_code(&refused.context("Failed to signal").unwrap_err()),
+            PERMISSION_DENIED
+        );
+    }
+
+    #[test]
+    fn test_batch() {
+        let gone = anyhow!(ZiroError::NotFound("gone".into()));
+        let other = anyhow!("boom");
+        assert_eq!(ZiroError::batch(2, [], "failed"), None);
+        assert_eq!(
+            ZiroError::batch(2, [&gone, &gone], "processes could not be killed"),
+            Some(ZiroError::NotFound(
+                "2 of 2 processes could not be killed".into()
+            ))
+        );
+        assert_eq!(
+            ZiroError::batch(3, [&gone, &gone], "failed").map(|e| e.exit_code()),
+            Some(PARTIAL_FAILURE)
+        );
+        assert_eq!(
+            ZiroError::batch(2, [&gone, &other], "failed").map(|e| e.exit_code()),
+            Some(PARTIAL_FAILURE)
+        );
+    }
+}
@@ -0,0 +1,32 @@
+//! Planning deletions the way `ziro remove` does
+//!
+//! ```no_run
+//! use std::path::PathBuf;
+//! use ziro::fs::{CollectOptions, remove_plan};
+//!
+//! let opts = CollectOptions {
+//!     recursive: true,
+//!     ..Default::default()
+//! };
+//! let plan = remove_plan(&[PathBuf::from("target")], &opts)?;
+//! let bytes: u64 = plan.files.iter().filter(|f| !f.is_dir).map(|f| f.size).sum();
+//! println!("{} entries, {bytes} bytes", plan.files.len());
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+use crate::core::fs_ops;
+use anyhow::Result;
+use std::path::PathBuf;
+
+pub use crate::core::fs_ops::{
+    CollectOptions, FileInfo, IgnoreFilter, LeftOutEntries, RemovalPlan, SkippedEntries,
+    SymlinkPolicy,
+};
+
+/// Everything removing `paths` would delete
+///
+/// Nothing is touched. Fails if a path does not exist, or on the first unreadable
+/// entry unless `opts.keep_going` is set.
+pub fn remove_plan(paths: &[PathBuf], opts: &CollectOptions) -> Result<RemovalPlan> {
+    fs_ops::validate_paths(paths)?;
+    fs_ops::collect_files_to_remove(paths, opts)
+}
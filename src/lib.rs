@@ -1,4 +1,13 @@
+//! Ziro as a library: port lookup, process termination and deletion planning
+//!
+//! The modules below return typed results and never print or prompt; the `ziro`
+//! binary is built on top of them. `cli`, `core`, `platform` and `ui` hold the
+//! command-line tool itself and may change between releases.
 pub mod cli;
 pub mod core;
+pub mod error;
+pub mod fs;
 pub mod platform;
+pub mod ports;
+pub mod processes;
 pub mod ui;
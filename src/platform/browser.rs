@@ -0,0 +1,49 @@
+/// Opening URLs in the default browser through the platform's own launcher
+use super::{command::find_program, wsl};
+use anyhow::{Context, Result, bail};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// Hand `url` to the default browser; returns once the launcher has it
+pub fn open_url(url: &str) -> Result<()> {
+    let Some((program, args)) = launcher() else {
+        bail!("No way to open a browser found (install xdg-utils, or open {url} yourself)");
+    };
+    tracing::debug!("opening {url} with {}", program.display());
+    let status = Command::new(&program)
+        .args(args)
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .with_context(|| format!("Failed to run {}", program.display()))?;
+    // explorer.exe exits with 1 even when it opened the page
+    if !status.success() && !program.ends_with("explorer.exe") {
+        bail!(
+            "{} failed with {status}; open {url} yourself",
+            program.display()
+        );
+    }
+    Ok(())
+}
+
+/// The launcher and the arguments before the URL
+fn launcher() -> Option<(PathBuf, &'static [&'static str])> {
+    if cfg!(target_os = "macos") {
+        return Some((PathBuf::from("open"), &[]));
+    }
+    if cfg!(windows) {
+        // `start` treats its first quoted argument as the window title
+        return Some((PathBuf::from("cmd"), &["/C", "start", ""]));
+    }
+    if wsl::is_wsl() {
+        // wslu's wslview, else the Windows shell through interop
+        return find_program("wslview")
+            .map(|path| (path, &[][..]))
+            .or_else(|| Some((wsl::windows_program("explorer.exe"), &[])));
+    }
+    [("xdg-open", &[][..]), ("gio", &["open"][..])]
+        .into_iter()
+        .find_map(|(program, args)| Some((find_program(program)?, args)))
+}
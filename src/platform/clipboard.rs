@@ -0,0 +1,102 @@
+/// The system clipboard through the platform's own tools, with the OSC 52 terminal
+/// escape as a fallback (which also reaches the local clipboard over SSH)
+use super::command::find_program;
+use anyhow::{Context, Result, bail};
+use std::io::{self, IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+/// Put `text` on the clipboard
+pub fn copy(text: &str) -> Result<()> {
+    for (program, args) in candidates() {
+        if find_program(program).is_none() {
+            continue;
+        }
+        tracing::debug!("copying with {program}");
+        return pipe_into(program, args, text);
+    }
+    if io::stdout().is_terminal() {
+        return osc52(text);
+    }
+    bail!("No clipboard tool found (install wl-clipboard, xclip or xsel)")
+}
+
+/// Tools to try, in order, with their arguments
+fn candidates() -> Vec<(&'static str, &'static [&'static str])> {
+    if cfg!(target_os = "macos") {
+        vec![("pbcopy", &[])]
+    } else if cfg!(windows) {
+        vec![("clip", &[])]
+    } else {
+        let mut tools: Vec<(&str, &[&str])> = Vec::new();
+        if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+            tools.push(("wl-copy", &[]));
+        }
+        if std::env::var_os("DISPLAY").is_some() {
+            tools.push(("xclip", &["-selection", "clipboard"]));
+            tools.push(("xsel", &["--clipboard", "--input"]));
+        }
+        tools
+    }
+}
+
+fn pipe_into(program: &str, args: &[&str], text: &str) -> Result<()> {
+    // xclip and wl-copy stay around to serve the selection; they must not hold our pipes
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("Failed to run {program}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(text.as_bytes())
+            .with_context(|| format!("Failed to write to {program}"))?;
+    }
+    let status = child.wait()?;
+    if !status.success() {
+        bail!("{program} failed with {status}");
+    }
+    Ok(())
+}
+
+/// Ask the terminal to set the clipboard; terminals without support ignore it
+fn osc52(text: &str) -> Result<()> {
+    let mut stdout = io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", base64(text.as_bytes()))?;
+    stdout.flush()?;
+    Ok(())
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base64() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"8080 1234"), "ODA4MCAxMjM0");
+    }
+}
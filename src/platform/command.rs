@@ -0,0 +1,163 @@
+/// Running external tools (lsof, netstat, PowerShell, ...) without hanging on them
+use anyhow::{Context, Result};
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::process::{Child, Command, Output, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Used unless `--command-timeout` or `ZIRO_COMMAND_TIMEOUT` (seconds) say otherwise
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Milliseconds; 0 means not set
+static TIMEOUT_MS: AtomicU64 = AtomicU64::new(0);
+
+/// Override the timeout of `run_command` for the rest of the run
+pub fn set_command_timeout(timeout: Duration) {
+    TIMEOUT_MS.store(timeout.as_millis().max(1) as u64, Ordering::Relaxed);
+}
+
+/// The timeout `run_command` applies
+pub fn command_timeout() -> Duration {
+    match TIMEOUT_MS.load(Ordering::Relaxed) {
+        0 => std::env::var("ZIRO_COMMAND_TIMEOUT")
+            .ok()
+            .and_then(|secs| secs.trim().parse::<u64>().ok())
+            .filter(|&secs| secs > 0)
+            .map_or(DEFAULT_TIMEOUT, Duration::from_secs),
+        ms => Duration::from_millis(ms),
+    }
+}
+
+/// `run_command_with_timeout` with the configured timeout
+pub fn run_command(command: &mut Command) -> Result<Output> {
+    run_command_with_timeout(command, command_timeout())
+}
+
+/// Like `Command::output`, but kills the child once `timeout` has passed
+///
+/// Stdin is closed so a tool waiting for input cannot block. A timeout is reported as an
+/// `io::ErrorKind::TimedOut` error in the chain.
+pub fn run_command_with_timeout(command: &mut Command, timeout: Duration) -> Result<Output> {
+    let program = command.get_program().to_string_lossy().into_owned();
+    tracing::debug!(
+        "running {program} {:?}",
+        command.get_args().collect::<Vec<_>>()
+    );
+    let started = Instant::now();
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run {program}"))?;
+
+    // Read both pipes while waiting, or a chatty child blocks on a full pipe
+    let stdout = child.stdout.take().map(drain);
+    let stderr = child.stderr.take().map(drain);
+
+    let status = match wait_with_deadline(&mut child, Instant::now() + timeout)? {
+        Some(status) => status,
+        None => {
+            // Killing only the child may leave grandchildren holding the pipes open, so
+            // the reader threads are left behind rather than joined
+            let _ = child.kill();
+            let _ = child.wait();
+            tracing::debug!("{program} timed out after {timeout:?}");
+            return Err(
+                anyhow::Error::new(io::Error::from(io::ErrorKind::TimedOut)).context(format!(
+                    "{program} did not finish within {}s and was stopped",
+                    timeout.as_secs_f32()
+                )),
+            );
+        }
+    };
+
+    tracing::debug!(
+        "{program} exited with {status} after {:?}",
+        started.elapsed()
+    );
+    let collect = |reader: Option<JoinHandle<Vec<u8>>>| {
+        reader
+            .map(|handle| handle.join().unwrap_or_default())
+            .unwrap_or_default()
+    };
+    Ok(Output {
+        status,
+        stdout: collect(stdout),
+        stderr: collect(stderr),
+    })
+}
+
+/// Where `program` would be found on `PATH` (with the `PATHEXT` extensions on Windows)
+pub fn find_program(program: &str) -> Option<PathBuf> {
+    let extensions: Vec<String> = if cfg!(windows) {
+        std::env::var("PATHEXT")
+            .unwrap_or_else(|_| ".EXE;.CMD;.BAT;.COM".to_string())
+            .split(';')
+            .map(str::to_string)
+            .collect()
+    } else {
+        vec![String::new()]
+    };
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .flat_map(|dir| {
+            extensions
+                .iter()
+                .map(move |ext| dir.join(format!("{program}{ext}")))
+        })
+        .find(|path| path.is_file())
+}
+
+fn drain(mut pipe: impl Read + Send + 'static) -> JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = pipe.read_to_end(&mut buf);
+        buf
+    })
+}
+
+/// Poll the child until it exits (`Some`) or the deadline passes (`None`)
+fn wait_with_deadline(
+    child: &mut Child,
+    deadline: Instant,
+) -> Result<Option<std::process::ExitStatus>> {
+    let mut delay = Duration::from_millis(5);
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return Ok(None);
+        }
+        thread::sleep(delay.min(deadline - now));
+        delay = (delay * 2).min(Duration::from_millis(100));
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_command_with_timeout() {
+        let output =
+            run_command_with_timeout(Command::new("echo").arg("hi"), Duration::from_secs(5))
+                .unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"hi\n");
+
+        let started = Instant::now();
+        let err =
+            run_command_with_timeout(Command::new("sleep").arg("10"), Duration::from_millis(100))
+                .unwrap_err();
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(
+            err.chain()
+                .filter_map(|cause| cause.downcast_ref::<io::Error>())
+                .any(|e| e.kind() == io::ErrorKind::TimedOut)
+        );
+    }
+}
@@ -0,0 +1,55 @@
+/// Per-user directories for ziro's own files
+use std::env;
+use std::path::PathBuf;
+
+/// Directory for state such as the trash journal (not created here)
+pub fn state_dir() -> Option<PathBuf> {
+    #[cfg(target_os = "windows")]
+    {
+        env::var_os("LOCALAPPDATA").map(|dir| PathBuf::from(dir).join("ziro"))
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        home_dir().map(|home| home.join("Library/Application Support/ziro"))
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        env::var_os("XDG_STATE_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| home_dir().map(|home| home.join(".local/state")))
+            .map(|dir| dir.join("ziro"))
+    }
+}
+
+/// Directory holding `config.toml`: `%APPDATA%\ziro` on Windows, `~/.config/ziro` elsewhere
+pub fn config_dir() -> Option<PathBuf> {
+    #[cfg(target_os = "windows")]
+    {
+        env::var_os("APPDATA").map(|dir| PathBuf::from(dir).join("ziro"))
+    }
+
+    // macOS too: command-line tools are expected under ~/.config rather than Library
+    #[cfg(not(target_os = "windows"))]
+    {
+        env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| home_dir().map(|home| home.join(".config")))
+            .map(|dir| dir.join("ziro"))
+    }
+}
+
+/// The user's home directory
+pub fn home_dir() -> Option<PathBuf> {
+    let var = if cfg!(target_os = "windows") {
+        "USERPROFILE"
+    } else {
+        "HOME"
+    };
+    env::var_os(var)
+        .filter(|home| !home.is_empty())
+        .map(PathBuf::from)
+}
@@ -0,0 +1,121 @@
+/// Reverse DNS lookups through the system resolver (`getnameinfo`)
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::thread;
+
+/// How many lookups run at once; each may wait for the resolver's own timeout
+const PARALLEL_LOOKUPS: usize = 16;
+
+/// Host names of `ips`, each looked up once; addresses without a name are left out
+pub fn reverse_lookup_all(ips: impl IntoIterator<Item = IpAddr>) -> HashMap<IpAddr, String> {
+    let mut unique: Vec<IpAddr> = ips.into_iter().collect();
+    unique.sort_unstable();
+    unique.dedup();
+
+    let mut names = HashMap::new();
+    for batch in unique.chunks(PARALLEL_LOOKUPS) {
+        thread::scope(|scope| {
+            let lookups: Vec<_> = batch
+                .iter()
+                .map(|&ip| scope.spawn(move || (ip, reverse_lookup(ip))))
+                .collect();
+            for lookup in lookups {
+                if let Ok((ip, Some(name))) = lookup.join() {
+                    names.insert(ip, name);
+                }
+            }
+        });
+    }
+    names
+}
+
+/// Host name of `ip`, if the resolver knows one
+#[cfg(unix)]
+pub fn reverse_lookup(ip: IpAddr) -> Option<String> {
+    use nix::sys::socket::{SockaddrLike, SockaddrStorage};
+
+    let addr = SockaddrStorage::from(SocketAddr::new(ip, 0));
+    let mut host = [0 as libc::c_char; 1025];
+    // SAFETY: the address and buffer outlive the call, with their lengths passed along
+    let status = unsafe {
+        libc::getnameinfo(
+            addr.as_ptr(),
+            addr.len(),
+            host.as_mut_ptr(),
+            host.len() as _,
+            std::ptr::null_mut(),
+            0,
+            libc::NI_NAMEREQD,
+        )
+    };
+    if status != 0 {
+        return None;
+    }
+    // SAFETY: getnameinfo wrote a NUL-terminated name into the buffer
+    let name = unsafe { std::ffi::CStr::from_ptr(host.as_ptr()) };
+    Some(name.to_string_lossy().into_owned())
+}
+
+#[cfg(windows)]
+pub fn reverse_lookup(ip: IpAddr) -> Option<String> {
+    use std::sync::Once;
+    use windows_sys::Win32::Networking::WinSock::{
+        AF_INET, AF_INET6, GetNameInfoW, NI_NAMEREQD, SOCKADDR, SOCKADDR_IN, SOCKADDR_IN6, WSADATA,
+        WSAStartup,
+    };
+
+    static STARTUP: Once = Once::new();
+    // SAFETY: WSAStartup fills the WSADATA it is given; Winsock stays loaded for the run
+    STARTUP.call_once(|| unsafe {
+        let mut data: WSADATA = std::mem::zeroed();
+        WSAStartup(0x0202, &mut data);
+    });
+
+    let mut host = [0u16; 1025];
+    // SAFETY: the sockaddr structs are plain data; each outlives the call it is passed to
+    let status = unsafe {
+        let lookup = |addr: *const SOCKADDR, len: usize, host: &mut [u16]| {
+            GetNameInfoW(
+                addr,
+                len as i32,
+                host.as_mut_ptr(),
+                host.len() as u32,
+                std::ptr::null_mut(),
+                0,
+                NI_NAMEREQD as i32,
+            )
+        };
+        match SocketAddr::new(ip, 0) {
+            SocketAddr::V4(v4) => {
+                let mut addr: SOCKADDR_IN = std::mem::zeroed();
+                addr.sin_family = AF_INET;
+                addr.sin_addr.S_un.S_addr = u32::from_ne_bytes(v4.ip().octets());
+                lookup(
+                    (&addr as *const SOCKADDR_IN).cast(),
+                    size_of::<SOCKADDR_IN>(),
+                    &mut host,
+                )
+            }
+            SocketAddr::V6(v6) => {
+                let mut addr: SOCKADDR_IN6 = std::mem::zeroed();
+                addr.sin6_family = AF_INET6;
+                addr.sin6_addr.u.Byte = v6.ip().octets();
+                lookup(
+                    (&addr as *const SOCKADDR_IN6).cast(),
+                    size_of::<SOCKADDR_IN6>(),
+                    &mut host,
+                )
+            }
+        }
+    };
+    if status != 0 {
+        return None;
+    }
+    let len = host.iter().position(|&c| c == 0).unwrap_or(host.len());
+    Some(String::from_utf16_lossy(&host[..len]))
+}
+
+#[cfg(not(any(unix, windows)))]
+pub fn reverse_lookup(_ip: IpAddr) -> Option<String> {
+    None
+}
@@ -0,0 +1,266 @@
+/// Detecting missing privileges and re-running ziro with them (sudo / UAC)
+use anyhow::{Context, Result};
+use std::ffi::OsString;
+use std::process::{Command, ExitStatus};
+
+/// Whether ziro runs as root (Unix) or as an elevated administrator (Windows)
+#[cfg(unix)]
+pub fn is_elevated() -> bool {
+    nix::unistd::geteuid().is_root()
+}
+
+#[cfg(windows)]
+pub fn is_elevated() -> bool {
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows_sys::Win32::Security::{
+        GetTokenInformation, TOKEN_ELEVATION, TOKEN_QUERY, TokenElevation,
+    };
+    use windows_sys::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+    // SAFETY: plain Win32 calls on a token handle we own and close
+    unsafe {
+        let mut token: HANDLE = std::ptr::null_mut();
+        if OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token) == 0 {
+            return false;
+        }
+        let mut elevation = TOKEN_ELEVATION { TokenIsElevated: 0 };
+        let mut size = 0u32;
+        let ok = GetTokenInformation(
+            token,
+            TokenElevation,
+            (&mut elevation as *mut TOKEN_ELEVATION).cast(),
+            size_of::<TOKEN_ELEVATION>() as u32,
+            &mut size,
+        );
+        CloseHandle(token);
+        ok != 0 && elevation.TokenIsElevated != 0
+    }
+}
+
+/// Whether `err` comes down to missing privileges
+pub fn is_permission_error(err: &anyhow::Error) -> bool {
+    err.chain()
+        .filter_map(|cause| cause.downcast_ref::<std::io::Error>())
+        .any(|io_err| io_err.kind() == std::io::ErrorKind::PermissionDenied)
+}
+
+/// Why the OS refused to act on a process or file
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(tag = "reason", content = "owner", rename_all = "snake_case")]
+pub enum Refusal {
+    /// It belongs to another (named) user
+    OtherUser(String),
+    /// It belongs to root, SYSTEM or a service account
+    System,
+    /// Refused although ziro is already elevated: a protected process, an immutable
+    /// file, a read-only mount
+    Protected,
+    /// Nothing more specific found; elevated privileges should do
+    NeedsElevation,
+}
+
+impl Refusal {
+    /// Whether re-running elevated can get past the refusal
+    pub fn elevation_helps(&self) -> bool {
+        !matches!(self, Self::Protected)
+    }
+}
+
+impl std::fmt::Display for Refusal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OtherUser(user) => write!(f, "it belongs to user {user}"),
+            Self::System if cfg!(windows) => write!(f, "it belongs to a system account"),
+            Self::System => write!(f, "it belongs to root"),
+            Self::Protected => write!(f, "it is protected, even from administrators"),
+            Self::NeedsElevation => write!(f, "administrator privileges are required"),
+        }
+    }
+}
+
+/// A refused operation and the reason found for it, e.g.
+/// `Failed to signal process 1: it belongs to root`
+#[derive(Debug)]
+pub struct Refused {
+    pub action: String,
+    pub refusal: Refusal,
+}
+
+impl std::fmt::Display for Refused {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.action, self.refusal)
+    }
+}
+
+/// The reason attached to `err`, if the refusal was diagnosed
+pub fn refusal_of(err: &anyhow::Error) -> Option<&Refusal> {
+    // anyhow finds context values at any depth of the chain
+    err.downcast_ref::<Refused>()
+        .map(|refused| &refused.refusal)
+}
+
+/// Whether `err` is a refusal that re-running elevated can get past
+pub fn elevation_would_help(err: &anyhow::Error) -> bool {
+    is_permission_error(err) && refusal_of(err).is_none_or(Refusal::elevation_helps)
+}
+
+/// How to get the privileges, for error hints
+pub fn elevation_hint() -> &'static str {
+    if cfg!(windows) {
+        "run it from an administrator terminal, or add --elevate"
+    } else {
+        "run it with sudo, or add --elevate"
+    }
+}
+
+/// Run this ziro binary again with `args` as root / administrator and wait for it
+///
+/// On Unix this goes through `sudo` in the same terminal. On Windows the UAC prompt
+/// starts it in a new console window, so only its exit status comes back.
+pub fn run_elevated(args: &[OsString]) -> Result<ExitStatus> {
+    let exe = std::env::current_exe().context("Cannot locate the ziro executable")?;
+    let mut command = elevated_command(exe.into_os_string(), args);
+    command
+        .status()
+        .with_context(|| format!("Failed to start {} (is it installed?)", elevator_name()))
+}
+
+#[cfg(unix)]
+fn elevated_command(exe: OsString, args: &[OsString]) -> Command {
+    let mut command = Command::new("sudo");
+    command.arg(exe).args(args);
+    command
+}
+
+#[cfg(windows)]
+fn elevated_command(exe: OsString, args: &[OsString]) -> Command {
+    // Start-Process joins an argument array with bare spaces, splitting paths that
+    // contain them, so it gets one ready-quoted command line instead
+    let list = if args.is_empty() {
+        String::new()
+    } else {
+        format!(
+            " -ArgumentList {}",
+            powershell_string(&windows_command_line(args))
+        )
+    };
+    let script = format!(
+        "$p = Start-Process -FilePath {}{list} -Verb RunAs -Wait -PassThru; exit $p.ExitCode",
+        powershell_string(&exe.to_string_lossy())
+    );
+    let mut command = Command::new("powershell");
+    command.args(["-NoProfile", "-NonInteractive", "-Command", &script]);
+    command
+}
+
+/// Single-quoted PowerShell literal
+#[cfg(windows)]
+fn powershell_string(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+/// `args` joined into one command line that `CommandLineToArgvW` splits back into them
+///
+/// Arguments with whitespace or quotes are double-quoted; quotes inside are escaped
+/// with a backslash, and backslashes before a quote are doubled.
+#[cfg(any(windows, test))]
+fn windows_command_line(args: &[OsString]) -> String {
+    let mut line = String::new();
+    for arg in args {
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        let arg = arg.to_string_lossy();
+        if !arg.is_empty() && !arg.contains([' ', '\t', '\n', '"']) {
+            line.push_str(&arg);
+            continue;
+        }
+        line.push('"');
+        let mut backslashes = 0;
+        for c in arg.chars() {
+            match c {
+                '\\' => backslashes += 1,
+                '"' => {
+                    line.extend(std::iter::repeat_n('\\', backslashes * 2 + 1));
+                    line.push('"');
+                    backslashes = 0;
+                }
+                _ => {
+                    line.extend(std::iter::repeat_n('\\', backslashes));
+                    line.push(c);
+                    backslashes = 0;
+                }
+            }
+        }
+        // The closing quote must not be escaped by trailing backslashes
+        line.extend(std::iter::repeat_n('\\', backslashes * 2));
+        line.push('"');
+    }
+    line
+}
+
+fn elevator_name() -> &'static str {
+    if cfg!(windows) { "PowerShell" } else { "sudo" }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_permission_error() {
+        let denied = anyhow::Error::new(std::io::Error::from(std::io::ErrorKind::PermissionDenied))
+            .context("Failed to delete file: /etc/shadow");
+        assert!(is_permission_error(&denied));
+        assert!(!is_permission_error(&anyhow::anyhow!(
+            "Process 1 does not exist"
+        )));
+    }
+
+    #[test]
+    fn test_elevation_would_help() {
+        let refused = |refusal| {
+            anyhow::Error::new(std::io::Error::from(std::io::ErrorKind::PermissionDenied)).context(
+                Refused {
+                    action: "Failed to signal process 1".to_string(),
+                    refusal,
+                },
+            )
+        };
+        let other = refused(Refusal::OtherUser("alice".to_string()));
+        assert_eq!(
+            other.to_string(),
+            "Failed to signal process 1: it belongs to user alice"
+        );
+        assert!(elevation_would_help(&other));
+        assert!(!elevation_would_help(&refused(Refusal::Protected)));
+        assert_eq!(
+            refusal_of(&refused(Refusal::System)),
+            Some(&Refusal::System)
+        );
+        // Undiagnosed refusals are still worth an elevated retry
+        assert!(elevation_would_help(&anyhow::Error::new(
+            std::io::Error::from(std::io::ErrorKind::PermissionDenied)
+        )));
+    }
+
+    #[test]
+    fn test_windows_command_line() {
+        let line = |args: &[&str]| {
+            windows_command_line(&args.iter().map(OsString::from).collect::<Vec<_>>())
+        };
+        assert_eq!(
+            line(&["remove", "-f", "C:\\tmp\\a"]),
+            "remove -f C:\\tmp\\a"
+        );
+        assert_eq!(
+            line(&["remove", "C:\\Program Files\\app"]),
+            "remove \"C:\\Program Files\\app\""
+        );
+        // A trailing backslash must not escape the closing quote
+        assert_eq!(line(&["C:\\My Dir\\"]), "\"C:\\My Dir\\\\\"");
+        assert_eq!(line(&["say \"hi\""]), "\"say \\\"hi\\\"\"");
+        assert_eq!(line(&["a\\\"b"]), "\"a\\\\\\\"b\"");
+        assert_eq!(line(&["", "x"]), "\"\" x");
+    }
+}
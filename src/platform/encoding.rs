@@ -1,10 +1,23 @@
+#[cfg(target_os = "windows")]
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// UTF-8 as a Windows code page
+pub const CP_UTF8: u32 = 65001;
+
+/// Console output code page before `init_windows_console` switched it; 0 if unknown
+#[cfg(target_os = "windows")]
+static ORIGINAL_OUTPUT_CP: AtomicU32 = AtomicU32::new(0);
+
 // Windows console UTF-8 initialization
 #[cfg(target_os = "windows")]
 pub fn init_windows_console() {
-    use windows_sys::Win32::System::Console::{SetConsoleCP, SetConsoleOutputCP};
+    use windows_sys::Win32::System::Console::{
+        GetConsoleOutputCP, SetConsoleCP, SetConsoleOutputCP,
+    };
     unsafe {
-        SetConsoleOutputCP(65001);
-        SetConsoleCP(65001);
+        ORIGINAL_OUTPUT_CP.store(GetConsoleOutputCP(), Ordering::Relaxed);
+        SetConsoleOutputCP(CP_UTF8);
+        SetConsoleCP(CP_UTF8);
     }
 
     enable_virtual_terminal_processing();
@@ -14,6 +27,36 @@ pub fn init_windows_console() {
 #[cfg(not(target_os = "windows"))]
 pub fn init_windows_console() {}
 
+/// Code pages native tools (netstat, wmic, handle.exe) may write in when not UTF-8,
+/// most likely first: the console's own as it was before ziro switched it, then the
+/// OEM and ANSI code pages of the system
+#[cfg(target_os = "windows")]
+pub fn legacy_codepages() -> Vec<u32> {
+    use windows_sys::Win32::Globalization::{GetACP, GetOEMCP};
+    use windows_sys::Win32::System::Console::GetConsoleOutputCP;
+
+    // SAFETY: plain queries without arguments
+    let (console, oem, ansi) = unsafe { (GetConsoleOutputCP(), GetOEMCP(), GetACP()) };
+    let mut codepages = Vec::new();
+    for cp in [
+        ORIGINAL_OUTPUT_CP.load(Ordering::Relaxed),
+        console,
+        oem,
+        ansi,
+    ] {
+        if cp != 0 && cp != CP_UTF8 && !codepages.contains(&cp) {
+            codepages.push(cp);
+        }
+    }
+    codepages
+}
+
+/// Tools elsewhere write UTF-8
+#[cfg(not(target_os = "windows"))]
+pub fn legacy_codepages() -> Vec<u32> {
+    Vec::new()
+}
+
 /// Enable Windows console virtual terminal sequences for cursor movement, screen clearing, etc.
 #[cfg(target_os = "windows")]
 fn enable_virtual_terminal_processing() {
@@ -1,2 +1,12 @@
+pub mod browser;
+pub mod clipboard;
+pub mod command;
+pub mod dirs;
+pub mod dns;
+pub mod elevation;
 pub mod encoding;
+pub mod notify;
+pub mod probe;
+pub mod temp;
 pub mod term;
+pub mod wsl;
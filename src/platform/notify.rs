@@ -0,0 +1,63 @@
+/// Desktop notifications through the platform's own tools
+use std::process::{Command, Stdio};
+use std::thread;
+
+/// Show a desktop notification; best effort, failures are ignored
+pub fn desktop_notify(title: &str, body: &str) {
+    let mut command = notify_command(title, body);
+    let spawned = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    if let Ok(mut child) = spawned {
+        thread::spawn(move || child.wait());
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn notify_command(title: &str, body: &str) -> Command {
+    let script = format!(
+        "display notification {} with title {}",
+        applescript_string(body),
+        applescript_string(title)
+    );
+    let mut command = Command::new("osascript");
+    command.args(["-e", &script]);
+    command
+}
+
+#[cfg(target_os = "macos")]
+fn applescript_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(target_os = "windows")]
+fn notify_command(title: &str, body: &str) -> Command {
+    // Balloon tip via Windows Forms, available in every PowerShell without extra modules
+    let script = format!(
+        "Add-Type -AssemblyName System.Windows.Forms; \
+         $n = New-Object System.Windows.Forms.NotifyIcon; \
+         $n.Icon = [System.Drawing.SystemIcons]::Information; \
+         $n.Visible = $true; \
+         $n.ShowBalloonTip(5000, {}, {}, 'Info'); \
+         Start-Sleep -Seconds 6; $n.Dispose()",
+        powershell_string(title),
+        powershell_string(body)
+    );
+    let mut command = Command::new("powershell");
+    command.args(["-NoProfile", "-NonInteractive", "-Command", &script]);
+    command
+}
+
+#[cfg(target_os = "windows")]
+fn powershell_string(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn notify_command(title: &str, body: &str) -> Command {
+    let mut command = Command::new("notify-send");
+    command.args(["--app-name=ziro", title, body]);
+    command
+}
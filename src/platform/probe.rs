@@ -0,0 +1,290 @@
+/// Asking the terminal what it supports instead of guessing from the environment
+///
+/// On Unix the terminal is sent a UTF-8 character followed by a cursor position report
+/// (CPR), a DECRQM query for the alternate screen and Primary Device Attributes (DA1),
+/// which every VT-compatible terminal answers. The answers are kept per terminal session
+/// so later runs in the same shell skip the round trip. A Windows console is asked
+/// through its mode flags and output code page.
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+/// What the terminal said about itself
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Probe {
+    /// Escape sequences are interpreted: it answered Primary Device Attributes, or the
+    /// Windows console has virtual terminal processing on
+    pub vt: bool,
+    /// A two-byte UTF-8 character took one column
+    pub utf8: Option<bool>,
+    /// DECRQM reported the alternate screen (mode 1049) as known
+    pub alt_screen: Option<bool>,
+}
+
+/// The terminal's answers, asked once per run; `None` when it was not asked (output is not
+/// a terminal, `ZIRO_NO_PROBE` is set, or the platform has no query channel)
+pub fn probe() -> Option<Probe> {
+    static PROBE: OnceLock<Option<Probe>> = OnceLock::new();
+    *PROBE.get_or_init(|| {
+        if super::term::is_truthy_env("ZIRO_NO_PROBE") {
+            return None;
+        }
+        imp::probe()
+    })
+}
+
+/// Parse whatever the terminal wrote back: `ESC[row;colR`, `ESC[?1049;Ps$y`, `ESC[?...c`
+///
+/// The probe character is written at column 1, so a UTF-8 terminal reports column 2.
+#[cfg(any(unix, test))]
+fn parse_replies(replies: &[u8]) -> Probe {
+    let text = String::from_utf8_lossy(replies);
+    let mut probe = Probe::default();
+    for reply in text.split('\x1b').filter_map(|r| r.strip_prefix('[')) {
+        let end = reply
+            .find(|c: char| !(c.is_ascii_digit() || matches!(c, ';' | '?' | '$')))
+            .unwrap_or(reply.len());
+        let params = &reply[..end];
+        match reply[end..].chars().next() {
+            Some('c') if params.starts_with('?') => probe.vt = true,
+            Some('y') => {
+                if let Some(state) = params
+                    .strip_prefix("?1049;")
+                    .and_then(|p| p.strip_suffix('$'))
+                    .and_then(|p| p.parse::<u8>().ok())
+                {
+                    // 0: not recognized; 1-4: set, reset, permanently set or reset
+                    probe.alt_screen = Some(state != 0);
+                }
+            }
+            Some('R') => {
+                if let Some(column) = params
+                    .split_once(';')
+                    .and_then(|(_, column)| column.parse::<u16>().ok())
+                {
+                    probe.utf8 = Some(column == 2);
+                }
+            }
+            _ => {}
+        }
+    }
+    probe
+}
+
+#[cfg(unix)]
+mod imp {
+    use super::{Probe, parse_replies};
+    use crate::platform::dirs;
+    use std::fs::{File, OpenOptions};
+    use std::io::{IsTerminal, Read, Write};
+    use std::os::fd::AsRawFd;
+    use std::time::{Duration, Instant};
+
+    /// How long a terminal gets to answer; local terminals take well under a millisecond
+    const REPLY_TIMEOUT: Duration = Duration::from_millis(150);
+
+    /// CR, the probe character, CPR, DECRQM 1049, DA1, then CR and erase the line again
+    const QUERY: &[u8] = b"\r\xc3\xa9\x1b[6n\x1b[?1049$p\x1b[c\r\x1b[K";
+
+    pub(super) fn probe() -> Option<Probe> {
+        if !std::io::stdout().is_terminal() {
+            return None;
+        }
+        // Without a terminal type the query would be printed as is
+        match std::env::var("TERM") {
+            Ok(term) if !term.is_empty() && term != "dumb" => {}
+            _ => return None,
+        }
+        let mut tty = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open("/dev/tty")
+            .ok()?;
+        let key = session_key(&tty)?;
+        if let Some(probe) = load(&key) {
+            return Some(probe);
+        }
+        let probe = query(&mut tty)?;
+        // Silence is kept too, so a terminal that never answers costs the timeout only once
+        save(&key, probe);
+        Some(probe)
+    }
+
+    /// The controlling terminal, its session and its type: the same key means the same
+    /// terminal window, so earlier answers still hold
+    fn session_key(tty: &File) -> Option<String> {
+        let fd = tty.as_raw_fd();
+        // SAFETY: plain queries on an open descriptor; ttyname's buffer is copied at once
+        let (session, name) = unsafe {
+            // A background job changing terminal modes would be stopped by SIGTTOU
+            if libc::tcgetpgrp(fd) != libc::getpgrp() {
+                return None;
+            }
+            // `/dev/tty` names no particular terminal; stdout does
+            let name = libc::ttyname(libc::STDOUT_FILENO);
+            let name = if name.is_null() {
+                String::new()
+            } else {
+                std::ffi::CStr::from_ptr(name)
+                    .to_string_lossy()
+                    .into_owned()
+            };
+            (libc::getsid(0), name)
+        };
+        let term = std::env::var("TERM").unwrap_or_default();
+        Some(format!("{name} {session} {term}"))
+    }
+
+    /// Send the queries with echo and line buffering off, collecting replies until DA1
+    /// arrives or the terminal stays silent
+    fn query(tty: &mut File) -> Option<Probe> {
+        let fd = tty.as_raw_fd();
+        // SAFETY: termios is plain data, filled in by tcgetattr before use
+        let original = unsafe {
+            let mut original: libc::termios = std::mem::zeroed();
+            if libc::tcgetattr(fd, &mut original) != 0 {
+                return None;
+            }
+            original
+        };
+        let mut raw = original;
+        raw.c_lflag &= !(libc::ICANON | libc::ECHO);
+        raw.c_cc[libc::VMIN] = 0;
+        raw.c_cc[libc::VTIME] = 0;
+        // SAFETY: as above; the original modes are put back before returning
+        if unsafe { libc::tcsetattr(fd, libc::TCSANOW, &raw) } != 0 {
+            return None;
+        }
+
+        let mut replies = Vec::new();
+        if tty.write_all(QUERY).and_then(|()| tty.flush()).is_ok() {
+            let deadline = Instant::now() + REPLY_TIMEOUT;
+            let mut buf = [0u8; 256];
+            // Terminals answer in order, so DA1 comes last
+            while !parse_replies(&replies).vt {
+                let left = deadline.saturating_duration_since(Instant::now());
+                if left.is_zero() || !readable(fd, left) {
+                    break;
+                }
+                match tty.read(&mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => replies.extend_from_slice(&buf[..n]),
+                }
+            }
+        }
+
+        // SAFETY: restores the modes read above
+        unsafe { libc::tcsetattr(fd, libc::TCSANOW, &original) };
+        Some(parse_replies(&replies))
+    }
+
+    fn readable(fd: i32, timeout: Duration) -> bool {
+        let mut pollfd = libc::pollfd {
+            fd,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let millis = timeout.as_millis().clamp(1, i32::MAX as u128) as i32;
+        // SAFETY: one valid pollfd
+        unsafe { libc::poll(&mut pollfd, 1, millis) > 0 }
+    }
+
+    fn cache_path() -> Option<std::path::PathBuf> {
+        dirs::state_dir().map(|dir| dir.join("term-probe"))
+    }
+
+    /// The cached answers, when they were recorded for `key`
+    fn load(key: &str) -> Option<Probe> {
+        let cached = std::fs::read_to_string(cache_path()?).ok()?;
+        let (cached_key, answers) = cached.trim_end().split_once('\t')?;
+        if cached_key != key {
+            return None;
+        }
+        serde_json::from_str(answers).ok()
+    }
+
+    /// Remember the answers for this session only; another terminal overwrites them
+    fn save(key: &str, probe: Probe) {
+        let Some(path) = cache_path() else {
+            return;
+        };
+        let Ok(answers) = serde_json::to_string(&probe) else {
+            return;
+        };
+        if let Some(dir) = path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        let _ = std::fs::write(path, format!("{key}\t{answers}\n"));
+    }
+}
+
+/// The Windows console is asked through its mode and code page instead
+#[cfg(windows)]
+mod imp {
+    use super::Probe;
+    use crate::platform::encoding::CP_UTF8;
+
+    pub(super) fn probe() -> Option<Probe> {
+        use windows_sys::Win32::Foundation::INVALID_HANDLE_VALUE;
+        use windows_sys::Win32::System::Console::{
+            ENABLE_VIRTUAL_TERMINAL_PROCESSING, GetConsoleMode, GetConsoleOutputCP, GetStdHandle,
+            STD_OUTPUT_HANDLE,
+        };
+
+        // SAFETY: plain queries on the process's own standard handle
+        unsafe {
+            let handle = GetStdHandle(STD_OUTPUT_HANDLE);
+            let mut mode: u32 = 0;
+            // Redirected, or a pipe-based terminal such as mintty: there is no console to ask
+            if handle.is_null()
+                || handle == INVALID_HANDLE_VALUE
+                || GetConsoleMode(handle, &mut mode) == 0
+            {
+                return None;
+            }
+            Some(Probe {
+                vt: mode & ENABLE_VIRTUAL_TERMINAL_PROCESSING != 0,
+                utf8: Some(GetConsoleOutputCP() == CP_UTF8),
+                alt_screen: None,
+            })
+        }
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod imp {
+    use super::Probe;
+
+    pub(super) fn probe() -> Option<Probe> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_replies() {
+        let replies = b"\x1b[24;2R\x1b[?1049;2$y\x1b[?62;22c";
+        assert_eq!(
+            parse_replies(replies),
+            Probe {
+                vt: true,
+                utf8: Some(true),
+                alt_screen: Some(true),
+            }
+        );
+        // Latin-1 terminal: both bytes take a column; DECRQM unknown to it
+        assert_eq!(
+            parse_replies(b"\x1b[5;3R\x1b[?1;2c"),
+            Probe {
+                vt: true,
+                utf8: Some(false),
+                alt_screen: None,
+            }
+        );
+        assert_eq!(parse_replies(b"\x1b[?1049;0$y").alt_screen, Some(false));
+        assert!(!parse_replies(b"\x1b[24;2R\x1b[?1049;2$y").vt);
+        assert_eq!(parse_replies(b""), Probe::default());
+    }
+}
@@ -0,0 +1,44 @@
+/// Platform temp and cache locations
+use std::env;
+use std::path::PathBuf;
+
+/// Temp directories that are safe to clean on this platform
+///
+/// Only existing directories are returned, with duplicates (e.g. symlinked `/tmp`) removed.
+pub fn temp_dirs() -> Vec<PathBuf> {
+    let mut candidates = vec![env::temp_dir()];
+
+    #[cfg(target_os = "windows")]
+    {
+        if let Some(local) = env::var_os("LOCALAPPDATA") {
+            candidates.push(PathBuf::from(local).join("Temp"));
+        }
+        if let Some(windir) = env::var_os("WINDIR") {
+            candidates.push(PathBuf::from(windir).join("Temp"));
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        candidates.push(PathBuf::from("/tmp"));
+        candidates.push(PathBuf::from("/var/tmp"));
+    }
+
+    #[cfg(target_os = "macos")]
+    if let Some(home) = env::var_os("HOME") {
+        candidates.push(PathBuf::from(home).join("Library/Caches"));
+    }
+
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    let mut seen: Vec<PathBuf> = Vec::new();
+    for dir in candidates {
+        let Ok(canonical) = dir.canonicalize() else {
+            continue;
+        };
+        if canonical.is_dir() && !seen.contains(&canonical) {
+            seen.push(canonical);
+            dirs.push(dir);
+        }
+    }
+    dirs
+}
@@ -1,8 +1,12 @@
+use super::command::run_command;
+use super::probe::{self, Probe};
 use crate::cli::Cli;
+use crate::cli::config::ThemeName;
+use serde::Serialize;
 use std::process::Command;
 use std::{env, sync::OnceLock};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct TerminalProfile {
     pub plain: bool,
     pub ascii_icons: bool,
@@ -39,21 +43,80 @@ pub fn global_profile() -> TerminalProfile {
         .unwrap_or_else(TerminalProfile::default)
 }
 
-pub fn detect_profile(cli: &Cli) -> TerminalProfile {
-    // User-explicit arguments take priority
+pub fn detect_profile(cli: &Cli, theme: &ThemeName) -> TerminalProfile {
+    // User-explicit arguments take priority, then the environment, then the config file
     let mut profile = TerminalProfile {
-        plain: cli.plain || is_truthy_env("ZIRO_PLAIN"),
-        ascii_icons: cli.ascii || is_truthy_env("ZIRO_ASCII_ICONS"),
-        no_color: cli.no_color || is_truthy_env("ZIRO_NO_COLOR") || is_truthy_env("NO_COLOR"),
+        plain: cli.plain || is_truthy_env("ZIRO_PLAIN") || *theme == ThemeName::Plain,
+        ascii_icons: cli.ascii || is_truthy_env("ZIRO_ASCII_ICONS") || *theme == ThemeName::Ascii,
+        no_color: cli.no_color
+            || is_truthy_env("ZIRO_NO_COLOR")
+            || is_truthy_env("NO_COLOR")
+            || *theme == ThemeName::NoColor,
         narrow: cli.narrow || is_truthy_env("ZIRO_NARROW"),
         ..TerminalProfile::default()
     };
 
-    // Detect terminal capabilities
+    let TerminalCapabilities {
+        vt_supported,
+        looks_modern,
+        utf8: utf8_ok,
+        probe,
+    } = detect_capabilities();
     let is_windows = cfg!(target_os = "windows");
+
+    // Improved smart degradation strategy
+    // Auto-degradation condition analysis:
+    // 1. User explicitly requests plain mode
+    // 2. Non-Windows system without UTF-8 environment (likely garbled output)
+    // 3. Unsafe combinations on Windows:
+    //    - Neither UTF-8 nor modern terminal
+    //    - Windows PowerShell 5.1 not running inside a modern terminal
+    //    - Legacy console environment detected (conhost)
+    // What the terminal says about itself beats the environment: a console that cannot
+    // turn on escape sequences is legacy conhost, while Unix silence may just be a slow link
+    let should_degrade = profile.plain
+        || match probe {
+            Some(probe) if probe.vt => probe.utf8 == Some(false),
+            Some(_) if is_windows => true,
+            _ if is_windows => should_degrade_on_windows(utf8_ok, looks_modern, vt_supported),
+            _ => !utf8_ok,
+        };
+    if probe.and_then(|probe| probe.alt_screen) == Some(false) {
+        profile.alt_screen = false;
+    }
+
+    if should_degrade {
+        profile.plain = true;
+        profile.ascii_icons = true;
+        profile.no_color = true;
+        profile.narrow = true;
+        profile.alt_screen = false;
+        profile.incremental = false;
+    }
+
+    profile
+}
+
+/// What the terminal looks able to do, before flags and the config are applied
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct TerminalCapabilities {
+    /// ANSI escape sequences are interpreted (always assumed outside Windows)
+    pub vt_supported: bool,
+    /// A terminal known to handle colors and Unicode
+    pub looks_modern: bool,
+    /// The locale or code page is UTF-8
+    pub utf8: bool,
+    /// The terminal's own answers; `None` when it was not asked
+    pub probe: Option<Probe>,
+}
+
+pub fn detect_capabilities() -> TerminalCapabilities {
+    let is_windows = cfg!(target_os = "windows");
+    let probe = probe::probe();
     let vt_supported = has_virtual_terminal_processing();
 
-    let looks_modern = env::var("WT_SESSION").is_ok()
+    let looks_modern = probe.is_some_and(|probe| probe.vt)
+        || env::var("WT_SESSION").is_ok()
         || env::var("TERM")
             .map(|t| {
                 let t = t.to_lowercase();
@@ -68,7 +131,9 @@ pub fn detect_profile(cli: &Cli) -> TerminalProfile {
         || env::var("TERM_PROGRAM").is_ok()
         || vt_supported
         || is_modern_terminal();
-    let utf8_ok = if is_windows {
+    let utf8_ok = if let Some(utf8) = probe.and_then(|probe| probe.utf8) {
+        utf8
+    } else if is_windows {
         detect_windows_utf8()
             || env::var("LC_ALL")
                 .or_else(|_| env::var("LANG"))
@@ -81,28 +146,12 @@ pub fn detect_profile(cli: &Cli) -> TerminalProfile {
             .unwrap_or(true)
     };
 
-    // Improved smart degradation strategy
-    // Auto-degradation condition analysis:
-    // 1. User explicitly requests plain mode
-    // 2. Non-Windows system without UTF-8 environment (likely garbled output)
-    // 3. Unsafe combinations on Windows:
-    //    - Neither UTF-8 nor modern terminal
-    //    - Windows PowerShell 5.1 not running inside a modern terminal
-    //    - Legacy console environment detected (conhost)
-    let should_degrade = profile.plain
-        || (!is_windows && !utf8_ok)
-        || (is_windows && should_degrade_on_windows(utf8_ok, looks_modern, vt_supported));
-
-    if should_degrade {
-        profile.plain = true;
-        profile.ascii_icons = true;
-        profile.no_color = true;
-        profile.narrow = true;
-        profile.alt_screen = false;
-        profile.incremental = false;
+    TerminalCapabilities {
+        vt_supported,
+        looks_modern,
+        utf8: utf8_ok,
+        probe,
     }
-
-    profile
 }
 
 pub fn apply_profile_env(profile: &TerminalProfile) {
@@ -364,7 +413,7 @@ fn detect_windows_utf8() -> bool {
     }
 
     // Method 1: Check active code page
-    if let Ok(output) = Command::new("cmd").args(["/C", "chcp"]).output() {
+    if let Ok(output) = run_command(Command::new("cmd").args(["/C", "chcp"])) {
         if let Ok(text) = String::from_utf8(output.stdout) {
             // Look for "Active code page: 65001" or similar patterns
             if text.contains("65001") {
@@ -374,7 +423,7 @@ fn detect_windows_utf8() -> bool {
     }
 
     // Method 2: Check system default output code page
-    if let Ok(output) = Command::new("cmd").args(["/C", "echo %LANG%"]).output() {
+    if let Ok(output) = run_command(Command::new("cmd").args(["/C", "echo %LANG%"])) {
         if let Ok(lang) = String::from_utf8(output.stdout) {
             let lang = lang.trim().to_lowercase();
             if lang.contains("utf-8") || lang.contains("65001") {
@@ -0,0 +1,37 @@
+/// Windows Subsystem for Linux: detection and the Windows tools reachable through interop
+use super::command;
+use std::path::PathBuf;
+
+/// Running inside WSL (1 or 2)
+pub fn is_wsl() -> bool {
+    if !cfg!(target_os = "linux") {
+        return false;
+    }
+    std::env::var_os("WSL_DISTRO_NAME").is_some()
+        || std::fs::read_to_string("/proc/sys/kernel/osrelease")
+            .is_ok_and(|release| is_wsl_kernel(&release))
+}
+
+/// WSL kernels name themselves, e.g. `5.15.153.1-microsoft-standard-WSL2` or `4.4.0-19041-Microsoft`
+fn is_wsl_kernel(release: &str) -> bool {
+    release.to_ascii_lowercase().contains("microsoft")
+}
+
+/// A Windows program such as `netstat.exe`: from `PATH` when interop appends the Windows
+/// directories to it, otherwise from System32 on the default `C:` mount
+pub fn windows_program(name: &str) -> PathBuf {
+    command::find_program(name)
+        .unwrap_or_else(|| PathBuf::from("/mnt/c/Windows/System32").join(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_wsl_kernel() {
+        assert!(is_wsl_kernel("5.15.153.1-microsoft-standard-WSL2\n"));
+        assert!(is_wsl_kernel("4.4.0-19041-Microsoft"));
+        assert!(!is_wsl_kernel("6.8.0-45-generic"));
+    }
+}
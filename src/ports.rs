@@ -0,0 +1,44 @@
+//! Which process holds which port
+//!
+//! ```no_run
+//! for info in ziro::ports::find(&[3000, 8080])? {
+//!     println!("{} is held by {} ({})", info.port, info.process.name, info.process.pid);
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+use crate::core::port;
+use anyhow::Result;
+
+pub use crate::core::port::{PortInfo, ProcessInfo, Snapshot};
+
+/// Read the connection and process tables once, for a command that looks several
+/// things up in them
+pub fn snapshot() -> Result<Snapshot> {
+    Snapshot::take()
+}
+
+/// Processes holding any of `ports`, in the order given; free ports are left out
+pub fn find(ports: &[u16]) -> Result<Vec<PortInfo>> {
+    port::find_processes_by_ports(ports)
+}
+
+/// Every port in use, ascending
+pub fn list() -> Result<Vec<PortInfo>> {
+    port::list_all_ports()
+}
+
+/// Every port held on the Windows host, ascending, when running inside WSL; the
+/// processes carry Windows PIDs
+pub fn list_host() -> Result<Vec<PortInfo>> {
+    port::list_host_ports()
+}
+
+/// `list` and `list_host` at once (concurrently with the `async` feature)
+pub fn list_with_host() -> (Result<Vec<PortInfo>>, Result<Vec<PortInfo>>) {
+    port::list_with_host_ports()
+}
+
+/// Ports held by one process, ascending
+pub fn of_process(pid: u32) -> Result<Vec<u16>> {
+    port::ports_of_process(pid)
+}
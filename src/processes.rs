@@ -0,0 +1,39 @@
+//! Stopping processes
+//!
+//! ```no_run
+//! let holders: Vec<u32> = ziro::ports::find(&[3000])?
+//!     .iter()
+//!     .map(|info| info.process.pid)
+//!     .collect();
+//! for report in ziro::processes::kill_and_wait(&holders) {
+//!     report.result?;
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+use crate::core::process;
+use anyhow::Result;
+
+pub use crate::core::process::{KillReport, KillSignal};
+
+/// Kill a process (SIGKILL / TerminateProcess) without waiting for it to go away
+pub fn kill(pid: u32) -> Result<()> {
+    process::kill_process(pid)
+}
+
+/// Kill processes and wait until they are gone, re-sending the signal to stragglers
+///
+/// Returns one report per distinct PID, in the order given; a process that is already
+/// gone counts as killed.
+pub fn kill_and_wait(pids: &[u32]) -> Vec<KillReport> {
+    process::kill_processes_force(pids)
+}
+
+/// Ask a process to stop; `KillSignal::Terminate` lets it clean up first on Unix
+pub fn signal(pid: u32, signal: KillSignal) -> Result<()> {
+    process::send_signal(pid, signal)
+}
+
+/// Whether a process with this PID is running
+pub fn exists(pid: u32) -> bool {
+    process::process_exists(pid)
+}
@@ -0,0 +1,145 @@
+/// Translated messages, looked up by their English text
+///
+/// English is the source language: messages are written in English in the code and
+/// wrapped in `tr!`, and a message missing from a catalog is shown untranslated.
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+mod zh;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Lang {
+    #[default]
+    En,
+    /// Simplified Chinese
+    Zh,
+}
+
+static LANG: AtomicU8 = AtomicU8::new(0);
+
+/// Select the language of every message shown from now on
+pub fn set_lang(lang: Lang) {
+    LANG.store(lang as u8, Ordering::Relaxed);
+}
+
+/// The selected language; English until `set_lang` is called
+pub fn lang() -> Lang {
+    match LANG.load(Ordering::Relaxed) {
+        1 => Lang::Zh,
+        _ => Lang::En,
+    }
+}
+
+/// The language named by `LC_ALL`, `LC_MESSAGES` or `LANG`, checked in that order
+pub fn lang_from_env() -> Lang {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .map_or(Lang::En, |locale| lang_of_locale(&locale))
+}
+
+/// `zh_CN.UTF-8` → Chinese; unknown locales fall back to English
+fn lang_of_locale(locale: &str) -> Lang {
+    if locale.to_ascii_lowercase().starts_with("zh") {
+        Lang::Zh
+    } else {
+        Lang::En
+    }
+}
+
+/// The translation of `msg` in the selected language, if there is one
+pub fn translate(msg: &str) -> Option<&'static str> {
+    translate_in(lang(), msg)
+}
+
+/// The translation of `msg` in `lang`, if there is one
+fn translate_in(lang: Lang, msg: &str) -> Option<&'static str> {
+    match lang {
+        Lang::En => None,
+        Lang::Zh => zh::MESSAGES
+            .iter()
+            .find(|(en, _)| *en == msg)
+            .map(|(_, zh)| *zh),
+    }
+}
+
+/// `msg` in the selected language
+pub fn tr(msg: &'static str) -> &'static str {
+    translate(msg).unwrap_or(msg)
+}
+
+/// Put `args` into the `{}` placeholders of `template`, in order
+pub fn fill(template: &str, args: &[&dyn Display]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut rest = template;
+    while let Some(at) = rest.find("{}") {
+        out.push_str(&rest[..at]);
+        match args.next() {
+            Some(arg) => out.push_str(&arg.to_string()),
+            None => out.push_str("{}"),
+        }
+        rest = &rest[at + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// A message in the selected language; arguments fill its `{}` placeholders
+///
+/// ```
+/// let port = 8080;
+/// println!("{}", ziro::tr!("Port {} is not in use", port));
+/// ```
+#[macro_export]
+macro_rules! tr {
+    ($msg:literal) => {
+        $crate::ui::i18n::tr($msg)
+    };
+    ($msg:literal, $($arg:expr),+ $(,)?) => {
+        $crate::ui::i18n::fill(
+            $crate::ui::i18n::tr($msg),
+            &[$(&$arg as &dyn ::std::fmt::Display),+],
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fill() {
+        assert_eq!(fill("Port {} - {}", &[&80, &"nginx"]), "Port 80 - nginx");
+        assert_eq!(fill("{} of {}", &[&1]), "1 of {}");
+        assert_eq!(lang_of_locale("zh_TW.UTF-8"), Lang::Zh);
+        assert_eq!(lang_of_locale("C.UTF-8"), Lang::En);
+    }
+
+    #[test]
+    fn test_catalogs_keep_placeholders() {
+        for (en, zh) in zh::MESSAGES {
+            assert_eq!(en.matches("{}").count(), zh.matches("{}").count(), "{en}");
+        }
+        let mut keys: Vec<&str> = zh::MESSAGES.iter().map(|(en, _)| *en).collect();
+        keys.sort_unstable();
+        keys.dedup();
+        assert_eq!(keys.len(), zh::MESSAGES.len(), "duplicate message");
+    }
+
+    #[test]
+    fn test_translate_views_beyond_ports() {
+        let zh = |msg| translate_in(Lang::Zh, msg);
+        assert_eq!(
+            zh("Preview mode - no files will be deleted"),
+            Some("预览模式 - 不会删除任何文件")
+        );
+        assert_eq!(
+            zh("{} procs").map(|t| fill(t, &[&3])),
+            Some("3 个进程".to_string())
+        );
+        assert_eq!(zh("No temp directories found"), Some("未找到临时目录"));
+        assert_eq!(translate_in(Lang::En, "{} procs"), None);
+    }
+}
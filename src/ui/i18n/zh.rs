@@ -0,0 +1,1262 @@
+/// Simplified Chinese
+pub(super) const MESSAGES: &[(&str, &str)] = &[
+    // Help layout
+    (
+        "{before-help}{about-with-newline}\n{usage-heading} {usage}\n\n{all-args}{after-help}",
+        "{before-help}{about-with-newline}\n用法: {usage}\n\n{all-args}{after-help}",
+    ),
+    ("Commands", "命令"),
+    ("Arguments", "参数"),
+    ("Options", "选项"),
+    ("Print help", "显示帮助"),
+    (
+        "Print help (see more with '--help')",
+        "显示帮助（使用 '--help' 查看更多）",
+    ),
+    (
+        "Print help (see a summary with '-h')",
+        "显示帮助（使用 '-h' 查看摘要）",
+    ),
+    ("Print version", "显示版本"),
+    (
+        "Print this message or the help of the given subcommand(s)",
+        "显示此信息或指定子命令的帮助",
+    ),
+    ("Print help for the subcommand(s)", "显示子命令的帮助"),
+    // Global options
+    (
+        "Cross-platform port and process management tool",
+        "跨平台端口与进程管理工具",
+    ),
+    (
+        "Force ASCII icons (equivalent to ZIRO_ASCII_ICONS=1)",
+        "强制使用 ASCII 图标（等同于 ZIRO_ASCII_ICONS=1）",
+    ),
+    (
+        "Disable colors (equivalent to ZIRO_NO_COLOR=1)",
+        "禁用颜色（等同于 ZIRO_NO_COLOR=1）",
+    ),
+    (
+        "Use narrow-width symbols (equivalent to ZIRO_NARROW=1)",
+        "使用窄字符符号（等同于 ZIRO_NARROW=1）",
+    ),
+    (
+        "Plain text mode: ASCII + no color (equivalent to ZIRO_PLAIN=1)",
+        "纯文本模式：ASCII 且无颜色（等同于 ZIRO_PLAIN=1）",
+    ),
+    (
+        "Color theme: default, solarized, monochrome, high-contrast or one from the config file (equivalent to ZIRO_THEME)",
+        "颜色主题：default、solarized、monochrome、high-contrast 或配置文件中定义的主题（等同于 ZIRO_THEME）",
+    ),
+    (
+        "Output format [default: text, or `output` in the config file]",
+        "输出格式 [默认：text，或配置文件中的 `output`]",
+    ),
+    ("Shorthand for --output json", "--output json 的简写"),
+    (
+        "Seconds to wait for lsof, netstat or PowerShell before giving up on them (equivalent to ZIRO_COMMAND_TIMEOUT; default 15)",
+        "等待 lsof、netstat 或 PowerShell 的秒数，超时即放弃（等同于 ZIRO_COMMAND_TIMEOUT；默认 15）",
+    ),
+    (
+        "Language of the output [default: from LANG]",
+        "输出语言 [默认：取自 LANG]",
+    ),
+    (
+        "Print long listings directly instead of through $PAGER",
+        "长列表直接输出，不经过 $PAGER",
+    ),
+    // find / kill / list
+    (
+        "Find processes occupying specified ports",
+        "查找占用指定端口的进程",
+    ),
+    (
+        "Port numbers or @groups to find (multiple allowed)",
+        "要查找的端口号或 @分组（可指定多个）",
+    ),
+    (
+        "Copy the PIDs found to the clipboard",
+        "将找到的 PID 复制到剪贴板",
+    ),
+    (
+        "Kill processes occupying specified ports",
+        "终止占用指定端口的进程",
+    ),
+    (
+        "Port numbers or @groups to kill (multiple allowed)",
+        "要释放的端口号或 @分组（可指定多个）",
+    ),
+    ("Force kill without confirmation", "强制终止，不再确认"),
+    (
+        "Also kill every child process of the port holders",
+        "同时终止端口占用进程的所有子进程",
+    ),
+    (
+        "Signal for confirmed kills [default: kill, or `kill.signal` in the config file]",
+        "确认后终止时使用的信号 [默认：kill，或配置文件中的 `kill.signal`]",
+    ),
+    (
+        "Re-run kills refused for lack of privileges through sudo (UAC on Windows)",
+        "因权限不足被拒绝的终止操作通过 sudo 重新执行（Windows 上为 UAC）",
+    ),
+    ("List all port usage", "列出所有端口占用情况"),
+    (
+        "Inside WSL, also list ports held by Windows processes on the host",
+        "在 WSL 中同时列出宿主机上 Windows 进程占用的端口",
+    ),
+    // who / unlock
+    (
+        "Find processes locking specified files or directories",
+        "查找锁定指定文件或目录的进程",
+    ),
+    (
+        "File or directory paths to check (multiple allowed)",
+        "要检查的文件或目录路径（可指定多个）",
+    ),
+    (
+        "Free locked files by killing the processes holding them",
+        "终止持有锁的进程以释放被锁定的文件",
+    ),
+    (
+        "File or directory paths to unlock (multiple allowed)",
+        "要解锁的文件或目录路径（可指定多个）",
+    ),
+    (
+        "Kill all locking processes without confirmation",
+        "不经确认终止所有锁定进程",
+    ),
+    (
+        "Command to run once the paths are free (e.g. `ziro unlock app.db -- cargo build`)",
+        "路径释放后要运行的命令（例如 `ziro unlock app.db -- cargo build`）",
+    ),
+    // remove / restore
+    (
+        "Remove files or directories (supports recursive deletion)",
+        "删除文件或目录（支持递归删除）",
+    ),
+    (
+        "File or directory paths to remove (multiple allowed)",
+        "要删除的文件或目录路径（可指定多个）",
+    ),
+    ("Force removal without confirmation", "强制删除，不再确认"),
+    (
+        "Recursively remove directories and their contents",
+        "递归删除目录及其内容",
+    ),
+    (
+        "Show what would be deleted without actually deleting",
+        "只显示将被删除的内容，不实际删除",
+    ),
+    ("Show detailed deletion progress", "显示详细的删除进度"),
+    (
+        "Force kill processes locking the files, then delete",
+        "强制终止锁定文件的进程，然后删除",
+    ),
+    (
+        "Force kill processes locking the files, then delete\n\nLockers are killed again before each retry; `--verbose` lists the PIDs killed for each entry. Entries that stay locked can be moved into `.ziro-pending-delete` to free their paths.",
+        "强制终止锁定文件的进程，然后删除\n\n每次重试前都会再次终止锁定进程；`--verbose` 会列出为每个条目终止的 PID。仍被锁定的条目可以移入 `.ziro-pending-delete` 以释放其路径。",
+    ),
+    (
+        "Only remove entries up to this depth below each directory (1 = direct children)",
+        "只删除各目录下此深度以内的条目（1 = 直接子项）",
+    ),
+    (
+        "Skip directories on a different filesystem than the given path",
+        "跳过与指定路径不在同一文件系统上的目录",
+    ),
+    (
+        "Read additional paths from stdin (newline or NUL separated)",
+        "从标准输入读取更多路径（以换行或 NUL 分隔）",
+    ),
+    (
+        "Archive everything into a zstd-compressed tar before deleting it",
+        "删除前将所有内容打包为 zstd 压缩的 tar 归档",
+    ),
+    (
+        "Retries for entries locked by another process [default: 5, or `remove.retries` in the config file]",
+        "被其他进程锁定的条目的重试次数 [默认：5，或配置文件中的 `remove.retries`]",
+    ),
+    (
+        "Initial delay between retries in milliseconds, doubling on each retry [default: 100, or `remove.retry_delay` in the config file]",
+        "重试之间的初始延迟毫秒数，每次重试翻倍 [默认：100，或配置文件中的 `remove.retry_delay`]",
+    ),
+    (
+        "Skip unreadable entries while scanning and report them in the summary",
+        "扫描时跳过无法读取的条目，并在汇总中列出",
+    ),
+    (
+        "Stop at the first entry that fails to delete",
+        "遇到第一个删除失败的条目即停止",
+    ),
+    (
+        "Include dotfiles and hidden/system files (default)",
+        "包含点文件和隐藏/系统文件（默认）",
+    ),
+    (
+        "Leave dotfiles and hidden/system files out of the walk",
+        "遍历时排除点文件和隐藏/系统文件",
+    ),
+    (
+        "Skip entries matched by .gitignore/.ignore rules",
+        "跳过匹配 .gitignore/.ignore 规则的条目",
+    ),
+    (
+        "Only remove entries matched by .gitignore/.ignore rules (like `git clean -X`)",
+        "只删除匹配 .gitignore/.ignore 规则的条目（类似 `git clean -X`）",
+    ),
+    (
+        "Ask yes/no/all/quit for each path given on the command line (like `rm -i`)",
+        "对命令行中的每个路径询问 是/否/全部/退出（类似 `rm -i`）",
+    ),
+    ("How to treat symlinks", "符号链接的处理方式"),
+    (
+        "Move to the system trash instead of deleting (undo with `ziro restore`)",
+        "移入系统回收站而不是删除（可用 `ziro restore` 撤销）",
+    ),
+    (
+        "Delete for good even if `remove.trash` is set in the config file",
+        "即使配置文件设置了 `remove.trash` 也直接永久删除",
+    ),
+    (
+        "Re-check removed paths afterwards and name processes that recreate them",
+        "删除后再次检查路径，并指出重新创建它们的进程",
+    ),
+    (
+        "Re-run deletions refused for lack of privileges through sudo (UAC on Windows)",
+        "因权限不足被拒绝的删除操作通过 sudo 重新执行（Windows 上为 UAC）",
+    ),
+    (
+        "Restore entries removed with `remove --trash`",
+        "恢复用 `remove --trash` 删除的条目",
+    ),
+    (
+        "Restore the most recent trash removal without asking",
+        "不经询问恢复最近一次移入回收站的内容",
+    ),
+    // du / big / tmp
+    (
+        "Show disk usage per subdirectory (largest entries first)",
+        "按子目录显示磁盘占用（从大到小）",
+    ),
+    (
+        "Directories to analyze (default: current directory)",
+        "要分析的目录（默认：当前目录）",
+    ),
+    (
+        "Number of largest entries to show per directory",
+        "每个目录显示的最大条目数",
+    ),
+    ("Directory levels to expand", "展开的目录层数"),
+    (
+        "Find the largest files under a path",
+        "查找路径下最大的文件",
+    ),
+    (
+        "Directories to search (default: current directory)",
+        "要搜索的目录（默认：当前目录）",
+    ),
+    ("Number of files to show", "显示的文件数量"),
+    (
+        "Only include these extensions (comma separated, e.g. log,zip)",
+        "只包含这些扩展名（逗号分隔，例如 log,zip）",
+    ),
+    (
+        "Only include files not modified within this age (e.g. 30m, 12h, 7d, 2w)",
+        "只包含在此时长内未修改的文件（例如 30m、12h、7d、2w）",
+    ),
+    (
+        "Clean OS and user temp directories, skipping files in use",
+        "清理系统和用户临时目录，跳过正在使用的文件",
+    ),
+    (
+        "Only clean entries not modified within this age (e.g. 12h, 7d)",
+        "只清理在此时长内未修改的条目（例如 12h、7d）",
+    ),
+    (
+        "Preview what would be cleaned without deleting",
+        "预览将被清理的内容，不实际删除",
+    ),
+    ("Skip confirmation", "跳过确认"),
+    // top
+    (
+        "Monitor process memory usage in real time (like top)",
+        "实时监控进程内存占用（类似 top）",
+    ),
+    (
+        "Refresh interval in seconds [default: 1, or `top.interval` in the config file]",
+        "刷新间隔秒数 [默认：1，或配置文件中的 `top.interval`]",
+    ),
+    ("Number of processes to display", "显示的进程数量"),
+    (
+        "Show CPU usage alongside memory",
+        "在内存之外同时显示 CPU 占用",
+    ),
+    ("Show process command lines", "显示进程命令行"),
+    (
+        "Show disk read/write rates per process",
+        "显示每个进程的磁盘读写速率",
+    ),
+    (
+        "Show how much each process's memory changed since the previous refresh",
+        "显示每个进程的内存自上次刷新以来的变化",
+    ),
+    (
+        "Show the number of threads of each process",
+        "显示每个进程的线程数",
+    ),
+    (
+        "Show the number of open file descriptors of each process (Linux)",
+        "显示每个进程打开的文件描述符数量（Linux）",
+    ),
+    (
+        "Show the container or cgroup of each process (Linux)",
+        "显示每个进程所属的容器或 cgroup（Linux）",
+    ),
+    (
+        "Show GPU memory and utilization of each process (NVIDIA; needs the `gpu` build feature)",
+        "显示每个进程的显存和 GPU 利用率（NVIDIA；需要 `gpu` 构建特性）",
+    ),
+    (
+        "Show listening sockets and established connections of each process",
+        "显示每个进程的监听套接字和已建立的连接",
+    ),
+    (
+        "Show memory as a share of each process's cgroup limit instead of host RAM (Linux)",
+        "以进程所属 cgroup 的内存上限而非主机内存为基准显示内存占比（Linux）",
+    ),
+    (
+        "Output once without continuous refresh",
+        "只输出一次，不持续刷新",
+    ),
+    (
+        "Print plain, timestamped frames for logs and pipes (like `top -b`)",
+        "输出带时间戳的纯文本帧，便于日志和管道（类似 `top -b`）",
+    ),
+    ("Stop after this many refreshes", "刷新指定次数后停止"),
+    ("Show processes under their parents", "在父进程下显示子进程"),
+    (
+        "With --tree, count children's memory in their parent's row",
+        "配合 --tree，将子进程内存计入父进程所在行",
+    ),
+    (
+        "Fold processes with the same executable name into one row (Enter expands a group)",
+        "将可执行文件名相同的进程合并为一行（按 Enter 展开分组）",
+    ),
+    ("Sort key", "排序依据"),
+    ("Reverse the sort direction", "反转排序方向"),
+    (
+        "Only show processes whose name or command line matches this regex (case-insensitive)",
+        "只显示名称或命令行匹配此正则的进程（不区分大小写）",
+    ),
+    (
+        "Only show processes owned by this user (`me` for yourself)",
+        "只显示属于此用户的进程（`me` 表示自己）",
+    ),
+    (
+        "Only show zombie processes (exited but not reaped by their parent)",
+        "只显示僵尸进程（已退出但未被父进程回收）",
+    ),
+    (
+        "Only show processes whose cgroup path contains NAME, e.g. a container ID (Linux)",
+        "只显示 cgroup 路径包含 NAME 的进程，例如容器 ID（Linux）",
+    ),
+    (
+        "Hide processes using less memory than this (e.g. 50MB)",
+        "隐藏内存占用低于此值的进程（例如 50MB）",
+    ),
+    (
+        "Hide processes using less CPU than this (e.g. 1%)",
+        "隐藏 CPU 占用低于此值的进程（例如 1%）",
+    ),
+    (
+        "Write each refresh as timestamped rows instead of the live view",
+        "将每次刷新输出为带时间戳的行，而非实时界面",
+    ),
+    (
+        "Append exported rows to this file and keep the live view",
+        "将导出的行追加到此文件，同时保留实时界面",
+    ),
+    (
+        "Save every refresh to a session file for later `--replay`",
+        "将每次刷新保存到会话文件，供之后 `--replay` 使用",
+    ),
+    (
+        "Play back a session recorded with `--record`",
+        "回放用 `--record` 录制的会话",
+    ),
+    (
+        "Replay speed multiplier (e.g. 4 plays four times faster)",
+        "回放速度倍数（例如 4 表示四倍速）",
+    ),
+    (
+        "Highlight processes using at least this much memory (e.g. 2G, 512M, 25%)",
+        "高亮内存占用不低于此值的进程（例如 2G、512M、25%）",
+    ),
+    (
+        "Highlight processes using at least this much CPU, in percent",
+        "高亮 CPU 占用不低于此百分比的进程",
+    ),
+    (
+        "Ring the terminal bell when a process crosses an alert threshold",
+        "进程超过告警阈值时响铃",
+    ),
+    (
+        "Send a desktop notification when a process crosses an alert threshold",
+        "进程超过告警阈值时发送桌面通知",
+    ),
+    (
+        "Send a desktop notification when the removal finishes",
+        "删除完成时发送桌面通知",
+    ),
+    (
+        "Send a desktop notification when the wait ends",
+        "等待结束时发送桌面通知",
+    ),
+    (
+        "Re-run a ziro subcommand or any other command on an interval (e.g. `ziro watch find 8080`)",
+        "按间隔重复运行 ziro 子命令或任意命令（例如 `ziro watch find 8080`）",
+    ),
+    ("Seconds between runs", "两次运行之间的秒数"),
+    ("Exit after this many runs", "运行指定次数后退出"),
+    (
+        "The command: a ziro subcommand with its arguments, or any program",
+        "要运行的命令：ziro 子命令及其参数，或任意程序",
+    ),
+    (
+        "Run a shell command when a process crosses an alert threshold",
+        "进程超过告警阈值时运行 shell 命令",
+    ),
+    (
+        "Run a shell command when a process crosses an alert threshold\n\nThe process is described by ZIRO_ALERT_PID, ZIRO_ALERT_NAME and ZIRO_ALERT_REASON.",
+        "进程超过告警阈值时运行 shell 命令\n\n进程信息通过 ZIRO_ALERT_PID、ZIRO_ALERT_NAME 和 ZIRO_ALERT_REASON 提供。",
+    ),
+    (
+        "Follow one process: memory/CPU history, children, ports and open files",
+        "跟踪单个进程：内存/CPU 历史、子进程、端口和打开的文件",
+    ),
+    // ps / info / pause / nice / waitpid / pkill / reap / pstree
+    (
+        "List processes once, with filters (like `ps aux`)",
+        "按条件列出一次进程（类似 `ps aux`）",
+    ),
+    ("Show at most this many processes", "最多显示这么多进程"),
+    (
+        "Also refuse these ports this time, e.g. 22,5432-5439 (on top of `kill.protected`)",
+        "本次额外拒绝这些端口，如 22,5432-5439（在 `kill.protected` 之外）",
+    ),
+    (
+        "Kill even ports the safe-port policy protects",
+        "即使端口受安全端口策略保护也终止",
+    ),
+    (
+        "Refusing to kill port {}: protected by {} (use --allow-protected to override)",
+        "拒绝终止端口 {}：受 {} 保护（使用 --allow-protected 强制终止）",
+    ),
+    (
+        "the default kill.protected list",
+        "默认的 kill.protected 列表",
+    ),
+    (
+        "Only show processes started at least this long ago (e.g. 30m, 2h, 1d)",
+        "只显示启动于该时长之前的进程（如 30m、2h、1d）",
+    ),
+    (
+        "Only show processes started less than this long ago (e.g. 10m)",
+        "只显示在该时长内启动的进程（如 10m）",
+    ),
+    (
+        "Only match processes started at least this long ago (e.g. 30m, 2h, 1d)",
+        "只匹配启动于该时长之前的进程（如 30m、2h、1d）",
+    ),
+    (
+        "Only match processes started less than this long ago (e.g. 10m)",
+        "只匹配在该时长内启动的进程（如 10m）",
+    ),
+    (
+        "Per-process network statistics: connections, listening sockets, ports and traffic",
+        "按进程统计网络：连接数、监听套接字、端口和流量",
+    ),
+    (
+        "Only show processes with a listening socket",
+        "只显示有监听套接字的进程",
+    ),
+    (
+        "List established connections with their remote peers",
+        "列出已建立的连接及其远端地址",
+    ),
+    (
+        "Only show connections to this remote address",
+        "只显示连接到该远端地址的连接",
+    ),
+    (
+        "Look up the host names of remote addresses (reverse DNS)",
+        "查询远端地址的主机名（反向 DNS）",
+    ),
+    (
+        "Kill the processes connected to this remote address",
+        "终止连接到该远端地址的进程",
+    ),
+    (
+        "With --kill-remote, force kill without confirmation",
+        "配合 --kill-remote，不经确认直接强制终止",
+    ),
+    (
+        "Share a directory over HTTP on the LAN, on a free port unless one is given",
+        "通过 HTTP 在局域网内共享目录，未指定端口时自动选择空闲端口",
+    ),
+    ("Directory to serve", "要共享的目录"),
+    (
+        "Check that a port accepts connections, and optionally answers HTTP 2xx",
+        "检查端口能否接受连接，并可选检查 HTTP 是否返回 2xx",
+    ),
+    ("Port to check", "要检查的端口"),
+    (
+        "Open the web page served on a port in the default browser",
+        "在默认浏览器中打开端口上的网页",
+    ),
+    ("Port the page is served on", "网页所在的端口"),
+    ("Host to connect to", "要连接的主机"),
+    (
+        "Also GET this path and require a 2xx status (e.g. /healthz)",
+        "同时 GET 该路径并要求返回 2xx 状态（如 /healthz）",
+    ),
+    (
+        "Attempts after the first before giving up",
+        "首次之外、放弃前的重试次数",
+    ),
+    (
+        "Pause between attempts (e.g. 1s, 1m)",
+        "两次尝试之间的间隔（如 1s、1m）",
+    ),
+    (
+        "How long one attempt may take to connect, and to answer with --http",
+        "每次尝试连接（以及 --http 时等待应答）的最长时间",
+    ),
+    (
+        "Port to listen on [default: the first free one from 8000]",
+        "监听端口 [默认：从 8000 起第一个空闲端口]",
+    ),
+    (
+        "Show everything known about one process",
+        "显示单个进程的全部信息",
+    ),
+    ("Process ID", "进程 ID"),
+    (
+        "Also print the process's environment variables",
+        "同时输出进程的环境变量",
+    ),
+    (
+        "Freeze processes without killing them (SIGSTOP); undo with `resume`",
+        "冻结进程而不终止（SIGSTOP）；用 `resume` 恢复",
+    ),
+    ("Process IDs (multiple allowed)", "进程 ID（可指定多个）"),
+    (
+        "Let paused processes run again (SIGCONT)",
+        "让暂停的进程继续运行（SIGCONT）",
+    ),
+    (
+        "Change the scheduling priority of processes (e.g. `ziro nice 1234 --to 10`)",
+        "修改进程的调度优先级（例如 `ziro nice 1234 --to 10`）",
+    ),
+    (
+        "Niceness from -20 (highest priority) to 19 (lowest); mapped to a priority class on Windows",
+        "nice 值，从 -20（最高优先级）到 19（最低）；在 Windows 上映射为优先级类别",
+    ),
+    (
+        "Wait until processes exit (e.g. before starting a replacement server)",
+        "等待进程退出（例如在启动替代服务之前）",
+    ),
+    (
+        "Process IDs to wait for (multiple allowed)",
+        "要等待的进程 ID（可指定多个）",
+    ),
+    (
+        "Also wait for every process whose name or command line matches this regex now",
+        "同时等待当前名称或命令行匹配此正则的所有进程",
+    ),
+    (
+        "Give up and exit with an error after this long (e.g. 30s, 5m)",
+        "超过此时长后放弃并报错退出（例如 30s、5m）",
+    ),
+    (
+        "Seconds between status lines while waiting",
+        "等待期间状态行之间的间隔秒数",
+    ),
+    (
+        "Kill processes whose name or command line matches a regex, after a preview",
+        "预览后终止名称或命令行匹配正则的进程",
+    ),
+    (
+        "Regex matched against process names and command lines (case-insensitive)",
+        "用于匹配进程名称和命令行的正则（不区分大小写）",
+    ),
+    (
+        "Only kill the longest-running match",
+        "只终止运行时间最长的匹配进程",
+    ),
+    (
+        "Only kill the most recently started match",
+        "只终止最近启动的匹配进程",
+    ),
+    (
+        "Kill every match without picking them one by one",
+        "终止所有匹配进程，不逐个选择",
+    ),
+    (
+        "Only match processes owned by this user (`me` for yourself)",
+        "只匹配属于此用户的进程（`me` 表示自己）",
+    ),
+    (
+        "List zombie and orphaned processes, and get the zombies reaped",
+        "列出僵尸进程和孤儿进程，并让僵尸进程被回收",
+    ),
+    (
+        "Only list orphans that have been running this long (e.g. 30s, 5m)",
+        "只列出已运行至少此时长的孤儿进程（例如 30s、5m）",
+    ),
+    (
+        "Send SIGCHLD to the parents of zombies so they collect them",
+        "向僵尸进程的父进程发送 SIGCHLD 以便其回收",
+    ),
+    (
+        "Terminate the parents of zombies so init adopts and reaps them",
+        "终止僵尸进程的父进程，让 init 接管并回收它们",
+    ),
+    (
+        "Terminate parents without confirmation",
+        "不经确认终止父进程",
+    ),
+    (
+        "Show the process hierarchy as a tree, with memory and CPU per process",
+        "以树形显示进程层级，并显示每个进程的内存和 CPU",
+    ),
+    (
+        "Only show this process and its descendants",
+        "只显示此进程及其后代进程",
+    ),
+    // completions / config / snapshot
+    (
+        "Print a shell completion script (e.g. `ziro completions bash > ~/.local/share/bash-completion/completions/ziro`)",
+        "输出 shell 补全脚本（例如 `ziro completions bash > ~/.local/share/bash-completion/completions/ziro`）",
+    ),
+    (
+        "Show or change the defaults in the config file (flags always override them)",
+        "查看或修改配置文件中的默认值（命令行参数始终优先）",
+    ),
+    ("Print every key with its value", "列出所有配置项及其值"),
+    (
+        "Print one value (e.g. `ziro config get top.interval`)",
+        "输出单个配置值（例如 `ziro config get top.interval`）",
+    ),
+    (
+        "Change one value; lists are comma-separated (e.g. `ziro config set top.columns cpu,net`)",
+        "修改单个配置值；列表用逗号分隔（例如 `ziro config set top.columns cpu,net`）",
+    ),
+    (
+        "Go back to the built-in default of a key",
+        "将配置项恢复为内置默认值",
+    ),
+    ("Print where the config file lives", "输出配置文件所在位置"),
+    (
+        "Save the process table to a file or compare two of them",
+        "将进程表保存到文件，或比较两份进程表",
+    ),
+    ("Capture the current process table", "保存当前进程表"),
+    ("File to write the snapshot to", "快照写入的文件"),
+    (
+        "Report started and exited processes and memory changes between two snapshots",
+        "报告两份快照之间新启动、已退出的进程和内存变化",
+    ),
+    ("Older snapshot", "较早的快照"),
+    (
+        "Newer snapshot (default: the current process table)",
+        "较新的快照（默认：当前进程表）",
+    ),
+    (
+        "Hide memory changes smaller than this (e.g. 10M)",
+        "隐藏小于此值的内存变化（例如 10M）",
+    ),
+    (
+        "Number of processes to list per section",
+        "每部分列出的进程数量",
+    ),
+    // Output
+    ("Error:", "错误："),
+    (
+        "Please specify at least one port number",
+        "请至少指定一个端口号",
+    ),
+    ("Port {} is not in use", "端口 {} 未被占用"),
+    ("Copied to the clipboard: {}", "已复制到剪贴板：{}"),
+    (
+        "No processes found occupying the specified ports",
+        "未找到占用指定端口的进程",
+    ),
+    (
+        "type to filter by port, PID, name or command line; space to toggle",
+        "输入以按端口、PID、名称或命令行筛选；空格切换选中",
+    ),
+    ("Port {} - {} (PID: {})", "端口 {} - {} (PID: {})"),
+    ("Select processes to kill:", "选择要终止的进程："),
+    ("No processes selected", "未选择任何进程"),
+    (
+        "Also include their {} child process(es)?",
+        "是否同时包括它们的 {} 个子进程？",
+    ),
+    ("Kill them", "终止它们"),
+    ("Kill it", "终止它"),
+    (
+        "Kill it and its {} child process(es)",
+        "终止它及其 {} 个子进程",
+    ),
+    ("Lower its priority (nice {})", "降低它的优先级（nice {}）"),
+    ("What should happen to this process?", "如何处理这个进程？"),
+    ("Ports", "端口"),
+    (
+        "Start the picker with every process ticked [default: none, or `kill.preselect`]",
+        "选择列表打开时勾选所有进程 [默认：不勾选，或配置文件中的 `kill.preselect`]",
+    ),
+    (
+        "Start the picker with no process ticked",
+        "选择列表打开时不勾选任何进程",
+    ),
+    (
+        "Lower their priority (nice {})",
+        "降低它们的优先级（nice {}）",
+    ),
+    ("Cancel", "取消"),
+    (
+        "What should happen to these processes?",
+        "如何处理这些进程？",
+    ),
+    ("Operation cancelled", "操作已取消"),
+    ("Child processes:", "子进程："),
+    ("... and {} more", "……以及另外 {} 个"),
+    ("Successfully killed process {}", "已终止进程 {}"),
+    ("Failed to kill process {}", "终止进程 {} 失败"),
+    ("Successfully force-killed process {}", "已强制终止进程 {}"),
+    ("Failed to force-kill process {}", "强制终止进程 {} 失败"),
+    ("ports {}", "端口 {}"),
+    ("Port Query Results", "端口查询结果"),
+    ("Process", "进程"),
+    ("Command", "命令"),
+    ("Resources", "资源"),
+    ("{} CPU, {} Memory", "CPU {}，内存 {}"),
+    ("(free)", "（空闲）"),
+    ("No ports are currently in use", "当前没有被占用的端口"),
+    ("Port Usage", "端口占用"),
+    ("Windows Host Ports", "Windows 宿主机端口"),
+    ("Windows PID {}", "Windows PID {}"),
+    ("Memory", "内存"),
+    ("({} total)", "（共 {} 个）"),
+    ("Force Kill Processes", "强制终止进程"),
+    ("Target processes:", "目标进程："),
+    ("Kill results:", "终止结果："),
+    ("Force kill complete", "强制终止完成"),
+    ("Success: {}", "成功：{}"),
+    ("Failed: {}", "失败：{}"),
+    // remove / restore output
+    ("No paths found to check", "没有可检查的路径"),
+    ("File Lock Query", "文件锁查询"),
+    ("Dir", "目录"),
+    ("File", "文件"),
+    ("Symlink", "符号链接"),
+    ("Locked", "已锁定"),
+    ("Free", "空闲"),
+    (
+        "No locking process found, may need admin privileges or handle.exe",
+        "未找到锁定进程，可能需要管理员权限或 handle.exe",
+    ),
+    ("Confirm killing these processes?", "确认终止这些进程？"),
+    ("Summary:", "摘要："),
+    ("{} files", "{} 个文件"),
+    ("{} directories", "{} 个目录"),
+    ("Total size: {}", "总大小：{}"),
+    ("Hidden:", "隐藏项："),
+    (
+        "{} included, {} skipped (--no-hidden)",
+        "包含 {} 个，跳过 {} 个（--no-hidden）",
+    ),
+    (
+        "{} included (use --no-hidden to skip)",
+        "包含 {} 个（使用 --no-hidden 跳过）",
+    ),
+    ("... {} more items", "……还有 {} 项"),
+    ("Warning: skipping {} ({})", "警告：跳过 {}（{}）"),
+    (
+        "Preview mode - no files will be deleted",
+        "预览模式 - 不会删除任何文件",
+    ),
+    (
+        "About to move the following to the trash",
+        "即将把以下内容移入回收站",
+    ),
+    ("About to delete the following", "即将删除以下内容"),
+    (
+        "Move these items to the trash? Use 'ziro restore' to undo",
+        "将这些项移入回收站？可使用 'ziro restore' 撤销",
+    ),
+    (
+        "Confirm deleting these items? This cannot be undone!",
+        "确认删除这些项？此操作无法撤销！",
+    ),
+    (
+        "Use --force to skip this confirmation",
+        "使用 --force 跳过此确认",
+    ),
+    ("Delete each path", "逐个删除路径"),
+    ("{} items, {}", "{} 项，{}"),
+    ("Delete {} ({})?", "删除 {}（{}）？"),
+    ("[y]es/[n]o/[a]ll/[q]uit", "[y]是/[n]否/[a]全部/[q]退出"),
+    ("Unable to check file locks", "无法检查文件锁"),
+    ("Will proceed with deletion", "将继续删除"),
+    (
+        "Files locked, killing locking processes...",
+        "文件被锁定，正在终止锁定进程……",
+    ),
+    ("Killed process PID: {}", "已终止进程 PID：{}"),
+    (
+        "Failed to kill process PID {}: {}",
+        "终止进程 PID {} 失败：{}",
+    ),
+    ("Files are locked", "文件被锁定"),
+    (
+        "These files are in use, continue trying to delete?",
+        "这些文件正在使用中，是否继续尝试删除？",
+    ),
+    (
+        "Use --anyway to auto-kill locking processes and delete",
+        "使用 --anyway 自动终止锁定进程并删除",
+    ),
+    ("Skipped: {}", "跳过：{}"),
+    ("Done", "完成"),
+    ("Skipped {}", "已跳过 {}"),
+    ("Failed to delete {}", "删除 {} 失败"),
+    ("Preview {}", "预览 {}"),
+    ("Delete {}", "删除 {}"),
+    ("Trash {}", "移入回收站 {}"),
+    ("Not attempted {}", "未尝试 {}"),
+    ("killed locking PID {}", "已终止锁定进程 PID {}"),
+    ("Would free {} in {} file", "将释放 {}，共 {} 个文件"),
+    ("Would free {} in {} files", "将释放 {}，共 {} 个文件"),
+    (
+        "Freed {} in {} file in {}",
+        "已释放 {}，共 {} 个文件，用时 {}",
+    ),
+    (
+        "Freed {} in {} files in {}",
+        "已释放 {}，共 {} 个文件，用时 {}",
+    ),
+    ("{} entries are still locked", "仍有 {} 个条目被锁定"),
+    (
+        "Move them into {} and delete them as soon as possible?",
+        "将它们移入 {} 并尽快删除？",
+    ),
+    (
+        "Frees the paths now; on Windows the files are deleted on reboot",
+        "立即释放这些路径；在 Windows 上文件将在重启时删除",
+    ),
+    ("Locked entries", "被锁定的条目"),
+    ("Moved aside and deleted", "已移走并删除"),
+    (
+        "Moved aside, deleted on next reboot",
+        "已移走，将在下次重启时删除",
+    ),
+    (
+        "Moved aside, delete it once it is released",
+        "已移走，释放后请删除",
+    ),
+    (
+        "Still in place, deleted on next reboot",
+        "仍在原处，将在下次重启时删除",
+    ),
+    ("Could not be moved or scheduled", "无法移走或安排删除"),
+    ("Path is free", "路径已释放"),
+    ("Path is still taken", "路径仍被占用"),
+    (
+        "Verified: all removed entries are gone",
+        "已验证：所有已删除的条目均不存在",
+    ),
+    (
+        "{} removed entries exist again",
+        "{} 个已删除的条目再次出现",
+    ),
+    (
+        "No process found holding it or its directory",
+        "未找到占用它或其目录的进程",
+    ),
+    ("Watching directory", "监视目录"),
+    ("Held by", "占用者"),
+    ("Backup saved to", "备份已保存到"),
+    ("Select entries to restore:", "选择要恢复的条目："),
+    ("No entries selected", "未选择任何条目"),
+    ("just now", "刚刚"),
+    ("{}m ago", "{} 分钟前"),
+    ("{}h ago", "{} 小时前"),
+    ("{}d ago", "{} 天前"),
+    ("Restored {}", "已恢复 {}"),
+    ("Not found in trash: {}", "回收站中未找到：{}"),
+    ("symlink", "符号链接"),
+    ("on a different filesystem", "位于其他文件系统"),
+    // ps / pause / nice / waitpid / reap / pkill output
+    ("No matching processes", "没有匹配的进程"),
+    ("USER", "用户"),
+    ("MEMORY", "内存"),
+    ("COMMAND", "命令"),
+    ("Paused process {}", "已暂停进程 {}"),
+    ("Resumed process {}", "已恢复进程 {}"),
+    ("Failed to pause process {}", "暂停进程 {} 失败"),
+    ("Failed to resume process {}", "恢复进程 {} 失败"),
+    (
+        "Run `ziro resume` with the same PIDs to let them continue",
+        "使用相同的 PID 运行 `ziro resume` 让它们继续",
+    ),
+    ("Set process {} to nice {}", "已将进程 {} 的 nice 值设为 {}"),
+    (
+        "Failed to change the priority of process {}",
+        "修改进程 {} 的优先级失败",
+    ),
+    ("Waiting for {} process(es): {}", "正在等待 {} 个进程：{}"),
+    (
+        "No matching processes are running",
+        "没有正在运行的匹配进程",
+    ),
+    ("Still running after {}: {}", "{} 后仍在运行：{}"),
+    (
+        "All {} process(es) exited after {}",
+        "全部 {} 个进程已退出，用时 {}",
+    ),
+    ("No zombie processes", "没有僵尸进程"),
+    ("Zombie processes ({})", "僵尸进程（{}）"),
+    ("has not reaped {}", "有 {} 个未回收"),
+    (
+        "Zombies are gone once their parent collects them: try `ziro reap --nudge`, or `ziro reap --kill-parents` so init adopts and reaps them",
+        "僵尸进程在父进程回收后才会消失：可尝试 `ziro reap --nudge`，或 `ziro reap --kill-parents` 让 init 接管并回收",
+    ),
+    (
+        "PID 1 is not reaping its zombies; in a container this usually means it runs without an init process (e.g. `docker run --init`)",
+        "PID 1 没有回收它的僵尸进程；在容器中这通常意味着没有 init 进程（例如 `docker run --init`）",
+    ),
+    (
+        "No orphaned processes running for {} or longer",
+        "没有运行 {} 或更久的孤儿进程",
+    ),
+    ("Orphaned processes ({})", "孤儿进程（{}）"),
+    ("(up {})", "（已运行 {}）"),
+    (
+        "Their parent is gone; stop any you no longer need with `ziro pkill`",
+        "它们的父进程已退出；可用 `ziro pkill` 停止不再需要的进程",
+    ),
+    ("Terminated parent {}", "已终止父进程 {}"),
+    ("Sent SIGCHLD to parent {}", "已向父进程 {} 发送 SIGCHLD"),
+    ("Failed to signal parent {}", "向父进程 {} 发送信号失败"),
+    (
+        "Terminate {} parent process(es) holding zombies?",
+        "终止 {} 个持有僵尸进程的父进程？",
+    ),
+    ("All zombies have been reaped", "所有僵尸进程均已回收"),
+    ("{} of {} zombie(s) remain", "{} 个僵尸进程仍在（共 {} 个）"),
+    ("Matching processes ({})", "匹配的进程（{}）"),
+    ("Kill {} process(es)?", "终止 {} 个进程？"),
+    ("up {}", "已运行 {}"),
+    // du / big / tmp output
+    ("({} files)", "（{} 个文件）"),
+    (
+        "{} hidden entries skipped (--no-hidden)",
+        "跳过了 {} 个隐藏条目（--no-hidden）",
+    ),
+    (
+        "Use 'ziro remove -r <path>' to clean up large entries",
+        "使用 'ziro remove -r <路径>' 清理大条目",
+    ),
+    ("No matching files found", "未找到匹配的文件"),
+    ("{} largest files", "最大的 {} 个文件"),
+    (
+        "Select files to delete (Esc to skip):",
+        "选择要删除的文件（按 Esc 跳过）：",
+    ),
+    (
+        "Delete {} files ({})? This cannot be undone!",
+        "删除 {} 个文件（{}）？此操作无法撤销！",
+    ),
+    (
+        "{} entries could not be read and are not counted",
+        "{} 个条目无法读取，未计入",
+    ),
+    ("{}s ago", "{} 秒前"),
+    ("... {} more entries ({})", "……还有 {} 个条目（{}）"),
+    ("Temp Cleanup", "临时文件清理"),
+    (
+        "Nothing older than {} to clean",
+        "没有早于 {} 的内容需要清理",
+    ),
+    ("< 1 day", "< 1 天"),
+    ("1-7 days", "1-7 天"),
+    ("1-4 weeks", "1-4 周"),
+    ("> 4 weeks", "> 4 周"),
+    ("By age", "按时间"),
+    ("By owner", "按所有者"),
+    ("Reclaimable:", "可回收："),
+    ("in {} entries", "共 {} 个条目"),
+    (
+        "Kept: {} recent, {} in use, {} unreadable",
+        "保留：{} 个较新，{} 个使用中，{} 个无法读取",
+    ),
+    ("Delete {} temp entries ({})?", "删除 {} 个临时条目（{}）？"),
+    ("{} entries", "{} 个条目"),
+    ("{} days", "{} 天"),
+    ("{} hours", "{} 小时"),
+    ("{} seconds", "{} 秒"),
+    // net / conns / serve output
+    ("No processes with open sockets", "没有打开套接字的进程"),
+    ("NAME", "名称"),
+    ("PORTS", "端口"),
+    ("LOCAL", "本地"),
+    ("REMOTE", "远端"),
+    ("No established connections", "没有已建立的连接"),
+    ("Serving", "正在提供"),
+    ("on port {}", "端口 {}"),
+    ("Press Ctrl+C to stop", "按 Ctrl+C 停止"),
+    ("Stopped; port {} is free again", "已停止；端口 {} 已释放"),
+    // doctor output
+    ("System", "系统"),
+    ("Elevated", "已提权"),
+    ("yes", "是"),
+    ("no", "否"),
+    (
+        "(needed for other users' processes and files: {})",
+        "（操作其他用户的进程和文件时需要：{}）",
+    ),
+    ("Terminal", "终端"),
+    ("Program", "程序"),
+    ("terminal", "终端"),
+    ("pipe or file", "管道或文件"),
+    ("Modern", "现代终端"),
+    ("Probe", "探测"),
+    ("answered", "已响应"),
+    ("no answer", "无响应"),
+    ("not asked", "未探测"),
+    ("Colors", "颜色"),
+    ("Icons", "图标"),
+    ("Unicode, narrow", "Unicode，窄字符"),
+    ("Mode", "模式"),
+    (
+        "(requested, or the terminal looked unable to do more)",
+        "（已请求，或终端看起来不支持更多功能）",
+    ),
+    ("Tools", "工具"),
+    ("not found", "未找到"),
+    ("Backends", "后端"),
+    ("Settings", "设置"),
+    ("Config", "配置"),
+    ("(not created, defaults apply)", "（未创建，使用默认值）"),
+    ("Language", "语言"),
+    ("Input", "输入"),
+    ("Timeout", "超时"),
+    ("interactive", "交互式"),
+    ("assume yes", "自动确认"),
+    ("no input", "禁止输入"),
+    (
+        "file locks when /proc is unreadable; open files in `info`",
+        "/proc 不可读时查询文件锁；`info` 中的打开文件",
+    ),
+    (
+        "ports, file locks and open files",
+        "端口、文件锁和打开的文件",
+    ),
+    ("traffic rates in `net`", "`net` 中的流量速率"),
+    ("ports", "端口"),
+    ("file locks when lsof is missing", "缺少 lsof 时查询文件锁"),
+    (
+        "file locks; open files in `info`",
+        "文件锁；`info` 中的打开文件",
+    ),
+    (
+        "ports; file locks when lsof is missing",
+        "端口；缺少 lsof 时查询文件锁",
+    ),
+    (
+        "file locks when Restart Manager fails; --elevate",
+        "Restart Manager 失败时查询文件锁；--elevate",
+    ),
+    (
+        "Sysinternals handle.exe, for lock holders ziro cannot see",
+        "Sysinternals handle.exe，用于 ziro 无法识别的锁持有者",
+    ),
+    ("traffic", "流量"),
+    ("file locks", "文件锁"),
+    ("signals", "信号"),
+    ("processes", "进程"),
+    ("trash", "回收站"),
+    ("elevation", "提权"),
+    // info / snapshot / config output
+    ("Executable", "可执行文件"),
+    ("Directory", "目录"),
+    ("Parent", "父进程"),
+    ("User", "用户"),
+    ("Started", "启动"),
+    ("{} ({} ago)", "{}（{} 前）"),
+    ("Open files", "打开的文件"),
+    ("Environment", "环境变量"),
+    ("none", "无"),
+    ("Resident", "常驻"),
+    ("Virtual", "虚拟"),
+    ("Anonymous", "匿名"),
+    ("Shared", "共享"),
+    ("Swap", "交换"),
+    (
+        "Saved {} processes ({}) to {}",
+        "已保存 {} 个进程（{}）到 {}",
+    ),
+    ("Process changes over {}s", "{} 秒内的进程变化"),
+    ("Memory: {} -> {} ({})", "内存：{} -> {}（{}）"),
+    (
+        "{} started, {} exited, {} changed",
+        "{} 个启动，{} 个退出，{} 个变化",
+    ),
+    ("Exited", "已退出"),
+    ("Changed", "已变化"),
+    ("(default)", "（默认）"),
+    // top / watch output
+    ("Process Memory Usage", "进程内存占用"),
+    ("Frame", "帧"),
+    ("Refresh", "刷新"),
+    ("Paused", "已暂停"),
+    ("(1 zombie)", "（1 个僵尸进程）"),
+    ("({} zombies)", "（{} 个僵尸进程）"),
+    (
+        "{} | Interval: {} | Processes: {}{} | Memory: {} / {} ({}) | Sort: {}",
+        "{} | 间隔：{} | 进程：{}{} | 内存：{} / {}（{}）| 排序：{}",
+    ),
+    ("Filter: {}", "过滤：{}"),
+    ("User: {}", "用户：{}"),
+    (
+        "(Enter to confirm, Esc to cancel)",
+        "（Enter 确认，Esc 取消）",
+    ),
+    (
+        "m/c/p/n/s/o sort | r reverse | t tree | g group | i io | d delta | ↑/↓ select | k/K kill | e renice | y copy | / filter | space pause | q quit",
+        "m/c/p/n/s/o 排序 | r 反转 | t 树形 | g 分组 | i IO | d 变化 | ↑/↓ 选择 | k/K 终止 | e 调整优先级 | y 复制 | / 过滤 | 空格 暂停 | q 退出",
+    ),
+    ("Ctrl+C to exit", "按 Ctrl+C 退出"),
+    ("Name", "名称"),
+    ("Container", "容器"),
+    ("{} procs", "{} 个进程"),
+    ("[exited]", "[已退出]"),
+    (
+        "{} | Interval: {} | User: {} | Parent: {} | Up: {}",
+        "{} | 间隔：{} | 用户：{} | 父进程：{} | 已运行：{}",
+    ),
+    ("space pause | q quit", "空格 暂停 | q 退出"),
+    ("Threads", "线程"),
+    (
+        "{} | FDs: {} | Read: {} | Write: {}",
+        "{} | 文件描述符：{} | 读：{} | 写：{}",
+    ),
+    ("Children ({})", "子进程（{}）"),
+    ("Ports ({})", "端口（{}）"),
+    ("Open files ({})", "打开的文件（{}）"),
+    ("killed", "已被终止"),
+    ("Every {}s: {}", "每 {} 秒：{}"),
+    ("Swap: {} / {} ({})", "交换：{} / {}（{}）"),
+    ("Swap: none", "交换：无"),
+    ("Load: {}", "负载：{}"),
+    ("Cores: {} {}", "核心：{} {}"),
+    ("new", "新"),
+    // errors and command results
+    (
+        "--host-ports only works inside WSL",
+        "--host-ports 仅在 WSL 中可用",
+    ),
+    (
+        "Please specify at least one file or directory path",
+        "请至少指定一个文件或目录路径",
+    ),
+    ("Some paths are still locked", "部分路径仍被锁定"),
+    ("No temp directories found", "未找到临时目录"),
+    ("--speed must be a positive number", "--speed 必须是正数"),
+    (
+        "--interval must be a positive number",
+        "--interval 必须是正数",
+    ),
+    ("Please specify a command to watch", "请指定要监视的命令"),
+    ("ziro watch cannot watch itself", "ziro watch 不能监视自身"),
+    ("No processes match the pattern", "没有进程匹配该模式"),
+    (
+        "--json only lists; run --nudge or --kill-parents without it",
+        "--json 仅用于列出；请在不带它的情况下运行 --nudge 或 --kill-parents",
+    ),
+    (
+        "JSON output is only available with --check",
+        "JSON 输出仅在使用 --check 时可用",
+    ),
+    (
+        "JSON output cannot prompt for confirmation, use --force or --dry-run",
+        "JSON 输出无法提示确认，请使用 --force 或 --dry-run",
+    ),
+    ("Nothing to restore", "没有可恢复的内容"),
+    (
+        "No matching files or directories found",
+        "未找到匹配的文件或目录",
+    ),
+    (
+        "Only init holds zombies; there is no parent to signal",
+        "僵尸进程仅由 init 持有；没有可通知的父进程",
+    ),
+    ("Elevated run completed", "提权运行已完成"),
+    (
+        "{} item(s) were refused even with elevated privileges",
+        "即使提权后仍有 {} 项被拒绝",
+    ),
+    (
+        "{} item(s) need more privileges; {}",
+        "{} 项需要更高权限；{}",
+    ),
+    (
+        "Re-running with elevated privileges: ziro {}",
+        "正在以提权方式重新运行：ziro {}",
+    ),
+    ("Elevated run failed ({})", "提权运行失败（{}）"),
+    ("Failed to run command: {}", "无法运行命令：{}"),
+    ("Command exited with {}", "命令退出：{}"),
+    (
+        "Nothing accepts connections on {}",
+        "{} 上没有任何程序接受连接",
+    ),
+    ("{} is unhealthy: {}", "{} 状态异常：{}"),
+    ("No process is connected to {}", "没有进程连接到 {}"),
+    (
+        "Timed out with {} process(es) still running",
+        "已超时，仍有 {} 个进程在运行",
+    ),
+    ("{} process(es) exited", "{} 个进程已退出"),
+    ("Set {} = {}", "已设置 {} = {}"),
+    ("{} is back to its default ({})", "{} 已恢复为默认值（{}）"),
+    ("ziro {} is up to date", "ziro {} 已是最新版本"),
+    (
+        "ziro {} is available (current {}): {}",
+        "ziro {} 可用（当前 {}）：{}",
+    ),
+    ("Update ziro {} to {}?", "将 ziro {} 更新到 {}？"),
+    ("Updated to ziro {} ({})", "已更新到 ziro {}（{}）"),
+    (
+        "Refusing to remove {}: {} is protected (remove.protected in {})",
+        "拒绝删除 {}：{} 受保护（见 {} 中的 remove.protected）",
+    ),
+    (
+        "Removed {} of {} entries in {}",
+        "已删除 {} 项（共 {} 项），用时 {}",
+    ),
+    (", {} failed", "，{} 项失败"),
+    ("{} of {} {}", "{} / {} {}"),
+    ("entries could not be removed", "项无法删除"),
+    ("priorities could not be changed", "个进程无法调整优先级"),
+    ("processes could not be killed", "个进程无法终止"),
+    ("processes could not be paused", "个进程无法暂停"),
+    ("processes could not be resumed", "个进程无法恢复"),
+    // kill --name
+    (
+        "Kill processes whose name or command line matches this regex instead of port holders",
+        "终止名称或命令行匹配该正则的进程，而不是端口占用者",
+    ),
+];
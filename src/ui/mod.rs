@@ -1,5 +1,9 @@
+pub mod i18n;
 pub mod icons;
+pub mod pager;
+pub mod prompt;
 pub mod render;
+pub mod text;
 pub mod theme;
 
 pub use icons::Icons;
@@ -23,3 +27,16 @@ pub fn format_size(size: u64) -> String {
         format!("{:.1} {}", size, UNITS[unit_index])
     }
 }
+
+/// Format a count with thousands separators (12304 -> "12,304")
+pub fn format_count(count: u64) -> String {
+    let digits = count.to_string();
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
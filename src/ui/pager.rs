@@ -0,0 +1,90 @@
+/// Long listings through `$PAGER`: output is collected first and only paged when it would
+/// scroll past the terminal; `--no-pager` turns it off
+use crate::platform::command;
+use crate::ui::prompt;
+use std::fmt::Display;
+use std::io::{self, IsTerminal, Write};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static DISABLED: AtomicBool = AtomicBool::new(false);
+
+/// `--no-pager`: print everything directly for the rest of the run
+pub fn disable() {
+    DISABLED.store(true, Ordering::Relaxed);
+}
+
+/// Output of one listing, shown at once by `show`
+#[derive(Default)]
+pub struct Paged {
+    text: String,
+    lines: usize,
+}
+
+impl Paged {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn line(&mut self, line: impl Display) {
+        use std::fmt::Write as _;
+        let _ = writeln!(self.text, "{line}");
+        self.lines += 1;
+    }
+
+    /// Print the listing, through the pager when it is taller than the terminal
+    pub fn show(self) {
+        let height = crossterm::terminal::size().map_or(usize::MAX, |(_, rows)| rows as usize);
+        let paged = self.lines >= height
+            && !DISABLED.load(Ordering::Relaxed)
+            && prompt::input_mode() != prompt::InputMode::NoInput
+            && io::stdout().is_terminal()
+            && io::stdin().is_terminal()
+            && page(&self.text).is_ok();
+        if !paged {
+            print!("{}", self.text);
+        }
+    }
+}
+
+/// `ZIRO_PAGER`, then `PAGER`, then `less`; empty or `cat` means no pager
+fn pager_command() -> Option<Command> {
+    let configured = std::env::var("ZIRO_PAGER")
+        .or_else(|_| std::env::var("PAGER"))
+        .ok();
+    let line = match configured {
+        Some(line) => line,
+        None => {
+            command::find_program("less")?;
+            "less".to_string()
+        }
+    };
+    let mut words = line.split_whitespace();
+    let program = words.next().filter(|program| *program != "cat")?;
+    let mut command = Command::new(program);
+    command.args(words);
+    // Keep colors, quit when the text fits after all, leave the screen as it was
+    if std::env::var_os("LESS").is_none() {
+        command.env("LESS", "FRX");
+    }
+    if std::env::var_os("LV").is_none() {
+        command.env("LV", "-c");
+    }
+    Some(command)
+}
+
+/// Write `text` into the pager and wait for the user to quit it
+fn page(text: &str) -> io::Result<()> {
+    let mut command = pager_command().ok_or(io::ErrorKind::NotFound)?;
+    let mut child = command.stdin(Stdio::piped()).spawn()?;
+    let written = child
+        .stdin
+        .take()
+        .map_or(Ok(()), |mut stdin| stdin.write_all(text.as_bytes()));
+    child.wait()?;
+    match written {
+        // Quitting before the end closes the pipe
+        Err(e) if e.kind() != io::ErrorKind::BrokenPipe => Err(e),
+        _ => Ok(()),
+    }
+}
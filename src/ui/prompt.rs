@@ -0,0 +1,132 @@
+/// Asking the user, or not: `--yes` answers every prompt and `--no-input` refuses to ask,
+/// so scripts and CI jobs never hang on a question
+use crate::error::ZiroError;
+use anyhow::{Result, bail};
+use inquire::{Confirm, MultiSelect, Select};
+use std::fmt::Display;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum InputMode {
+    /// Prompt on the terminal
+    #[default]
+    Interactive,
+    /// Take the confirming answer of every prompt
+    AssumeYes,
+    /// Fail instead of prompting
+    NoInput,
+}
+
+static MODE: AtomicU8 = AtomicU8::new(0);
+
+pub fn set_input_mode(mode: InputMode) {
+    MODE.store(mode as u8, Ordering::Relaxed);
+}
+
+pub fn input_mode() -> InputMode {
+    match MODE.load(Ordering::Relaxed) {
+        1 => InputMode::AssumeYes,
+        2 => InputMode::NoInput,
+        _ => InputMode::Interactive,
+    }
+}
+
+pub fn is_interactive() -> bool {
+    input_mode() == InputMode::Interactive
+}
+
+/// `--yes` wins; `--no-input`, a set `ZIRO_NONINTERACTIVE` or having no terminal at all
+/// (neither stdin nor stderr) mean no prompts
+pub fn detect_input_mode(yes: bool, no_input: bool) -> InputMode {
+    let env_says_no = std::env::var("ZIRO_NONINTERACTIVE")
+        .is_ok_and(|value| !matches!(value.trim(), "" | "0" | "false"));
+    let has_terminal = std::io::stdin().is_terminal() || std::io::stderr().is_terminal();
+    if yes {
+        InputMode::AssumeYes
+    } else if no_input || env_says_no || !has_terminal {
+        InputMode::NoInput
+    } else {
+        InputMode::Interactive
+    }
+}
+
+/// Ask `prompt`; `--yes` answers yes
+pub fn confirm(prompt: Confirm) -> Result<bool> {
+    match input_mode() {
+        InputMode::Interactive => Ok(prompt.prompt()?),
+        InputMode::AssumeYes => Ok(true),
+        InputMode::NoInput => Err(no_input(prompt.message)),
+    }
+}
+
+/// Ask `prompt`; `--yes` takes its preselected options, and fails without any
+pub fn multi_select<T: Display>(prompt: MultiSelect<T>) -> Result<Vec<T>> {
+    match input_mode() {
+        InputMode::Interactive => Ok(prompt.prompt()?),
+        InputMode::AssumeYes => {
+            let Some(defaults) = prompt.default else {
+                bail!(ZiroError::Usage(format!(
+                    "'{}' needs a choice that --yes cannot make; run it in a terminal",
+                    prompt.message.trim_end_matches(':')
+                )));
+            };
+            Ok(prompt
+                .options
+                .into_iter()
+                .enumerate()
+                .filter(|(i, _)| defaults.contains(i))
+                .map(|(_, option)| option)
+                .collect())
+        }
+        InputMode::NoInput => Err(no_input(prompt.message)),
+    }
+}
+
+/// Ask `prompt`; `--yes` takes the option at `yes`
+pub fn select<T: Display>(prompt: Select<T>, yes: usize) -> Result<T> {
+    match input_mode() {
+        InputMode::Interactive => Ok(prompt.prompt()?),
+        InputMode::AssumeYes => prompt
+            .options
+            .into_iter()
+            .nth(yes)
+            .ok_or_else(|| anyhow::anyhow!("No option {yes} in '{}'", prompt.message)),
+        InputMode::NoInput => Err(no_input(prompt.message)),
+    }
+}
+
+/// The error for a question asked while input is off, a usage error
+pub fn no_input(question: &str) -> anyhow::Error {
+    anyhow::anyhow!(ZiroError::Usage(format!(
+        "'{}' needs an answer, but input is disabled (--no-input, ZIRO_NONINTERACTIVE or no terminal); pass --yes to confirm",
+        question.trim_end_matches([':', '?']).trim()
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assume_yes_answers() {
+        set_input_mode(InputMode::AssumeYes);
+        let options = vec!["a", "b", "c"];
+        let picked = multi_select(MultiSelect::new("Pick:", options.clone()).with_default(&[0, 2]));
+        assert_eq!(picked.unwrap(), ["a", "c"]);
+        assert!(multi_select(MultiSelect::new("Pick:", options.clone())).is_err());
+        assert_eq!(
+            select(Select::new("Do:", options).with_starting_cursor(2), 0).unwrap(),
+            "a"
+        );
+        assert!(confirm(Confirm::new("Sure?").with_default(false)).unwrap());
+
+        set_input_mode(InputMode::NoInput);
+        let err = confirm(Confirm::new("Delete 3 files?")).unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("'Delete 3 files' needs an answer")
+        );
+        set_input_mode(InputMode::Interactive);
+    }
+}
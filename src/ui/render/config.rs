@@ -0,0 +1,45 @@
+use crate::tr;
+use crate::ui::Theme;
+use anyhow::Result;
+use serde::Serialize;
+use std::path::Path;
+
+/// `ziro config list`: one `key = value` line per key, defaults dimmed
+pub fn display_config(path: &Path, entries: &[(String, String, bool)]) {
+    let theme = Theme::new();
+    println!("{}", theme.muted(format!("# {}", path.display())));
+    let width = entries
+        .iter()
+        .map(|(key, _, _)| key.len())
+        .max()
+        .unwrap_or(0);
+    for (key, value, is_default) in entries {
+        let line = format!("{key:<width$} = {value}");
+        if *is_default {
+            println!("{}", theme.muted(format!("{line}  {}", tr!("(default)"))));
+        } else {
+            println!("{line}");
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ConfigEntryJson<'a> {
+    key: &'a str,
+    /// TOML spelling of the value
+    value: &'a str,
+    default: bool,
+}
+
+pub fn display_config_json(entries: &[(String, String, bool)]) -> Result<()> {
+    let entries: Vec<ConfigEntryJson> = entries
+        .iter()
+        .map(|(key, value, is_default)| ConfigEntryJson {
+            key,
+            value,
+            default: *is_default,
+        })
+        .collect();
+    println!("{}", serde_json::to_string_pretty(&entries)?);
+    Ok(())
+}
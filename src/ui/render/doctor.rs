@@ -0,0 +1,139 @@
+use crate::cli::doctor::Diagnostics;
+use crate::platform::elevation;
+use crate::tr;
+use crate::ui::{Theme, i18n};
+use anyhow::Result;
+use console::{Alignment, pad_str};
+
+/// Width of the label column
+const LABEL_WIDTH: usize = 12;
+
+/// `ziro doctor`
+pub fn display_diagnostics(report: &Diagnostics) {
+    let theme = Theme::new();
+    let row = |label: &'static str, value: String| {
+        let label = pad_str(i18n::tr(label), LABEL_WIDTH, Alignment::Left, None);
+        println!("  {} {value}", theme.info(label));
+    };
+    let yes_no = |value: bool| if value { tr!("yes") } else { tr!("no") }.to_string();
+
+    println!("{} {}", theme.icon_search(), theme.title("ziro doctor"));
+    println!();
+
+    println!("{}", theme.title(tr!("System")));
+    row("ziro", report.version.to_string());
+    row("OS", format!("{} {}", report.os, report.arch));
+    row(
+        "Elevated",
+        if report.elevated {
+            tr!("yes").to_string()
+        } else {
+            format!(
+                "{} {}",
+                tr!("no"),
+                theme.muted(tr!(
+                    "(needed for other users' processes and files: {})",
+                    elevation::elevation_hint()
+                ))
+            )
+        },
+    );
+    println!();
+
+    let terminal = &report.terminal;
+    let profile = &terminal.profile;
+    println!("{}", theme.title(tr!("Terminal")));
+    row("TERM", terminal.term.clone().unwrap_or_else(|| "-".into()));
+    if let Some(program) = &terminal.term_program {
+        row("Program", program.clone());
+    }
+    row(
+        "stdout",
+        if terminal.stdout_is_terminal {
+            tr!("terminal")
+        } else {
+            tr!("pipe or file")
+        }
+        .to_string(),
+    );
+    row("UTF-8", yes_no(terminal.capabilities.utf8));
+    row("ANSI", yes_no(terminal.capabilities.vt_supported));
+    row("Modern", yes_no(terminal.capabilities.looks_modern));
+    row(
+        "Probe",
+        match terminal.capabilities.probe {
+            Some(probe) if probe.vt => tr!("answered"),
+            Some(_) => tr!("no answer"),
+            None => tr!("not asked"),
+        }
+        .to_string(),
+    );
+    row("Colors", yes_no(terminal.colors));
+    row(
+        "Icons",
+        match (profile.ascii_icons, profile.narrow) {
+            (true, _) => "ASCII",
+            (false, true) => tr!("Unicode, narrow"),
+            (false, false) => "Unicode",
+        }
+        .to_string(),
+    );
+    if profile.plain {
+        row(
+            "Mode",
+            format!(
+                "plain {}",
+                theme.muted(tr!("(requested, or the terminal looked unable to do more)"))
+            ),
+        );
+    }
+    println!();
+
+    println!("{}", theme.title(tr!("Tools")));
+    for tool in &report.tools {
+        let (icon, location) = match &tool.path {
+            Some(path) => (theme.icon_success(), path.display().to_string()),
+            None if tool.required => (theme.icon_error(), theme.error(tr!("not found"))),
+            None => (theme.icon_warning(), theme.warn(tr!("not found"))),
+        };
+        println!(
+            "  {icon} {:<width$} {location} {}",
+            tool.name,
+            theme.muted(format!("({})", i18n::tr(tool.purpose))),
+            width = LABEL_WIDTH - 2
+        );
+    }
+    println!();
+
+    println!("{}", theme.title(tr!("Backends")));
+    for backend in &report.backends {
+        row(backend.task, backend.backend.to_string());
+    }
+    println!();
+
+    println!("{}", theme.title(tr!("Settings")));
+    let config = &report.config;
+    let path = config
+        .path
+        .as_ref()
+        .map_or_else(|| "-".to_string(), |path| path.display().to_string());
+    row(
+        "Config",
+        match (&config.error, config.exists) {
+            (Some(error), _) => format!("{path} {}", theme.error(format!("({error})"))),
+            (None, true) => path,
+            (None, false) => format!(
+                "{path} {}",
+                theme.muted(tr!("(not created, defaults apply)"))
+            ),
+        },
+    );
+    row("Language", report.language.to_string());
+    row("Input", i18n::tr(report.input).to_string());
+    row("Timeout", format!("{}s", report.command_timeout_secs));
+}
+
+pub fn display_diagnostics_json(report: &Diagnostics) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(report)?);
+    Ok(())
+}
@@ -0,0 +1,260 @@
+use crate::core::fs_ops::{DiskUsage, FileInfo, LargeFile, UsageNode};
+use crate::tr;
+use crate::ui::{Theme, prompt};
+use anyhow::Result;
+use console::{Alignment, measure_text_width, pad_str};
+use inquire::{Confirm, MultiSelect};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use super::{format_count, format_size, tree_branches, truncate_string};
+
+const NAME_W: usize = 32;
+const SIZE_W: usize = 10;
+const BAR_W: usize = 20;
+
+/// Display disk usage as a tree of the largest entries with percentage bars
+pub fn display_disk_usage(disk_usage: &DiskUsage, limit: usize, depth: usize) {
+    let theme = Theme::new();
+    let usage = &disk_usage.root;
+
+    println!(
+        "{} {} {} {}",
+        theme.icon_folder(),
+        theme.title(usage.path.display().to_string()),
+        theme.warn(format_size(usage.size)),
+        theme.muted(tr!("({} files)", format_count(usage.file_count)))
+    );
+
+    display_usage_children(usage, usage.size, "", limit, depth, &theme);
+
+    display_unreadable(&disk_usage.skipped, &theme);
+    if disk_usage.hidden_skipped > 0 {
+        println!(
+            "{}",
+            theme.muted(tr!(
+                "{} hidden entries skipped (--no-hidden)",
+                format_count(disk_usage.hidden_skipped as u64)
+            ))
+        );
+    }
+
+    println!();
+    println!(
+        "{}",
+        theme.muted(tr!("Use 'ziro remove -r <path>' to clean up large entries"))
+    );
+}
+
+/// Display the largest files, biggest first
+pub fn display_largest_files(files: &[LargeFile], skipped: &[(PathBuf, anyhow::Error)]) {
+    let theme = Theme::new();
+
+    if files.is_empty() {
+        println!("{}", theme.warn(tr!("No matching files found")));
+        display_unreadable(skipped, &theme);
+        return;
+    }
+
+    let total: u64 = files.iter().map(|f| f.file.size).sum();
+    println!(
+        "{} {} {}",
+        theme.icon_search(),
+        theme.title(tr!("{} largest files", files.len())),
+        theme.muted(tr!("({} total)", format_size(total)))
+    );
+    println!();
+
+    let now = SystemTime::now();
+    let index_w = files.len().to_string().len();
+    for (index, large) in files.iter().enumerate() {
+        let size = format_size(large.file.size);
+        let age = large
+            .modified
+            .and_then(|m| now.duration_since(m).ok())
+            .map(format_age)
+            .unwrap_or_else(|| "?".to_string());
+
+        println!(
+            "{} {} {} {}",
+            theme.muted(format!("{:>index_w$}.", index + 1)),
+            theme.warn(pad_str(&size, SIZE_W, Alignment::Right, None)),
+            theme.muted(pad_str(&age, 8, Alignment::Right, None)),
+            theme.highlight(large.file.path.display().to_string())
+        );
+    }
+
+    display_unreadable(skipped, &theme);
+}
+
+/// Let the user pick large files to delete
+///
+/// Returns the selected files, or an empty list if cancelled.
+pub fn select_files_to_delete(files: &[LargeFile]) -> Result<Vec<FileInfo>> {
+    let theme = Theme::new();
+
+    let options: Vec<String> = files
+        .iter()
+        .enumerate()
+        .map(|(i, large)| {
+            format!(
+                "[{}] {} - {}",
+                i,
+                format_size(large.file.size),
+                truncate_string(&large.file.path.display().to_string(), 70)
+            )
+        })
+        .collect();
+
+    println!();
+    let selected = MultiSelect::new(tr!("Select files to delete (Esc to skip):"), options)
+        .prompt_skippable()?
+        .unwrap_or_default();
+
+    let chosen: Vec<FileInfo> = selected
+        .iter()
+        .filter_map(|s| {
+            let idx_str = s.trim_start_matches('[').split(']').next()?;
+            let idx: usize = idx_str.parse().ok()?;
+            files.get(idx).map(|large| large.file.clone())
+        })
+        .collect();
+
+    if chosen.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let size: u64 = chosen.iter().map(|f| f.size).sum();
+    let confirm = prompt::confirm(
+        Confirm::new(&tr!(
+            "Delete {} files ({})? This cannot be undone!",
+            chosen.len(),
+            format_size(size)
+        ))
+        .with_default(false),
+    )?;
+
+    if confirm {
+        Ok(chosen)
+    } else {
+        println!("{}", theme.warn(tr!("Operation cancelled")));
+        Ok(vec![])
+    }
+}
+
+fn display_unreadable(skipped: &[(PathBuf, anyhow::Error)], theme: &Theme) {
+    if skipped.is_empty() {
+        return;
+    }
+    println!();
+    println!(
+        "{} {}",
+        theme.icon_warning(),
+        theme.warn(tr!(
+            "{} entries could not be read and are not counted",
+            skipped.len()
+        ))
+    );
+}
+
+fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    match secs {
+        0..60 => tr!("{}s ago", secs),
+        60..3600 => tr!("{}m ago", secs / 60),
+        3600..86400 => tr!("{}h ago", secs / 3600),
+        _ => tr!("{}d ago", secs / 86400),
+    }
+}
+
+fn display_usage_children(
+    node: &UsageNode,
+    total: u64,
+    prefix: &str,
+    limit: usize,
+    depth: usize,
+    theme: &Theme,
+) {
+    if depth == 0 || node.children.is_empty() {
+        return;
+    }
+
+    let shown = node.children.len().min(limit.max(1));
+    let hidden = &node.children[shown..];
+    let rows = shown + usize::from(!hidden.is_empty());
+
+    for (index, child) in node.children.iter().take(shown).enumerate() {
+        let (branch, continuation) = tree_branches(rows, index);
+
+        let name = child
+            .path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| child.path.display().to_string());
+        let name = if child.is_dir {
+            format!("{name}/")
+        } else {
+            name
+        };
+        let name_w = NAME_W.saturating_sub(measure_text_width(prefix) + 3);
+        let name_cell = pad_str(
+            &truncate_string(&name, name_w),
+            name_w,
+            Alignment::Left,
+            None,
+        )
+        .into_owned();
+        let name_cell = if child.is_dir {
+            theme.blue(name_cell)
+        } else {
+            theme.success(name_cell)
+        };
+
+        let pct = percent(child.size, total);
+        let size = format_size(child.size);
+        let size_cell = pad_str(&size, SIZE_W, Alignment::Right, None);
+
+        println!(
+            "{prefix}{branch} {name_cell} {} {} {}",
+            theme.warn(size_cell),
+            theme.muted(usage_bar(pct)),
+            theme.accent(format!("{pct:5.1}%"))
+        );
+
+        display_usage_children(
+            child,
+            total,
+            &format!("{prefix}{continuation}"),
+            limit,
+            depth - 1,
+            theme,
+        );
+    }
+
+    if !hidden.is_empty() {
+        let hidden_size: u64 = hidden.iter().map(|c| c.size).sum();
+        let (branch, _) = tree_branches(rows, rows - 1);
+        println!(
+            "{prefix}{branch} {}",
+            theme.muted(tr!(
+                "... {} more entries ({})",
+                hidden.len(),
+                format_size(hidden_size)
+            ))
+        );
+    }
+}
+
+fn percent(size: u64, total: u64) -> f64 {
+    if total > 0 {
+        size as f64 / total as f64 * 100.0
+    } else {
+        0.0
+    }
+}
+
+fn usage_bar(pct: f64) -> String {
+    let filled = ((pct / 100.0) * BAR_W as f64).round() as usize;
+    let filled = filled.min(BAR_W);
+    format!("[{}{}]", "=".repeat(filled), "·".repeat(BAR_W - filled))
+}
@@ -1,22 +1,32 @@
-use crate::core::fs_ops::FileInfo;
+use crate::core::fs_ops::{
+    DeletionError, FileInfo, LingeringEntry, PENDING_DIR, PendingDelete, RemovalOutcome,
+    RemovalPlan, RemovalReport, TrashRecord,
+};
 use crate::core::process::FileLockInfo;
-use crate::ui::Theme;
+use crate::platform::elevation::{self, Refusal};
+use crate::tr;
+use crate::ui::{Theme, i18n, prompt};
 use anyhow::Result;
-use inquire::Confirm;
-use std::path::PathBuf;
+use inquire::{Confirm, MultiSelect};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
 
-use super::{format_size, tree_branches, truncate_string};
+use super::{format_count, format_size, tree_branches, truncate_string};
 
 /// Display file/directory lock status
 pub fn display_file_locks(infos: &[FileLockInfo]) {
     let theme = Theme::new();
 
     if infos.is_empty() {
-        println!("{}", theme.warn("No paths found to check"));
+        println!("{}", theme.warn(tr!("No paths found to check")));
         return;
     }
 
-    println!("{} {}", theme.icon_search(), theme.title("File Lock Query"));
+    println!(
+        "{} {}",
+        theme.icon_search(),
+        theme.title(tr!("File Lock Query"))
+    );
     println!();
 
     let total = infos.len();
@@ -24,15 +34,15 @@ pub fn display_file_locks(infos: &[FileLockInfo]) {
         let (branch, continuation) = tree_branches(total, index);
 
         let kind = if info.path.is_dir() {
-            theme.blue("Dir")
+            theme.blue(tr!("Dir"))
         } else {
-            theme.success("File")
+            theme.success(tr!("File"))
         };
 
         let status = if info.locked {
-            theme.error("Locked")
+            theme.error(tr!("Locked"))
         } else {
-            theme.success("Free")
+            theme.success(tr!("Free"))
         };
 
         println!(
@@ -46,7 +56,9 @@ pub fn display_file_locks(infos: &[FileLockInfo]) {
             if info.locked {
                 println!(
                     "{continuation}└─ {}",
-                    theme.warn("No locking process found, may need admin privileges or handle.exe")
+                    theme.warn(tr!(
+                        "No locking process found, may need admin privileges or handle.exe"
+                    ))
                 );
             }
         } else {
@@ -58,7 +70,7 @@ pub fn display_file_locks(infos: &[FileLockInfo]) {
 
                 println!(
                     "{continuation}{proc_branch} {} {} ({})",
-                    theme.info("Process"),
+                    theme.info(tr!("Process")),
                     theme.success(&proc_info.name),
                     theme.muted(format!("PID: {}", proc_info.pid))
                 );
@@ -66,7 +78,7 @@ pub fn display_file_locks(infos: &[FileLockInfo]) {
                 if !proc_info.cmd.is_empty() {
                     println!(
                         "{continuation}{proc_continuation} {} {}",
-                        theme.info("Command"),
+                        theme.info(tr!("Command")),
                         theme.muted(truncate_string(&proc_info.cmd, 80))
                     );
                 }
@@ -79,10 +91,73 @@ pub fn display_file_locks(infos: &[FileLockInfo]) {
     }
 }
 
+/// Let the user pick which lock-holding processes to kill
+///
+/// Returns the selected PIDs (deduplicated), or an empty list if cancelled.
+pub fn select_lock_holders_to_kill(infos: &[FileLockInfo]) -> Result<Vec<u32>> {
+    let theme = Theme::new();
+
+    let mut holders: Vec<(u32, String, Vec<String>)> = Vec::new();
+    for info in infos {
+        for proc_info in &info.processes {
+            let path = info.path.display().to_string();
+            match holders.iter_mut().find(|(pid, _, _)| *pid == proc_info.pid) {
+                Some((_, _, paths)) => paths.push(path),
+                None => holders.push((proc_info.pid, proc_info.name.clone(), vec![path])),
+            }
+        }
+    }
+
+    let options: Vec<String> = holders
+        .iter()
+        .enumerate()
+        .map(|(i, (pid, name, paths))| {
+            format!(
+                "[{}] {} (PID: {}) - {}",
+                i,
+                name,
+                pid,
+                truncate_string(&paths.join(", "), 60)
+            )
+        })
+        .collect();
+
+    let defaults: Vec<usize> = (0..options.len()).collect();
+
+    let selected = prompt::multi_select(
+        MultiSelect::new(tr!("Select processes to kill:"), options).with_default(&defaults),
+    )?;
+
+    let pids: Vec<u32> = selected
+        .iter()
+        .filter_map(|s| {
+            let idx_str = s.trim_start_matches('[').split(']').next()?;
+            let idx: usize = idx_str.parse().ok()?;
+            holders.get(idx).map(|(pid, _, _)| *pid)
+        })
+        .collect();
+
+    if pids.is_empty() {
+        println!("{}", theme.warn(tr!("No processes selected")));
+        return Ok(vec![]);
+    }
+
+    let confirm =
+        prompt::confirm(Confirm::new(tr!("Confirm killing these processes?")).with_default(false))?;
+
+    if confirm {
+        Ok(pids)
+    } else {
+        println!("{}", theme.warn(tr!("Operation cancelled")));
+        Ok(vec![])
+    }
+}
+
 /// Display deletion preview
-pub fn display_deletion_preview(files: &[FileInfo]) {
+pub fn display_deletion_preview(plan: &RemovalPlan) {
     let theme = Theme::new();
-    let total_size: u64 = files.iter().map(|f| f.size).sum();
+    let files = &plan.files;
+    let total_size: u64 = files.iter().filter(|f| !f.is_dir).map(|f| f.size).sum();
     let (file_count, dir_count) = files.iter().fold((0, 0), |(files, dirs), f| {
         if f.is_dir {
             (files, dirs + 1)
@@ -93,11 +168,33 @@ pub fn display_deletion_preview(files: &[FileInfo]) {
 
     println!(
         "{} {} {} {}",
-        theme.title("Summary:"),
-        theme.success(format!("{file_count} files")),
-        theme.blue(format!("{dir_count} directories")),
-        theme.warn(format!("Total size: {}", format_size(total_size)))
+        theme.title(tr!("Summary:")),
+        theme.success(tr!("{} files", file_count)),
+        theme.blue(tr!("{} directories", dir_count)),
+        theme.warn(tr!("Total size: {}", format_size(total_size)))
     );
+
+    let hidden_included = files.iter().filter(|f| f.is_hidden).count();
+    if plan.hidden_skipped > 0 {
+        println!(
+            "{} {}",
+            theme.title(tr!("Hidden:")),
+            theme.muted(tr!(
+                "{} included, {} skipped (--no-hidden)",
+                hidden_included,
+                plan.hidden_skipped
+            ))
+        );
+    } else if hidden_included > 0 {
+        println!(
+            "{} {}",
+            theme.title(tr!("Hidden:")),
+            theme.muted(tr!(
+                "{} included (use --no-hidden to skip)",
+                hidden_included
+            ))
+        );
+    }
     println!();
 
     let total = files.len().min(10);
@@ -110,7 +207,7 @@ pub fn display_deletion_preview(files: &[FileInfo]) {
             theme.icon_file()
         };
 
-        let size_str = if !file.is_dir && !file.is_symlink {
+        let size_str = if !file.is_symlink {
             let size = format!(" ({})", format_size(file.size));
             theme.muted(size)
         } else {
@@ -118,17 +215,27 @@ pub fn display_deletion_preview(files: &[FileInfo]) {
         };
 
         let file_type = if file.is_dir {
-            theme.blue("Dir")
+            theme.blue(tr!("Dir"))
         } else if file.is_symlink {
-            theme.accent("Symlink")
+            theme.accent(tr!("Symlink"))
         } else {
-            theme.success("File")
+            theme.success(tr!("File"))
+        };
+
+        let target_str = match &file.link_target {
+            Some(target) => format!(
+                " {} {}",
+                theme.muted("->"),
+                theme.accent(target.display().to_string())
+            ),
+            None => String::new(),
         };
 
         println!(
-            "  {} {} {}{}",
+            "  {} {}{} {}{}",
             icon,
             file.path.display(),
+            target_str,
             file_type,
             size_str
         );
@@ -137,46 +244,193 @@ pub fn display_deletion_preview(files: &[FileInfo]) {
     if files.len() > 10 {
         println!(
             "{}",
-            theme.muted(format!("  ... {} more items", files.len() - 10))
+            theme.muted(format!("  {}", tr!("... {} more items", files.len() - 10)))
         );
     }
 
     println!();
 }
 
+/// How `confirm_deletion` should ask
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ConfirmOptions {
+    /// Do not ask at all (`--force`)
+    pub skip: bool,
+    /// Only preview
+    pub dry_run: bool,
+    /// Ask for every top-level path instead of once
+    pub each: bool,
+    /// Entries go to the trash rather than being deleted
+    pub trash: bool,
+}
+
+/// Warn about entries a walk did not collect, e.g. `skipping /mnt/usb (on a different filesystem)`
+pub fn display_left_out(left_out: &[(PathBuf, &'static str)]) {
+    for (path, reason) in left_out {
+        eprintln!(
+            "{}",
+            tr!(
+                "Warning: skipping {} ({})",
+                path.display(),
+                i18n::tr(reason)
+            )
+        );
+    }
+}
+
 /// Confirm deletion operation
-pub fn confirm_deletion(files: &[FileInfo], skip_confirm: bool, dry_run: bool) -> Result<bool> {
+///
+/// With `each`, declined paths are dropped from `plan`. Returns false if nothing is left to do.
+pub fn confirm_deletion(
+    plan: &mut RemovalPlan,
+    roots: &[PathBuf],
+    opts: &ConfirmOptions,
+) -> Result<bool> {
     let theme = Theme::new();
 
-    if dry_run {
+    if opts.dry_run {
         println!(
             "{} {}",
             theme.icon_search(),
-            theme.info_bold("Preview mode - no files will be deleted")
+            theme.info_bold(tr!("Preview mode - no files will be deleted"))
         );
-        display_deletion_preview(files);
+        display_deletion_preview(plan);
         return Ok(true);
     }
 
-    if skip_confirm {
+    if opts.each {
+        return confirm_each_root(plan, roots);
+    }
+
+    if opts.skip {
         return Ok(true);
     }
 
-    println!(
-        "{} {}",
-        theme.icon_warning(),
-        theme.error_bold("About to delete the following")
-    );
-    display_deletion_preview(files);
+    if opts.trash {
+        println!(
+            "{} {}",
+            theme.icon_warning(),
+            theme.info_bold(tr!("About to move the following to the trash"))
+        );
+    } else {
+        println!(
+            "{} {}",
+            theme.icon_warning(),
+            theme.error_bold(tr!("About to delete the following"))
+        );
+    }
+    display_deletion_preview(plan);
 
-    let confirm = Confirm::new("Confirm deleting these items? This cannot be undone!")
-        .with_default(false)
-        .with_help_message("Use --force to skip this confirmation")
-        .prompt()?;
+    let question = if opts.trash {
+        tr!("Move these items to the trash? Use 'ziro restore' to undo")
+    } else {
+        tr!("Confirm deleting these items? This cannot be undone!")
+    };
+    let confirm = prompt::confirm(
+        Confirm::new(question)
+            .with_default(false)
+            .with_help_message(tr!("Use --force to skip this confirmation")),
+    )?;
 
     Ok(confirm)
 }
 
+/// Answer to a per-path prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ItemChoice {
+    Yes,
+    No,
+    All,
+    Quit,
+}
+
+fn confirm_each_root(plan: &mut RemovalPlan, roots: &[PathBuf]) -> Result<bool> {
+    let theme = Theme::new();
+    let mut declined: Vec<&PathBuf> = Vec::new();
+    let mut accept_rest = match prompt::input_mode() {
+        prompt::InputMode::Interactive => false,
+        prompt::InputMode::AssumeYes => true,
+        prompt::InputMode::NoInput => return Err(prompt::no_input(tr!("Delete each path"))),
+    };
+
+    for (index, root) in roots.iter().enumerate() {
+        let items: Vec<&FileInfo> = plan
+            .files
+            .iter()
+            .filter(|f| plan.origin_of(&f.path).starts_with(root))
+            .collect();
+        if items.is_empty() || accept_rest {
+            continue;
+        }
+
+        let size: u64 = items.iter().filter(|f| !f.is_dir).map(|f| f.size).sum();
+        let detail = if items.len() == 1 && !items[0].is_dir {
+            format_size(size)
+        } else {
+            tr!("{} items, {}", items.len(), format_size(size))
+        };
+        let question = format!(
+            "{} {} ",
+            tr!(
+                "Delete {} ({})?",
+                theme.highlight(root.display().to_string()),
+                theme.muted(detail)
+            ),
+            theme.muted(tr!("[y]es/[n]o/[a]ll/[q]uit"))
+        );
+
+        match prompt_item_choice(&question)? {
+            ItemChoice::Yes => {}
+            ItemChoice::No => declined.push(root),
+            ItemChoice::All => accept_rest = true,
+            ItemChoice::Quit => {
+                declined.extend(&roots[index..]);
+                break;
+            }
+        }
+    }
+
+    let kept: Vec<FileInfo> = std::mem::take(&mut plan.files)
+        .into_iter()
+        .filter(|f| {
+            let origin = plan.origin_of(&f.path);
+            !declined.iter().any(|root| origin.starts_with(root))
+        })
+        .collect();
+    plan.files = kept;
+    Ok(!plan.files.is_empty())
+}
+
+/// Read a single-key answer, falling back to a line of input when not on a terminal
+fn prompt_item_choice(question: &str) -> Result<ItemChoice> {
+    let term = console::Term::stderr();
+
+    loop {
+        term.write_str(question)?;
+        let key = if term.is_term() {
+            term.read_char()?
+        } else {
+            let mut line = String::new();
+            std::io::stdin().read_line(&mut line)?;
+            if line.is_empty() {
+                // EOF: treat as quit rather than deleting without an answer
+                term.write_line("")?;
+                return Ok(ItemChoice::Quit);
+            }
+            line.trim().chars().next().unwrap_or('\n')
+        };
+        term.write_line(&key.to_string())?;
+
+        match key.to_ascii_lowercase() {
+            'y' => return Ok(ItemChoice::Yes),
+            'n' | '\n' | '\r' => return Ok(ItemChoice::No),
+            'a' => return Ok(ItemChoice::All),
+            'q' | '\u{1b}' => return Ok(ItemChoice::Quit),
+            _ => {}
+        }
+    }
+}
+
 /// Check file locks and handle them
 ///
 /// - Default: show lock info and ask user whether to continue
@@ -196,10 +450,10 @@ pub fn check_and_warn_file_locks(files: &[FileInfo], anyway: bool) -> Result<boo
             eprintln!(
                 "{} {}: {}",
                 theme.icon_warning(),
-                theme.warn("Unable to check file locks"),
+                theme.warn(tr!("Unable to check file locks")),
                 e
             );
-            eprintln!("{}", theme.muted("Will proceed with deletion"));
+            eprintln!("{}", theme.muted(tr!("Will proceed with deletion")));
             return Ok(true);
         }
     };
@@ -227,28 +481,27 @@ pub fn check_and_warn_file_locks(files: &[FileInfo], anyway: bool) -> Result<boo
         println!(
             "{} {}",
             theme.icon_warning(),
-            theme.error_bold("Files locked, killing locking processes...")
+            theme.error_bold(tr!("Files locked, killing locking processes..."))
         );
         println!();
         display_file_locks(&locked_files);
         println!();
 
-        let results = kill_processes_force(&pids);
-
-        for (pid, result) in results {
-            match result {
-                Ok(_) => {
+        for report in kill_processes_force(&pids) {
+            let pid = report.pid;
+            match report.result {
+                Ok(()) => {
                     println!(
                         "{} {}",
                         theme.icon_success(),
-                        theme.muted(format!("Killed process PID: {pid}"))
+                        theme.muted(tr!("Killed process PID: {}", pid))
                     );
                 }
                 Err(e) => {
                     println!(
                         "{} {}",
                         theme.icon_error(),
-                        theme.error(format!("Failed to kill process PID {pid}: {e}"))
+                        theme.error(tr!("Failed to kill process PID {}: {}", pid, e))
                     );
                 }
             }
@@ -262,7 +515,7 @@ pub fn check_and_warn_file_locks(files: &[FileInfo], anyway: bool) -> Result<boo
     println!(
         "{} {}",
         theme.icon_warning(),
-        theme.error_bold("Files are locked")
+        theme.error_bold(tr!("Files are locked"))
     );
     println!();
 
@@ -270,76 +523,472 @@ pub fn check_and_warn_file_locks(files: &[FileInfo], anyway: bool) -> Result<boo
 
     println!();
 
-    let confirm = Confirm::new("These files are in use, continue trying to delete?")
-        .with_default(false)
-        .with_help_message("Use --anyway to auto-kill locking processes and delete")
-        .prompt()?;
+    let confirm = prompt::confirm(
+        Confirm::new(tr!("These files are in use, continue trying to delete?"))
+            .with_default(false)
+            .with_help_message(tr!(
+                "Use --anyway to auto-kill locking processes and delete"
+            )),
+    )?;
 
     Ok(confirm)
 }
 
 /// Display deletion results
-pub fn display_removal_results(
-    results: &[(std::path::PathBuf, Result<()>)],
-    dry_run: bool,
-    verbose: bool,
-) {
+pub fn display_removal_results(plan: &RemovalPlan, report: &RemovalReport, verbose: bool) {
     let theme = Theme::new();
-    let action = if dry_run { "Preview" } else { "Delete" };
-    let (success_count, error_count) =
-        results
-            .iter()
-            .fold((0, 0), |(success, error), (_, result)| {
-                if result.is_ok() {
-                    (success + 1, error)
-                } else {
-                    (success, error + 1)
-                }
-            });
+
+    let success_count = report.succeeded();
+    let error_count = report.failures().count();
+    let skipped_count = plan.skipped.len() + report.skipped();
+
+    let skipped = if skipped_count == 0 {
+        String::new()
+    } else {
+        format!(" {}", theme.warn(tr!("Skipped: {}", skipped_count)))
+    };
+    println!(
+        "{} {} {}{}",
+        theme.title(tr!("Done")),
+        theme.success(tr!("Success: {}", success_count)),
+        theme.error(tr!("Failed: {}", error_count)),
+        skipped
+    );
+    display_reclaimed_space(report);
+
+    for (path, e) in &plan.skipped {
+        println!(
+            "{} {} {:#}",
+            theme.icon_warning(),
+            theme.warn(tr!("Skipped {}", path.display())),
+            e
+        );
+    }
 
     if !verbose {
+        for (path, e) in report.failures() {
+            println!(
+                "{} {} {}",
+                theme.icon_error(),
+                theme.error(tr!("Failed to delete {}", path.display())),
+                e
+            );
+        }
+        return;
+    }
+
+    for entry in &report.entries {
+        let path = entry.path.display();
+        match &entry.outcome {
+            RemovalOutcome::Removed if report.dry_run => println!(
+                "{} {}",
+                theme.icon_success(),
+                theme.muted(tr!("Preview {}", path))
+            ),
+            RemovalOutcome::Removed => println!(
+                "{} {}",
+                theme.icon_success(),
+                theme.muted(tr!("Delete {}", path))
+            ),
+            RemovalOutcome::Trashed => println!(
+                "{} {}",
+                theme.icon_success(),
+                theme.muted(tr!("Trash {}", path))
+            ),
+            RemovalOutcome::Skipped => println!(
+                "{} {}",
+                theme.icon_warning(),
+                theme.warn(tr!("Not attempted {}", path))
+            ),
+            RemovalOutcome::Failed { error, .. } => println!(
+                "{} {} {}",
+                theme.icon_error(),
+                theme.error(tr!("Failed to delete {}", path)),
+                error
+            ),
+        }
+        if !entry.killed.is_empty() {
+            let pids: Vec<String> = entry.killed.iter().map(u32::to_string).collect();
+            println!(
+                "  {}",
+                theme.muted(tr!("killed locking PID {}", pids.join(", ")))
+            );
+        }
+    }
+}
+
+/// Display reclaimed space, e.g. "Freed 1.8 GB in 12,304 files in 4.2s"
+fn display_reclaimed_space(report: &RemovalReport) {
+    let theme = Theme::new();
+    let (bytes, file_count) = (report.bytes_freed, report.files_freed);
+    let count = format_count(file_count as u64);
+    let elapsed = format!("{:.1}s", report.elapsed.as_secs_f64());
+
+    let line = match (report.dry_run, file_count == 1) {
+        (true, true) => tr!("Would free {} in {} file", format_size(bytes), count),
+        (true, false) => tr!("Would free {} in {} files", format_size(bytes), count),
+        (false, true) => tr!(
+            "Freed {} in {} file in {}",
+            format_size(bytes),
+            count,
+            elapsed
+        ),
+        (false, false) => tr!(
+            "Freed {} in {} files in {}",
+            format_size(bytes),
+            count,
+            elapsed
+        ),
+    };
+    println!("{}", theme.warn(line));
+}
+
+#[derive(Serialize)]
+struct RemovalReportJson<'a> {
+    dry_run: bool,
+    summary: RemovalSummaryJson,
+    entries: Vec<RemovalEntryJson<'a>>,
+    skipped: Vec<SkippedEntryJson>,
+    /// Only present with `--verify`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lingering: Option<Vec<LingeringEntryJson>>,
+}
+
+#[derive(Serialize)]
+struct LingeringEntryJson {
+    path: String,
+    via_parent: bool,
+    holders: Vec<HolderJson>,
+}
+
+#[derive(Serialize)]
+struct HolderJson {
+    pid: u32,
+    name: String,
+}
+
+#[derive(Serialize)]
+struct SkippedEntryJson {
+    path: String,
+    message: String,
+}
+
+#[derive(Serialize)]
+struct RemovalSummaryJson {
+    total: usize,
+    succeeded: usize,
+    failed: usize,
+    skipped: usize,
+    bytes_freed: u64,
+    duration_ms: u128,
+}
+
+#[derive(Serialize)]
+struct RemovalEntryJson<'a> {
+    path: String,
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<RemovalErrorJson<'a>>,
+    /// Processes killed to unlock the entry
+    #[serde(skip_serializing_if = "<[u32]>::is_empty")]
+    killed: &'a [u32],
+}
+
+#[derive(Serialize)]
+struct RemovalErrorJson<'a> {
+    kind: DeletionError,
+    message: String,
+    /// Why a permission error happened, when it could be told
+    #[serde(skip_serializing_if = "Option::is_none")]
+    refusal: Option<&'a Refusal>,
+}
+
+/// Display deletion results as a JSON report
+pub fn display_removal_results_json(
+    plan: &RemovalPlan,
+    report: &RemovalReport,
+    lingering: Option<&[LingeringEntry]>,
+) -> Result<()> {
+    let entries: Vec<RemovalEntryJson> = report
+        .entries
+        .iter()
+        .map(|entry| {
+            let (status, error) = match &entry.outcome {
+                RemovalOutcome::Removed => ("removed", None),
+                RemovalOutcome::Trashed => ("trashed", None),
+                RemovalOutcome::Skipped => ("skipped", None),
+                RemovalOutcome::Failed { kind, error } => (
+                    "failed",
+                    Some(RemovalErrorJson {
+                        kind: *kind,
+                        message: format!("{error:#}"),
+                        refusal: elevation::refusal_of(error),
+                    }),
+                ),
+            };
+            RemovalEntryJson {
+                path: entry.path.display().to_string(),
+                status,
+                error,
+                killed: &entry.killed,
+            }
+        })
+        .collect();
+
+    let json = RemovalReportJson {
+        dry_run: report.dry_run,
+        summary: RemovalSummaryJson {
+            total: report.entries.len(),
+            succeeded: report.succeeded(),
+            failed: report.failures().count(),
+            skipped: plan.skipped.len() + report.skipped(),
+            bytes_freed: report.bytes_freed,
+            duration_ms: report.elapsed.as_millis(),
+        },
+        entries,
+        skipped: plan
+            .skipped
+            .iter()
+            .map(|(path, e)| SkippedEntryJson {
+                path: path.display().to_string(),
+                message: format!("{e:#}"),
+            })
+            .collect(),
+        lingering: lingering.map(|entries| {
+            entries
+                .iter()
+                .map(|entry| LingeringEntryJson {
+                    path: entry.path.display().to_string(),
+                    via_parent: entry.via_parent,
+                    holders: entry
+                        .holders
+                        .iter()
+                        .map(|p| HolderJson {
+                            pid: p.pid,
+                            name: p.name.clone(),
+                        })
+                        .collect(),
+                })
+                .collect()
+        }),
+    };
+
+    println!("{}", serde_json::to_string_pretty(&json)?);
+    Ok(())
+}
+
+/// Offer to move entries that are still locked into the pending-delete area
+pub fn confirm_pending_delete(locked: &[PathBuf]) -> Result<bool> {
+    let theme = Theme::new();
+
+    println!();
+    println!(
+        "{} {}",
+        theme.icon_warning(),
+        theme.error_bold(tr!("{} entries are still locked", locked.len()))
+    );
+    for path in locked {
+        println!("  {}", theme.highlight(path.display().to_string()));
+    }
+    println!();
+
+    let confirm = prompt::confirm(
+        Confirm::new(&tr!(
+            "Move them into {} and delete them as soon as possible?",
+            PENDING_DIR
+        ))
+        .with_default(false)
+        .with_help_message(tr!(
+            "Frees the paths now; on Windows the files are deleted on reboot"
+        )),
+    )?;
+
+    Ok(confirm)
+}
+
+/// Display what happened to entries moved into the pending-delete area
+pub fn display_pending_deletes(pending: &[PendingDelete]) {
+    let theme = Theme::new();
+
+    println!();
+    println!(
+        "{} {}",
+        theme.icon_folder(),
+        theme.title(tr!("Locked entries"))
+    );
+
+    let total = pending.len();
+    for (index, entry) in pending.iter().enumerate() {
+        let (branch, continuation) = tree_branches(total, index);
         println!(
-            "{} {} {}",
-            theme.title("Done"),
-            theme.success(format!("Success: {success_count}")),
-            theme.error(format!("Failed: {error_count}"))
+            "{branch} {}",
+            theme.highlight(entry.path.display().to_string())
         );
 
-        if error_count > 0 {
-            for (path, result) in results {
-                if let Err(e) = result {
-                    println!(
-                        "{} {} {}",
-                        theme.icon_error(),
-                        theme.error(format!("Failed to delete {}", path.display())),
-                        e
-                    );
-                }
+        let status = match (&entry.moved_to, entry.removed, entry.on_reboot) {
+            (Some(_), true, _) => theme.success(tr!("Moved aside and deleted")),
+            (Some(_), false, true) => theme.info(tr!("Moved aside, deleted on next reboot")),
+            (Some(_), false, false) => {
+                theme.warn(tr!("Moved aside, delete it once it is released"))
             }
+            (None, _, true) => theme.info(tr!("Still in place, deleted on next reboot")),
+            (None, _, false) => theme.error(tr!("Could not be moved or scheduled")),
+        };
+        println!("{continuation}├─ {status}");
+        match &entry.moved_to {
+            Some(moved_to) if !entry.removed => println!(
+                "{continuation}└─ {}",
+                theme.muted(moved_to.display().to_string())
+            ),
+            Some(_) => println!("{continuation}└─ {}", theme.muted(tr!("Path is free"))),
+            None => println!(
+                "{continuation}└─ {}",
+                theme.muted(tr!("Path is still taken"))
+            ),
         }
+    }
+}
+
+/// Display the outcome of `--verify`
+pub fn display_verify_results(lingering: &[LingeringEntry]) {
+    let theme = Theme::new();
+
+    println!();
+    if lingering.is_empty() {
+        println!(
+            "{} {}",
+            theme.icon_success(),
+            theme.success(tr!("Verified: all removed entries are gone"))
+        );
         return;
     }
 
+    println!(
+        "{} {}",
+        theme.icon_warning(),
+        theme.error_bold(tr!("{} removed entries exist again", lingering.len()))
+    );
+
+    let total = lingering.len();
+    for (index, entry) in lingering.iter().enumerate() {
+        let (branch, continuation) = tree_branches(total, index);
+        println!(
+            "{branch} {}",
+            theme.highlight(entry.path.display().to_string())
+        );
+
+        if entry.holders.is_empty() {
+            println!(
+                "{continuation}└─ {}",
+                theme.muted(tr!("No process found holding it or its directory"))
+            );
+            continue;
+        }
+
+        let label = if entry.via_parent {
+            tr!("Watching directory")
+        } else {
+            tr!("Held by")
+        };
+        let holder_total = entry.holders.len();
+        for (holder_index, holder) in entry.holders.iter().enumerate() {
+            let (holder_branch, _) = tree_branches(holder_total, holder_index);
+            println!(
+                "{continuation}{holder_branch} {} {} ({})",
+                theme.info(label),
+                theme.success(&holder.name),
+                theme.muted(format!("PID: {}", holder.pid))
+            );
+        }
+    }
+}
+
+/// Display where the pre-deletion backup archive was written
+pub fn display_backup_saved(path: &Path) {
+    let theme = Theme::new();
     println!(
         "{} {} {}",
-        theme.title("Done"),
-        theme.success(format!("Success: {success_count}")),
-        theme.error(format!("Failed: {error_count}"))
+        theme.icon_success(),
+        theme.success(tr!("Backup saved to")),
+        theme.highlight(path.display().to_string())
     );
+}
+
+/// Let the user pick journaled trash entries to restore, newest first
+///
+/// Returns `(trash time, original path)` pairs, or an empty list if cancelled.
+pub fn select_trash_entries_to_restore(records: &[TrashRecord]) -> Result<Vec<(i64, PathBuf)>> {
+    let theme = Theme::new();
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default();
+
+    let entries: Vec<(i64, &PathBuf)> = records
+        .iter()
+        .rev()
+        .flat_map(|record| record.paths.iter().map(move |path| (record.time, path)))
+        .collect();
+
+    let options: Vec<String> = entries
+        .iter()
+        .enumerate()
+        .map(|(i, (time, path))| {
+            format!(
+                "[{}] {} ({})",
+                i,
+                truncate_string(&path.display().to_string(), 70),
+                format_elapsed((now - time).max(0) as u64)
+            )
+        })
+        .collect();
+
+    let selected =
+        prompt::multi_select(MultiSelect::new(tr!("Select entries to restore:"), options))?;
+
+    let chosen: Vec<(i64, PathBuf)> = selected
+        .iter()
+        .filter_map(|s| {
+            let idx_str = s.trim_start_matches('[').split(']').next()?;
+            let idx: usize = idx_str.parse().ok()?;
+            entries
+                .get(idx)
+                .map(|(time, path)| (*time, (*path).clone()))
+        })
+        .collect();
+
+    if chosen.is_empty() {
+        println!("{}", theme.warn(tr!("No entries selected")));
+    }
+    Ok(chosen)
+}
+
+fn format_elapsed(secs: u64) -> String {
+    match secs {
+        0..60 => tr!("just now").to_string(),
+        60..3600 => tr!("{}m ago", secs / 60),
+        3600..86400 => tr!("{}h ago", secs / 3600),
+        _ => tr!("{}d ago", secs / 86400),
+    }
+}
+
+/// Display which requested entries came back from the trash
+pub fn display_restore_results(requested: &[PathBuf], restored: &[PathBuf]) {
+    let theme = Theme::new();
 
-    for (path, result) in results {
-        match result {
-            Ok(()) => println!(
+    for path in requested {
+        if restored.contains(path) {
+            println!(
                 "{} {}",
                 theme.icon_success(),
-                theme.muted(format!("{} {}", action, path.display()))
-            ),
-            Err(e) => println!(
-                "{} {} {}",
+                theme.success(tr!("Restored {}", path.display()))
+            );
+        } else {
+            println!(
+                "{} {}",
                 theme.icon_error(),
-                theme.error(format!("Failed to delete {}", path.display())),
-                e
-            ),
+                theme.error(tr!("Not found in trash: {}", path.display()))
+            );
         }
     }
 }
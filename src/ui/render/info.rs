@@ -0,0 +1,130 @@
+use crate::core::process::ProcessInfo;
+use crate::tr;
+use crate::ui::{Theme, i18n};
+use anyhow::Result;
+use console::{Alignment, pad_str};
+use std::time::{Duration, UNIX_EPOCH};
+
+use super::top::{format_uptime, utc_timestamp};
+use super::{format_size, tree_branches};
+
+/// Everything known about one process, as a tree of sections
+pub fn display_process_info(info: &ProcessInfo) {
+    let theme = Theme::new();
+
+    println!(
+        "{} {} {}",
+        theme.icon_lightning(),
+        theme.title(format!("{} ({})", info.name, info.pid)),
+        theme.muted(format!("[{}]", info.status))
+    );
+    println!();
+
+    let dash = || "-".to_string();
+    let mut sections: Vec<(&'static str, Vec<String>)> = vec![
+        (
+            "Process",
+            vec![
+                field(&theme, "Command", info.cmd.join(" ")),
+                field(
+                    &theme,
+                    "Executable",
+                    info.exe
+                        .as_ref()
+                        .map_or_else(dash, |p| p.display().to_string()),
+                ),
+                field(
+                    &theme,
+                    "Directory",
+                    info.cwd
+                        .as_ref()
+                        .map_or_else(dash, |p| p.display().to_string()),
+                ),
+                field(
+                    &theme,
+                    "Parent",
+                    info.parent.map_or_else(dash, |p| p.to_string()),
+                ),
+                field(&theme, "User", info.user.clone().unwrap_or_else(dash)),
+                field(
+                    &theme,
+                    "Started",
+                    tr!(
+                        "{} ({} ago)",
+                        utc_timestamp(UNIX_EPOCH + Duration::from_secs(info.start_time)),
+                        format_uptime(info.run_time)
+                    ),
+                ),
+                field(&theme, "CPU", format!("{:.1}%", info.cpu)),
+            ],
+        ),
+        ("Memory", memory_lines(&theme, info)),
+        (
+            "Ports",
+            info.ports
+                .iter()
+                .map(|port| theme.highlight(port.to_string()))
+                .collect(),
+        ),
+        (
+            "Open files",
+            info.open_files
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect(),
+        ),
+    ];
+    if let Some(environ) = &info.environ {
+        sections.push(("Environment", environ.clone()));
+    }
+
+    let total = sections.len();
+    for (index, (title, lines)) in sections.iter().enumerate() {
+        let (branch, continuation) = tree_branches(total, index);
+        println!(
+            "{branch} {} {}",
+            theme.info_bold(i18n::tr(title)),
+            theme.muted(format!("({})", lines.len()))
+        );
+        if lines.is_empty() {
+            println!("{continuation}└─ {}", theme.muted(tr!("none")));
+        }
+        for (line_index, line) in lines.iter().enumerate() {
+            let (line_branch, _) = tree_branches(lines.len(), line_index);
+            println!("{continuation}{line_branch} {line}");
+        }
+    }
+}
+
+pub fn display_process_info_json(info: &ProcessInfo) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(info)?);
+    Ok(())
+}
+
+fn field(theme: &Theme, label: &'static str, value: String) -> String {
+    let label = pad_str(i18n::tr(label), 10, Alignment::Left, None);
+    format!("{} {value}", theme.info(label))
+}
+
+fn memory_lines(theme: &Theme, info: &ProcessInfo) -> Vec<String> {
+    let memory = &info.memory;
+    let mut lines = vec![
+        field(
+            theme,
+            "Resident",
+            theme.accent(format_size(memory.resident)),
+        ),
+        field(theme, "Virtual", format_size(memory.virtual_bytes)),
+    ];
+    for (label, bytes) in [
+        ("Anonymous", memory.anonymous),
+        ("File", memory.file_backed),
+        ("Shared", memory.shared),
+        ("Swap", memory.swap),
+    ] {
+        if let Some(bytes) = bytes {
+            lines.push(field(theme, label, format_size(bytes)));
+        }
+    }
+    lines
+}
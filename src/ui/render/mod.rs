@@ -1,25 +1,35 @@
+pub mod config;
+pub mod doctor;
+pub mod du;
 pub mod file_ops;
+pub mod info;
+pub mod net;
 pub mod port;
+pub mod ps;
+pub mod serve;
+pub mod snapshot;
+pub mod temp;
 pub mod top;
 
+pub use config::*;
+pub use doctor::*;
+pub use du::*;
 pub use file_ops::*;
+pub use info::*;
+pub use net::*;
 pub use port::*;
+pub use ps::*;
+pub use serve::*;
+pub use snapshot::*;
+pub use temp::*;
 pub use top::*;
 
-use crate::ui::Theme;
+use crate::tr;
+use crate::ui::{Theme, text};
 
-/// Truncate string to specified length
-pub fn truncate_string(s: &str, max_len: usize) -> String {
-    if s.chars().count() <= max_len {
-        s.to_string()
-    } else {
-        let end = s
-            .char_indices()
-            .map(|(i, _)| i)
-            .nth(max_len.saturating_sub(3))
-            .unwrap_or(s.len());
-        format!("{}...", &s[..end])
-    }
+/// Truncate string to at most `max_width` terminal columns, with an ellipsis when cut
+pub fn truncate_string(s: &str, max_width: usize) -> String {
+    text::truncate_width(s, max_width, text::ellipsis())
 }
 
 /// Return tree drawing characters for the given position
@@ -32,10 +42,10 @@ pub fn tree_branches(total: usize, index: usize) -> (&'static str, &'static str)
     }
 }
 
-pub use super::format_size;
+pub use super::{format_count, format_size};
 
 /// Display error message
 pub fn display_error(error: &anyhow::Error) {
     let theme = Theme::new();
-    eprintln!("{} {}", theme.error_bold("Error:"), error);
+    eprintln!("{} {}", theme.error_bold(tr!("Error:")), error);
 }
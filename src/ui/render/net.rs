@@ -0,0 +1,137 @@
+use crate::core::port::conns::Connection;
+use crate::core::port::net::NetProcess;
+use crate::tr;
+use crate::ui::Theme;
+use anyhow::Result;
+use console::{Alignment, pad_str, truncate_str};
+use std::io::{self, IsTerminal};
+
+use super::top::format_rate;
+use super::truncate_string;
+
+/// Per-process network table for `ziro net`
+pub fn display_net_stats(processes: &[NetProcess]) {
+    let theme = Theme::new();
+
+    if processes.is_empty() {
+        println!("{}", theme.warn(tr!("No processes with open sockets")));
+        return;
+    }
+
+    // Long port lists are cut at the terminal edge; piped output keeps them whole
+    let width = io::stdout()
+        .is_terminal()
+        .then(|| crossterm::terminal::size().ok())
+        .flatten()
+        .map(|(columns, _)| columns as usize);
+    // Platforms without per-process byte counters get no rate columns at all
+    let rates = processes.iter().any(|p| p.rx_rate.is_some());
+
+    let mut header = format!(
+        "{:>8} {} {:>6} {:>6}",
+        "PID",
+        pad_str(tr!("NAME"), 20, Alignment::Left, None),
+        "LISTEN",
+        "CONNS"
+    );
+    if rates {
+        header.push_str(&format!(" {:>10} {:>10}", "RX/s", "TX/s"));
+    }
+    header.push(' ');
+    header.push_str(tr!("PORTS"));
+    println!("{}", theme.muted(header));
+
+    for process in processes {
+        let ports = process
+            .ports
+            .iter()
+            .map(u16::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
+        let mut line = format!(
+            "{:>8} {} {:>6} {:>6}",
+            process.pid,
+            theme.success(pad_str(
+                &truncate_string(&process.name, 20),
+                20,
+                Alignment::Left,
+                None
+            )),
+            process.listening,
+            process.established,
+        );
+        if rates {
+            line.push_str(&format!(
+                " {} {}",
+                theme.accent(format!(
+                    "{:>10}",
+                    format_rate(process.rx_rate.unwrap_or(0.0))
+                )),
+                theme.accent(format!(
+                    "{:>10}",
+                    format_rate(process.tx_rate.unwrap_or(0.0))
+                )),
+            ));
+        }
+        line.push(' ');
+        line.push_str(&theme.info(ports));
+        match width {
+            Some(width) => println!("{}", truncate_str(&line, width, "")),
+            None => println!("{line}"),
+        }
+    }
+}
+
+pub fn display_net_stats_json(processes: &[NetProcess]) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(processes)?);
+    Ok(())
+}
+
+/// Established connections for `ziro conns`, one row each
+pub fn display_connections(connections: &[Connection]) {
+    let theme = Theme::new();
+
+    if connections.is_empty() {
+        println!("{}", theme.warn(tr!("No established connections")));
+        return;
+    }
+
+    println!(
+        "{}",
+        theme.muted(format!(
+            "{:>8} {} {} {}",
+            "PID",
+            pad_str(tr!("NAME"), 20, Alignment::Left, None),
+            pad_str(tr!("LOCAL"), 26, Alignment::Left, None),
+            tr!("REMOTE")
+        ))
+    );
+    for connection in connections {
+        let remote = match &connection.remote_host {
+            Some(host) => format!("{} ({host})", connection.remote),
+            None => connection.remote.to_string(),
+        };
+        println!(
+            "{:>8} {} {} {}",
+            connection.pid,
+            theme.success(pad_str(
+                &truncate_string(&connection.name, 20),
+                20,
+                Alignment::Left,
+                None
+            )),
+            theme.muted(pad_str(
+                &connection.local.to_string(),
+                26,
+                Alignment::Left,
+                None
+            )),
+            theme.info(remote)
+        );
+    }
+}
+
+pub fn display_connections_json(connections: &[Connection]) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(connections)?);
+    Ok(())
+}
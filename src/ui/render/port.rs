@@ -1,69 +1,360 @@
 use crate::core::port::PortInfo;
-use crate::ui::Theme;
+use crate::core::port::check::{CheckAttempt, CheckOutcome};
+use crate::core::process::{ChildProcess, KillReport};
+use crate::tr;
+use crate::ui::pager::Paged;
+use crate::ui::{Theme, prompt};
 use anyhow::Result;
-use inquire::{Confirm, MultiSelect};
+use inquire::{Confirm, MultiSelect, Select};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::Duration;
 
 use super::{format_size, tree_branches, truncate_string};
 
 /// Display message for port not in use
 pub fn display_port_not_found(port: u16) {
     let theme = Theme::new();
-    println!("{}", theme.warn(format!("Port {port} is not in use")));
+    println!("{}", theme.warn(tr!("Port {} is not in use", port)));
+}
+
+/// `ziro open` found a page to open
+pub fn display_opening_url(url: &str, info: &PortInfo) {
+    let theme = Theme::new();
+    println!(
+        "{} {} {}",
+        theme.success("Opening"),
+        theme.info(url),
+        theme.muted(format!("({}, PID {})", info.process.name, info.process.pid))
+    );
+}
+
+/// `ziro open` found a listener that does not answer HTTP
+pub fn display_not_http(port: u16) {
+    let theme = Theme::new();
+    println!(
+        "{}",
+        theme.warn(format!(
+            "Port {port} does not answer HTTP; not opening a browser"
+        ))
+    );
+}
+
+/// Nice value offered as the gentler alternative to killing
+pub const BACKGROUND_NICE: i32 = 10;
+
+/// What to do with the processes picked in `select_processes_to_kill`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KillAction {
+    Kill,
+    /// Lower their priority to `BACKGROUND_NICE` instead
+    Renice,
+}
+
+/// What the user picked in `select_processes_to_kill`; no processes means cancelled
+pub struct KillSelection {
+    pub processes: Vec<PortInfo>,
+    pub action: KillAction,
+    /// Apply the action to the children of the picked processes too
+    pub with_children: bool,
+}
+
+impl KillSelection {
+    fn cancelled() -> Self {
+        Self {
+            processes: Vec::new(),
+            action: KillAction::Kill,
+            with_children: false,
+        }
+    }
+}
+
+/// Which rows of the kill picker start out checked
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Preselect {
+    All,
+    /// Nothing is killed without being ticked; `--yes` then has no choice to take
+    #[default]
+    None,
+}
+
+/// Longest child list shown per process before eliding the rest
+const CHILDREN_SHOWN: usize = 8;
+
+/// Command line shown after each process in the picker; the filter sees all of it
+const PICKER_CMD_WIDTH: usize = 48;
+
+/// One row of the kill picker
+struct KillOption<'a> {
+    info: &'a PortInfo,
+    /// What typing filters on: port, PID, name and the whole command line
+    search: String,
+}
+
+impl<'a> KillOption<'a> {
+    fn new(info: &'a PortInfo) -> Self {
+        let search = format!(
+            "{} {} {} {}",
+            info.port,
+            info.process.pid,
+            info.process.name,
+            info.process.cmd.join(" ")
+        );
+        Self { info, search }
+    }
+}
+
+impl std::fmt::Display for KillOption<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let process = &self.info.process;
+        write!(
+            f,
+            "{}",
+            tr!(
+                "Port {} - {} (PID: {})",
+                self.info.port,
+                process.name,
+                process.pid
+            )
+        )?;
+        if !process.cmd.is_empty() {
+            let cmd = truncate_string(&process.cmd.join(" "), PICKER_CMD_WIDTH);
+            write!(f, "  {cmd}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Fuzzy score of `option` for what was typed; `None` hides it. The shown (truncated) text
+/// is ignored in favor of `KillOption::search`.
+fn score_kill_option(input: &str, option: &KillOption, _shown: &str, index: usize) -> Option<i64> {
+    MultiSelect::<KillOption>::DEFAULT_SCORER(input, option, &option.search, index)
 }
 
 /// Display multiple port info with interactive selection
-pub fn select_processes_to_kill(port_infos: Vec<PortInfo>) -> Result<Vec<PortInfo>> {
+///
+/// `children` maps PIDs to their descendants; the ones of picked processes are listed,
+/// and unless `with_children` is already set the user is asked whether to include them.
+/// A single process skips the picker: its card is shown and one question asked.
+pub fn select_processes_to_kill(
+    port_infos: Vec<PortInfo>,
+    children: &HashMap<u32, Vec<ChildProcess>>,
+    with_children: bool,
+    preselect: Preselect,
+) -> Result<KillSelection> {
     let theme = Theme::new();
 
     if port_infos.is_empty() {
         println!(
             "{}",
-            theme.warn("No processes found occupying the specified ports")
+            theme.warn(tr!("No processes found occupying the specified ports"))
         );
-        return Ok(vec![]);
+        return Ok(KillSelection::cancelled());
     }
-
-    let options: Vec<String> = port_infos
+    if port_infos
         .iter()
-        .enumerate()
-        .map(|(i, info)| {
-            format!(
-                "[{}] Port {} - {} (PID: {})",
-                i, info.port, info.process.name, info.process.pid
-            )
-        })
-        .collect();
+        .all(|info| info.process.pid == port_infos[0].process.pid)
+    {
+        return confirm_single_kill(port_infos, children, with_children);
+    }
 
+    let options: Vec<KillOption> = port_infos.iter().map(KillOption::new).collect();
     let defaults: Vec<usize> = (0..options.len()).collect();
+    let help = tr!("type to filter by port, PID, name or command line; space to toggle");
+    let mut picker = MultiSelect::new(tr!("Select processes to kill:"), options)
+        .with_scorer(&score_kill_option)
+        .with_help_message(help)
+        .with_page_size(15);
+    if preselect == Preselect::All {
+        picker = picker.with_default(&defaults);
+    }
 
-    let selected = MultiSelect::new("Select processes to kill:", options)
-        .with_default(&defaults)
-        .prompt()?;
+    let selected = prompt::multi_select(picker)?;
 
     let result: Vec<PortInfo> = selected
-        .iter()
-        .filter_map(|s| {
-            let idx_str = s.trim_start_matches('[').split(']').next()?;
-            let idx: usize = idx_str.parse().ok()?;
-            port_infos.get(idx).cloned()
-        })
+        .into_iter()
+        .map(|option| option.info.clone())
         .collect();
 
     if result.is_empty() {
-        println!("{}", theme.warn("No processes selected"));
-        return Ok(vec![]);
+        println!("{}", theme.warn(tr!("No processes selected")));
+        return Ok(KillSelection::cancelled());
     }
 
-    let confirm = Confirm::new("Confirm killing these processes?")
-        .with_default(false)
-        .prompt()?;
+    let child_count = display_children(&theme, &result, children);
+    let with_children = with_children
+        || (child_count > 0
+            // Only on request; `--yes` confirms, it does not widen what gets killed
+            && prompt::is_interactive()
+            && prompt::confirm(
+                Confirm::new(&tr!(
+                    "Also include their {} child process(es)?",
+                    child_count
+                ))
+                .with_default(false),
+            )?);
+
+    let kill = tr!("Kill them").to_string();
+    let renice = tr!("Lower their priority (nice {})", BACKGROUND_NICE);
+    let cancel = tr!("Cancel").to_string();
+    // Starts on Cancel so a stray Enter kills nothing
+    let choice = prompt::select(
+        Select::new(
+            tr!("What should happen to these processes?"),
+            vec![kill.clone(), renice.clone(), cancel],
+        )
+        .with_starting_cursor(2),
+        0,
+    )?;
+
+    let action = if choice == kill {
+        KillAction::Kill
+    } else if choice == renice {
+        KillAction::Renice
+    } else {
+        println!("{}", theme.warn(tr!("Operation cancelled")));
+        return Ok(KillSelection::cancelled());
+    };
+    Ok(KillSelection {
+        processes: result,
+        action,
+        with_children,
+    })
+}
 
-    if confirm {
-        Ok(result)
+/// One process (possibly on several ports): its card, then a single question that also
+/// covers its children
+fn confirm_single_kill(
+    port_infos: Vec<PortInfo>,
+    children: &HashMap<u32, Vec<ChildProcess>>,
+    with_children: bool,
+) -> Result<KillSelection> {
+    let theme = Theme::new();
+    display_process_card(&theme, &port_infos);
+    let child_count = display_children(&theme, &port_infos[..1], children);
+
+    let kill = tr!("Kill it").to_string();
+    let kill_tree = tr!("Kill it and its {} child process(es)", child_count);
+    let renice = tr!("Lower its priority (nice {})", BACKGROUND_NICE);
+    let cancel = tr!("Cancel").to_string();
+    let mut choices = vec![kill.clone()];
+    // `--tree` already includes them; `--yes` takes the first choice, never the wider one
+    if child_count > 0 && !with_children {
+        choices.push(kill_tree.clone());
+    }
+    choices.push(renice.clone());
+    choices.push(cancel);
+    let cancel_index = choices.len() - 1;
+    // Starts on Cancel so a stray Enter kills nothing
+    let choice = prompt::select(
+        Select::new(tr!("What should happen to this process?"), choices)
+            .with_starting_cursor(cancel_index),
+        0,
+    )?;
+
+    let (action, with_children) = if choice == kill {
+        (KillAction::Kill, with_children)
+    } else if choice == kill_tree {
+        (KillAction::Kill, true)
+    } else if choice == renice {
+        (KillAction::Renice, with_children)
     } else {
-        println!("{}", theme.warn("Operation cancelled"));
-        Ok(vec![])
+        println!("{}", theme.warn(tr!("Operation cancelled")));
+        return Ok(KillSelection::cancelled());
+    };
+    Ok(KillSelection {
+        processes: port_infos,
+        action,
+        with_children,
+    })
+}
+
+/// Everything known about the process behind the ports, before asking about it
+fn display_process_card(theme: &Theme, port_infos: &[PortInfo]) {
+    let process = &port_infos[0].process;
+    let ports = port_infos
+        .iter()
+        .map(|info| info.port.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    println!(
+        "{} {} {}",
+        theme.icon_lightning(),
+        theme.success(&process.name),
+        theme.muted(format!("(PID: {})", process.pid))
+    );
+    println!(
+        "├─ {}: {}",
+        theme.info(tr!("Ports")),
+        theme.highlight(ports)
+    );
+    if !process.cmd.is_empty() {
+        println!(
+            "├─ {}: {}",
+            theme.info(tr!("Command")),
+            theme.muted(process.cmd.join(" "))
+        );
+    }
+    println!(
+        "└─ {}: {}",
+        theme.info(tr!("Resources")),
+        tr!(
+            "{} CPU, {} Memory",
+            theme.accent(format!("{:.1}%", process.cpu_usage)),
+            theme.accent(format_size(process.memory))
+        )
+    );
+    println!();
+}
+
+/// List the children of the picked processes; returns how many there are
+fn display_children(
+    theme: &Theme,
+    picked: &[PortInfo],
+    children: &HashMap<u32, Vec<ChildProcess>>,
+) -> usize {
+    let mut total = 0;
+    let mut seen = Vec::new();
+    for info in picked {
+        let pid = info.process.pid;
+        let Some(kids) = children.get(&pid).filter(|kids| !kids.is_empty()) else {
+            continue;
+        };
+        // Several ports can belong to the same process
+        if seen.contains(&pid) {
+            continue;
+        }
+        seen.push(pid);
+        if total == 0 {
+            println!("{}", theme.title(tr!("Child processes:")));
+        }
+        total += kids.len();
+
+        println!(
+            "  {} {}",
+            theme.success(&info.process.name),
+            theme.muted(format!("(PID: {pid})"))
+        );
+        for child in kids.iter().take(CHILDREN_SHOWN) {
+            println!(
+                "  {}└─ {} {}",
+                "   ".repeat(child.depth - 1),
+                child.name,
+                theme.muted(format!("(PID: {})", child.pid))
+            );
+        }
+        if kids.len() > CHILDREN_SHOWN {
+            println!(
+                "  {}",
+                theme.muted(tr!("... and {} more", kids.len() - CHILDREN_SHOWN))
+            );
+        }
+    }
+    if total > 0 {
+        println!();
     }
+    total
 }
 
 /// Display kill results
@@ -75,12 +366,12 @@ pub fn display_kill_results(results: &[(u32, Result<()>)]) {
             Ok(()) => println!(
                 "{} {}",
                 theme.icon_success(),
-                theme.success(format!("Successfully killed process {pid}"))
+                theme.success(tr!("Successfully killed process {}", pid))
             ),
             Err(e) => println!(
                 "{} {}: {}",
                 theme.icon_error(),
-                theme.error(format!("Failed to kill process {pid}")),
+                theme.error(tr!("Failed to kill process {}", pid)),
                 e
             ),
         }
@@ -98,7 +389,7 @@ pub fn display_ports_tree(ports: &[u16], port_infos: Vec<PortInfo>) {
     println!(
         "{} {}",
         theme.icon_lightning(),
-        theme.title("Port Query Results")
+        theme.title(tr!("Port Query Results"))
     );
     println!();
 
@@ -122,7 +413,7 @@ pub fn display_ports_tree(ports: &[u16], port_infos: Vec<PortInfo>) {
             println!(
                 "{}├─ {}: {} ({})",
                 continuation,
-                theme.info("Process"),
+                theme.info(tr!("Process")),
                 theme.success(&info.process.name),
                 theme.muted(info.process.pid.to_string())
             );
@@ -131,16 +422,19 @@ pub fn display_ports_tree(ports: &[u16], port_infos: Vec<PortInfo>) {
             println!(
                 "{}├─ {}: {}",
                 continuation,
-                theme.info("Command"),
+                theme.info(tr!("Command")),
                 theme.muted(cmd)
             );
 
             println!(
-                "{}└─ {}: {} CPU, {} Memory",
+                "{}└─ {}: {}",
                 continuation,
-                theme.info("Resources"),
-                theme.accent(format!("{:.1}%", info.process.cpu_usage)),
-                theme.accent(format_size(info.process.memory))
+                theme.info(tr!("Resources")),
+                tr!(
+                    "{} CPU, {} Memory",
+                    theme.accent(format!("{:.1}%", info.process.cpu_usage)),
+                    theme.accent(format_size(info.process.memory))
+                )
             );
         } else {
             println!(
@@ -148,7 +442,7 @@ pub fn display_ports_tree(ports: &[u16], port_infos: Vec<PortInfo>) {
                 branch,
                 theme.highlight(port.to_string()),
                 theme.icon_error(),
-                theme.muted("(free)")
+                theme.muted(tr!("(free)"))
             );
         }
 
@@ -158,117 +452,289 @@ pub fn display_ports_tree(ports: &[u16], port_infos: Vec<PortInfo>) {
     }
 }
 
-/// Display all port usage in tree structure (for list command)
-pub fn display_ports_tree_all(port_infos: Vec<PortInfo>) {
+/// Display all port usage in tree structure (for list command), followed by the
+/// Windows host's ports when listed from WSL
+pub fn display_ports_tree_all(port_infos: Vec<PortInfo>, host_ports: &[PortInfo]) {
     let theme = Theme::new();
+    let mut out = Paged::new();
 
-    if port_infos.is_empty() {
-        println!("{}", theme.warn("No ports are currently in use"));
+    if port_infos.is_empty() && host_ports.is_empty() {
+        println!("{}", theme.warn(tr!("No ports are currently in use")));
         return;
     }
 
-    println!(
+    if !port_infos.is_empty() {
+        write_port_tree(&mut out, &theme, &port_infos);
+    }
+    if !host_ports.is_empty() {
+        if !port_infos.is_empty() {
+            out.line("");
+        }
+        write_host_port_tree(&mut out, &theme, host_ports);
+    }
+    out.show();
+}
+
+fn write_port_tree(out: &mut Paged, theme: &Theme, port_infos: &[PortInfo]) {
+    out.line(format_args!(
         "{} {} {}",
         theme.icon_lightning(),
-        theme.title("Port Usage"),
-        theme.muted(format!("({} total)", port_infos.len()))
-    );
-    println!();
+        theme.title(tr!("Port Usage")),
+        theme.muted(tr!("({} total)", port_infos.len()))
+    ));
+    out.line("");
 
     let total = port_infos.len();
     for (index, info) in port_infos.iter().enumerate() {
         let (branch, continuation) = tree_branches(total, index);
 
-        println!(
+        out.line(format_args!(
             "{} {} {}",
             branch,
             theme.highlight(info.port.to_string()),
             theme.icon_success()
-        );
+        ));
 
-        println!(
+        out.line(format_args!(
             "{}├─ {}: {} ({})",
             continuation,
-            theme.info("Process"),
+            theme.info(tr!("Process")),
             theme.success(&info.process.name),
             theme.muted(info.process.pid.to_string())
-        );
+        ));
 
         let cmd = truncate_string(&info.process.cmd.join(" "), 60);
-        println!(
+        out.line(format_args!(
             "{}├─ {}: {}",
             continuation,
-            theme.info("Command"),
+            theme.info(tr!("Command")),
             theme.muted(cmd)
-        );
+        ));
 
-        println!(
-            "{}└─ {}: {} CPU, {} Memory",
+        out.line(format_args!(
+            "{}└─ {}: {}",
             continuation,
-            theme.info("Resources"),
-            theme.accent(format!("{:.1}%", info.process.cpu_usage)),
-            theme.accent(format_size(info.process.memory))
-        );
+            theme.info(tr!("Resources")),
+            tr!(
+                "{} CPU, {} Memory",
+                theme.accent(format!("{:.1}%", info.process.cpu_usage)),
+                theme.accent(format_size(info.process.memory))
+            )
+        ));
 
         if continuation == "│  " {
-            println!("{continuation}");
+            out.line(continuation);
+        }
+    }
+}
+
+/// Host processes have no command line or CPU figure to show, and their PIDs are Windows PIDs
+fn write_host_port_tree(out: &mut Paged, theme: &Theme, host_ports: &[PortInfo]) {
+    out.line(format_args!(
+        "{} {} {}",
+        theme.icon_lightning(),
+        theme.title(tr!("Windows Host Ports")),
+        theme.muted(tr!("({} total)", host_ports.len()))
+    ));
+    out.line("");
+
+    let total = host_ports.len();
+    for (index, info) in host_ports.iter().enumerate() {
+        let (branch, continuation) = tree_branches(total, index);
+        out.line(format_args!(
+            "{} {} {}",
+            branch,
+            theme.highlight(info.port.to_string()),
+            theme.icon_success()
+        ));
+        out.line(format_args!(
+            "{}├─ {}: {} ({})",
+            continuation,
+            theme.info(tr!("Process")),
+            theme.success(&info.process.name),
+            theme.muted(tr!("Windows PID {}", info.process.pid))
+        ));
+        out.line(format_args!(
+            "{}└─ {}: {}",
+            continuation,
+            theme.info(tr!("Memory")),
+            theme.accent(format_size(info.process.memory))
+        ));
+        if continuation == "│  " {
+            out.line(continuation);
         }
     }
 }
 
+/// Display force kill results, with how long each process took to go away
+pub fn display_force_kill_reports(reports: &[KillReport]) {
+    let theme = Theme::new();
+    for report in reports {
+        print_force_kill_report(&theme, report, &[]);
+    }
+}
+
+/// One report line; `ports` are the requested ports the process held
+fn print_force_kill_report(theme: &Theme, report: &KillReport, ports: &[u16]) {
+    let mut elapsed = format!("{:.2}s", report.elapsed.as_secs_f64());
+    if !ports.is_empty() {
+        let ports: Vec<String> = ports.iter().map(u16::to_string).collect();
+        elapsed = format!("{}; {elapsed}", tr!("ports {}", ports.join(", ")));
+    }
+    let elapsed = theme.muted(format!("({elapsed})"));
+    match &report.result {
+        Ok(()) => println!(
+            "{} {} {elapsed}",
+            theme.icon_success(),
+            theme.success(tr!("Successfully force-killed process {}", report.pid))
+        ),
+        Err(e) => println!(
+            "{} {}: {e} {elapsed}",
+            theme.icon_error(),
+            theme.error(tr!("Failed to force-kill process {}", report.pid))
+        ),
+    }
+}
+
 /// Display force kill results
-pub fn display_kill_results_force(port_infos: &[PortInfo], results: &[(u32, Result<()>)]) {
+pub fn display_kill_results_force(port_infos: &[PortInfo], reports: &[KillReport]) {
     let theme = Theme::new();
 
     println!(
         "{} {}",
         theme.icon_fire(),
-        theme.error_bold("Force Kill Processes")
+        theme.error_bold(tr!("Force Kill Processes"))
     );
     println!();
 
-    println!("{}", theme.title("Target processes:"));
+    println!("{}", theme.title(tr!("Target processes:")));
     for info in port_infos {
         println!(
-            "  Port {} - {} (PID: {})",
-            theme.highlight(info.port.to_string()),
-            theme.success(&info.process.name),
-            theme.muted(info.process.pid.to_string())
+            "  {}",
+            tr!(
+                "Port {} - {} (PID: {})",
+                theme.highlight(info.port.to_string()),
+                theme.success(&info.process.name),
+                theme.muted(info.process.pid.to_string())
+            )
         );
     }
     println!();
 
-    println!("{}", theme.title("Kill results:"));
-    let mut success_count = 0;
-    let mut error_count = 0;
-
-    for (pid, result) in results {
-        match result {
-            Ok(()) => {
-                success_count += 1;
-                println!(
-                    "{} {}",
-                    theme.icon_success(),
-                    theme.success(format!("Successfully force-killed process {pid}"))
-                );
-            }
-            Err(e) => {
-                error_count += 1;
-                println!(
-                    "{} {}: {}",
-                    theme.icon_error(),
-                    theme.error(format!("Failed to force-kill process {pid}")),
-                    e
-                );
-            }
-        }
+    println!("{}", theme.title(tr!("Kill results:")));
+    // One report per process, shared by every port it held
+    for report in reports {
+        let ports: Vec<u16> = port_infos
+            .iter()
+            .filter(|info| info.process.pid == report.pid)
+            .map(|info| info.port)
+            .collect();
+        print_force_kill_report(&theme, report, &ports);
     }
+    let success_count = reports.iter().filter(|r| r.result.is_ok()).count();
+    let error_count = reports.len() - success_count;
 
     println!();
     println!(
         "{} {} {}",
-        theme.title("Force kill complete"),
-        theme.success(format!("Success: {success_count}")),
-        theme.error(format!("Failed: {error_count}"))
+        theme.title(tr!("Force kill complete")),
+        theme.success(tr!("Success: {}", success_count)),
+        theme.error(tr!("Failed: {}", error_count))
+    );
+}
+
+/// A failed `ziro check` attempt that will be tried again
+pub fn display_check_retry(attempt: &CheckAttempt, retries: u32, interval: Duration) {
+    let theme = Theme::new();
+    let failure = attempt
+        .failure
+        .as_ref()
+        .map(ToString::to_string)
+        .unwrap_or_default();
+    println!(
+        "{} {}",
+        theme.muted(format!("[{}/{}]", attempt.attempt, retries + 1)),
+        theme.warn(format!(
+            "{failure}; retrying in {}s",
+            interval.as_secs_f32()
+        ))
     );
 }
+
+pub fn display_check_outcome(outcome: &CheckOutcome) {
+    let theme = Theme::new();
+    let mut target = format!("{}:{}", outcome.host, outcome.port);
+    if let Some(path) = &outcome.http {
+        target.push_str(path);
+    }
+    let last = outcome.attempts.last();
+    let ms = last.map_or(0, |a| a.elapsed_ms);
+    match last.and_then(|a| a.failure.as_ref()) {
+        None => {
+            let status = last
+                .and_then(|a| a.status)
+                .map(|s| format!("HTTP {s}, "))
+                .unwrap_or_default();
+            println!(
+                "{} {}",
+                theme.icon_success(),
+                theme.success(format!("{target} is healthy ({status}{ms} ms)"))
+            );
+        }
+        Some(failure) => println!(
+            "{} {}",
+            theme.icon_error(),
+            theme.error(format!(
+                "{target}: {failure} after {} attempt(s)",
+                outcome.attempts.len()
+            ))
+        ),
+    }
+}
+
+pub fn display_check_outcome_json(outcome: &CheckOutcome) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(outcome)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::port::ProcessInfo;
+
+    #[test]
+    fn test_kill_picker_filters_on_command_line() {
+        let info = |port, pid, name: &str, cmd: &str| PortInfo {
+            port,
+            process: ProcessInfo {
+                pid,
+                name: name.to_string(),
+                cmd: cmd.split(' ').map(str::to_string).collect(),
+                cpu_usage: 0.0,
+                memory: 0,
+            },
+        };
+        let infos = [
+            info(
+                5173,
+                101,
+                "node",
+                "node /srv/app/node_modules/.bin/vite --port 5173",
+            ),
+            info(3000, 102, "python3", "python3 -m http.server 3000"),
+        ];
+        let options: Vec<KillOption> = infos.iter().map(KillOption::new).collect();
+        let matches = |input: &str| -> Vec<u32> {
+            options
+                .iter()
+                .enumerate()
+                .filter(|(i, option)| score_kill_option(input, option, "", *i).is_some())
+                .map(|(_, option)| option.info.process.pid)
+                .collect()
+        };
+        assert_eq!(matches("vite"), [101]);
+        assert_eq!(matches("httpserver"), [102]);
+        assert_eq!(matches("10"), [101, 102]);
+        assert_eq!(matches("3000"), [102]);
+    }
+}
@@ -0,0 +1,441 @@
+use crate::core::process::{INIT_PID, ReapReport, WaitOutcome, WaitedProcess};
+use crate::core::top::{ProcessNode, ProcessView};
+use crate::tr;
+use crate::ui::pager::Paged;
+use crate::ui::{Theme, prompt};
+use anyhow::Result;
+use console::{Alignment, pad_str, truncate_str};
+use inquire::{Confirm, MultiSelect};
+use std::io::{self, IsTerminal};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::top::format_uptime;
+use super::{format_size, tree_branches, truncate_string};
+
+/// Process table for `ziro ps`, one row per process like `ps aux`
+pub fn display_process_list(processes: &[ProcessView]) {
+    let theme = Theme::new();
+
+    if processes.is_empty() {
+        println!("{}", theme.warn(tr!("No matching processes")));
+        return;
+    }
+
+    // Long command lines are cut at the terminal edge; piped output keeps them whole
+    let width = io::stdout()
+        .is_terminal()
+        .then(|| crossterm::terminal::size().ok())
+        .flatten()
+        .map(|(columns, _)| columns as usize);
+
+    // Padded by display width, so translated headers stay aligned
+    let header = |text: &str, width, align| pad_str(text, width, align, None).into_owned();
+    println!(
+        "{}",
+        theme.muted(format!(
+            "{} {} S {} {} {} {}",
+            header("PID", 8, Alignment::Right),
+            header(tr!("USER"), 12, Alignment::Left),
+            header(tr!("MEMORY"), 10, Alignment::Right),
+            header("MEM%", 6, Alignment::Right),
+            header("CPU%", 6, Alignment::Right),
+            tr!("COMMAND")
+        ))
+    );
+    for process in processes {
+        let command = if process.cmd.is_empty() {
+            format!("[{}]", process.name)
+        } else {
+            process.cmd.clone()
+        };
+        let line = format!(
+            "{:>8} {} {} {} {:>5.1}% {:>5.1}% {}",
+            process.pid,
+            theme.info(pad_str(
+                &truncate_string(process.user.as_deref().unwrap_or("-"), 12),
+                12,
+                Alignment::Left,
+                None
+            )),
+            process.state,
+            theme.warn(format!("{:>10}", format_size(process.memory_bytes))),
+            process.memory_percent,
+            process.cpu,
+            command
+        );
+        match width {
+            Some(width) => println!("{}", truncate_str(&line, width, "")),
+            None => println!("{line}"),
+        }
+    }
+}
+
+pub fn display_process_list_json(processes: &[ProcessView]) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(processes)?);
+    Ok(())
+}
+
+/// Process hierarchy for `ziro pstree`, each node with its memory and CPU
+pub fn display_process_tree(forest: &[ProcessNode]) {
+    let theme = Theme::new();
+    let mut out = Paged::new();
+    let total = forest.len();
+    for (index, node) in forest.iter().enumerate() {
+        // A single root (e.g. `--pid`) starts flush left like `pstree`
+        if total == 1 {
+            print_node(&mut out, &theme, node, "");
+            print_children(&mut out, &theme, &node.children, "");
+        } else {
+            let (branch, continuation) = tree_branches(total, index);
+            print_node(&mut out, &theme, node, &format!("{branch} "));
+            print_children(&mut out, &theme, &node.children, continuation);
+        }
+    }
+    out.show();
+}
+
+pub fn display_process_tree_json(forest: &[ProcessNode]) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(forest)?);
+    Ok(())
+}
+
+fn print_children(out: &mut Paged, theme: &Theme, children: &[ProcessNode], prefix: &str) {
+    let total = children.len();
+    for (index, child) in children.iter().enumerate() {
+        let (branch, continuation) = tree_branches(total, index);
+        print_node(out, theme, child, &format!("{prefix}{branch} "));
+        print_children(
+            out,
+            theme,
+            &child.children,
+            &format!("{prefix}{continuation}"),
+        );
+    }
+}
+
+fn print_node(out: &mut Paged, theme: &Theme, node: &ProcessNode, prefix: &str) {
+    let process = &node.process;
+    out.line(format_args!(
+        "{prefix}{} {} {} {}",
+        theme.success(&process.name),
+        theme.muted(format!("({})", process.pid)),
+        theme.accent(format_size(process.memory_bytes)),
+        theme.accent(format!("{:.1}%", process.cpu))
+    ));
+}
+
+/// Outcome of `ziro pause` / `ziro resume`
+pub fn display_pause_results(results: &[(u32, Result<()>)], paused: bool) {
+    let theme = Theme::new();
+    for (pid, result) in results {
+        match result {
+            Ok(()) => println!(
+                "{} {}",
+                theme.icon_success(),
+                theme.success(if paused {
+                    tr!("Paused process {}", pid)
+                } else {
+                    tr!("Resumed process {}", pid)
+                })
+            ),
+            Err(e) => println!(
+                "{} {}: {}",
+                theme.icon_error(),
+                theme.error(if paused {
+                    tr!("Failed to pause process {}", pid)
+                } else {
+                    tr!("Failed to resume process {}", pid)
+                }),
+                e
+            ),
+        }
+    }
+    if paused && results.iter().any(|(_, result)| result.is_ok()) {
+        println!(
+            "{}",
+            theme.muted(tr!(
+                "Run `ziro resume` with the same PIDs to let them continue"
+            ))
+        );
+    }
+}
+
+/// Outcome of `ziro nice`
+pub fn display_nice_results(results: &[(u32, Result<()>)], nice: i32) {
+    let theme = Theme::new();
+    for (pid, result) in results {
+        match result {
+            Ok(()) => println!(
+                "{} {}",
+                theme.icon_success(),
+                theme.success(tr!("Set process {} to nice {}", pid, nice))
+            ),
+            Err(e) => println!(
+                "{} {}: {}",
+                theme.icon_error(),
+                theme.error(tr!("Failed to change the priority of process {}", pid)),
+                e
+            ),
+        }
+    }
+}
+
+/// Periodic status line of `ziro waitpid`
+pub fn display_wait_progress(elapsed: Duration, remaining: &[WaitedProcess]) {
+    let theme = Theme::new();
+    println!(
+        "{} {}",
+        theme.muted(format!("[{}s]", elapsed.as_secs())),
+        theme.info(tr!(
+            "Waiting for {} process(es): {}",
+            remaining.len(),
+            process_names(remaining)
+        ))
+    );
+}
+
+pub fn display_wait_outcome(outcome: &WaitOutcome) {
+    let theme = Theme::new();
+    let secs = format!("{:.1}s", outcome.elapsed_ms as f64 / 1000.0);
+
+    if outcome.exited.is_empty() && outcome.remaining.is_empty() {
+        println!("{}", theme.warn(tr!("No matching processes are running")));
+    } else if outcome.timed_out {
+        println!(
+            "{} {}",
+            theme.icon_error(),
+            theme.error(tr!(
+                "Still running after {}: {}",
+                secs,
+                process_names(&outcome.remaining)
+            ))
+        );
+    } else {
+        println!(
+            "{} {}",
+            theme.icon_success(),
+            theme.success(tr!(
+                "All {} process(es) exited after {}",
+                outcome.exited.len(),
+                secs
+            ))
+        );
+    }
+}
+
+pub fn display_wait_outcome_json(outcome: &WaitOutcome) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(outcome)?);
+    Ok(())
+}
+
+fn process_names(processes: &[WaitedProcess]) -> String {
+    processes
+        .iter()
+        .map(|p| format!("{} ({})", p.name, p.pid))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Zombies grouped by the parent that has to reap them, then orphans
+pub fn display_reap_report(report: &ReapReport, min_age: Duration) {
+    let theme = Theme::new();
+
+    if report.zombies.is_empty() {
+        println!(
+            "{} {}",
+            theme.icon_success(),
+            theme.success(tr!("No zombie processes"))
+        );
+    } else {
+        println!(
+            "{}",
+            theme.title(tr!("Zombie processes ({})", report.zombie_count()))
+        );
+        for parent in &report.zombies {
+            println!(
+                "  {} {} {}",
+                theme.highlight(&parent.name),
+                theme.muted(format!("(PID: {})", parent.pid)),
+                theme.muted(tr!("has not reaped {}", parent.zombies.len()))
+            );
+            for (i, zombie) in parent.zombies.iter().enumerate() {
+                let (branch, _) = tree_branches(parent.zombies.len(), i);
+                println!(
+                    "    {branch} {} {}",
+                    zombie.name,
+                    theme.muted(format!("(PID: {})", zombie.pid))
+                );
+            }
+        }
+        println!();
+        if report.zombies.iter().any(|parent| parent.pid != INIT_PID) {
+            println!(
+                "{}",
+                theme.muted(tr!(
+                    "Zombies are gone once their parent collects them: try `ziro reap --nudge`, \
+                     or `ziro reap --kill-parents` so init adopts and reaps them"
+                ))
+            );
+        }
+        if report.zombies.iter().any(|parent| parent.pid == INIT_PID) {
+            println!(
+                "{}",
+                theme.muted(tr!(
+                    "PID 1 is not reaping its zombies; in a container this usually means it \
+                     runs without an init process (e.g. `docker run --init`)"
+                ))
+            );
+        }
+    }
+
+    println!();
+    if report.orphans.is_empty() {
+        println!(
+            "{} {}",
+            theme.icon_success(),
+            theme.success(tr!(
+                "No orphaned processes running for {} or longer",
+                format_uptime(min_age.as_secs())
+            ))
+        );
+        return;
+    }
+    println!(
+        "{}",
+        theme.title(tr!("Orphaned processes ({})", report.orphans.len()))
+    );
+    for orphan in &report.orphans {
+        println!(
+            "  {:>8} {} {}",
+            orphan.pid,
+            orphan.name,
+            theme.muted(tr!("(up {})", format_uptime(orphan.run_time)))
+        );
+    }
+    println!(
+        "{}",
+        theme.muted(tr!(
+            "Their parent is gone; stop any you no longer need with `ziro pkill`"
+        ))
+    );
+}
+
+pub fn display_reap_report_json(report: &ReapReport) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(report)?);
+    Ok(())
+}
+
+/// Outcome of signalling zombie parents; `terminated` for `--kill-parents`
+pub fn display_reap_results(results: &[(u32, Result<()>)], terminated: bool) {
+    let theme = Theme::new();
+    for (pid, result) in results {
+        match result {
+            Ok(()) => println!(
+                "{} {}",
+                theme.icon_success(),
+                theme.success(if terminated {
+                    tr!("Terminated parent {}", pid)
+                } else {
+                    tr!("Sent SIGCHLD to parent {}", pid)
+                })
+            ),
+            Err(e) => println!(
+                "{} {}: {}",
+                theme.icon_error(),
+                theme.error(tr!("Failed to signal parent {}", pid)),
+                e
+            ),
+        }
+    }
+}
+
+pub fn confirm_kill_parents(count: usize) -> Result<bool> {
+    prompt::confirm(
+        Confirm::new(&tr!(
+            "Terminate {} parent process(es) holding zombies?",
+            count
+        ))
+        .with_default(false),
+    )
+}
+
+pub fn display_zombies_left(before: usize, left: usize) {
+    let theme = Theme::new();
+    if left == 0 {
+        println!(
+            "{} {}",
+            theme.icon_success(),
+            theme.success(tr!("All zombies have been reaped"))
+        );
+    } else {
+        println!(
+            "{}",
+            theme.warn(tr!("{} of {} zombie(s) remain", left, before))
+        );
+    }
+}
+
+/// Processes `ziro pkill` is about to kill
+pub fn display_pkill_targets(processes: &[ProcessView]) {
+    let theme = Theme::new();
+    println!(
+        "{} {}",
+        theme.icon_fire(),
+        theme.title(tr!("Matching processes ({})", processes.len()))
+    );
+    for process in processes {
+        println!("  {}", pkill_row(process));
+    }
+    println!();
+}
+
+/// Let the user pick among the matches of `ziro pkill`; all are preselected
+pub fn select_pkill_targets(processes: &[ProcessView]) -> Result<Vec<ProcessView>> {
+    let options: Vec<String> = processes
+        .iter()
+        .enumerate()
+        .map(|(i, process)| format!("[{i}] {}", pkill_row(process)))
+        .collect();
+    let defaults: Vec<usize> = (0..options.len()).collect();
+
+    let selected = prompt::multi_select(
+        MultiSelect::new(tr!("Select processes to kill:"), options).with_default(&defaults),
+    )?;
+
+    let chosen: Vec<ProcessView> = selected
+        .iter()
+        .filter_map(|s| {
+            let idx: usize = s.trim_start_matches('[').split(']').next()?.parse().ok()?;
+            processes.get(idx).cloned()
+        })
+        .collect();
+    if chosen.is_empty() {
+        let theme = Theme::new();
+        println!("{}", theme.warn(tr!("No processes selected")));
+    }
+    Ok(chosen)
+}
+
+pub fn confirm_pkill(count: usize) -> Result<bool> {
+    prompt::confirm(Confirm::new(&tr!("Kill {} process(es)?", count)).with_default(false))
+}
+
+/// `1234 node (alice, 120.5 MB, up 3h 2m) node server.js`
+fn pkill_row(process: &ProcessView) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    format!(
+        "{} {} ({}, {}, {}) {}",
+        process.pid,
+        process.name,
+        process.user.as_deref().unwrap_or("-"),
+        format_size(process.memory_bytes),
+        tr!(
+            "up {}",
+            format_uptime(now.saturating_sub(process.start_time))
+        ),
+        truncate_string(&process.cmd, 60)
+    )
+}
@@ -0,0 +1,43 @@
+use crate::core::serve::Request;
+use crate::tr;
+use crate::ui::Theme;
+use std::path::Path;
+
+/// Where `ziro serve` can be reached, printed once it listens
+pub fn display_serve_started(root: &Path, port: u16, urls: &[String]) {
+    let theme = Theme::new();
+    println!(
+        "{} {} {}",
+        theme.success(tr!("Serving")),
+        theme.info(root.display().to_string()),
+        theme.muted(tr!("on port {}", port))
+    );
+    for url in urls {
+        println!("  {}", theme.info(url));
+    }
+    println!("{}", theme.muted(tr!("Press Ctrl+C to stop")));
+}
+
+/// One access log line
+pub fn display_served_request(request: &Request) {
+    let theme = Theme::new();
+    let status = request.status.to_string();
+    let status = match request.status {
+        200..=399 => theme.success(status),
+        _ => theme.warn(status),
+    };
+    println!(
+        "{} {} {} {}",
+        theme.muted(request.peer.ip().to_string()),
+        request.method,
+        request.path,
+        status
+    );
+}
+
+pub fn display_serve_stopped(port: u16) {
+    println!(
+        "{}",
+        Theme::new().muted(tr!("Stopped; port {} is free again", port))
+    );
+}
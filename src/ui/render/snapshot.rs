@@ -0,0 +1,128 @@
+use crate::core::snapshot::{ProcessEntry, Snapshot, SnapshotDiff};
+use crate::tr;
+use crate::ui::Theme;
+use anyhow::Result;
+use std::path::Path;
+
+use super::{format_size, truncate_string};
+
+/// Confirm a saved snapshot
+pub fn display_snapshot_saved(path: &Path, snapshot: &Snapshot) {
+    let theme = Theme::new();
+    let total: u64 = snapshot.processes.iter().map(|p| p.memory_bytes).sum();
+    println!(
+        "{} {}",
+        theme.icon_success(),
+        theme.success(tr!(
+            "Saved {} processes ({}) to {}",
+            snapshot.processes.len(),
+            format_size(total),
+            path.display()
+        ))
+    );
+}
+
+/// Show what changed between two snapshots, `limit` rows per section
+pub fn display_snapshot_diff(diff: &SnapshotDiff, limit: usize) {
+    let theme = Theme::new();
+
+    let elapsed = diff.after_taken_at.saturating_sub(diff.before_taken_at);
+    let total_delta = diff.total_after_bytes as i64 - diff.total_before_bytes as i64;
+    println!(
+        "{} {}",
+        theme.icon_lightning(),
+        theme.title(tr!("Process changes over {}s", elapsed))
+    );
+    println!(
+        "{}",
+        tr!(
+            "Memory: {} -> {} ({})",
+            format_size(diff.total_before_bytes),
+            format_size(diff.total_after_bytes),
+            signed_size(total_delta)
+        )
+    );
+    println!(
+        "{}",
+        tr!(
+            "{} started, {} exited, {} changed",
+            diff.started.len(),
+            diff.exited.len(),
+            diff.changed.len()
+        )
+    );
+
+    section(&theme, tr!("Started"), &diff.started, limit, true);
+    section(&theme, tr!("Exited"), &diff.exited, limit, false);
+
+    if !diff.changed.is_empty() {
+        println!();
+        println!("{}", theme.info_bold(tr!("Changed")));
+        for change in diff.changed.iter().take(limit) {
+            let delta = signed_size(change.delta_bytes);
+            let delta = if change.delta_bytes > 0 {
+                theme.error(format!("{delta:>11}"))
+            } else {
+                theme.success(format!("{delta:>11}"))
+            };
+            println!(
+                "  {delta} {:>8} {:<32}  {}",
+                change.process.pid,
+                truncate_string(&change.process.name, 32),
+                theme.muted(format!(
+                    "{} -> {}",
+                    format_size(change.before_bytes),
+                    format_size(change.process.memory_bytes)
+                ))
+            );
+        }
+        more(&theme, diff.changed.len(), limit);
+    }
+}
+
+pub fn display_snapshot_diff_json(diff: &SnapshotDiff) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(diff)?);
+    Ok(())
+}
+
+fn section(theme: &Theme, title: &str, processes: &[ProcessEntry], limit: usize, started: bool) {
+    if processes.is_empty() {
+        return;
+    }
+    println!();
+    println!("{}", theme.info_bold(title));
+    for process in processes.iter().take(limit) {
+        let memory = if started {
+            theme.success(format!(
+                "{:>11}",
+                format!("+{}", format_size(process.memory_bytes))
+            ))
+        } else {
+            theme.muted(format!(
+                "{:>11}",
+                format!("-{}", format_size(process.memory_bytes))
+            ))
+        };
+        println!(
+            "  {memory} {:>8} {:<32}  {}",
+            process.pid,
+            truncate_string(&process.name, 32),
+            theme.muted(truncate_string(&process.cmd, 60))
+        );
+    }
+    more(theme, processes.len(), limit);
+}
+
+fn more(theme: &Theme, len: usize, limit: usize) {
+    if len > limit {
+        println!(
+            "{}",
+            theme.muted(format!("  {}", tr!("... and {} more", len - limit)))
+        );
+    }
+}
+
+fn signed_size(delta: i64) -> String {
+    let sign = if delta < 0 { '-' } else { '+' };
+    format!("{sign}{}", format_size(delta.unsigned_abs()))
+}
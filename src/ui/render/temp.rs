@@ -0,0 +1,163 @@
+use crate::core::fs_ops::{TempEntry, TempStatus};
+use crate::tr;
+use crate::ui::{Theme, i18n, prompt};
+use anyhow::Result;
+use console::{Alignment, pad_str};
+use inquire::Confirm;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use super::{format_count, format_size, tree_branches};
+
+const DAY: u64 = 24 * 60 * 60;
+
+/// Age buckets used to group reclaimable entries
+const AGE_BUCKETS: [(&str, u64); 4] = [
+    ("< 1 day", DAY),
+    ("1-7 days", 7 * DAY),
+    ("1-4 weeks", 28 * DAY),
+    ("> 4 weeks", u64::MAX),
+];
+
+/// Display what can be reclaimed from temp directories, grouped by age and owner
+pub fn display_temp_summary(roots: &[PathBuf], entries: &[TempEntry], older_than: Duration) {
+    let theme = Theme::new();
+
+    println!(
+        "{} {}",
+        theme.icon_search(),
+        theme.title(tr!("Temp Cleanup"))
+    );
+    for root in roots {
+        println!("   {}", theme.muted(root.display().to_string()));
+    }
+    println!();
+
+    let reclaimable: Vec<&TempEntry> = entries
+        .iter()
+        .filter(|e| e.status == TempStatus::Reclaimable)
+        .collect();
+
+    if reclaimable.is_empty() {
+        println!(
+            "{}",
+            theme.warn(tr!(
+                "Nothing older than {} to clean",
+                format_threshold(older_than)
+            ))
+        );
+    } else {
+        let now = SystemTime::now();
+        let by_age = group(&reclaimable, |entry| {
+            let age = entry
+                .modified
+                .and_then(|m| now.duration_since(m).ok())
+                .map(|d| d.as_secs())
+                .unwrap_or(u64::MAX);
+            AGE_BUCKETS
+                .iter()
+                .find(|(_, limit)| age < *limit)
+                .map(|(label, _)| i18n::tr(label).to_string())
+                .unwrap_or_default()
+        });
+        // Keep buckets in chronological order rather than by size
+        let mut by_age = by_age;
+        by_age.sort_by_key(|(label, _, _)| {
+            AGE_BUCKETS
+                .iter()
+                .position(|(bucket, _)| i18n::tr(bucket) == label)
+                .unwrap_or(usize::MAX)
+        });
+        display_groups(tr!("By age"), &by_age, &theme);
+
+        let by_owner = group(&reclaimable, |entry| {
+            entry.owner.clone().unwrap_or_else(|| "-".to_string())
+        });
+        if by_owner.len() > 1 || reclaimable.iter().any(|e| e.owner.is_some()) {
+            println!();
+            display_groups(tr!("By owner"), &by_owner, &theme);
+        }
+
+        let total: u64 = reclaimable.iter().map(|e| e.size).sum();
+        println!();
+        println!(
+            "{} {} {}",
+            theme.info(tr!("Reclaimable:")),
+            theme.warn(format_size(total)),
+            theme.muted(tr!("in {} entries", format_count(reclaimable.len() as u64)))
+        );
+    }
+
+    let count = |status| entries.iter().filter(|e| e.status == status).count();
+    let (recent, in_use, unreadable) = (
+        count(TempStatus::Recent),
+        count(TempStatus::InUse),
+        count(TempStatus::Unreadable),
+    );
+    if recent + in_use + unreadable > 0 {
+        println!(
+            "{}",
+            theme.muted(tr!(
+                "Kept: {} recent, {} in use, {} unreadable",
+                recent,
+                in_use,
+                unreadable
+            ))
+        );
+    }
+}
+
+/// Ask before cleaning temp entries
+pub fn confirm_temp_cleanup(entries: usize, size: u64) -> Result<bool> {
+    println!();
+    let confirm = prompt::confirm(
+        Confirm::new(&tr!(
+            "Delete {} temp entries ({})?",
+            entries,
+            format_size(size)
+        ))
+        .with_default(false),
+    )?;
+    Ok(confirm)
+}
+
+fn group(entries: &[&TempEntry], key: impl Fn(&TempEntry) -> String) -> Vec<(String, usize, u64)> {
+    let mut groups: Vec<(String, usize, u64)> = Vec::new();
+    for entry in entries {
+        let name = key(entry);
+        match groups.iter_mut().find(|(group, _, _)| *group == name) {
+            Some((_, count, size)) => {
+                *count += 1;
+                *size += entry.size;
+            }
+            None => groups.push((name, 1, entry.size)),
+        }
+    }
+    groups.sort_by(|a, b| b.2.cmp(&a.2));
+    groups
+}
+
+fn display_groups(title: &str, groups: &[(String, usize, u64)], theme: &Theme) {
+    println!("{}", theme.info_bold(title));
+    for (index, (name, count, size)) in groups.iter().enumerate() {
+        let (branch, _) = tree_branches(groups.len(), index);
+        let size = format_size(*size);
+        println!(
+            "{branch} {} {} {}",
+            theme.highlight(pad_str(name, 16, Alignment::Left, None)),
+            theme.warn(pad_str(&size, 10, Alignment::Right, None)),
+            theme.muted(tr!("{} entries", format_count(*count as u64)))
+        );
+    }
+}
+
+fn format_threshold(age: Duration) -> String {
+    let secs = age.as_secs();
+    if secs >= DAY && secs % DAY == 0 {
+        tr!("{} days", secs / DAY)
+    } else if secs >= 3600 && secs % 3600 == 0 {
+        tr!("{} hours", secs / 3600)
+    } else {
+        tr!("{} seconds", secs)
+    }
+}
@@ -1,7 +1,16 @@
-use crate::core::top::{ProcessView, TopRenderOptions};
+use crate::core::top::{
+    DetailRenderOptions, ProcessDetail, ProcessView, SystemSummary, TopNotice, TopRenderOptions,
+    WatchFrame, WatchRenderOptions, container_label,
+};
+use crate::tr;
 use crate::ui::Theme;
-use console::{Alignment, pad_str};
+use console::{Alignment, measure_text_width, pad_str, truncate_str};
+use crossterm::cursor::{Hide, MoveTo, MoveToNextLine, Show};
+use crossterm::queue;
+use crossterm::style::Print;
+use crossterm::terminal::{Clear, ClearType};
 use std::io::{self, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use super::{format_size, truncate_string};
 
@@ -10,15 +19,29 @@ pub fn display_top(
     processes: &[ProcessView],
     opts: &TopRenderOptions,
     last_frame: &mut Vec<String>,
-) {
-    let theme = Theme::new();
+) -> io::Result<()> {
+    // Batch frames go to logs: no colors, no cursor tricks
+    let theme = if opts.batch {
+        Theme::plain()
+    } else {
+        Theme::new()
+    };
 
     const RANK_W: usize = 4;
-    const NAME_W: usize = 26;
     const PID_W: usize = 10;
+    const STATE_W: usize = 1;
+    const USER_W: usize = 10;
+    const CONTAINER_W: usize = 14;
     const MEM_W: usize = 10;
     const MEM_PCT_W: usize = 7;
     const CPU_W: usize = 8;
+    const IO_W: usize = 11;
+    const DELTA_W: usize = 11;
+    const THREADS_W: usize = 6;
+    const FDS_W: usize = 6;
+    const GPU_W: usize = 10;
+    const GPU_PCT_W: usize = 5;
+    const NET_W: usize = 5;
 
     let mut lines: Vec<String> = Vec::new();
 
@@ -27,56 +50,205 @@ pub fn display_top(
     } else {
         "◐"
     };
-    lines.push(format!(
-        "{} {} {}",
-        theme.icon_lightning(),
-        theme.title("Process Memory Usage"),
-        theme.muted(format!("[{status_icon}]"))
-    ));
+    if opts.batch {
+        lines.push(format!("=== {} ===", utc_timestamp(SystemTime::now())));
+    } else {
+        lines.push(format!(
+            "{} {} {}",
+            theme.icon_lightning(),
+            theme.title(tr!("Process Memory Usage")),
+            theme.muted(format!("[{status_icon}]"))
+        ));
+    }
 
-    let mem_used_str = format_size(opts.used_memory);
-    let mem_total_str = format_size(opts.total_memory);
-    let mem_pct = if opts.total_memory > 0 {
-        (opts.used_memory as f64 / opts.total_memory as f64) * 100.0
+    let summary = &opts.summary;
+    let mem_used_str = format_size(summary.used_memory);
+    let mem_total_str = format_size(summary.total_memory);
+    let mem_pct = percent(summary.used_memory, summary.total_memory);
+
+    let label = if opts.replay {
+        tr!("Frame")
     } else {
-        0.0
+        tr!("Refresh")
     };
-
-    let status_line = format!(
-        "Refresh: {} | Interval: {:.1}s | Processes: {} | Memory: {} / {} ({:.1}%) | {}",
-        opts.refresh,
-        opts.interval,
-        processes.len(),
+    let state = if opts.paused {
+        format!(
+            "{} {}",
+            theme.warn(tr!("Paused")),
+            theme.muted(format!("({label} {})", opts.refresh))
+        )
+    } else {
+        format!("{label}: {}", opts.refresh)
+    };
+    let direction = if opts.sort.descending() != opts.reverse {
+        "desc"
+    } else {
+        "asc"
+    };
+    let sort_label = format!("{} {direction}", opts.sort.label());
+    let zombies = match opts.zombies {
+        0 => String::new(),
+        1 => format!(" {}", theme.error(tr!("(1 zombie)"))),
+        n => format!(" {}", theme.error(tr!("({} zombies)", n))),
+    };
+    let status_line = tr!(
+        "{} | Interval: {} | Processes: {}{} | Memory: {} / {} ({}) | Sort: {}",
+        state,
+        format!("{:.1}s", opts.interval),
+        opts.total_processes,
+        zombies,
         mem_used_str,
         mem_total_str,
-        mem_pct,
-        theme.muted("Ctrl+C to exit")
+        format!("{mem_pct:.1}%"),
+        sort_label
     );
     lines.push(status_line);
+    lines.push(system_line(summary, &theme));
+
+    let mut filters = Vec::new();
+    if let Some(filter) = &opts.filter {
+        filters.push(tr!("Filter: {}", theme.accent(filter)));
+    }
+    if let Some(user) = &opts.user {
+        filters.push(tr!("User: {}", theme.accent(user)));
+    }
+    if let Some(cgroup) = &opts.cgroup {
+        filters.push(format!("Cgroup: {}", theme.accent(cgroup)));
+    }
+    if let Some(min) = opts.min_memory {
+        filters.push(format!("Mem >= {}", theme.accent(format_size(min))));
+    }
+    if let Some(min) = opts.min_cpu {
+        filters.push(format!("CPU >= {}", theme.accent(format!("{min}%"))));
+    }
+    if !filters.is_empty() {
+        lines.push(filters.join(" | "));
+    }
+
+    let hint = match (&opts.prompt, &opts.notice) {
+        (Some((label, input)), _) => {
+            format!(
+                "{} {}{} {}",
+                theme.warn(label),
+                theme.highlight(input),
+                theme.muted("_"),
+                theme.muted(tr!("(Enter to confirm, Esc to cancel)"))
+            )
+        }
+        (None, Some(TopNotice::Info(msg))) => theme.success(msg).to_string(),
+        (None, Some(TopNotice::Error(msg))) => theme.error(msg).to_string(),
+        (None, None) if opts.interactive => theme
+            .muted(tr!("m/c/p/n/s/o sort | r reverse | t tree | g group | i io | d delta | ↑/↓ select | k/K kill | e renice | y copy | / filter | space pause | q quit"))
+            .to_string(),
+        (None, None) if opts.batch => String::new(),
+        (None, None) => theme.muted(tr!("Ctrl+C to exit")).to_string(),
+    };
+    if !hint.is_empty() {
+        lines.push(hint);
+    }
 
-    let bar_width = 30;
-    let filled = (mem_pct / 100.0 * bar_width as f64).round() as usize;
-    let bar = "=".repeat(filled) + &"·".repeat(bar_width - filled);
-    lines.push(theme.muted(format!("[{bar}]")).to_string());
+    if !opts.batch {
+        let bar_width = 30;
+        let filled = (mem_pct / 100.0 * bar_width as f64).round() as usize;
+        let bar = "=".repeat(filled) + &"·".repeat(bar_width - filled);
+        lines.push(theme.muted(format!("[{bar}]")).to_string());
+    }
     lines.push(String::new());
 
     let header_rank = pad_str("#", RANK_W, Alignment::Left, None);
-    let header_name = pad_str("Name", NAME_W, Alignment::Left, None);
+    // Column for the selection marker, only when rows can be selected
+    let gutter = if opts.interactive { " " } else { "" };
+    let io_len = if opts.show_io { 2 * (IO_W + 1) } else { 0 };
+    let delta_len = if opts.show_delta { DELTA_W + 1 } else { 0 };
+    // Everything but the name and command columns, separators included
+    let fixed = RANK_W
+        + PID_W
+        + STATE_W
+        + USER_W
+        + if opts.show_containers {
+            CONTAINER_W + 1
+        } else {
+            0
+        }
+        + MEM_W
+        + MEM_PCT_W
+        + CPU_W
+        + 7
+        + gutter.len()
+        + io_len
+        + delta_len
+        + if opts.show_threads { THREADS_W + 1 } else { 0 }
+        + if opts.show_fds { FDS_W + 1 } else { 0 }
+        + if opts.show_gpu {
+            GPU_W + GPU_PCT_W + 2
+        } else {
+            0
+        }
+        + if opts.show_net { 2 * (NET_W + 1) } else { 0 };
+    let (name_w, cmd_w) = column_widths(opts.width, fixed, opts.show_cmd);
+
+    let header_name = pad_str(tr!("Name"), name_w, Alignment::Left, None);
     let header_pid = pad_str("PID", PID_W, Alignment::Left, None);
-    let header_mem = pad_str("Memory", MEM_W, Alignment::Right, None);
-    let header_mem_pct = pad_str("Mem%", MEM_PCT_W, Alignment::Right, None);
+    let header_state = pad_str("S", STATE_W, Alignment::Left, None);
+    let header_user = pad_str(tr!("User"), USER_W, Alignment::Left, None);
+    let header_container = if opts.show_containers {
+        format!(
+            " {}",
+            pad_str(tr!("Container"), CONTAINER_W, Alignment::Left, None)
+        )
+    } else {
+        String::new()
+    };
+    let header_mem = pad_str(tr!("Memory"), MEM_W, Alignment::Right, None);
+    // Against cgroup limits the share can pass 100% of what the process may use
+    let mem_pct_label = if opts.cgroup_limit { "Lim%" } else { "Mem%" };
+    let header_mem_pct = pad_str(mem_pct_label, MEM_PCT_W, Alignment::Right, None);
     let header_cpu = pad_str("CPU", CPU_W, Alignment::Right, None);
-    let header_cmd = if opts.show_cmd { "Command" } else { "" };
+    let header_delta = if opts.show_delta {
+        format!(" {}", pad_str("ΔMem", DELTA_W, Alignment::Right, None))
+    } else {
+        String::new()
+    };
+    let mut header_counts = String::new();
+    if opts.show_threads {
+        header_counts += &format!(" {}", pad_str("Thr", THREADS_W, Alignment::Right, None));
+    }
+    if opts.show_fds {
+        header_counts += &format!(" {}", pad_str("FDs", FDS_W, Alignment::Right, None));
+    }
+    if opts.show_gpu {
+        header_counts += &format!(
+            " {} {}",
+            pad_str("GPU", GPU_W, Alignment::Right, None),
+            pad_str("GPU%", GPU_PCT_W, Alignment::Right, None)
+        );
+    }
+    if opts.show_net {
+        header_counts += &format!(
+            " {} {}",
+            pad_str("Lstn", NET_W, Alignment::Right, None),
+            pad_str("Conn", NET_W, Alignment::Right, None)
+        );
+    }
+    let header_io = if opts.show_io {
+        format!(
+            " {} {}",
+            pad_str("Read/s", IO_W, Alignment::Right, None),
+            pad_str("Write/s", IO_W, Alignment::Right, None)
+        )
+    } else {
+        String::new()
+    };
+    let header_cmd = if opts.show_cmd { tr!("Command") } else { "" };
 
     lines.push(format!(
-        "{header_rank} {header_name} {header_pid} {header_mem} {header_mem_pct} {header_cpu} {header_cmd}"
+        "{gutter}{header_rank} {header_name} {header_pid} {header_state} {header_user}{header_container} {header_mem}{header_delta} {header_mem_pct} {header_cpu}{header_counts}{header_io} {header_cmd}"
     ));
 
-    let sep_len = RANK_W + NAME_W + PID_W + MEM_W + MEM_PCT_W + CPU_W + 6;
-    lines.push(theme.muted("-".repeat(sep_len)).to_string());
+    lines.push(theme.muted("-".repeat(fixed + 1 + name_w)).to_string());
 
     for (index, process) in processes.iter().enumerate() {
-        let rank = index + 1;
+        let rank = opts.offset + index + 1;
         let rank_plain = rank.to_string();
         let rank_colored = match rank {
             1 => theme.highlight(&rank_plain),
@@ -85,57 +257,559 @@ pub fn display_top(
             _ => theme.muted(&rank_plain),
         };
 
-        let mem_str = format_size(process.memory_bytes);
-        let mem_pct_str = format!("{:.1}%", process.memory_percent);
+        let mem_pct = match process.tree_memory {
+            Some(memory) if summary.total_memory > 0 => percent(memory, summary.total_memory),
+            _ => process.memory_percent,
+        };
+        let mem_str = format_size(process.shown_memory());
+        let mem_pct_str = format!("{mem_pct:.1}%");
         let cpu_str = if opts.show_cpu {
             format!("{:.1}%", process.cpu)
         } else {
             "-".to_string()
         };
 
-        let name_plain = truncate_string(&process.name, NAME_W.saturating_sub(2));
-        let pid_plain = process.pid.to_string();
+        let indent = match (process.group_size, process.depth) {
+            (Some(_), _) if opts.expanded.contains(&process.name) => "▾ ".to_string(),
+            (Some(_), _) => "▸ ".to_string(),
+            (None, 0) => String::new(),
+            (None, depth) => format!("{}└─ ", "  ".repeat(depth - 1)),
+        };
+        let name_plain = format!(
+            "{indent}{}",
+            truncate_string(
+                &process.name,
+                name_w.saturating_sub(2 + measure_text_width(&indent))
+            )
+        );
+        let pid_plain = match process.group_size {
+            Some(size) => tr!("{} procs", size),
+            None => process.pid.to_string(),
+        };
         let cmd_display = if opts.show_cmd && !process.cmd.is_empty() {
-            format!(" {}", theme.muted(truncate_string(&process.cmd, 60)))
+            format!(" {}", theme.muted(truncate_string(&process.cmd, cmd_w)))
         } else {
             String::new()
         };
 
-        let name_padded = pad_str(&name_plain, NAME_W, Alignment::Left, None);
+        let name_padded = pad_str(&name_plain, name_w, Alignment::Left, None);
         let pid_padded = pad_str(&pid_plain, PID_W, Alignment::Left, None);
+        let user_plain = truncate_string(process.user.as_deref().unwrap_or("-"), USER_W);
+        let user_padded = pad_str(&user_plain, USER_W, Alignment::Left, None);
         let mem_padded = pad_str(&mem_str, MEM_W, Alignment::Right, None);
         let mem_pct_padded = pad_str(&mem_pct_str, MEM_PCT_W, Alignment::Right, None);
         let cpu_padded = pad_str(&cpu_str, CPU_W, Alignment::Right, None);
 
-        let name_cell = theme.success(name_padded);
+        // Zombies and processes stuck in uninterruptible sleep stand out like alerts
+        let name_cell = match process.state {
+            _ if process.alert => theme.error(name_padded),
+            'Z' => theme.error(name_padded),
+            'D' => theme.warn(name_padded),
+            _ => theme.success(name_padded),
+        };
+        let state_plain = process.state.to_string();
+        let state_cell = match process.state {
+            'R' => theme.success(&state_plain),
+            'Z' => theme.error(&state_plain),
+            'D' => theme.warn(&state_plain),
+            _ => theme.muted(&state_plain),
+        };
         let pid_cell = theme.muted(pid_padded);
+        let user_cell = theme.info(user_padded);
+        let container_cell = if opts.show_containers {
+            let label = process
+                .cgroup
+                .as_deref()
+                .map_or_else(|| "-".to_string(), container_label);
+            let label = truncate_string(&label, CONTAINER_W);
+            format!(
+                " {}",
+                theme.muted(pad_str(&label, CONTAINER_W, Alignment::Left, None))
+            )
+        } else {
+            String::new()
+        };
         let mem_cell = theme.warn(mem_padded);
         let mem_pct_cell = theme.warn(mem_pct_padded);
         let cpu_cell = theme.accent(cpu_padded);
+        let delta_cell = if opts.show_delta {
+            let delta = format_delta(process.memory_delta);
+            let padded = pad_str(&delta, DELTA_W, Alignment::Right, None);
+            let colored = match process.memory_delta {
+                Some(d) if d > 0 => theme.error(padded),
+                Some(d) if d < 0 => theme.success(padded),
+                _ => theme.muted(padded),
+            };
+            format!(" {colored}")
+        } else {
+            String::new()
+        };
+        let mut count_cells = String::new();
+        if opts.show_threads {
+            let threads = format_optional(process.threads);
+            count_cells += &format!(
+                " {}",
+                theme.muted(pad_str(&threads, THREADS_W, Alignment::Right, None))
+            );
+        }
+        if opts.show_fds {
+            let fds = format_optional(process.open_fds);
+            count_cells += &format!(
+                " {}",
+                theme.muted(pad_str(&fds, FDS_W, Alignment::Right, None))
+            );
+        }
+        if opts.show_gpu {
+            let memory = process
+                .gpu_memory
+                .map_or_else(|| "-".to_string(), format_size);
+            let util = process
+                .gpu_util
+                .map_or_else(|| "-".to_string(), |u| format!("{u}%"));
+            count_cells += &format!(
+                " {} {}",
+                theme.warn(pad_str(&memory, GPU_W, Alignment::Right, None)),
+                theme.accent(pad_str(&util, GPU_PCT_W, Alignment::Right, None))
+            );
+        }
+        if opts.show_net {
+            // Most processes have no sockets; dashes keep the busy ones visible
+            let count = |n: Option<usize>| match n {
+                Some(0) | None => "-".to_string(),
+                Some(n) => n.to_string(),
+            };
+            count_cells += &format!(
+                " {} {}",
+                theme.info(pad_str(
+                    &count(process.listening),
+                    NET_W,
+                    Alignment::Right,
+                    None
+                )),
+                theme.info(pad_str(
+                    &count(process.connections),
+                    NET_W,
+                    Alignment::Right,
+                    None
+                ))
+            );
+        }
+        let io_cells = if opts.show_io {
+            let read = format_rate(process.read_rate);
+            let write = format_rate(process.write_rate);
+            format!(
+                " {} {}",
+                theme.info(pad_str(&read, IO_W, Alignment::Right, None)),
+                theme.info(pad_str(&write, IO_W, Alignment::Right, None))
+            )
+        } else {
+            String::new()
+        };
 
         let rank_cell = pad_str(&rank_colored, RANK_W, Alignment::Left, None);
+        let marker = if opts.selected == Some(index) {
+            theme.highlight(">").to_string()
+        } else {
+            gutter.to_string()
+        };
 
         lines.push(format!(
-            "{rank_cell} {name_cell} {pid_cell} {mem_cell} {mem_pct_cell} {cpu_cell}{cmd_display}"
+            "{marker}{rank_cell} {name_cell} {pid_cell} {state_cell} {user_cell}{container_cell} {mem_cell}{delta_cell} {mem_pct_cell} {cpu_cell}{count_cells}{io_cells}{cmd_display}"
         ));
     }
 
-    render_frame(&lines, opts.incremental, last_frame);
+    // Never wrap: a wrapped line would push the rest of the frame off screen
+    if let Some(width) = opts.width {
+        for line in &mut lines {
+            *line = truncate_str(line, width, "").into_owned();
+        }
+    }
+
+    if opts.batch {
+        // Blank line between frames
+        lines.push(String::new());
+    }
+
+    render_frame(&lines, opts.incremental, opts.interactive, last_frame)
+}
+
+/// Expanded panel for `top --pid`
+pub fn display_process_detail(
+    detail: &ProcessDetail,
+    opts: &DetailRenderOptions,
+    last_frame: &mut Vec<String>,
+) -> io::Result<()> {
+    let theme = if opts.batch {
+        Theme::plain()
+    } else {
+        Theme::new()
+    };
+    let process = &detail.process;
+    let mut lines: Vec<String> = Vec::new();
+
+    if opts.batch {
+        lines.push(format!("=== {} ===", utc_timestamp(SystemTime::now())));
+    }
+    lines.push(format!(
+        "{} {} {}",
+        theme.icon_lightning(),
+        theme.title(format!("{} ({})", process.name, process.pid)),
+        if detail.exited {
+            theme.error(tr!("[exited]"))
+        } else {
+            theme.muted(format!("[{}]", process.state))
+        }
+    ));
+
+    let state = if opts.paused {
+        format!(
+            "{} {}",
+            theme.warn(tr!("Paused")),
+            theme.muted(format!("({} {})", tr!("Refresh"), opts.refresh))
+        )
+    } else {
+        format!("{}: {}", tr!("Refresh"), opts.refresh)
+    };
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    lines.push(tr!(
+        "{} | Interval: {} | User: {} | Parent: {} | Up: {}",
+        state,
+        format!("{:.1}s", opts.interval),
+        process.user.as_deref().unwrap_or("-"),
+        process
+            .parent
+            .map_or_else(|| "-".to_string(), |p| p.to_string()),
+        format_uptime(now.saturating_sub(process.start_time))
+    ));
+    lines.push(system_line(&opts.summary, &theme));
+
+    let hint = match &opts.notice {
+        Some(TopNotice::Info(msg)) => theme.success(msg),
+        Some(TopNotice::Error(msg)) => theme.error(msg),
+        None if opts.interactive => theme.muted(tr!("space pause | q quit")),
+        None if opts.batch => String::new(),
+        None => theme.muted(tr!("Ctrl+C to exit")),
+    };
+    if !hint.is_empty() {
+        lines.push(hint);
+    }
+    lines.push(String::new());
+
+    // Labels padded by display width, so translated ones stay aligned
+    let label = |text: &str| pad_str(text, 8, Alignment::Left, None).into_owned();
+    if !process.cmd.is_empty() {
+        lines.push(format!("{} {}", label(tr!("Command")), process.cmd));
+    }
+    if let Some(cgroup) = &process.cgroup {
+        lines.push(format!("{} {cgroup}", label("Cgroup")));
+    }
+    let memory_history: Vec<f64> = detail.memory_history.iter().map(|&m| m as f64).collect();
+    let memory_peak = memory_history.iter().copied().fold(0.0, f64::max);
+    lines.push(format!(
+        "{} {} {} {}",
+        label(tr!("Memory")),
+        pad_str(
+            &format_size(process.memory_bytes),
+            10,
+            Alignment::Right,
+            None
+        ),
+        theme.muted(format!("{:5.1}%", process.memory_percent)),
+        theme.accent(sparkline(&memory_history, memory_peak))
+    ));
+    let cpu_history: Vec<f64> = detail.cpu_history.iter().map(|&c| c as f64).collect();
+    // Multi-threaded processes can go past 100%
+    let cpu_peak = cpu_history.iter().copied().fold(100.0, f64::max);
+    lines.push(format!(
+        "{} {}        {}",
+        label("CPU"),
+        pad_str(&format!("{:.1}%", process.cpu), 10, Alignment::Right, None),
+        theme.accent(sparkline(&cpu_history, cpu_peak))
+    ));
+    lines.push(format!(
+        "{} {}",
+        label(tr!("Threads")),
+        tr!(
+            "{} | FDs: {} | Read: {} | Write: {}",
+            format_optional(process.threads),
+            format_optional(process.open_fds),
+            format_rate(process.read_rate),
+            format_rate(process.write_rate)
+        )
+    ));
+    lines.push(String::new());
+
+    lines.push(theme.info_bold(tr!("Children ({})", detail.children.len())));
+    for child in detail.children.iter().take(opts.limit) {
+        lines.push(format!(
+            "  {} {} {}",
+            pad_str(&child.pid.to_string(), 8, Alignment::Right, None),
+            pad_str(&format_size(child.memory_bytes), 10, Alignment::Right, None),
+            child.name
+        ));
+    }
+    more_line(&mut lines, detail.children.len(), opts.limit, &theme);
+
+    let ports: Vec<String> = detail.ports.iter().map(|p| p.to_string()).collect();
+    lines.push(format!(
+        "{} {}",
+        theme.info_bold(tr!("Ports ({})", ports.len())),
+        ports.join(", ")
+    ));
+
+    lines.push(theme.info_bold(tr!("Open files ({})", detail.open_files.len())));
+    for path in detail.open_files.iter().take(opts.limit) {
+        lines.push(format!("  {}", path.display()));
+    }
+    more_line(&mut lines, detail.open_files.len(), opts.limit, &theme);
+
+    if let Some(width) = opts.width {
+        for line in &mut lines {
+            *line = truncate_str(line, width, "").into_owned();
+        }
+    }
+    if opts.batch {
+        lines.push(String::new());
+    }
+
+    render_frame(&lines, opts.incremental, opts.interactive, last_frame)
+}
+
+/// `ziro watch`: a header with the command and its exit status, then its latest output
+pub fn display_watch(
+    frame: &WatchFrame,
+    opts: &WatchRenderOptions,
+    last_frame: &mut Vec<String>,
+) -> io::Result<()> {
+    let theme = Theme::new();
+    let mut lines: Vec<String> = Vec::new();
+
+    let status = match (&frame.error, frame.code) {
+        (Some(error), _) => theme.error(error),
+        (None, Some(0)) => theme.success("exit 0"),
+        (None, Some(code)) => theme.error(format!("exit {code}")),
+        (None, None) => theme.error(tr!("killed")),
+    };
+    let state = if opts.paused {
+        theme.warn(tr!("Paused"))
+    } else {
+        theme.muted(format!("{} {}", tr!("Refresh"), opts.refresh))
+    };
+    lines.push(format!(
+        "{} {} {} {} {}",
+        theme.icon_lightning(),
+        theme.title(tr!("Every {}s: {}", opts.interval, opts.label)),
+        status,
+        state,
+        theme.muted(utc_timestamp(frame.finished_at))
+    ));
+    lines.push(String::new());
+
+    let footer = opts
+        .interactive
+        .then(|| theme.muted(tr!("space pause | q quit")));
+    // Leave the last row free: the line break after it would scroll the screen
+    let room = opts.height.map_or(usize::MAX, |height| {
+        height.saturating_sub(lines.len() + 1 + usize::from(footer.is_some()))
+    });
+    let output: Vec<&str> = frame.output.lines().collect();
+    if output.len() > room {
+        let shown = room.saturating_sub(1);
+        lines.extend(
+            output[..shown]
+                .iter()
+                .map(|line| line.replace('\t', "    ")),
+        );
+        more_line(&mut lines, output.len(), shown, &theme);
+    } else {
+        lines.extend(output.iter().map(|line| line.replace('\t', "    ")));
+    }
+    lines.extend(footer);
+
+    if let Some(width) = opts.width {
+        for line in &mut lines {
+            *line = truncate_str(line, width, "").into_owned();
+        }
+    }
+    if !opts.incremental {
+        // Blank line between frames
+        lines.push(String::new());
+    }
+
+    render_frame(&lines, opts.incremental, opts.interactive, last_frame)
+}
+
+/// "... and N more" under a list cut at `limit`
+fn more_line(lines: &mut Vec<String>, len: usize, limit: usize, theme: &Theme) {
+    if len > limit {
+        lines.push(theme.muted(format!("  {}", tr!("... and {} more", len - limit))));
+    }
+}
+
+/// Compact running time, e.g. `3d 4h`, `2h 13m`, `45s`
+pub(super) fn format_uptime(secs: u64) -> String {
+    match secs {
+        0..60 => format!("{secs}s"),
+        60..3600 => format!("{}m {}s", secs / 60, secs % 60),
+        3600..86400 => format!("{}h {}m", secs / 3600, secs % 3600 / 60),
+        _ => format!("{}d {}h", secs / 86400, secs % 86400 / 3600),
+    }
+}
+
+/// `2026-10-16T09:30:00Z`; batch logs use UTC so frames compare across machines
+pub(super) fn utc_timestamp(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let (days, rem) = (secs / 86_400, secs % 86_400);
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60
+    )
+}
+
+/// Widths of the name and command columns; fixed defaults when the terminal width
+/// is unknown (e.g. piped output)
+fn column_widths(width: Option<usize>, fixed: usize, show_cmd: bool) -> (usize, usize) {
+    const NAME_W: usize = 26;
+    const NAME_MIN: usize = 12;
+    const NAME_MAX: usize = 48;
+    const CMD_W: usize = 60;
+    const CMD_MIN: usize = 10;
+
+    let Some(width) = width else {
+        return (NAME_W, CMD_W);
+    };
+    let available = width.saturating_sub(fixed + 1);
+    if show_cmd {
+        // The command line is the more useful column to grow
+        let name = (available / 3).clamp(NAME_MIN, NAME_W);
+        let cmd = available.saturating_sub(name + 1).max(CMD_MIN);
+        (name, cmd)
+    } else {
+        (available.clamp(NAME_MIN, NAME_MAX), 0)
+    }
+}
+
+fn percent(part: u64, total: u64) -> f64 {
+    if total > 0 {
+        part as f64 / total as f64 * 100.0
+    } else {
+        0.0
+    }
+}
+
+/// Swap, load average and a mini bar per core
+fn system_line(summary: &SystemSummary, theme: &Theme) -> String {
+    let mut parts = Vec::new();
+
+    parts.push(if summary.total_swap > 0 {
+        tr!(
+            "Swap: {} / {} ({})",
+            format_size(summary.used_swap),
+            format_size(summary.total_swap),
+            format!("{:.1}%", percent(summary.used_swap, summary.total_swap))
+        )
+    } else {
+        tr!("Swap: none").to_string()
+    });
+
+    if let Some([one, five, fifteen]) = summary.load_average {
+        parts.push(tr!("Load: {}", format!("{one:.2} {five:.2} {fifteen:.2}")));
+    }
+
+    // Recordings made before per-core sampling have no cores
+    if !summary.core_usage.is_empty() {
+        let usage: Vec<f64> = summary.core_usage.iter().map(|&u| u as f64).collect();
+        let bars = sparkline(&usage, 100.0);
+        parts.push(tr!(
+            "Cores: {} {}",
+            summary.core_usage.len(),
+            theme.accent(format!("[{bars}]"))
+        ));
+    }
+
+    parts.join(" | ")
+}
+
+/// One block character per value, scaled so `max` is a full block
+fn sparkline(values: &[f64], max: f64) -> String {
+    const LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    values
+        .iter()
+        .map(|value| {
+            let share = if max > 0.0 {
+                (value / max).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            LEVELS[(share * 7.0).round() as usize]
+        })
+        .collect()
+}
+
+fn format_optional(count: Option<usize>) -> String {
+    count.map_or_else(|| "-".to_string(), |n| n.to_string())
+}
+
+/// Signed memory change; "new" for processes missing from the previous refresh
+fn format_delta(delta: Option<i64>) -> String {
+    match delta {
+        None => tr!("new").to_string(),
+        Some(0) => "-".to_string(),
+        Some(d) => {
+            let sign = if d > 0 { '+' } else { '-' };
+            format!("{sign}{}", format_size(d.unsigned_abs()))
+        }
+    }
+}
+
+/// Bytes per second, blank when idle so busy rows stand out
+pub(super) fn format_rate(bytes_per_sec: f64) -> String {
+    if bytes_per_sec < 1.0 {
+        "-".to_string()
+    } else {
+        format!("{}/s", format_size(bytes_per_sec as u64))
+    }
 }
 
 /// Render built lines to terminal incrementally
-fn render_frame(lines: &[String], incremental: bool, last_frame: &mut Vec<String>) {
+fn render_frame(
+    lines: &[String],
+    incremental: bool,
+    raw: bool,
+    last_frame: &mut Vec<String>,
+) -> io::Result<()> {
+    let mut stdout = io::stdout();
+
+    // Raw mode (keyboard controls) doesn't translate \n into \r\n
     if !incremental {
+        let eol = if raw { "\r\n" } else { "\n" };
         for line in lines {
-            println!("{line}");
+            write!(stdout, "{line}{eol}")?;
         }
-        return;
+        return stdout.flush();
     }
 
-    let mut stdout = io::stdout();
-
-    let _ = write!(stdout, "\x1b[?25l");
-    let _ = write!(stdout, "\x1b[H");
+    queue!(stdout, Hide, MoveTo(0, 0))?;
 
     let max_len = lines.len().max(last_frame.len());
     let mut changed_lines = 0;
@@ -143,31 +817,31 @@ fn render_frame(lines: &[String], incremental: bool, last_frame: &mut Vec<String
     for i in 0..max_len {
         match (lines.get(i), last_frame.get(i)) {
             (Some(new_line), Some(old_line)) if new_line == old_line => {
-                let _ = write!(stdout, "\x1b[E");
+                queue!(stdout, MoveToNextLine(1))?;
             }
             (Some(new_line), _) => {
-                let _ = write!(stdout, "\x1b[2K{new_line}\r\n");
+                queue!(
+                    stdout,
+                    Clear(ClearType::CurrentLine),
+                    Print(new_line),
+                    Print("\r\n")
+                )?;
                 changed_lines += 1;
             }
             (None, Some(_)) => {
-                let _ = write!(stdout, "\x1b[2K\r\n");
+                queue!(stdout, Clear(ClearType::CurrentLine), Print("\r\n"))?;
                 changed_lines += 1;
             }
             (None, None) => break,
         }
     }
 
-    if max_len > lines.len() {
-        let _ = write!(stdout, "\x1b[{}J", max_len - lines.len() + 1);
-    } else {
-        let _ = write!(stdout, "\x1b[J");
-    }
-
-    let _ = write!(stdout, "\x1b[?25h");
-    let _ = stdout.flush();
+    queue!(stdout, Clear(ClearType::FromCursorDown), Show)?;
+    stdout.flush()?;
 
     if changed_lines > 0 || lines.len() != last_frame.len() {
         last_frame.clear();
         last_frame.extend(lines.iter().cloned());
     }
+    Ok(())
 }
@@ -0,0 +1,66 @@
+/// Terminal column widths: CJK and other wide characters take two columns, combining
+/// marks none, so names and paths are cut by what they occupy on screen
+use crate::platform::term;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Columns `s` takes up (without escape sequences)
+pub fn display_width(s: &str) -> usize {
+    s.width()
+}
+
+/// The mark for cut text; `…` is ambiguous-width, so narrow and ASCII terminals get `...`
+pub fn ellipsis() -> &'static str {
+    let profile = term::global_profile();
+    if profile.narrow || profile.ascii_icons || profile.plain {
+        "..."
+    } else {
+        "…"
+    }
+}
+
+/// `s` cut to at most `max_width` columns, ending in `tail` when anything was cut
+///
+/// A wide character that would straddle the limit is left out rather than split.
+pub fn truncate_width(s: &str, max_width: usize, tail: &str) -> String {
+    if display_width(s) <= max_width {
+        return s.to_string();
+    }
+    let tail_width = display_width(tail);
+    if tail_width >= max_width {
+        return tail.chars().take(max_width).collect();
+    }
+
+    let budget = max_width - tail_width;
+    let mut used = 0;
+    let mut out = String::new();
+    for c in s.chars() {
+        let width = c.width().unwrap_or(0);
+        if used + width > budget {
+            break;
+        }
+        used += width;
+        out.push(c);
+    }
+    out.push_str(tail);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_width() {
+        assert_eq!(truncate_width("node", 10, "…"), "node");
+        assert_eq!(
+            truncate_width("webpack-dev-server", 10, "..."),
+            "webpack..."
+        );
+        assert_eq!(truncate_width("webpack-dev-server", 10, "…"), "webpack-d…");
+        // Two columns per character; a fourth would straddle the limit
+        assert_eq!(display_width("微信开发者工具"), 14);
+        assert_eq!(truncate_width("微信开发者工具", 8, "…"), "微信开…");
+        assert_eq!(truncate_width("微信开发者工具", 7, "…"), "微信开…");
+        assert_eq!(truncate_width("/home/用户/项目", 2, "..."), "..");
+    }
+}
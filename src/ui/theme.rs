@@ -2,13 +2,144 @@ use crate::platform::term;
 use crate::ui::icons;
 use crate::ui::icons::StyledEmoji;
 use colored::{Color, Colorize};
+use inquire::ui::{RenderConfig, StyleSheet, Styled};
 use std::sync::OnceLock;
 
 static THEME: OnceLock<Theme> = OnceLock::new();
 
+/// Built-in palettes, by the name `--theme` and the config file use
+pub const BUILTIN_THEMES: &[&str] = &["default", "solarized", "monochrome", "high-contrast"];
+
+/// Color of each role in the output; `None` leaves the terminal's own color
+#[derive(Clone, Debug, PartialEq)]
+pub struct Palette {
+    pub title: Option<Color>,
+    pub success: Option<Color>,
+    pub error: Option<Color>,
+    pub warn: Option<Color>,
+    pub info: Option<Color>,
+    pub accent: Option<Color>,
+    pub link: Option<Color>,
+    pub muted: Option<Color>,
+    pub highlight: Option<Color>,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self {
+            title: Some(Color::Cyan),
+            success: Some(Color::Green),
+            error: Some(Color::Red),
+            warn: Some(Color::Yellow),
+            info: Some(Color::Cyan),
+            accent: Some(Color::Magenta),
+            link: Some(Color::Blue),
+            muted: Some(Color::BrightBlack),
+            highlight: Some(Color::Yellow),
+        }
+    }
+}
+
+impl Palette {
+    /// A palette from `BUILTIN_THEMES`
+    pub fn builtin(name: &str) -> Option<Self> {
+        let rgb = |r, g, b| Some(Color::TrueColor { r, g, b });
+        Some(match name {
+            "default" => Self::default(),
+            "solarized" => Self {
+                title: rgb(0x26, 0x8b, 0xd2),
+                success: rgb(0x85, 0x99, 0x00),
+                error: rgb(0xdc, 0x32, 0x2f),
+                warn: rgb(0xb5, 0x89, 0x00),
+                info: rgb(0x2a, 0xa1, 0x98),
+                accent: rgb(0xd3, 0x36, 0x82),
+                link: rgb(0x6c, 0x71, 0xc4),
+                muted: rgb(0x58, 0x6e, 0x75),
+                highlight: rgb(0xcb, 0x4b, 0x16),
+            },
+            // Bold and plain text only, for terminals whose colors clash with everything
+            "monochrome" => Self {
+                title: None,
+                success: None,
+                error: None,
+                warn: None,
+                info: None,
+                accent: None,
+                link: None,
+                muted: None,
+                highlight: None,
+            },
+            "high-contrast" => Self {
+                title: Some(Color::BrightWhite),
+                success: Some(Color::BrightGreen),
+                error: Some(Color::BrightRed),
+                warn: Some(Color::BrightYellow),
+                info: Some(Color::BrightCyan),
+                accent: Some(Color::BrightMagenta),
+                link: Some(Color::BrightBlue),
+                // Grey text is what gets lost first
+                muted: Some(Color::White),
+                highlight: Some(Color::BrightYellow),
+            },
+            _ => return None,
+        })
+    }
+
+    /// Set the color of `role` (a field name); for palettes from the config file
+    pub fn set(&mut self, role: &str, color: Option<Color>) -> Result<(), String> {
+        let slot = match role {
+            "title" => &mut self.title,
+            "success" => &mut self.success,
+            "error" => &mut self.error,
+            "warn" => &mut self.warn,
+            "info" => &mut self.info,
+            "accent" => &mut self.accent,
+            "link" => &mut self.link,
+            "muted" => &mut self.muted,
+            "highlight" => &mut self.highlight,
+            _ => return Err(format!("unknown theme color '{role}'")),
+        };
+        *slot = color;
+        Ok(())
+    }
+}
+
+/// `red`, `bright-black`, `#268bd2` or `none`
+pub fn parse_color(s: &str) -> Result<Option<Color>, String> {
+    let s = s.trim();
+    if s.eq_ignore_ascii_case("none") {
+        return Ok(None);
+    }
+    if let Some(hex) = s.strip_prefix('#') {
+        let channel = |i: usize| {
+            hex.get(i..i + 2)
+                .and_then(|c| u8::from_str_radix(c, 16).ok())
+        };
+        return match (hex.len(), channel(0), channel(2), channel(4)) {
+            (6, Some(r), Some(g), Some(b)) => Ok(Some(Color::TrueColor { r, g, b })),
+            _ => Err(format!("invalid color: {s} (expected #rrggbb)")),
+        };
+    }
+    s.replace(['-', '_'], " ").parse().map(Some).map_err(|()| {
+        format!("invalid color: {s} (expected a name like bright-red, #rrggbb or none)")
+    })
+}
+
 #[derive(Clone)]
 pub struct Theme {
     use_color: bool,
+    palette: Palette,
+}
+
+/// Use `palette` for the rest of the run, prompts included; call once at startup
+pub fn install(palette: Palette) {
+    let theme = Theme {
+        palette,
+        ..Theme::build()
+    };
+    inquire::set_global_render_config(theme.prompt_config());
+    // First call wins; second call would be a bug
+    let _ = THEME.set(theme);
 }
 
 impl Theme {
@@ -16,10 +147,51 @@ impl Theme {
         THEME.get().cloned().unwrap_or_else(Self::build)
     }
 
+    /// Theme without colors, for output meant for files and pipes
+    pub fn plain() -> Self {
+        Self {
+            use_color: false,
+            palette: Palette::default(),
+        }
+    }
+
+    pub fn uses_color(&self) -> bool {
+        self.use_color
+    }
+
     fn build() -> Self {
         Self {
             use_color: Self::detect_color_support(),
+            palette: Palette::default(),
+        }
+    }
+
+    /// inquire's look, in this theme's colors
+    fn prompt_config(&self) -> RenderConfig<'static> {
+        if !self.use_color {
+            return RenderConfig::empty();
         }
+        let sheet = |color: Option<Color>| match color {
+            Some(color) => StyleSheet::new().with_fg(prompt_color(color)),
+            None => StyleSheet::new(),
+        };
+        let styled = |text, color| Styled::new(text).with_style_sheet(sheet(color));
+        let palette = &self.palette;
+        let mut config = RenderConfig::default_colored()
+            .with_prompt_prefix(styled("?", palette.success))
+            .with_answered_prompt_prefix(styled(">", palette.success))
+            .with_help_message(sheet(palette.muted))
+            .with_answer(sheet(palette.info))
+            .with_highlighted_option_prefix(styled(">", palette.info))
+            .with_selected_checkbox(styled("[x]", palette.success))
+            .with_selected_option(Some(sheet(palette.info)))
+            .with_canceled_prompt_indicator(styled("<canceled>", palette.error));
+        config.placeholder = sheet(palette.muted);
+        config.error_message = config
+            .error_message
+            .with_prefix(styled("#", palette.error))
+            .with_message(sheet(palette.error));
+        config
     }
 
     /// Detect whether color is enabled
@@ -36,13 +208,16 @@ impl Theme {
         !plain && !no_color
     }
 
-    fn paint(&self, text: impl AsRef<str>, color: Color, bold: bool) -> String {
+    fn paint(&self, text: impl AsRef<str>, color: Option<Color>, bold: bool) -> String {
         let content = text.as_ref();
         if !self.use_color {
             return content.to_string();
         }
 
-        let styled = content.color(color);
+        let styled = match color {
+            Some(color) => content.color(color),
+            None => content.normal(),
+        };
         if bold {
             styled.bold().to_string()
         } else {
@@ -50,93 +225,92 @@ impl Theme {
         }
     }
 
-    fn paint_icon(&self, icon: StyledEmoji, color: Color) -> String {
+    fn paint_icon(&self, icon: StyledEmoji, color: Option<Color>) -> String {
         let base = icon.to_string();
-        if self.use_color {
-            base.color(color).to_string()
-        } else {
-            base
+        match color {
+            Some(color) if self.use_color => base.color(color).to_string(),
+            _ => base,
         }
     }
 
     pub fn title(&self, text: impl AsRef<str>) -> String {
-        self.paint(text, Color::Cyan, true)
+        self.paint(text, self.palette.title, true)
     }
 
     pub fn success(&self, text: impl AsRef<str>) -> String {
-        self.paint(text, Color::Green, false)
+        self.paint(text, self.palette.success, false)
     }
 
     pub fn error(&self, text: impl AsRef<str>) -> String {
-        self.paint(text, Color::Red, false)
+        self.paint(text, self.palette.error, false)
     }
 
     pub fn error_bold(&self, text: impl AsRef<str>) -> String {
-        self.paint(text, Color::Red, true)
+        self.paint(text, self.palette.error, true)
     }
 
     pub fn warn(&self, text: impl AsRef<str>) -> String {
-        self.paint(text, Color::Yellow, false)
+        self.paint(text, self.palette.warn, false)
     }
 
     pub fn info(&self, text: impl AsRef<str>) -> String {
-        self.paint(text, Color::Cyan, false)
+        self.paint(text, self.palette.info, false)
     }
 
     pub fn info_bold(&self, text: impl AsRef<str>) -> String {
-        self.paint(text, Color::Cyan, true)
+        self.paint(text, self.palette.info, true)
     }
 
     pub fn accent(&self, text: impl AsRef<str>) -> String {
-        self.paint(text, Color::Magenta, false)
+        self.paint(text, self.palette.accent, false)
     }
 
     pub fn blue(&self, text: impl AsRef<str>) -> String {
-        self.paint(text, Color::Blue, false)
+        self.paint(text, self.palette.link, false)
     }
 
     pub fn muted(&self, text: impl AsRef<str>) -> String {
-        self.paint(text, Color::BrightBlack, false)
+        self.paint(text, self.palette.muted, false)
     }
 
     pub fn highlight(&self, text: impl AsRef<str>) -> String {
-        self.paint(text, Color::Yellow, true)
+        self.paint(text, self.palette.highlight, true)
     }
 
     pub fn icon_success(&self) -> String {
-        self.paint_icon(icons::icons().check(), Color::Green)
+        self.paint_icon(icons::icons().check(), self.palette.success)
     }
 
     pub fn icon_error(&self) -> String {
-        self.paint_icon(icons::icons().cross(), Color::Red)
+        self.paint_icon(icons::icons().cross(), self.palette.error)
     }
 
     pub fn icon_lightning(&self) -> String {
-        self.paint_icon(icons::icons().lightning(), Color::Cyan)
+        self.paint_icon(icons::icons().lightning(), self.palette.info)
     }
 
     pub fn icon_search(&self) -> String {
-        self.paint_icon(icons::icons().search(), Color::Blue)
+        self.paint_icon(icons::icons().search(), self.palette.link)
     }
 
     pub fn icon_warning(&self) -> String {
-        self.paint_icon(icons::icons().warning(), Color::Red)
+        self.paint_icon(icons::icons().warning(), self.palette.error)
     }
 
     pub fn icon_fire(&self) -> String {
-        self.paint_icon(icons::icons().fire(), Color::Red)
+        self.paint_icon(icons::icons().fire(), self.palette.error)
     }
 
     pub fn icon_folder(&self) -> String {
-        self.paint_icon(icons::icons().folder(), Color::Cyan)
+        self.paint_icon(icons::icons().folder(), self.palette.info)
     }
 
     pub fn icon_file(&self) -> String {
-        self.paint_icon(icons::icons().file(), Color::Blue)
+        self.paint_icon(icons::icons().file(), self.palette.link)
     }
 
     pub fn icon_link(&self) -> String {
-        self.paint_icon(icons::icons().link(), Color::Magenta)
+        self.paint_icon(icons::icons().link(), self.palette.accent)
     }
 }
 
@@ -145,3 +319,52 @@ impl Default for Theme {
         Self::new()
     }
 }
+
+fn prompt_color(color: Color) -> inquire::ui::Color {
+    use inquire::ui::Color as C;
+    match color {
+        Color::Black => C::Black,
+        Color::Red => C::DarkRed,
+        Color::Green => C::DarkGreen,
+        Color::Yellow => C::DarkYellow,
+        Color::Blue => C::DarkBlue,
+        Color::Magenta => C::DarkMagenta,
+        Color::Cyan => C::DarkCyan,
+        Color::White => C::Grey,
+        Color::BrightBlack => C::DarkGrey,
+        Color::BrightRed => C::LightRed,
+        Color::BrightGreen => C::LightGreen,
+        Color::BrightYellow => C::LightYellow,
+        Color::BrightBlue => C::LightBlue,
+        Color::BrightMagenta => C::LightMagenta,
+        Color::BrightCyan => C::LightCyan,
+        Color::BrightWhite => C::White,
+        Color::TrueColor { r, g, b } => C::Rgb { r, g, b },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(parse_color("bright-black"), Ok(Some(Color::BrightBlack)));
+        assert_eq!(parse_color("Red"), Ok(Some(Color::Red)));
+        assert_eq!(
+            parse_color("#268bd2"),
+            Ok(Some(Color::TrueColor {
+                r: 0x26,
+                g: 0x8b,
+                b: 0xd2
+            }))
+        );
+        assert_eq!(parse_color("none"), Ok(None));
+        assert!(parse_color("#26").is_err() && parse_color("sparkly").is_err());
+
+        let mut palette = Palette::builtin("solarized").unwrap();
+        palette.set("muted", Some(Color::White)).unwrap();
+        assert_eq!(palette.muted, Some(Color::White));
+        assert!(palette.set("background", None).is_err());
+    }
+}